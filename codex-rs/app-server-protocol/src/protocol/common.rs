@@ -121,6 +121,10 @@ client_request_definitions! {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadUndo => "thread/undo" {
+        params: v2::ThreadUndoParams,
+        response: v2::ThreadUndoResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -133,6 +137,10 @@ client_request_definitions! {
         params: v2::ReviewStartParams,
         response: v2::ReviewStartResponse,
     },
+    ReviewFixFindings => "review/fixFindings" {
+        params: v2::ReviewFixFindingsParams,
+        response: v2::ReviewFixFindingsResponse,
+    },
 
     ModelList => "model/list" {
         params: v2::ModelListParams,
@@ -519,6 +527,7 @@ server_notification_definitions! {
     Error => "error" (v2::ErrorNotification),
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
     ThreadTokenUsageUpdated => "thread/tokenUsage/updated" (v2::ThreadTokenUsageUpdatedNotification),
+    ThreadTurnCostRecorded => "thread/turnCost/recorded" (v2::ThreadTurnCostRecordedNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
     TurnDiffUpdated => "turn/diff/updated" (v2::TurnDiffUpdatedNotification),
@@ -364,6 +364,9 @@ pub struct SandboxSettings {
     pub network_access: Option<bool>,
     pub exclude_tmpdir_env_var: Option<bool>,
     pub exclude_slash_tmp: Option<bool>,
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+    pub read_deny: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
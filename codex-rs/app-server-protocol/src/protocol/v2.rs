@@ -227,6 +227,10 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+    #[serde(default)]
+    pub read_deny: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -428,6 +432,10 @@ pub enum SandboxPolicy {
         exclude_tmpdir_env_var: bool,
         #[serde(default)]
         exclude_slash_tmp: bool,
+        #[serde(default)]
+        network_allowlist: Vec<String>,
+        #[serde(default)]
+        read_deny: Vec<String>,
     },
 }
 
@@ -443,11 +451,15 @@ impl SandboxPolicy {
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                network_allowlist,
+                read_deny,
             } => codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: writable_roots.clone(),
                 network_access: *network_access,
                 exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                 exclude_slash_tmp: *exclude_slash_tmp,
+                network_allowlist: network_allowlist.clone(),
+                read_deny: read_deny.clone(),
             },
         }
     }
@@ -465,11 +477,15 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                network_allowlist,
+                read_deny,
             } => SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                network_allowlist,
+                read_deny,
             },
         }
     }
@@ -735,6 +751,11 @@ pub struct Model {
     pub default_reasoning_effort: ReasoningEffort,
     // Only one model should be marked as default.
     pub is_default: bool,
+    /// Context window in tokens, when known.
+    pub context_window: Option<i64>,
+    /// Provider serving the model (e.g. `ollama`) for models discovered on a
+    /// local server; `null` for the default provider.
+    pub model_provider: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -967,6 +988,24 @@ pub struct ThreadCompactParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadCompactResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoParams {
+    pub thread_id: String,
+    /// Ghost snapshot to restore. Every turn from that snapshot on is undone.
+    /// Defaults to the most recent snapshot, undoing only the last turn.
+    pub snapshot_id: Option<String>,
+    /// Number of most recent turns to undo, as an alternative to
+    /// `snapshot_id`. The undone turns are removed from the thread.
+    pub turns: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUndoResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1011,6 +1050,22 @@ pub struct ThreadTokenUsageUpdatedNotification {
     pub token_usage: ThreadTokenUsage,
 }
 
+/// Cost ledger entry of a finished turn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTurnCostRecordedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub model: String,
+    /// Usage summed over every model request of the turn.
+    pub token_usage: TokenUsageBreakdown,
+    /// Whether the thread was compacted during the turn.
+    pub compacted: bool,
+    /// Cost in USD, or `null` when `model_prices` has no price for `model`.
+    pub cost_usd: Option<f64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1123,6 +1178,9 @@ pub struct TurnStartParams {
     pub effort: Option<ReasoningEffort>,
     /// Override the reasoning summary for this turn and subsequent turns.
     pub summary: Option<ReasoningSummary>,
+    /// Override the model provider for this turn and subsequent turns. Pass
+    /// the `modelProvider` of a `model/list` entry together with its `model`.
+    pub model_provider: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1150,6 +1208,41 @@ pub struct ReviewStartResponse {
     pub review_thread_id: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewFixFindingsParams {
+    pub thread_id: String,
+    /// Ids of the findings to fix, as reported on the `exitedReviewMode`
+    /// item. They must all come from the same review.
+    pub finding_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewFixFindingsResponse {
+    pub turn: Turn,
+}
+
+/// A finding reported by a review, addressable by `id` in
+/// `review/fixFindings`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewFindingItem {
+    pub id: String,
+    /// 1-based position of the finding in the review.
+    pub number: u32,
+    pub title: String,
+    pub body: String,
+    pub priority: i32,
+    pub confidence_score: f32,
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[ts(tag = "type", export_to = "v2/")]
@@ -1171,6 +1264,11 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the changes between two revisions, as in `git diff base..head`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Range { base: String, head: String },
+
     /// Arbitrary instructions, equivalent to the old free-form prompt.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1304,7 +1402,14 @@ pub enum ThreadItem {
     EnteredReviewMode { id: String, review: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
-    ExitedReviewMode { id: String, review: String },
+    ExitedReviewMode {
+        id: String,
+        review: String,
+        /// The review's findings; empty when it found nothing or was
+        /// interrupted.
+        #[serde(default)]
+        findings: Vec<ReviewFindingItem>,
+    },
 }
 
 impl From<CoreTurnItem> for ThreadItem {
@@ -1354,6 +1459,11 @@ pub struct FileUpdateChange {
     pub path: String,
     pub kind: PatchChangeKind,
     pub diff: String,
+    /// How closely the patch context matched the file, from 0 to 1, when it
+    /// was applied with fuzzy matching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub fuzz_score: Option<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -34,12 +34,14 @@ use codex_app_server_protocol::PatchChangeKind as V2PatchChangeKind;
 use codex_app_server_protocol::ReasoningSummaryPartAddedNotification;
 use codex_app_server_protocol::ReasoningSummaryTextDeltaNotification;
 use codex_app_server_protocol::ReasoningTextDeltaNotification;
+use codex_app_server_protocol::ReviewFindingItem;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
 use codex_app_server_protocol::TerminalInteractionNotification;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
+use codex_app_server_protocol::ThreadTurnCostRecordedNotification;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnCompletedNotification;
 use codex_app_server_protocol::TurnDiffUpdatedNotification;
@@ -67,6 +69,7 @@ use codex_core::review_prompts;
 use codex_protocol::ConversationId;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::review_finding_id;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -325,6 +328,19 @@ pub(crate) async fn apply_bespoke_event_handling(
             handle_token_count_event(conversation_id, event_turn_id, token_count_event, &outgoing)
                 .await;
         }
+        EventMsg::TurnCost(turn_cost_event) => {
+            let notification = ThreadTurnCostRecordedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: turn_cost_event.turn_id,
+                model: turn_cost_event.model,
+                token_usage: turn_cost_event.usage.into(),
+                compacted: turn_cost_event.compacted,
+                cost_usd: turn_cost_event.cost_usd,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadTurnCostRecorded(notification))
+                .await;
+        }
         EventMsg::Error(ev) => {
             let turn_error = TurnError {
                 message: ev.message,
@@ -426,13 +442,17 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .await;
         }
         EventMsg::ExitedReviewMode(review_event) => {
-            let review = match review_event.review_output {
-                Some(output) => render_review_output_text(&output),
-                None => REVIEW_FALLBACK_MESSAGE.to_string(),
+            let (review, findings) = match review_event.review_output {
+                Some(output) => (
+                    render_review_output_text(&output),
+                    review_finding_items(&event_turn_id, &output),
+                ),
+                None => (REVIEW_FALLBACK_MESSAGE.to_string(), Vec::new()),
             };
             let item = ThreadItem::ExitedReviewMode {
                 id: event_turn_id.clone(),
                 review,
+                findings,
             };
             let started = ItemStartedNotification {
                 thread_id: conversation_id.to_string(),
@@ -979,6 +999,25 @@ async fn on_exec_approval_response(
 
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
+fn review_finding_items(review_id: &str, output: &ReviewOutputEvent) -> Vec<ReviewFindingItem> {
+    output
+        .findings
+        .iter()
+        .enumerate()
+        .map(|(idx, finding)| ReviewFindingItem {
+            id: review_finding_id(review_id, idx + 1),
+            number: (idx + 1) as u32,
+            title: finding.title.clone(),
+            body: finding.body.clone(),
+            priority: finding.priority,
+            confidence_score: finding.confidence_score,
+            path: finding.code_location.absolute_file_path.clone(),
+            start_line: finding.code_location.line_range.start,
+            end_line: finding.code_location.line_range.end,
+        })
+        .collect()
+}
+
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
     let mut sections = Vec::new();
     let explanation = output.overall_explanation.trim();
@@ -1006,6 +1045,10 @@ fn convert_patch_changes(changes: &HashMap<PathBuf, CoreFileChange>) -> Vec<File
             path: path.to_string_lossy().into_owned(),
             kind: map_patch_change_kind(change),
             diff: format_file_change_diff(change),
+            fuzz_score: match change {
+                CoreFileChange::Update { fuzz_score, .. } => *fuzz_score,
+                _ => None,
+            },
         })
         .collect();
     converted.sort_by(|a, b| a.path.cmp(&b.path));
@@ -1014,7 +1057,9 @@ fn convert_patch_changes(changes: &HashMap<PathBuf, CoreFileChange>) -> Vec<File
 
 fn map_patch_change_kind(change: &CoreFileChange) -> V2PatchChangeKind {
     match change {
-        CoreFileChange::Add { .. } => V2PatchChangeKind::Add,
+        CoreFileChange::Add { .. }
+        | CoreFileChange::AddBinary { .. }
+        | CoreFileChange::AddSymlink { .. } => V2PatchChangeKind::Add,
         CoreFileChange::Delete { .. } => V2PatchChangeKind::Delete,
         CoreFileChange::Update { move_path, .. } => V2PatchChangeKind::Update {
             move_path: move_path.clone(),
@@ -1024,21 +1069,35 @@ fn map_patch_change_kind(change: &CoreFileChange) -> V2PatchChangeKind {
 
 fn format_file_change_diff(change: &CoreFileChange) -> String {
     match change {
-        CoreFileChange::Add { content } => content.clone(),
+        CoreFileChange::Add { content, mode } => with_mode_note(content.clone(), *mode),
+        CoreFileChange::AddBinary { size, mode } => {
+            with_mode_note(format!("Binary file ({size} bytes)"), *mode)
+        }
+        CoreFileChange::AddSymlink { target } => format!("Symlink to: {}", target.display()),
         CoreFileChange::Delete { content } => content.clone(),
         CoreFileChange::Update {
             unified_diff,
             move_path,
+            mode,
+            ..
         } => {
-            if let Some(path) = move_path {
+            let diff = if let Some(path) = move_path {
                 format!("{unified_diff}\n\nMoved to: {}", path.display())
             } else {
                 unified_diff.clone()
-            }
+            };
+            with_mode_note(diff, *mode)
         }
     }
 }
 
+fn with_mode_note(diff: String, mode: Option<u32>) -> String {
+    match mode {
+        Some(mode) => format!("{diff}\n\nMode: {mode:o}"),
+        None => diff,
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn on_file_change_request_approval_response(
     event_turn_id: String,
@@ -1,6 +1,7 @@
 use crate::bespoke_event_handling::apply_bespoke_event_handling;
 use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::fuzzy_file_search::FileSearchIndexes;
 use crate::fuzzy_file_search::run_fuzzy_file_search;
 use crate::models::supported_models;
 use crate::outgoing_message::OutgoingMessageSender;
@@ -69,6 +70,8 @@ use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::ResumeConversationResponse;
 use codex_app_server_protocol::ReviewDelivery as ApiReviewDelivery;
+use codex_app_server_protocol::ReviewFixFindingsParams;
+use codex_app_server_protocol::ReviewFixFindingsResponse;
 use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::ReviewStartResponse;
 use codex_app_server_protocol::ReviewTarget as ApiReviewTarget;
@@ -92,6 +95,8 @@ use codex_app_server_protocol::ThreadResumeResponse;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadUndoParams;
+use codex_app_server_protocol::ThreadUndoResponse;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
@@ -220,6 +225,7 @@ pub(crate) struct CodexMessageProcessor {
     pending_interrupts: PendingInterrupts,
     turn_summary_store: TurnSummaryStore,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    file_search_indexes: Arc<FileSearchIndexes>,
     feedback: CodexFeedback,
 }
 
@@ -263,6 +269,7 @@ impl CodexMessageProcessor {
         cli_overrides: Vec<(String, TomlValue)>,
         feedback: CodexFeedback,
     ) -> Self {
+        let file_search_indexes = Arc::new(FileSearchIndexes::new(config.codex_home.clone()));
         Self {
             auth_manager,
             conversation_manager,
@@ -275,6 +282,7 @@ impl CodexMessageProcessor {
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
+            file_search_indexes,
             feedback,
         }
     }
@@ -319,6 +327,16 @@ impl CodexMessageProcessor {
                     .filter(|t| !t.is_empty());
                 ApiReviewTarget::Commit { sha, title }
             }
+            ApiReviewTarget::Range { base, head } => {
+                let base = base.trim().to_string();
+                let head = head.trim().to_string();
+                if base.is_empty() || head.is_empty() {
+                    return Err(invalid_request(
+                        "base and head must not be empty".to_string(),
+                    ));
+                }
+                ApiReviewTarget::Range { base, head }
+            }
             ApiReviewTarget::Custom { instructions } => {
                 let trimmed = instructions.trim().to_string();
                 if trimmed.is_empty() {
@@ -336,6 +354,7 @@ impl CodexMessageProcessor {
             ApiReviewTarget::UncommittedChanges => CoreReviewTarget::UncommittedChanges,
             ApiReviewTarget::BaseBranch { branch } => CoreReviewTarget::BaseBranch { branch },
             ApiReviewTarget::Commit { sha, title } => CoreReviewTarget::Commit { sha, title },
+            ApiReviewTarget::Range { base, head } => CoreReviewTarget::Range { base, head },
             ApiReviewTarget::Custom { instructions } => CoreReviewTarget::Custom { instructions },
         };
 
@@ -343,6 +362,7 @@ impl CodexMessageProcessor {
         let review_request = ReviewRequest {
             target: core_target,
             user_facing_hint: Some(hint.clone()),
+            paths: Vec::new(),
         };
 
         Ok((review_request, hint))
@@ -373,6 +393,9 @@ impl CodexMessageProcessor {
                 self.send_unimplemented_error(request_id, "thread/compact")
                     .await;
             }
+            ClientRequest::ThreadUndo { request_id, params } => {
+                self.thread_undo(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
@@ -382,6 +405,9 @@ impl CodexMessageProcessor {
             ClientRequest::ReviewStart { request_id, params } => {
                 self.review_start(request_id, params).await;
             }
+            ClientRequest::ReviewFixFindings { request_id, params } => {
+                self.review_fix_findings(request_id, params).await;
+            }
             ClientRequest::NewConversation { request_id, params } => {
                 // Do not tokio::spawn() to process new_conversation()
                 // asynchronously because we need to ensure the conversation is
@@ -2668,7 +2694,8 @@ impl CodexMessageProcessor {
             || params.sandbox_policy.is_some()
             || params.model.is_some()
             || params.effort.is_some()
-            || params.summary.is_some();
+            || params.summary.is_some()
+            || params.model_provider.is_some();
 
         // If any overrides are provided, update the session turn context first.
         if has_any_overrides {
@@ -2680,6 +2707,7 @@ impl CodexMessageProcessor {
                     model: params.model,
                     effort: params.effort.map(Some),
                     summary: params.summary,
+                    model_provider: params.model_provider,
                 })
                 .await;
         }
@@ -2942,6 +2970,128 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn review_fix_findings(&self, request_id: RequestId, params: ReviewFixFindingsParams) {
+        let ReviewFixFindingsParams {
+            thread_id,
+            finding_ids,
+        } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let (review_id, findings) = match parse_review_finding_ids(&finding_ids) {
+            Ok(value) => value,
+            Err(message) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message,
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // Reject unknown reviews or findings before a turn is started for them.
+        if let Err(message) = conversation
+            .check_review_fix(Some(&review_id), &findings)
+            .await
+        {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message,
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        match conversation
+            .submit(Op::FixReviewFindings {
+                review_id: Some(review_id),
+                findings,
+            })
+            .await
+        {
+            Ok(turn_id) => {
+                let turn = Turn {
+                    id: turn_id,
+                    items: vec![],
+                    error: None,
+                    status: TurnStatus::InProgress,
+                };
+                self.outgoing
+                    .send_response(request_id, ReviewFixFindingsResponse { turn: turn.clone() })
+                    .await;
+
+                let notif = TurnStartedNotification { thread_id, turn };
+                self.outgoing
+                    .send_server_notification(ServerNotification::TurnStarted(notif))
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to fix review findings: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_undo(&self, request_id: RequestId, params: ThreadUndoParams) {
+        let ThreadUndoParams {
+            thread_id,
+            snapshot_id,
+            turns,
+        } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The outcome is reported through the undo events on the thread.
+        let op = match (snapshot_id, turns) {
+            (Some(_), Some(_)) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: "snapshotId and turns cannot both be set".to_string(),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+            (Some(snapshot_id), None) => Op::UndoTo { snapshot_id },
+            (None, Some(turns)) => Op::UndoTurns { turns },
+            (None, None) => Op::Undo,
+        };
+        match conversation.submit(op).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, ThreadUndoResponse {})
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to undo: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
     async fn turn_interrupt(&mut self, request_id: RequestId, params: TurnInterruptParams) {
         let TurnInterruptParams { thread_id, .. } = params;
 
@@ -3152,9 +3302,14 @@ impl CodexMessageProcessor {
             None => Arc::new(AtomicBool::new(false)),
         };
 
+        let indexes = self
+            .config
+            .features
+            .enabled(Feature::FileSearchIndex)
+            .then(|| Arc::clone(&self.file_search_indexes));
         let results = match query.as_str() {
             "" => vec![],
-            _ => run_fuzzy_file_search(query, roots, cancel_flag.clone()).await,
+            _ => run_fuzzy_file_search(query, roots, cancel_flag.clone(), indexes).await,
         };
 
         if let Some(token) = cancellation_token {
@@ -3393,6 +3548,29 @@ fn parse_datetime(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
     })
 }
 
+/// Splits finding ids (`<review id>#<n>`) into their review id and finding
+/// numbers. All ids must name findings of the same review.
+fn parse_review_finding_ids(finding_ids: &[String]) -> Result<(String, Vec<usize>), String> {
+    let mut review_id: Option<&str> = None;
+    let mut numbers = Vec::with_capacity(finding_ids.len());
+    for finding_id in finding_ids {
+        let (id, number) = finding_id
+            .rsplit_once('#')
+            .and_then(|(id, number)| Some((id, number.parse::<usize>().ok()?)))
+            .filter(|(id, number)| !id.is_empty() && *number > 0)
+            .ok_or_else(|| format!("invalid review finding id: {finding_id}"))?;
+        match review_id {
+            Some(existing) if existing != id => {
+                return Err("findingIds must all come from the same review".to_string());
+            }
+            _ => review_id = Some(id),
+        }
+        numbers.push(number);
+    }
+    let review_id = review_id.ok_or_else(|| "findingIds must not be empty".to_string())?;
+    Ok((review_id.to_string(), numbers))
+}
+
 fn summary_to_thread(summary: ConversationSummary) -> Thread {
     let ConversationSummary {
         conversation_id,
@@ -3436,6 +3614,18 @@ mod tests {
     use serde_json::json;
     use tempfile::TempDir;
 
+    #[test]
+    fn parse_review_finding_ids_groups_by_review() {
+        assert_eq!(
+            parse_review_finding_ids(&["7#1".to_string(), "7#3".to_string()]),
+            Ok(("7".to_string(), vec![1, 3]))
+        );
+        assert!(parse_review_finding_ids(&[]).is_err());
+        assert!(parse_review_finding_ids(&["7#0".to_string()]).is_err());
+        assert!(parse_review_finding_ids(&["7".to_string()]).is_err());
+        assert!(parse_review_finding_ids(&["7#1".to_string(), "8#2".to_string()]).is_err());
+    }
+
     #[test]
     fn extract_conversation_summary_prefers_plain_user_messages() -> Result<()> {
         let conversation_id = ConversationId::from_string("3f941c35-29b3-493b-b0a4-e25800d9aeb0")?;
@@ -1,12 +1,15 @@
+use std::collections::HashMap;
 use std::num::NonZero;
 use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
 use std::sync::atomic::AtomicBool;
 
 use codex_app_server_protocol::FuzzyFileSearchResult;
 use codex_file_search as file_search;
+use codex_file_search::FileSearchIndex;
 use tokio::task::JoinSet;
 use tracing::warn;
 
@@ -14,10 +17,46 @@ const LIMIT_PER_ROOT: usize = 50;
 const MAX_THREADS: usize = 12;
 const COMPUTE_INDICES: bool = true;
 
+/// Shared file-search indexes opened by this connection's searches, keyed by
+/// search root. Holding them keeps each index loaded until the connection
+/// closes; a root whose index could not be opened is remembered as `None` and
+/// walked instead.
+pub(crate) struct FileSearchIndexes {
+    codex_home: PathBuf,
+    indexes: StdMutex<HashMap<String, Option<Arc<FileSearchIndex>>>>,
+}
+
+impl FileSearchIndexes {
+    pub(crate) fn new(codex_home: PathBuf) -> Self {
+        Self {
+            codex_home,
+            indexes: StdMutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, root: &str) -> Option<Arc<FileSearchIndex>> {
+        #[expect(clippy::unwrap_used)]
+        let mut indexes = self.indexes.lock().unwrap();
+        indexes
+            .entry(root.to_string())
+            .or_insert_with(|| {
+                file_search::shared_index(&self.codex_home, Path::new(root))
+                    .inspect_err(|err| {
+                        warn!("file search index for '{root}' unavailable: {err:#}");
+                    })
+                    .ok()
+            })
+            .clone()
+    }
+}
+
+/// Searches every root, using the persistent indexes in `indexes` when set
+/// and falling back to a full walk if an index cannot be opened.
 pub(crate) async fn run_fuzzy_file_search(
     query: String,
     roots: Vec<String>,
     cancellation_flag: Arc<AtomicBool>,
+    indexes: Option<Arc<FileSearchIndexes>>,
 ) -> Vec<FuzzyFileSearchResult> {
     if roots.is_empty() {
         return Vec::new();
@@ -41,17 +80,30 @@ pub(crate) async fn run_fuzzy_file_search(
         let search_dir = PathBuf::from(&root);
         let query = query.clone();
         let cancel_flag = cancellation_flag.clone();
+        let indexes = indexes.clone();
         join_set.spawn_blocking(move || {
-            match file_search::run(
-                query.as_str(),
-                limit_per_root,
-                &search_dir,
-                Vec::new(),
-                threads,
-                cancel_flag,
-                COMPUTE_INDICES,
-                true,
-            ) {
+            let index = indexes.and_then(|indexes| indexes.get(&root));
+            let result = match index {
+                Some(index) => index.search(
+                    query.as_str(),
+                    limit_per_root,
+                    &search_dir,
+                    Vec::new(),
+                    cancel_flag,
+                    COMPUTE_INDICES,
+                ),
+                None => file_search::run(
+                    query.as_str(),
+                    limit_per_root,
+                    &search_dir,
+                    Vec::new(),
+                    threads,
+                    cancel_flag,
+                    COMPUTE_INDICES,
+                    true,
+                ),
+            };
+            match result {
                 Ok(res) => Ok((root, res)),
                 Err(err) => Err((root, err)),
             }
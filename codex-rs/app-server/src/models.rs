@@ -30,6 +30,8 @@ fn model_from_preset(preset: ModelPreset) -> Model {
         ),
         default_reasoning_effort: preset.default_reasoning_effort,
         is_default: preset.is_default,
+        context_window: preset.context_window,
+        model_provider: preset.model_provider,
     }
 }
 
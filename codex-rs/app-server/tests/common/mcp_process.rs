@@ -38,6 +38,7 @@ use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
+use codex_app_server_protocol::ReviewFixFindingsParams;
 use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
@@ -47,6 +48,7 @@ use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadUndoParams;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
 use std::process::Command as StdCommand;
@@ -390,6 +392,24 @@ impl McpProcess {
         self.send_request("review/start", params).await
     }
 
+    /// Send a `review/fixFindings` JSON-RPC request (v2).
+    pub async fn send_review_fix_findings_request(
+        &mut self,
+        params: ReviewFixFindingsParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("review/fixFindings", params).await
+    }
+
+    /// Send a `thread/undo` JSON-RPC request (v2).
+    pub async fn send_thread_undo_request(
+        &mut self,
+        params: ThreadUndoParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/undo", params).await
+    }
+
     /// Send a `cancelLoginChatGpt` JSON-RPC request.
     pub async fn send_cancel_login_chat_gpt_request(
         &mut self,
@@ -7,5 +7,6 @@ mod thread_archive;
 mod thread_list;
 mod thread_resume;
 mod thread_start;
+mod thread_undo;
 mod turn_interrupt;
 mod turn_start;
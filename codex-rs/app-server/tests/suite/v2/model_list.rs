@@ -73,6 +73,8 @@ async fn list_models_returns_all_models_with_large_limit() -> Result<()> {
             ],
             default_reasoning_effort: ReasoningEffort::Medium,
             is_default: true,
+            context_window: None,
+            model_provider: None,
         },
         Model {
             id: "gpt-5.1-codex".to_string(),
@@ -96,6 +98,8 @@ async fn list_models_returns_all_models_with_large_limit() -> Result<()> {
             ],
             default_reasoning_effort: ReasoningEffort::Medium,
             is_default: false,
+            context_window: None,
+            model_provider: None,
         },
         Model {
             id: "gpt-5.1-codex-mini".to_string(),
@@ -115,6 +119,8 @@ async fn list_models_returns_all_models_with_large_limit() -> Result<()> {
             ],
             default_reasoning_effort: ReasoningEffort::Medium,
             is_default: false,
+            context_window: None,
+            model_provider: None,
         },
         Model {
             id: "gpt-5.2".to_string(),
@@ -148,6 +154,8 @@ async fn list_models_returns_all_models_with_large_limit() -> Result<()> {
             ],
             default_reasoning_effort: ReasoningEffort::Medium,
             is_default: false,
+            context_window: None,
+            model_provider: None,
         },
         Model {
             id: "gpt-5.1".to_string(),
@@ -175,6 +183,8 @@ async fn list_models_returns_all_models_with_large_limit() -> Result<()> {
             ],
             default_reasoning_effort: ReasoningEffort::Medium,
             is_default: false,
+            context_window: None,
+            model_provider: None,
         },
     ];
 
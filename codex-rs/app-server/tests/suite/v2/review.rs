@@ -10,6 +10,9 @@ use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ReviewDelivery;
+use codex_app_server_protocol::ReviewFindingItem;
+use codex_app_server_protocol::ReviewFixFindingsParams;
+use codex_app_server_protocol::ReviewFixFindingsResponse;
 use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::ReviewStartResponse;
 use codex_app_server_protocol::ReviewTarget;
@@ -18,6 +21,7 @@ use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::TurnStatus;
 use serde_json::json;
+use std::path::PathBuf;
 use tempfile::TempDir;
 use tokio::time::timeout;
 
@@ -117,8 +121,26 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
         let completed: ItemCompletedNotification =
             serde_json::from_value(review_notif.params.expect("params must be present"))?;
         match completed.item {
-            ThreadItem::ExitedReviewMode { id, review } => {
+            ThreadItem::ExitedReviewMode {
+                id,
+                review,
+                findings,
+            } => {
                 assert_eq!(id, turn_id);
+                assert_eq!(
+                    findings,
+                    vec![ReviewFindingItem {
+                        id: format!("{turn_id}#1"),
+                        number: 1,
+                        title: "Prefer Stylize helpers".to_string(),
+                        body: "Use .dim()/.bold() chaining instead of manual Style.".to_string(),
+                        priority: 1,
+                        confidence_score: 0.9,
+                        path: PathBuf::from("/tmp/file.rs"),
+                        start_line: 10,
+                        end_line: 20,
+                    }]
+                );
                 review_body = Some(review);
                 break;
             }
@@ -133,6 +155,189 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
     Ok(())
 }
 
+#[tokio::test]
+async fn review_fix_findings_starts_turn_seeded_with_findings() -> Result<()> {
+    let review_payload = json!({
+        "findings": [
+            {
+                "title": "Unchecked unwrap",
+                "body": "This panics on malformed input.",
+                "confidence_score": 0.8,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": "/tmp/parse.rs",
+                    "line_range": {"start": 3, "end": 4}
+                }
+            },
+            {
+                "title": "Typo in comment",
+                "body": "Spelling.",
+                "confidence_score": 0.5,
+                "priority": 3,
+                "code_location": {
+                    "absolute_file_path": "/tmp/lib.rs",
+                    "line_range": {"start": 1, "end": 1}
+                }
+            }
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "One real bug.",
+        "overall_confidence_score": 0.7
+    })
+    .to_string();
+    let responses = vec![
+        create_final_assistant_message_sse_response(&review_payload)?,
+        create_final_assistant_message_sse_response("Fixed the unwrap.")?,
+    ];
+    let server = create_mock_chat_completions_server_unchecked(responses).await;
+
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_default_thread(&mut mcp).await?;
+
+    let review_req = mcp
+        .send_review_start_request(ReviewStartParams {
+            thread_id: thread_id.clone(),
+            delivery: Some(ReviewDelivery::Inline),
+            target: ReviewTarget::UncommittedChanges,
+        })
+        .await?;
+    let review_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(review_req)),
+    )
+    .await??;
+    let ReviewStartResponse { turn, .. } = to_response::<ReviewStartResponse>(review_resp)?;
+    let review_id = turn.id;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+
+    // Findings the review does not have are rejected before a turn starts.
+    let missing_req = mcp
+        .send_review_fix_findings_request(ReviewFixFindingsParams {
+            thread_id: thread_id.clone(),
+            finding_ids: vec![format!("{review_id}#3")],
+        })
+        .await?;
+    let missing: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_req)),
+    )
+    .await??;
+    assert_eq!(missing.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(
+        missing.error.message,
+        "The review has 2 finding(s); there is no finding 3."
+    );
+
+    let fix_req = mcp
+        .send_review_fix_findings_request(ReviewFixFindingsParams {
+            thread_id,
+            finding_ids: vec![format!("{review_id}#1")],
+        })
+        .await?;
+    let fix_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(fix_req)),
+    )
+    .await??;
+    let ReviewFixFindingsResponse { turn } = to_response::<ReviewFixFindingsResponse>(fix_resp)?;
+    assert_eq!(turn.status, TurnStatus::InProgress);
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("turn/completed"),
+    )
+    .await??;
+
+    let requests = server
+        .received_requests()
+        .await
+        .expect("mock server should record requests");
+    let fix_request =
+        String::from_utf8_lossy(&requests.last().expect("fix request").body).into_owned();
+    assert!(fix_request.contains(&format!("[{review_id}#1] Unchecked unwrap")));
+    assert!(fix_request.contains("/tmp/parse.rs:3-4"));
+    assert!(!fix_request.contains(&format!("[{review_id}#2]")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn review_fix_findings_rejects_malformed_ids() -> Result<()> {
+    let server = create_mock_chat_completions_server_unchecked(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let thread_id = start_default_thread(&mut mcp).await?;
+
+    let request_id = mcp
+        .send_review_fix_findings_request(ReviewFixFindingsParams {
+            thread_id,
+            finding_ids: vec!["not-a-finding".to_string()],
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert!(
+        error.error.message.contains("invalid review finding id"),
+        "unexpected message: {}",
+        error.error.message
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn review_fix_findings_rejects_unknown_review() -> Result<()> {
+    let server = create_mock_chat_completions_server_unchecked(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let thread_id = start_default_thread(&mut mcp).await?;
+
+    let request_id = mcp
+        .send_review_fix_findings_request(ReviewFixFindingsParams {
+            thread_id,
+            finding_ids: vec!["42#1".to_string()],
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(error.error.message, "No completed review with id 42.");
+
+    // No turn was started for the rejected request.
+    assert!(
+        timeout(
+            std::time::Duration::from_millis(250),
+            mcp.read_stream_until_notification_message("turn/started"),
+        )
+        .await
+        .is_err(),
+        "unexpected turn/started for a rejected fix request"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn review_start_rejects_empty_base_branch() -> Result<()> {
     let server = create_mock_chat_completions_server_unchecked(vec![]).await;
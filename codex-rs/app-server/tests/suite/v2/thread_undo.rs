@@ -0,0 +1,130 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCNotification;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::ThreadUndoParams;
+use codex_app_server_protocol::ThreadUndoResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const INVALID_REQUEST_ERROR_CODE: i64 = -32600;
+
+#[tokio::test]
+async fn thread_undo_turns_without_snapshots_reports_failure() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut mcp = start_mcp(codex_home.path()).await?;
+    let thread_id = start_thread(&mut mcp).await?;
+
+    let undo_id = mcp
+        .send_thread_undo_request(ThreadUndoParams {
+            thread_id,
+            snapshot_id: None,
+            turns: Some(2),
+        })
+        .await?;
+    let undo_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(undo_id)),
+    )
+    .await??;
+    let _: ThreadUndoResponse = to_response::<ThreadUndoResponse>(undo_resp)?;
+
+    let completed: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("codex/event/undo_completed"),
+    )
+    .await??;
+    let msg = completed
+        .params
+        .expect("undo_completed params must be present")["msg"]
+        .clone();
+    assert_eq!(msg["success"], false);
+    assert_eq!(
+        msg["message"],
+        "Cannot undo 2 turns: only 0 have a ghost snapshot."
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_undo_rejects_snapshot_id_with_turns() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let mut mcp = start_mcp(codex_home.path()).await?;
+    let thread_id = start_thread(&mut mcp).await?;
+
+    let undo_id = mcp
+        .send_thread_undo_request(ThreadUndoParams {
+            thread_id,
+            snapshot_id: Some("3f2c9a1e".to_string()),
+            turns: Some(1),
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(undo_id)),
+    )
+    .await??;
+
+    assert_eq!(err.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(err.error.message, "snapshotId and turns cannot both be set");
+
+    Ok(())
+}
+
+async fn start_mcp(codex_home: &std::path::Path) -> Result<McpProcess> {
+    let server = create_mock_chat_completions_server(vec![]).await;
+    create_config_toml(codex_home, &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    Ok(mcp)
+}
+
+async fn start_thread(mcp: &mut McpProcess) -> Result<String> {
+    let thread_req = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let thread_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(thread_req)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(thread_resp)?;
+    Ok(thread.id)
+}
+
+// Helper to create a config.toml pointing at the mock model server.
+fn create_config_toml(codex_home: &std::path::Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -536,10 +536,13 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                network_allowlist: vec![],
+                read_deny: vec![],
             }),
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
             summary: Some(ReasoningSummary::Auto),
+            model_provider: None,
         })
         .await?;
     timeout(
@@ -566,6 +569,7 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
             summary: Some(ReasoningSummary::Auto),
+            model_provider: None,
         })
         .await?;
     timeout(
@@ -714,6 +718,7 @@ async fn turn_start_file_change_approval_v2() -> Result<()> {
             path: expected_readme_path.clone(),
             kind: PatchChangeKind::Add,
             diff: "new line\n".to_string(),
+            fuzz_score: None,
         }]
     );
 
@@ -880,6 +885,7 @@ async fn turn_start_file_change_approval_decline_v2() -> Result<()> {
             path: expected_readme_path_str.clone(),
             kind: PatchChangeKind::Add,
             diff: "new line\n".to_string(),
+            fuzz_score: None,
         }]
     );
 
@@ -1,6 +1,7 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod transaction;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -8,7 +9,6 @@ use std::path::PathBuf;
 use std::str::Utf8Error;
 use std::sync::LazyLock;
 
-use anyhow::Context;
 use anyhow::Result;
 pub use parser::Hunk;
 pub use parser::ParseError;
@@ -17,6 +17,7 @@ use parser::UpdateFileChunk;
 pub use parser::parse_patch;
 use similar::TextDiff;
 use thiserror::Error;
+use transaction::PatchTransaction;
 use tree_sitter::LanguageError;
 use tree_sitter::Parser;
 use tree_sitter::Query;
@@ -31,6 +32,25 @@ pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_too
 
 const APPLY_PATCH_COMMANDS: [&str; 2] = ["apply_patch", "applypatch"];
 
+/// Minimum score for an update chunk whose lines cannot be found verbatim to
+/// be applied at the closest fuzzy match instead. `1.0` disables fuzzy
+/// matching.
+pub const DEFAULT_FUZZ_THRESHOLD: f32 = 0.85;
+
+/// Environment variable the `apply_patch` executable reads to override
+/// [`DEFAULT_FUZZ_THRESHOLD`].
+pub const FUZZ_THRESHOLD_ENV_VAR: &str = "CODEX_APPLY_PATCH_FUZZ_THRESHOLD";
+
+/// Returns the fuzz threshold from [`FUZZ_THRESHOLD_ENV_VAR`], falling back
+/// to [`DEFAULT_FUZZ_THRESHOLD`] when it is unset or not a number in `0..=1`.
+pub fn fuzz_threshold_from_env() -> f32 {
+    std::env::var(FUZZ_THRESHOLD_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<f32>().ok())
+        .filter(|threshold| (0.0..=1.0).contains(threshold))
+        .unwrap_or(DEFAULT_FUZZ_THRESHOLD)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ApplyPatchShell {
     Unix,
@@ -185,6 +205,15 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        /// Unix permission bits requested with `*** Set Mode:`.
+        mode: Option<u32>,
+    },
+    AddBinary {
+        contents: Vec<u8>,
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -192,8 +221,12 @@ pub enum ApplyPatchFileChange {
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        mode: Option<u32>,
         /// new_content that will result after the unified_diff is applied.
         new_content: String,
+        /// Lowest fuzzy-match score among the chunks, or `None` when every
+        /// chunk matched the file exactly (modulo whitespace and punctuation).
+        fuzz_score: Option<f32>,
     },
 }
 
@@ -256,7 +289,44 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
+        #[expect(clippy::expect_used)]
+        Self {
+            changes,
+            cwd: path
+                .parent()
+                .expect("path should have parent")
+                .to_path_buf(),
+            patch,
+        }
+    }
+
+    /// Like [`Self::new_add_for_test`], but adds a symlink at `path` pointing
+    /// to `target`.
+    pub fn new_add_symlink_for_test(path: &Path, target: &Path) -> Self {
+        if !path.is_absolute() {
+            panic!("path must be absolute");
+        }
+
+        let patch = format!(
+            r#"*** Begin Patch
+*** Add Symlink: {} -> {}
+*** End Patch"#,
+            path.display(),
+            target.display()
+        );
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::AddSymlink {
+                target: target.to_path_buf(),
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -271,7 +341,13 @@ impl ApplyPatchAction {
 
 /// cwd must be an absolute path so that we can resolve relative paths in the
 /// patch.
-pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
+/// `fuzz_threshold` is the minimum score for applying a chunk at a fuzzy
+/// match; see [`DEFAULT_FUZZ_THRESHOLD`].
+pub fn maybe_parse_apply_patch_verified(
+    argv: &[String],
+    cwd: &Path,
+    fuzz_threshold: f32,
+) -> MaybeApplyPatchVerified {
     // Detect a raw patch body passed directly as the command or as the body of a shell
     // script. In these cases, report an explicit error rather than applying the patch.
     if let [body] = argv
@@ -306,8 +382,20 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
                 match hunk {
-                    Hunk::AddFile { contents, .. } => {
-                        changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                    Hunk::AddFile { contents, mode, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Add {
+                                content: contents,
+                                mode,
+                            },
+                        );
+                    }
+                    Hunk::AddBinaryFile { contents, mode, .. } => {
+                        changes.insert(path, ApplyPatchFileChange::AddBinary { contents, mode });
+                    }
+                    Hunk::AddSymlink { target, .. } => {
+                        changes.insert(path, ApplyPatchFileChange::AddSymlink { target });
                     }
                     Hunk::DeleteFile { .. } => {
                         let content = match std::fs::read_to_string(&path) {
@@ -324,12 +412,21 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         changes.insert(path, ApplyPatchFileChange::Delete { content });
                     }
                     Hunk::UpdateFile {
-                        move_path, chunks, ..
+                        move_path,
+                        mode,
+                        chunks,
+                        ..
                     } => {
                         let ApplyPatchFileUpdate {
                             unified_diff,
                             content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
+                            fuzz_score,
+                        } = match unified_diff_from_chunks_with_context(
+                            &path,
+                            &chunks,
+                            1,
+                            fuzz_threshold,
+                        ) {
                             Ok(diff) => diff,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
@@ -340,7 +437,9 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                             ApplyPatchFileChange::Update {
                                 unified_diff,
                                 move_path: move_path.map(|p| effective_cwd.join(p)),
+                                mode,
                                 new_content: contents,
+                                fuzz_score,
                             },
                         );
                     }
@@ -561,7 +660,7 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::AddBinaryFile { .. } | Hunk::AddSymlink { .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
@@ -585,7 +684,7 @@ pub fn apply_hunks(
         .collect::<Vec<&Path>>();
 
     // Delegate to a helper that applies each hunk to the filesystem.
-    match apply_hunks_to_files(hunks) {
+    match apply_hunks_to_files(hunks, fuzz_threshold_from_env()) {
         Ok(affected) => {
             print_summary(&affected, stdout).map_err(ApplyPatchError::from)?;
             Ok(())
@@ -615,72 +714,115 @@ pub struct AffectedPaths {
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
-fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
+/// Every hunk is verified against the current file contents before anything is written, and the
+/// resulting changes are committed together, so a failed patch leaves the filesystem untouched.
+fn apply_hunks_to_files(hunks: &[Hunk], fuzz_threshold: f32) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
-    let mut added: Vec<PathBuf> = Vec::new();
-    let mut modified: Vec<PathBuf> = Vec::new();
-    let mut deleted: Vec<PathBuf> = Vec::new();
-    for hunk in hunks {
-        match hunk {
-            Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
-                added.push(path.clone());
-            }
-            Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
-                    .with_context(|| format!("Failed to delete file {}", path.display()))?;
-                deleted.push(path.clone());
+    let mut transaction = PatchTransaction::default();
+    let mut affected = AffectedPaths {
+        added: Vec::new(),
+        modified: Vec::new(),
+        deleted: Vec::new(),
+    };
+    for (idx, hunk) in hunks.iter().enumerate() {
+        stage_hunk(&mut transaction, hunk, fuzz_threshold, &mut affected).map_err(|err| {
+            anyhow::anyhow!(
+                "Failed to apply hunk {} of {} ({}); no files were modified.\n{err}",
+                idx + 1,
+                hunks.len(),
+                describe_hunk(hunk),
+            )
+        })?;
+    }
+    transaction
+        .commit()
+        .map_err(|err| anyhow::anyhow!("{err:#}; no files were modified."))?;
+    Ok(affected)
+}
+
+/// Applies `hunk` on top of the changes already staged in `transaction`.
+fn stage_hunk(
+    transaction: &mut PatchTransaction,
+    hunk: &Hunk,
+    fuzz_threshold: f32,
+    affected: &mut AffectedPaths,
+) -> anyhow::Result<()> {
+    match hunk {
+        Hunk::AddFile {
+            path,
+            contents,
+            mode,
+        } => {
+            transaction.write(path, contents.clone().into_bytes(), *mode);
+            affected.added.push(path.clone());
+        }
+        Hunk::AddBinaryFile {
+            path,
+            contents,
+            mode,
+        } => {
+            transaction.write(path, contents.clone(), *mode);
+            affected.added.push(path.clone());
+        }
+        Hunk::AddSymlink { path, target } => {
+            transaction.symlink(path, target);
+            affected.added.push(path.clone());
+        }
+        Hunk::DeleteFile { path } => {
+            if !transaction.is_deletable(path) {
+                anyhow::bail!("Failed to delete file {}", path.display());
             }
-            Hunk::UpdateFile {
-                path,
-                move_path,
-                chunks,
-            } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
-                if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
-                        .with_context(|| format!("Failed to remove original {}", path.display()))?;
-                    modified.push(dest.clone());
-                } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
-                    modified.push(path.clone());
-                }
+            transaction.delete(path);
+            affected.deleted.push(path.clone());
+        }
+        Hunk::UpdateFile {
+            path,
+            move_path,
+            mode,
+            chunks,
+        } => {
+            let original_contents = transaction.read_to_string(path).map_err(|err| {
+                ApplyPatchError::IoError(IoError {
+                    context: format!("Failed to read file to update {}", path.display()),
+                    source: err,
+                })
+            })?;
+            let AppliedPatch { new_contents, .. } =
+                derive_new_contents_from_text(path, original_contents, chunks, fuzz_threshold)?;
+            if let Some(dest) = move_path {
+                // The moved file keeps its mode unless the patch sets one.
+                let mode = mode.or_else(|| transaction.mode(path));
+                transaction.write(dest, new_contents.into_bytes(), mode);
+                transaction.delete(path);
+                affected.modified.push(dest.clone());
+            } else {
+                transaction.write(path, new_contents.into_bytes(), *mode);
+                affected.modified.push(path.clone());
             }
         }
     }
-    Ok(AffectedPaths {
-        added,
-        modified,
-        deleted,
-    })
+    Ok(())
+}
+
+/// Short description of a hunk for error messages, mirroring its header line.
+fn describe_hunk(hunk: &Hunk) -> String {
+    match hunk {
+        Hunk::AddFile { path, .. } => format!("Add File: {}", path.display()),
+        Hunk::AddBinaryFile { path, .. } => format!("Add Binary File: {}", path.display()),
+        Hunk::AddSymlink { path, .. } => format!("Add Symlink: {}", path.display()),
+        Hunk::DeleteFile { path } => format!("Delete File: {}", path.display()),
+        Hunk::UpdateFile { path, .. } => format!("Update File: {}", path.display()),
+    }
 }
 
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    /// Lowest score among chunks that needed a fuzzy match, if any did.
+    fuzz_score: Option<f32>,
 }
 
 /// Return *only* the new file contents (joined into a single `String`) after
@@ -688,6 +830,7 @@ struct AppliedPatch {
 fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
+    fuzz_threshold: f32,
 ) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let original_contents = match std::fs::read_to_string(path) {
         Ok(contents) => contents,
@@ -698,7 +841,16 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    derive_new_contents_from_text(path, original_contents, chunks, fuzz_threshold)
+}
 
+/// Applies the chunks to `original_contents`, which were read from `path`.
+fn derive_new_contents_from_text(
+    path: &Path,
+    original_contents: String,
+    chunks: &[UpdateFileChunk],
+    fuzz_threshold: f32,
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -707,7 +859,10 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let Replacements {
+        replacements,
+        fuzz_score,
+    } = compute_replacements(&original_lines, path, chunks, fuzz_threshold)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
     if !new_lines.last().is_some_and(String::is_empty) {
@@ -717,36 +872,58 @@ fn derive_new_contents_from_chunks(
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        fuzz_score,
     })
 }
 
+/// Replacements computed by [`compute_replacements`].
+struct Replacements {
+    /// `(start_index, old_len, new_lines)` triples sorted by `start_index`.
+    replacements: Vec<(usize, usize, Vec<String>)>,
+    /// Lowest score among chunks that needed a fuzzy match, if any did.
+    fuzz_score: Option<f32>,
+}
+
 /// Compute a list of replacements needed to transform `original_lines` into the
 /// new lines, given the patch `chunks`. Each replacement is returned as
-/// `(start_index, old_len, new_lines)`.
+/// `(start_index, old_len, new_lines)`. Chunks that cannot be located exactly
+/// fall back to a fuzzy match scoring at least `fuzz_threshold`.
 fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
-) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
+    fuzz_threshold: f32,
+) -> std::result::Result<Replacements, ApplyPatchError> {
     let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
     let mut line_index: usize = 0;
+    let mut fuzz_score: Option<f32> = None;
+    let mut record_fuzz = |score: f32| {
+        fuzz_score = Some(fuzz_score.map_or(score, |current| current.min(score)));
+    };
 
     for chunk in chunks {
         // If a chunk has a `change_context`, we use seek_sequence to find it, then
         // adjust our `line_index` to continue from there.
         if let Some(ctx_line) = &chunk.change_context {
-            if let Some(idx) = seek_sequence::seek_sequence(
+            let ctx_pattern = std::slice::from_ref(ctx_line);
+            if let Some(idx) =
+                seek_sequence::seek_sequence(original_lines, ctx_pattern, line_index, false)
+            {
+                line_index = idx + 1;
+            } else if let Some(found) = seek_sequence::fuzzy_seek_sequence(
                 original_lines,
-                std::slice::from_ref(ctx_line),
+                ctx_pattern,
                 line_index,
-                false,
+                fuzz_threshold,
             ) {
-                line_index = idx + 1;
+                record_fuzz(found.score);
+                line_index = found.start + found.len;
             } else {
                 return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
+                    "Failed to find context '{}' in {}{}",
                     ctx_line,
-                    path.display()
+                    path.display(),
+                    describe_closest_match(original_lines, ctx_pattern),
                 )));
             }
         }
@@ -799,18 +976,75 @@ fn compute_replacements(
         if let Some(start_idx) = found {
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
+        } else if let Some(fuzzy) =
+            seek_sequence::fuzzy_seek_sequence(original_lines, pattern, line_index, fuzz_threshold)
+        {
+            record_fuzz(fuzzy.score);
+            let new_lines = carry_over_context(original_lines, pattern, new_slice, &fuzzy);
+            replacements.push((fuzzy.start, fuzzy.len, new_lines));
+            line_index = fuzzy.start + fuzzy.len;
         } else {
             return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
+                "Failed to find expected lines in {}:\n{}{}",
                 path.display(),
                 chunk.old_lines.join("\n"),
+                describe_closest_match(original_lines, pattern),
             )));
         }
     }
 
     replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
 
-    Ok(replacements)
+    Ok(Replacements {
+        replacements,
+        fuzz_score,
+    })
+}
+
+/// Builds the replacement for a fuzzily matched chunk. Context lines (lines
+/// the chunk keeps unchanged) are taken from the file rather than the patch,
+/// so the small differences that forced the fuzzy match are not written back
+/// over the file's real contents.
+fn carry_over_context(
+    original_lines: &[String],
+    old_lines: &[String],
+    new_lines: &[String],
+    fuzzy: &seek_sequence::FuzzyMatch,
+) -> Vec<String> {
+    let mut result = new_lines.to_vec();
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, old_lines, new_lines) {
+        if let similar::DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = op
+        {
+            for offset in 0..len {
+                if let Some(file_idx) = fuzzy.alignment[old_index + offset] {
+                    result[new_index + offset] = original_lines[file_idx].clone();
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Minimum similarity for a region to be reported as the closest match.
+const CLOSEST_MATCH_MIN_SIMILARITY: f32 = 0.5;
+
+/// Describes the region of the file that best resembles `pattern`, as a
+/// suffix for a "failed to find" error, or an empty string when nothing is
+/// similar enough to be useful.
+fn describe_closest_match(original_lines: &[String], pattern: &[String]) -> String {
+    match seek_sequence::closest_sequence(original_lines, pattern) {
+        Some((start_idx, similarity)) if similarity >= CLOSEST_MATCH_MIN_SIMILARITY => format!(
+            "\nClosest match at line {} ({:.0}% similar):\n{}",
+            start_idx + 1,
+            similarity * 100.0,
+            original_lines[start_idx..start_idx + pattern.len()].join("\n"),
+        ),
+        _ => String::new(),
+    }
 }
 
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
@@ -842,33 +1076,38 @@ fn apply_replacements(
 }
 
 /// Intended result of a file update for apply_patch.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, PartialEq)]
 pub struct ApplyPatchFileUpdate {
     unified_diff: String,
     content: String,
+    /// Lowest score among chunks that needed a fuzzy match, if any did.
+    fuzz_score: Option<f32>,
 }
 
 pub fn unified_diff_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
-    unified_diff_from_chunks_with_context(path, chunks, 1)
+    unified_diff_from_chunks_with_context(path, chunks, 1, DEFAULT_FUZZ_THRESHOLD)
 }
 
 pub fn unified_diff_from_chunks_with_context(
     path: &Path,
     chunks: &[UpdateFileChunk],
     context: usize,
+    fuzz_threshold: f32,
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
     let AppliedPatch {
         original_contents,
         new_contents,
-    } = derive_new_contents_from_chunks(path, chunks)?;
+        fuzz_score,
+    } = derive_new_contents_from_chunks(path, chunks, fuzz_threshold)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
     Ok(ApplyPatchFileUpdate {
         unified_diff,
         content: new_contents,
+        fuzz_score,
     })
 }
 
@@ -946,6 +1185,7 @@ mod tests {
         vec![Hunk::AddFile {
             path: PathBuf::from("foo"),
             contents: "hi\n".to_string(),
+            mode: None,
         }]
     }
 
@@ -978,7 +1218,7 @@ mod tests {
         let args = vec![patch];
         let dir = tempdir().unwrap();
         assert_matches!(
-            maybe_parse_apply_patch_verified(&args, dir.path()),
+            maybe_parse_apply_patch_verified(&args, dir.path(), DEFAULT_FUZZ_THRESHOLD),
             MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ImplicitInvocation)
         );
     }
@@ -989,7 +1229,7 @@ mod tests {
         let args = args_bash(script);
         let dir = tempdir().unwrap();
         assert_matches!(
-            maybe_parse_apply_patch_verified(&args, dir.path()),
+            maybe_parse_apply_patch_verified(&args, dir.path(), DEFAULT_FUZZ_THRESHOLD),
             MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ImplicitInvocation)
         );
     }
@@ -1011,7 +1251,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -1036,7 +1277,8 @@ mod tests {
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -1076,7 +1318,8 @@ PATCH"#,
                     hunks,
                     vec![Hunk::AddFile {
                         path: PathBuf::from("foo"),
-                        contents: "hi\n".to_string()
+                        contents: "hi\n".to_string(),
+                        mode: None,
                     }]
                 );
             }
@@ -1276,6 +1519,41 @@ PATCH"#,
         assert_eq!(contents, "line2\n");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_mode_symlink_and_binary_hunks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let tool = dir.path().join("tool.py");
+        fs::write(&tool, "print('hi')\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {tool}
+*** Set Mode: 755
+*** Add Binary File: {bin}
++AAEC/w==
+*** Add Symlink: {link} -> tool.py"#,
+            tool = tool.display(),
+            bin = dir.path().join("data.bin").display(),
+            link = dir.path().join("latest").display(),
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(fs::read_to_string(&tool).unwrap(), "print('hi')\n");
+        let mode = fs::metadata(&tool).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode, 0o755);
+        assert_eq!(
+            fs::read(dir.path().join("data.bin")).unwrap(),
+            vec![0, 1, 2, 255]
+        );
+        assert_eq!(
+            fs::read_link(dir.path().join("latest")).unwrap(),
+            PathBuf::from("tool.py")
+        );
+    }
+
     /// Verify that a single `Update File` hunk with multiple change chunks can update different
     /// parts of a file and that the file is listed only once in the summary.
     #[test]
@@ -1474,6 +1752,7 @@ PATCH"#,
         let expected = ApplyPatchFileUpdate {
             unified_diff: expected_diff.to_string(),
             content: "foo\nBAR\nbaz\nQUX\n".to_string(),
+            fuzz_score: None,
         };
         assert_eq!(expected, diff);
     }
@@ -1510,6 +1789,7 @@ PATCH"#,
         let expected = ApplyPatchFileUpdate {
             unified_diff: expected_diff.to_string(),
             content: "FOO\nbar\nbaz\n".to_string(),
+            fuzz_score: None,
         };
         assert_eq!(expected, diff);
     }
@@ -1547,6 +1827,7 @@ PATCH"#,
         let expected = ApplyPatchFileUpdate {
             unified_diff: expected_diff.to_string(),
             content: "foo\nbar\nBAZ\n".to_string(),
+            fuzz_score: None,
         };
         assert_eq!(expected, diff);
     }
@@ -1581,6 +1862,7 @@ PATCH"#,
         let expected = ApplyPatchFileUpdate {
             unified_diff: expected_diff.to_string(),
             content: "foo\nbar\nbaz\nquux\n".to_string(),
+            fuzz_score: None,
         };
         assert_eq!(expected, diff);
     }
@@ -1636,6 +1918,7 @@ PATCH"#,
         let expected = ApplyPatchFileUpdate {
             unified_diff: expected_diff.to_string(),
             content: "a\nB\nc\nd\nE\nf\ng\n".to_string(),
+            fuzz_score: None,
         };
 
         assert_eq!(expected, diff);
@@ -1678,7 +1961,8 @@ g
                 .to_string(),
         ];
 
-        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path());
+        let result =
+            maybe_parse_apply_patch_verified(&argv, session_dir.path(), DEFAULT_FUZZ_THRESHOLD);
 
         // Verify the patch contents - as otherwise we may have pulled contents
         // from the wrong file (as we're using relative paths)
@@ -1694,7 +1978,9 @@ g
 "#
                         .to_string(),
                         move_path: None,
+                        mode: None,
                         new_content: "updated session directory content\n".to_string(),
+                        fuzz_score: None,
                     },
                 )]),
                 patch: argv[1].clone(),
@@ -1726,7 +2012,8 @@ g
         let shell_script = format!("cd {worktree_rel} && apply_patch <<'PATCH'\n{patch}\nPATCH");
         let argv = vec!["bash".into(), "-lc".into(), shell_script];
 
-        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path());
+        let result =
+            maybe_parse_apply_patch_verified(&argv, session_dir.path(), DEFAULT_FUZZ_THRESHOLD);
         let action = match result {
             MaybeApplyPatchVerified::Body(action) => action,
             other => panic!("expected verified body, got {other:?}"),
@@ -1769,4 +2056,68 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_fuzzy_match_keeps_file_context_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fuzzy.rs");
+        fs::write(
+            &path,
+            "fn main() {\n    let total = compute(1, 2);\n    println!(\"{total}\");\n}\n",
+        )
+        .unwrap();
+
+        // The context line has a small typo and the wrong indentation.
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+  let total = compute(1,2);
+-    println!("{{total}}");
++    println!("total = {{total}}");"#,
+            path.display()
+        ));
+
+        let argv = vec!["apply_patch".to_string(), patch.clone()];
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, dir.path(), DEFAULT_FUZZ_THRESHOLD)
+        else {
+            panic!("expected verified patch");
+        };
+        match action.changes().get(&path) {
+            Some(ApplyPatchFileChange::Update {
+                fuzz_score: Some(score),
+                ..
+            }) => assert!(*score < 1.0 && *score >= DEFAULT_FUZZ_THRESHOLD),
+            other => panic!("expected fuzzy update, got {other:?}"),
+        }
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "fn main() {\n    let total = compute(1, 2);\n    println!(\"total = {total}\");\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_match_disabled_by_threshold_of_one() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("strict.txt");
+        fs::write(&path, "alpha beta\ngamma\n").unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n alpha betta\n-gamma\n+delta",
+            path.display()
+        ));
+        let argv = vec!["apply_patch".to_string(), patch];
+
+        assert!(matches!(
+            maybe_parse_apply_patch_verified(&argv, dir.path(), 1.0),
+            MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ComputeReplacements(_))
+        ));
+        assert!(matches!(
+            maybe_parse_apply_patch_verified(&argv, dir.path(), DEFAULT_FUZZ_THRESHOLD),
+            MaybeApplyPatchVerified::Body(_)
+        ));
+    }
 }
@@ -7,33 +7,46 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
-//! add_hunk: "*** Add File: " filename LF add_line+
+//! hunk: add_hunk | add_binary_hunk | add_symlink_hunk | delete_hunk | update_hunk
+//! add_hunk: "*** Add File: " filename LF set_mode? add_line+
+//! add_binary_hunk: "*** Add Binary File: " filename LF set_mode? base64_line+
+//! add_symlink_hunk: "*** Add Symlink: " symlink_spec LF
 //! delete_hunk: "*** Delete File: " filename LF
-//! update_hunk: "*** Update File: " filename LF change_move? change?
+//! update_hunk: "*** Update File: " filename LF change_move? set_mode? change?
 //! filename: /(.+)/
+//! symlink_spec: /(.+) -> (.+)/
 //! add_line: "+" /(.+)/ LF -> line
+//! base64_line: "+" /([A-Za-z0-9+\/=]*)/ LF
 //!
+//! set_mode: "*** Set Mode: " /(0?[0-7]{3})/ LF
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
 //! change_context: ("@@" | "@@ " /(.+)/) LF
 //! change_line: ("+" | "-" | " ") /(.+)/ LF
 //! eof_line: "*** End of File" LF
 //!
+//! An update hunk must contain a `change` unless it sets a mode. The base64 lines
+//! of a binary hunk are concatenated before decoding.
+//!
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
 use crate::ApplyPatchArgs;
 use std::path::Path;
 use std::path::PathBuf;
 
+use base64::Engine;
 use thiserror::Error;
 
 const BEGIN_PATCH_MARKER: &str = "*** Begin Patch";
 const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
+const ADD_BINARY_FILE_MARKER: &str = "*** Add Binary File: ";
+const ADD_SYMLINK_MARKER: &str = "*** Add Symlink: ";
+const SYMLINK_TARGET_SEPARATOR: &str = " -> ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
+const SET_MODE_MARKER: &str = "*** Set Mode: ";
 const EOF_MARKER: &str = "*** End of File";
 const CHANGE_CONTEXT_MARKER: &str = "@@ ";
 const EMPTY_CHANGE_CONTEXT_MARKER: &str = "@@";
@@ -61,6 +74,20 @@ pub enum Hunk {
     AddFile {
         path: PathBuf,
         contents: String,
+        /// Unix permission bits from a `*** Set Mode:` line, if any.
+        mode: Option<u32>,
+    },
+    AddBinaryFile {
+        path: PathBuf,
+        /// Decoded from the base64 lines of the hunk.
+        contents: Vec<u8>,
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        path: PathBuf,
+        /// Stored verbatim as the link target, so relative targets resolve
+        /// against the link's directory.
+        target: PathBuf,
     },
     DeleteFile {
         path: PathBuf,
@@ -68,6 +95,7 @@ pub enum Hunk {
     UpdateFile {
         path: PathBuf,
         move_path: Option<PathBuf>,
+        mode: Option<u32>,
 
         /// Chunks should be in order, i.e. the `change_context` of one chunk
         /// should occur later in the file than the previous chunk.
@@ -79,6 +107,8 @@ impl Hunk {
     pub fn resolve_path(&self, cwd: &Path) -> PathBuf {
         match self {
             Hunk::AddFile { path, .. } => cwd.join(path),
+            Hunk::AddBinaryFile { path, .. } => cwd.join(path),
+            Hunk::AddSymlink { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
         }
@@ -247,9 +277,10 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
     let first_line = lines[0].trim();
     if let Some(path) = first_line.strip_prefix(ADD_FILE_MARKER) {
         // Add File
+        let mode = parse_set_mode(&lines[1..], line_number + 1)?;
+        let mut parsed_lines = 1 + usize::from(mode.is_some());
         let mut contents = String::new();
-        let mut parsed_lines = 1;
-        for add_line in &lines[1..] {
+        for add_line in &lines[parsed_lines..] {
             if let Some(line_to_add) = add_line.strip_prefix('+') {
                 contents.push_str(line_to_add);
                 contents.push('\n');
@@ -262,9 +293,54 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             AddFile {
                 path: PathBuf::from(path),
                 contents,
+                mode,
+            },
+            parsed_lines,
+        ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_BINARY_FILE_MARKER) {
+        // Add Binary File
+        let mode = parse_set_mode(&lines[1..], line_number + 1)?;
+        let mut parsed_lines = 1 + usize::from(mode.is_some());
+        let mut encoded = String::new();
+        for add_line in &lines[parsed_lines..] {
+            if let Some(line_to_add) = add_line.strip_prefix('+') {
+                encoded.push_str(line_to_add.trim());
+                parsed_lines += 1;
+            } else {
+                break;
+            }
+        }
+        let contents = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .map_err(|err| InvalidHunkError {
+                message: format!("Invalid base64 contents for binary file '{path}': {err}"),
+                line_number,
+            })?;
+        return Ok((
+            AddBinaryFile {
+                path: PathBuf::from(path),
+                contents,
+                mode,
             },
             parsed_lines,
         ));
+    } else if let Some(spec) = first_line.strip_prefix(ADD_SYMLINK_MARKER) {
+        // Add Symlink
+        let Some((path, target)) = spec.split_once(SYMLINK_TARGET_SEPARATOR) else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Add symlink hunk must have the form '*** Add Symlink: {{path}} -> {{target}}', got: '{first_line}'"
+                ),
+                line_number,
+            });
+        };
+        return Ok((
+            AddSymlink {
+                path: PathBuf::from(path),
+                target: PathBuf::from(target),
+            },
+            1,
+        ));
     } else if let Some(path) = first_line.strip_prefix(DELETE_FILE_MARKER) {
         // Delete File
         return Ok((
@@ -288,6 +364,13 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             parsed_lines += 1;
         }
 
+        // Optional: set mode line
+        let mode = parse_set_mode(remaining_lines, line_number + parsed_lines)?;
+        if mode.is_some() {
+            remaining_lines = &remaining_lines[1..];
+            parsed_lines += 1;
+        }
+
         let mut chunks = Vec::new();
         // NOTE: we need to know to stop once we reach the next special marker header.
         while !remaining_lines.is_empty() {
@@ -312,7 +395,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             remaining_lines = &remaining_lines[chunk_lines..]
         }
 
-        if chunks.is_empty() {
+        if chunks.is_empty() && mode.is_none() {
             return Err(InvalidHunkError {
                 message: format!("Update file hunk for path '{path}' is empty"),
                 line_number,
@@ -323,6 +406,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             UpdateFile {
                 path: PathBuf::from(path),
                 move_path: move_path.map(PathBuf::from),
+                mode,
                 chunks,
             },
             parsed_lines,
@@ -331,12 +415,40 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Add Binary File: {{path}}', '*** Add Symlink: {{path}} -> {{target}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
         ),
         line_number,
     })
 }
 
+/// Parses an optional `*** Set Mode: <octal>` line at the start of `lines`.
+fn parse_set_mode(lines: &[&str], line_number: usize) -> Result<Option<u32>, ParseError> {
+    let Some(value) = lines
+        .first()
+        .and_then(|line| line.trim().strip_prefix(SET_MODE_MARKER))
+    else {
+        return Ok(None);
+    };
+    let value = value.trim();
+    let is_octal =
+        (3..=4).contains(&value.len()) && value.bytes().all(|b| matches!(b, b'0'..=b'7'));
+    match u32::from_str_radix(value, 8) {
+        Ok(mode) if is_octal && mode > 0o777 => Err(InvalidHunkError {
+            message: format!(
+                "Invalid file mode '{value}': setuid, setgid and sticky bits cannot be set"
+            ),
+            line_number,
+        }),
+        Ok(mode) if is_octal => Ok(Some(mode)),
+        _ => Err(InvalidHunkError {
+            message: format!(
+                "Invalid file mode '{value}': expected octal permissions such as 755 or 644"
+            ),
+            line_number,
+        }),
+    }
+}
+
 fn parse_update_file_chunk(
     lines: &[&str],
     line_number: usize,
@@ -486,7 +598,8 @@ fn test_parse_patch() {
         vec![
             AddFile {
                 path: PathBuf::from("path/add.py"),
-                contents: "abc\ndef\n".to_string()
+                contents: "abc\ndef\n".to_string(),
+                mode: None
             },
             DeleteFile {
                 path: PathBuf::from("path/delete.py")
@@ -494,6 +607,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("path/update.py"),
                 move_path: Some(PathBuf::from("path/update2.py")),
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
@@ -521,6 +635,7 @@ fn test_parse_patch() {
             UpdateFile {
                 path: PathBuf::from("file.py"),
                 move_path: None,
+                mode: None,
                 chunks: vec![UpdateFileChunk {
                     change_context: None,
                     old_lines: vec![],
@@ -530,7 +645,8 @@ fn test_parse_patch() {
             },
             AddFile {
                 path: PathBuf::from("other.py"),
-                contents: "content\n".to_string()
+                contents: "content\n".to_string(),
+                mode: None
             }
         ]
     );
@@ -551,6 +667,7 @@ fn test_parse_patch() {
         vec![UpdateFile {
             path: PathBuf::from("file2.py"),
             move_path: None,
+            mode: None,
             chunks: vec![UpdateFileChunk {
                 change_context: None,
                 old_lines: vec!["import foo".to_string()],
@@ -561,6 +678,84 @@ fn test_parse_patch() {
     );
 }
 
+#[test]
+fn test_parse_mode_symlink_and_binary_hunks() {
+    assert_eq!(
+        parse_patch_text(
+            "*** Begin Patch\n\
+             *** Add File: run.sh\n\
+             *** Set Mode: 755\n\
+             +#!/bin/sh\n\
+             *** Add Binary File: logo.bin\n\
+             +AAEC\n\
+             +/w==\n\
+             *** Add Symlink: latest -> releases/v2\n\
+             *** Update File: tool.py\n\
+             *** Set Mode: 0644\n\
+             *** End Patch",
+            ParseMode::Strict
+        )
+        .unwrap()
+        .hunks,
+        vec![
+            AddFile {
+                path: PathBuf::from("run.sh"),
+                contents: "#!/bin/sh\n".to_string(),
+                mode: Some(0o755),
+            },
+            AddBinaryFile {
+                path: PathBuf::from("logo.bin"),
+                contents: vec![0, 1, 2, 255],
+                mode: None,
+            },
+            AddSymlink {
+                path: PathBuf::from("latest"),
+                target: PathBuf::from("releases/v2"),
+            },
+            UpdateFile {
+                path: PathBuf::from("tool.py"),
+                move_path: None,
+                mode: Some(0o644),
+                chunks: Vec::new(),
+            },
+        ]
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Update File: tool.py", "*** Set Mode: 9"], 7),
+        Err(InvalidHunkError {
+            message: "Invalid file mode '9': expected octal permissions such as 755 or 644"
+                .to_string(),
+            line_number: 8,
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Update File: tool.py", "*** Set Mode: 4755"], 7),
+        Err(InvalidHunkError {
+            message: "Invalid file mode '4755': setuid, setgid and sticky bits cannot be set"
+                .to_string(),
+            line_number: 8,
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Binary File: logo.bin", "+not base64!"], 3),
+        Err(InvalidHunkError {
+            message:
+                "Invalid base64 contents for binary file 'logo.bin': Invalid symbol 32, offset 3."
+                    .to_string(),
+            line_number: 3,
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Symlink: latest"], 5),
+        Err(InvalidHunkError {
+            message: "Add symlink hunk must have the form '*** Add Symlink: {path} -> {target}', \
+                      got: '*** Add Symlink: latest'"
+                .to_string(),
+            line_number: 5,
+        })
+    );
+}
+
 #[test]
 fn test_parse_patch_lenient() {
     let patch_text = r#"*** Begin Patch
@@ -571,6 +766,7 @@ fn test_parse_patch_lenient() {
     let expected_patch = vec![UpdateFile {
         path: PathBuf::from("file2.py"),
         move_path: None,
+        mode: None,
         chunks: vec![UpdateFileChunk {
             change_context: None,
             old_lines: vec!["import foo".to_string()],
@@ -653,7 +849,8 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', \
+            '*** Add Symlink: {path} -> {target}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
             line_number: 234
         })
     );
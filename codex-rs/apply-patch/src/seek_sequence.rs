@@ -109,8 +109,302 @@ pub(crate) fn seek_sequence(
     None
 }
 
+/// A region of the file located by [`fuzzy_seek_sequence`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FuzzyMatch {
+    /// Index of the first line of the matched region.
+    pub start: usize,
+    /// Number of lines in the matched region. This differs from the pattern
+    /// length by one when a context line was dropped or duplicated.
+    pub len: usize,
+    /// Similarity of the region to the pattern in `0.0..=1.0`.
+    pub score: f32,
+    /// For each pattern line, the index of the file line it lines up with, if
+    /// the two are close enough to be considered the same line.
+    pub alignment: Vec<Option<usize>>,
+}
+
+/// Patterns longer than this are never matched fuzzily, which keeps the
+/// alignment below cheap.
+const MAX_FUZZY_PATTERN_LINES: usize = 64;
+
+/// Cost of a pattern line with no counterpart in the file, or vice versa.
+/// Kept below the cost of a full mismatch so a single dropped or duplicated
+/// context line does not sink an otherwise exact match.
+const FUZZY_GAP_COST: f32 = 0.5;
+
+/// Added to the upper bound of a region's score before comparing it, so
+/// rounding never skips a region whose exact score would qualify.
+const FUZZY_BOUND_SLACK: f32 = 1e-4;
+
+/// Minimum similarity for two lines to be aligned with each other.
+const FUZZY_ALIGN_MIN_SIMILARITY: f32 = 0.5;
+
+/// Last-resort search used when [`seek_sequence`] finds nothing: scores every
+/// region at or after `start` whose length is within one line of the
+/// pattern by a line-level edit distance, where substituting a line costs
+/// one minus its similarity with surrounding whitespace ignored. Returns the
+/// best region if it scores at least `threshold` and no other region ties
+/// with it.
+pub(crate) fn fuzzy_seek_sequence(
+    lines: &[String],
+    pattern: &[String],
+    start: usize,
+    threshold: f32,
+) -> Option<FuzzyMatch> {
+    let m = pattern.len();
+    if m == 0 || m > MAX_FUZZY_PATTERN_LINES || start >= lines.len() || threshold > 1.0 {
+        return None;
+    }
+
+    let searchable = &lines[start..];
+    // Diffing every line against every pattern line is what makes fuzzy
+    // matching expensive, so regions are first scored with a cheap upper
+    // bound on each line similarity and only the regions that could still
+    // reach `threshold` (and the best score so far) are diffed for real.
+    let pattern_sketches: Vec<LineSketch> =
+        pattern.iter().map(|pat| LineSketch::new(pat)).collect();
+    // bound[i * m + j] bounds the similarity of searchable[i] and pattern[j].
+    let bound: Vec<f32> = searchable
+        .iter()
+        .flat_map(|line| {
+            let line = LineSketch::new(line);
+            pattern_sketches
+                .iter()
+                .map(move |pat| line.similarity_bound(pat))
+        })
+        .collect();
+    // similarity[i * m + j] compares searchable[i] with pattern[j]; rows are
+    // filled in the first time a region covering them is diffed.
+    let mut similarity: Vec<f32> = vec![f32::NAN; searchable.len() * m];
+
+    let mut best: Option<FuzzyMatch> = None;
+    let mut tied = false;
+    for offset in 0..searchable.len() {
+        for len in m.saturating_sub(1).max(1)..=m + 1 {
+            if offset + len > searchable.len() {
+                break;
+            }
+            let max_len = m.max(len) as f32;
+            let (min_distance, _) = align(|i, j| bound[(offset + i) * m + j], m, offset, len);
+            // The slack absorbs float rounding between the two alignments.
+            let max_score = 1.0 - min_distance / max_len + FUZZY_BOUND_SLACK;
+            if max_score < threshold || best.as_ref().is_some_and(|best| max_score < best.score) {
+                continue;
+            }
+            for row in offset..offset + len {
+                if similarity[row * m].is_nan() {
+                    for (j, pat) in pattern.iter().enumerate() {
+                        similarity[row * m + j] = line_similarity(&searchable[row], pat);
+                    }
+                }
+            }
+            let (distance, alignment) =
+                align(|i, j| similarity[(offset + i) * m + j], m, offset, len);
+            let score = 1.0 - distance / max_len;
+            if score < threshold {
+                continue;
+            }
+            match &best {
+                Some(current) if score < current.score => {}
+                Some(current) if score == current.score => {
+                    // Ties between overlapping regions are the same match
+                    // seen with one line more or less; only distinct
+                    // regions make the result ambiguous.
+                    if offset + start >= current.start + current.len {
+                        tied = true;
+                    }
+                }
+                _ => {
+                    tied = false;
+                    best = Some(FuzzyMatch {
+                        start: start + offset,
+                        len,
+                        score,
+                        alignment: alignment
+                            .into_iter()
+                            .map(|idx| idx.map(|idx| idx + start))
+                            .collect(),
+                    });
+                }
+            }
+        }
+    }
+    if tied { None } else { best }
+}
+
+/// Edit distance between the pattern and `searchable[offset..offset + len]`,
+/// along with the file line each pattern line was aligned with. `sim(i, j)`
+/// is the similarity of the window's line `i` and pattern line `j`.
+fn align(
+    sim: impl Fn(usize, usize) -> f32,
+    m: usize,
+    offset: usize,
+    len: usize,
+) -> (f32, Vec<Option<usize>>) {
+    // cost[i][j] aligns the first i window lines with the first j pattern lines.
+    let width = m + 1;
+    let mut cost = vec![0.0_f32; (len + 1) * width];
+    for i in 0..=len {
+        for j in 0..=m {
+            cost[i * width + j] = if i == 0 {
+                j as f32 * FUZZY_GAP_COST
+            } else if j == 0 {
+                i as f32 * FUZZY_GAP_COST
+            } else {
+                let substitute = cost[(i - 1) * width + j - 1] + (1.0 - sim(i - 1, j - 1));
+                let skip_line = cost[(i - 1) * width + j] + FUZZY_GAP_COST;
+                let skip_pattern = cost[i * width + j - 1] + FUZZY_GAP_COST;
+                substitute.min(skip_line).min(skip_pattern)
+            };
+        }
+    }
+
+    let mut alignment = vec![None; m];
+    let (mut i, mut j) = (len, m);
+    while i > 0 && j > 0 {
+        let here = cost[i * width + j];
+        let similarity = sim(i - 1, j - 1);
+        if here == cost[(i - 1) * width + j - 1] + (1.0 - similarity) {
+            if similarity >= FUZZY_ALIGN_MIN_SIMILARITY {
+                alignment[j - 1] = Some(offset + i - 1);
+            }
+            i -= 1;
+            j -= 1;
+        } else if here == cost[(i - 1) * width + j] + FUZZY_GAP_COST {
+            i -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    (cost[len * width + m], alignment)
+}
+
+/// Buckets in a [`LineSketch`].
+const SKETCH_BUCKETS: usize = 32;
+
+/// Lines are cut to this many characters before they are compared, so long
+/// (e.g. minified) lines cannot make a single diff expensive.
+const MAX_COMPARED_LINE_CHARS: usize = 512;
+
+/// Most windows [`closest_sequence`] diffs against the pattern. The ones
+/// with the highest similarity bound go first.
+const MAX_CLOSEST_WINDOWS: usize = 256;
+
+/// Character counts of a trimmed line, hashed into [`SKETCH_BUCKETS`]
+/// buckets. Equal characters always land in the same bucket, so two sketches
+/// bound the number of characters a diff of the lines can match.
+struct LineSketch {
+    counts: [u32; SKETCH_BUCKETS],
+    len: u32,
+}
+
+impl LineSketch {
+    fn new(line: &str) -> Self {
+        let mut counts = [0; SKETCH_BUCKETS];
+        let mut len = 0;
+        for ch in compared_part(line).chars() {
+            counts[ch as usize % SKETCH_BUCKETS] += 1;
+            len += 1;
+        }
+        Self { counts, len }
+    }
+
+    /// Upper bound on [`line_similarity`] of the two lines.
+    fn similarity_bound(&self, other: &LineSketch) -> f32 {
+        let total = self.len + other.len;
+        if total == 0 {
+            return 1.0;
+        }
+        let shared: u32 = self
+            .counts
+            .iter()
+            .zip(&other.counts)
+            .map(|(a, b)| *a.min(b))
+            .sum();
+        ((2 * shared) as f32 / total as f32).min(1.0)
+    }
+}
+
+/// Finds the window of `lines` that most resembles `pattern`, so a failed
+/// `seek_sequence` can point at the region the patch most likely meant.
+/// Lines are compared with surrounding whitespace trimmed. Returns the start
+/// index of the best window together with its mean per-line similarity in
+/// `0.0..=1.0`, or `None` when `pattern` is empty or longer than `lines`.
+///
+/// Like [`fuzzy_seek_sequence`], only the first [`MAX_FUZZY_PATTERN_LINES`]
+/// pattern lines are compared and windows are ranked by a cheap upper bound
+/// first; at most [`MAX_CLOSEST_WINDOWS`] of them are diffed.
+pub(crate) fn closest_sequence(lines: &[String], pattern: &[String]) -> Option<(usize, f32)> {
+    if pattern.is_empty() || pattern.len() > lines.len() {
+        return None;
+    }
+    let windows = lines.len() - pattern.len() + 1;
+    let pattern = &pattern[..pattern.len().min(MAX_FUZZY_PATTERN_LINES)];
+    let m = pattern.len() as f32;
+
+    let pattern_sketches: Vec<LineSketch> =
+        pattern.iter().map(|pat| LineSketch::new(pat)).collect();
+    let line_sketches: Vec<LineSketch> = lines[..windows + pattern.len() - 1]
+        .iter()
+        .map(|line| LineSketch::new(line))
+        .collect();
+    let mut candidates: Vec<(usize, f32)> = (0..windows)
+        .map(|i| {
+            let bound: f32 = pattern_sketches
+                .iter()
+                .enumerate()
+                .map(|(p_idx, pat)| line_sketches[i + p_idx].similarity_bound(pat))
+                .sum();
+            (i, bound / m)
+        })
+        .collect();
+    candidates.sort_by(|(a_idx, a_bound), (b_idx, b_bound)| {
+        b_bound.total_cmp(a_bound).then(a_idx.cmp(b_idx))
+    });
+
+    let mut best: Option<(usize, f32)> = None;
+    for (i, bound) in candidates.into_iter().take(MAX_CLOSEST_WINDOWS) {
+        if best.is_some_and(|(_, best_score)| bound + FUZZY_BOUND_SLACK < best_score) {
+            break;
+        }
+        let total: f32 = pattern
+            .iter()
+            .enumerate()
+            .map(|(p_idx, pat)| line_similarity(&lines[i + p_idx], pat))
+            .sum();
+        let score = total / m;
+        // Among equally close windows, the earliest one wins.
+        let better = best.is_none_or(|(best_idx, best_score)| {
+            score > best_score || (score == best_score && i < best_idx)
+        });
+        if better {
+            best = Some((i, score));
+        }
+    }
+    best
+}
+
+fn line_similarity(line: &str, pattern: &str) -> f32 {
+    similar::TextDiff::from_chars(compared_part(line), compared_part(pattern)).ratio()
+}
+
+/// The part of `line` that is compared: trimmed and cut to
+/// [`MAX_COMPARED_LINE_CHARS`] characters.
+fn compared_part(line: &str) -> &str {
+    let line = line.trim();
+    match line.char_indices().nth(MAX_COMPARED_LINE_CHARS) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::LineSketch;
+    use super::closest_sequence;
+    use super::fuzzy_seek_sequence;
+    use super::line_similarity;
     use super::seek_sequence;
     use std::string::ToString;
 
@@ -148,4 +442,127 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_closest_sequence_prefers_most_similar_window() {
+        let lines = to_vec(&["fn main() {", "    let x = 1;", "    println!(x);", "}"]);
+        let pattern = to_vec(&["let x = 2;", "println!(x);"]);
+        let (index, score) = closest_sequence(&lines, &pattern).expect("window");
+        assert_eq!(index, 1);
+        assert!(score > 0.9, "unexpected score {score}");
+    }
+
+    #[test]
+    fn test_fuzzy_match_tolerates_small_edits_and_indentation() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "        let total = compute(1, 2);",
+            "        println!(\"{total}\");",
+            "}",
+        ]);
+        let pattern = to_vec(&[
+            "    let total = compute(1,2);",
+            "    println!(\"{total}\");",
+        ]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, 0.9).expect("fuzzy match");
+        assert_eq!((found.start, found.len), (1, 2));
+        assert_eq!(found.alignment, vec![Some(1), Some(2)]);
+        assert!(found.score > 0.9, "unexpected score {}", found.score);
+    }
+
+    #[test]
+    fn test_fuzzy_match_tolerates_dropped_and_duplicated_lines() {
+        let lines = to_vec(&[
+            "use std::fs;",
+            "let config = load();",
+            "if config.verbose {",
+            "    init_logging();",
+            "}",
+            "run(config);",
+        ]);
+
+        let dropped = to_vec(&[
+            "let config = load();",
+            "if config.verbose {",
+            "}",
+            "run(config);",
+        ]);
+        let found = fuzzy_seek_sequence(&lines, &dropped, 0, 0.85).expect("dropped line");
+        assert_eq!((found.start, found.len), (1, 5));
+        assert_eq!(found.alignment, vec![Some(1), Some(2), Some(4), Some(5)]);
+
+        let duplicated = to_vec(&[
+            "let config = load();",
+            "if config.verbose {",
+            "if config.verbose {",
+            "    init_logging();",
+            "}",
+        ]);
+        let found = fuzzy_seek_sequence(&lines, &duplicated, 0, 0.85).expect("duplicated line");
+        assert_eq!((found.start, found.len), (1, 4));
+    }
+
+    #[test]
+    fn test_fuzzy_match_respects_threshold_and_ambiguity() {
+        let lines = to_vec(&["alpha", "beta", "gamma"]);
+        let pattern = to_vec(&["completely different"]);
+        assert_eq!(fuzzy_seek_sequence(&lines, &pattern, 0, 0.8), None);
+
+        let repeated = to_vec(&["x = 1", "y = 2", "x = 1", "y = 2"]);
+        let pattern = to_vec(&["x = 3", "y = 2"]);
+        assert_eq!(fuzzy_seek_sequence(&repeated, &pattern, 0, 0.5), None);
+    }
+
+    #[test]
+    fn test_sketch_bound_never_underestimates_similarity() {
+        let samples = [
+            "",
+            "    let x = 1;",
+            "let y = 2;",
+            "println!(\"{x}\");",
+            "fn main() {",
+            "}",
+            "上下文窗口",
+            "😀 emoji line",
+        ];
+        for a in samples {
+            for b in samples {
+                let bound = LineSketch::new(a).similarity_bound(&LineSketch::new(b));
+                assert!(
+                    bound >= line_similarity(a, b),
+                    "bound {bound} below similarity of {a:?} and {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_closest_sequence_stays_bounded_on_large_files_and_long_lines() {
+        let long_line = "x".repeat(100_000);
+        let mut lines: Vec<String> = (0..20_000).map(|i| format!("filler_{i}();")).collect();
+        for line in lines.iter_mut().step_by(100) {
+            *line = long_line.clone();
+        }
+        lines[15_000] = "if config.verbose {".to_string();
+        lines[15_001] = "    init_logging(level);".to_string();
+        lines[15_002] = "}".to_string();
+        let mut pattern = to_vec(&["if config.verbose {", "    init_logging();", "}"]);
+        pattern.extend((0..100).map(|i| format!("trailing_{i}();")));
+
+        let (index, _score) = closest_sequence(&lines, &pattern).expect("window");
+        assert_eq!(index, 15_000);
+    }
+
+    #[test]
+    fn test_fuzzy_match_in_large_file_finds_the_edited_region() {
+        let mut lines: Vec<String> = (0..5_000).map(|i| format!("filler_{i}();")).collect();
+        lines[4_000] = "if config.verbose {".to_string();
+        lines[4_001] = "    init_logging(level);".to_string();
+        lines[4_002] = "}".to_string();
+        let pattern = to_vec(&["if config.verbose {", "    init_logging();", "}"]);
+
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, 0.85).expect("fuzzy match");
+        assert_eq!((found.start, found.len), (4_000, 3));
+    }
 }
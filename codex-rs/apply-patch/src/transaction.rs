@@ -0,0 +1,422 @@
+//! All-or-nothing application of a parsed patch.
+//!
+//! Hunks are first applied to an in-memory overlay of the filesystem so every
+//! one of them can be verified before anything is written. Once the whole
+//! patch checks out, new contents (and new symlinks) are staged in temp files
+//! next to their targets and renamed into place; if any step of that fails, the renames
+//! that already happened are undone.
+
+use std::collections::HashMap;
+use std::fs;
+use std::fs::OpenOptions;
+use std::fs::Permissions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+
+/// What a touched path should hold once the transaction commits.
+#[derive(Debug)]
+enum Entry {
+    File {
+        contents: Vec<u8>,
+        /// Unix permission bits to apply; `None` keeps those of the file
+        /// being replaced.
+        mode: Option<u32>,
+    },
+    Symlink {
+        target: PathBuf,
+    },
+}
+
+/// Pending filesystem changes, keyed by path, in the order they were first
+/// touched by the patch.
+#[derive(Debug, Default)]
+pub(crate) struct PatchTransaction {
+    /// Final state of each touched path: `Some(entry)` to write, `None` to
+    /// delete.
+    changes: Vec<(PathBuf, Option<Entry>)>,
+    index: HashMap<PathBuf, usize>,
+}
+
+impl PatchTransaction {
+    /// Reads `path` as it would look after the changes staged so far.
+    pub(crate) fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.pending(path) {
+            Some(Some(Entry::File { contents, .. })) => String::from_utf8(contents.clone())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Some(Some(Entry::Symlink { .. })) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "path is a symlink added by this patch",
+            )),
+            Some(None) => Err(io::Error::from(io::ErrorKind::NotFound)),
+            None => fs::read_to_string(path),
+        }
+    }
+
+    /// Unix permission bits `path` would have after the changes staged so
+    /// far, when known.
+    pub(crate) fn mode(&self, path: &Path) -> Option<u32> {
+        match self.pending(path) {
+            Some(Some(Entry::File { mode, .. })) => *mode,
+            Some(_) => None,
+            None => file_mode(path),
+        }
+    }
+
+    /// Returns `true` when `path` would be a file that `delete` can remove.
+    pub(crate) fn is_deletable(&self, path: &Path) -> bool {
+        match self.pending(path) {
+            Some(pending) => pending.is_some(),
+            None => fs::symlink_metadata(path).is_ok_and(|metadata| !metadata.is_dir()),
+        }
+    }
+
+    /// Stages `contents` for `path`. A `mode` of `None` keeps the mode
+    /// already staged for `path`, if any.
+    pub(crate) fn write(&mut self, path: &Path, contents: Vec<u8>, mode: Option<u32>) {
+        let mode = mode.or_else(|| match self.pending(path) {
+            Some(Some(Entry::File { mode, .. })) => *mode,
+            _ => None,
+        });
+        self.set(path, Some(Entry::File { contents, mode }));
+    }
+
+    pub(crate) fn symlink(&mut self, path: &Path, target: &Path) {
+        self.set(
+            path,
+            Some(Entry::Symlink {
+                target: target.to_path_buf(),
+            }),
+        );
+    }
+
+    pub(crate) fn delete(&mut self, path: &Path) {
+        self.set(path, None);
+    }
+
+    fn pending(&self, path: &Path) -> Option<&Option<Entry>> {
+        self.index.get(path).map(|idx| &self.changes[*idx].1)
+    }
+
+    fn set(&mut self, path: &Path, entry: Option<Entry>) {
+        match self.index.get(path) {
+            Some(idx) => self.changes[*idx].1 = entry,
+            None => {
+                self.index.insert(path.to_path_buf(), self.changes.len());
+                self.changes.push((path.to_path_buf(), entry));
+            }
+        }
+    }
+
+    /// Writes every pending change to disk, or none of them.
+    pub(crate) fn commit(self) -> anyhow::Result<()> {
+        let mut commit = Commit::default();
+        let result = commit
+            .stage(self.changes)
+            .and_then(|()| commit.swap_into_place());
+        match result {
+            Ok(()) => {
+                commit.discard_backups();
+                Ok(())
+            }
+            Err(err) => {
+                commit.roll_back();
+                Err(err)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    target: PathBuf,
+    /// Temp file holding the new contents, or `None` for a deletion.
+    staged: Option<PathBuf>,
+    /// Where the previous file at `target` was moved during the swap.
+    backup: Option<PathBuf>,
+    /// Whether `staged` has been renamed onto `target`.
+    installed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Commit {
+    steps: Vec<Step>,
+    created_dirs: Vec<PathBuf>,
+}
+
+impl Commit {
+    fn stage(&mut self, changes: Vec<(PathBuf, Option<Entry>)>) -> anyhow::Result<()> {
+        for (idx, (path, entry)) in changes.into_iter().enumerate() {
+            let (contents, mode) = match entry {
+                Some(Entry::File { contents, mode }) => (contents, mode),
+                Some(Entry::Symlink { target }) => {
+                    self.stage_symlink(idx, path, &target)?;
+                    continue;
+                }
+                None => {
+                    self.steps.push(Step {
+                        target: path,
+                        staged: None,
+                        backup: None,
+                        installed: false,
+                    });
+                    continue;
+                }
+            };
+
+            // Write through symlinks rather than replacing them, as a plain
+            // write would.
+            let target = match fs::symlink_metadata(&path) {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    fs::canonicalize(&path).unwrap_or(path)
+                }
+                _ => path,
+            };
+            self.create_parent_dirs(&target)?;
+            let existing = fs::metadata(&target).ok();
+            if existing.is_some() {
+                // Renaming over a file only needs write access to the
+                // directory, so check the file itself is writable first.
+                OpenOptions::new()
+                    .write(true)
+                    .open(&target)
+                    .with_context(|| format!("Failed to write file {}", target.display()))?;
+            }
+
+            let staged = sibling_path(&target, idx, "tmp");
+            let permissions = staged_permissions(mode, existing.map(|m| m.permissions()));
+            let result = write_staged(&staged, &contents, permissions);
+            let context = format!("Failed to write file {}", target.display());
+            // Record the step before checking the result so a partially
+            // written temp file is cleaned up on rollback.
+            self.steps.push(Step {
+                target,
+                staged: Some(staged),
+                backup: None,
+                installed: false,
+            });
+            result.context(context)?;
+        }
+        Ok(())
+    }
+
+    /// Symlinks replace whatever is at `path` rather than writing through it.
+    fn stage_symlink(&mut self, idx: usize, path: PathBuf, target: &Path) -> anyhow::Result<()> {
+        self.create_parent_dirs(&path)?;
+        let staged = sibling_path(&path, idx, "tmp");
+        let result = create_symlink(target, &staged);
+        let context = format!("Failed to create symlink {}", path.display());
+        self.steps.push(Step {
+            target: path,
+            staged: Some(staged),
+            backup: None,
+            installed: false,
+        });
+        result.context(context)
+    }
+
+    fn create_parent_dirs(&mut self, path: &Path) -> anyhow::Result<()> {
+        let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        else {
+            return Ok(());
+        };
+        let missing: Vec<PathBuf> = parent
+            .ancestors()
+            .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        self.created_dirs.extend(missing);
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create parent directories for {}", path.display()))
+    }
+
+    fn swap_into_place(&mut self) -> anyhow::Result<()> {
+        for (idx, step) in self.steps.iter_mut().enumerate() {
+            if fs::symlink_metadata(&step.target).is_ok() {
+                let backup = sibling_path(&step.target, idx, "orig");
+                fs::rename(&step.target, &backup).with_context(|| match step.staged {
+                    Some(_) => format!("Failed to write file {}", step.target.display()),
+                    None => format!("Failed to delete file {}", step.target.display()),
+                })?;
+                step.backup = Some(backup);
+            }
+            if let Some(staged) = &step.staged {
+                fs::rename(staged, &step.target)
+                    .with_context(|| format!("Failed to write file {}", step.target.display()))?;
+                step.installed = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn discard_backups(&self) {
+        for backup in self.steps.iter().filter_map(|step| step.backup.as_ref()) {
+            let _ = fs::remove_file(backup);
+        }
+    }
+
+    /// Best-effort restore of the state before `commit` started.
+    fn roll_back(&mut self) {
+        for step in self.steps.iter().rev() {
+            if step.installed {
+                let _ = fs::remove_file(&step.target);
+            } else if let Some(staged) = &step.staged {
+                let _ = fs::remove_file(staged);
+            }
+            if let Some(backup) = &step.backup {
+                let _ = fs::rename(backup, &step.target);
+            }
+        }
+        // Remove the deepest directories first so their parents are empty.
+        self.created_dirs
+            .sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in &self.created_dirs {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+fn write_staged(path: &Path, contents: &[u8], permissions: Option<Permissions>) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents)?;
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn staged_permissions(mode: Option<u32>, existing: Option<Permissions>) -> Option<Permissions> {
+    use std::os::unix::fs::PermissionsExt;
+    mode.map(Permissions::from_mode).or(existing)
+}
+
+/// Modes are a Unix concept; elsewhere the existing permissions are kept.
+#[cfg(not(unix))]
+fn staged_permissions(_mode: Option<u32>, existing: Option<Permissions>) -> Option<Permissions> {
+    existing
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+/// Hidden path in the same directory as `target`, so renames stay on one
+/// filesystem.
+fn sibling_path(target: &Path, idx: usize, suffix: &str) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(
+        ".{name}.apply_patch.{}.{idx}.{suffix}",
+        std::process::id()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn failed_commit_restores_original_files() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let existing = dir.path().join("existing.txt");
+        let blocker = dir.path().join("blocker");
+        fs::write(&existing, "before\n")?;
+        fs::write(&blocker, "not a directory\n")?;
+
+        let mut transaction = PatchTransaction::default();
+        transaction.write(&existing, b"after\n".to_vec(), None);
+        transaction.write(
+            &dir.path().join("new/dir/file.txt"),
+            b"new\n".to_vec(),
+            None,
+        );
+        // The parent of this path is a regular file, so staging it fails.
+        transaction.write(&blocker.join("child.txt"), b"child\n".to_vec(), None);
+
+        assert!(transaction.commit().is_err());
+        assert_eq!(fs::read_to_string(&existing)?, "before\n");
+        let mut remaining: Vec<String> = fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+            .collect::<io::Result<_>>()?;
+        remaining.sort();
+        assert_eq!(remaining, vec!["blocker", "existing.txt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_see_staged_changes() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("file.txt");
+        fs::write(&path, "on disk\n")?;
+
+        let mut transaction = PatchTransaction::default();
+        assert_eq!(transaction.read_to_string(&path)?, "on disk\n");
+        transaction.write(&path, b"staged\n".to_vec(), None);
+        assert_eq!(transaction.read_to_string(&path)?, "staged\n");
+        transaction.delete(&path);
+        assert!(transaction.read_to_string(&path).is_err());
+        assert!(!transaction.is_deletable(&path));
+        assert!(!transaction.is_deletable(dir.path()));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn commits_modes_and_symlinks() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir()?;
+        let script = dir.path().join("run.sh");
+        let link = dir.path().join("links/latest");
+        fs::write(&script, "echo old\n")?;
+
+        let mut transaction = PatchTransaction::default();
+        transaction.write(&script, b"echo new\n".to_vec(), Some(0o755));
+        // A later write without a mode keeps the staged one.
+        transaction.write(&script, b"echo newer\n".to_vec(), None);
+        transaction.symlink(&link, Path::new("../run.sh"));
+        assert_eq!(transaction.mode(&script), Some(0o755));
+        transaction.commit()?;
+
+        assert_eq!(fs::read_to_string(&script)?, "echo newer\n");
+        assert_eq!(fs::metadata(&script)?.permissions().mode() & 0o7777, 0o755);
+        assert_eq!(fs::read_link(&link)?, PathBuf::from("../run.sh"));
+        assert_eq!(fs::read_to_string(&link)?, "echo newer\n");
+        Ok(())
+    }
+}
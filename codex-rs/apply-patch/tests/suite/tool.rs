@@ -104,7 +104,7 @@ fn test_apply_patch_cli_reports_missing_context() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Update File: modify.txt\n@@\n-missing\n+changed\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Failed to find expected lines in modify.txt:\nmissing\n");
+        .stderr("Failed to apply hunk 1 of 1 (Update File: modify.txt); no files were modified.\nFailed to find expected lines in modify.txt:\nmissing\n");
     assert_eq!(fs::read_to_string(&target_path)?, "line1\nline2\n");
 
     Ok(())
@@ -118,7 +118,7 @@ fn test_apply_patch_cli_rejects_missing_file_delete() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Delete File: missing.txt\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Failed to delete file missing.txt\n");
+        .stderr("Failed to apply hunk 1 of 1 (Delete File: missing.txt); no files were modified.\nFailed to delete file missing.txt\n");
 
     Ok(())
 }
@@ -145,7 +145,7 @@ fn test_apply_patch_cli_requires_existing_file_for_update() -> anyhow::Result<()
         .assert()
         .failure()
         .stderr(
-            "Failed to read file to update missing.txt: No such file or directory (os error 2)\n",
+            "Failed to apply hunk 1 of 1 (Update File: missing.txt); no files were modified.\nFailed to read file to update missing.txt: No such file or directory (os error 2)\n",
         );
 
     Ok(())
@@ -201,7 +201,7 @@ fn test_apply_patch_cli_delete_directory_fails() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Delete File: dir\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Failed to delete file dir\n");
+        .stderr("Failed to apply hunk 1 of 1 (Delete File: dir); no files were modified.\nFailed to delete file dir\n");
 
     Ok(())
 }
@@ -214,7 +214,7 @@ fn test_apply_patch_cli_rejects_invalid_hunk_header() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Frobnicate File: foo\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'\n");
+        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', '*** Add Symlink: {path} -> {target}', '*** Delete File: {path}', '*** Update File: {path}'\n");
 
     Ok(())
 }
@@ -240,18 +240,78 @@ fn test_apply_patch_cli_updates_file_appends_trailing_newline() -> anyhow::Resul
 }
 
 #[test]
-fn test_apply_patch_cli_failure_after_partial_success_leaves_changes() -> anyhow::Result<()> {
+fn test_apply_patch_cli_failure_after_partial_success_leaves_no_changes() -> anyhow::Result<()> {
     let tmp = tempdir()?;
     let new_file = tmp.path().join("created.txt");
+    let existing = tmp.path().join("existing.txt");
+    fs::write(&existing, "before\n")?;
 
     apply_patch_command(tmp.path())?
-        .arg("*** Begin Patch\n*** Add File: created.txt\n+hello\n*** Update File: missing.txt\n@@\n-old\n+new\n*** End Patch")
+        .arg("*** Begin Patch\n*** Add File: created.txt\n+hello\n*** Update File: existing.txt\n@@\n-before\n+after\n*** Update File: missing.txt\n@@\n-old\n+new\n*** End Patch")
         .assert()
         .failure()
         .stdout("")
-        .stderr("Failed to read file to update missing.txt: No such file or directory (os error 2)\n");
+        .stderr("Failed to apply hunk 3 of 3 (Update File: missing.txt); no files were modified.\nFailed to read file to update missing.txt: No such file or directory (os error 2)\n");
 
-    assert_eq!(fs::read_to_string(&new_file)?, "hello\n");
+    assert!(!new_file.exists());
+    assert_eq!(fs::read_to_string(&existing)?, "before\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_cli_reports_closest_match() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let target_path = tmp.path().join("modify.txt");
+    fs::write(&target_path, "fn main() {\n    let total = 1;\n}\n")?;
+
+    apply_patch_command(tmp.path())?
+        .env("CODEX_APPLY_PATCH_FUZZ_THRESHOLD", "1")
+        .arg("*** Begin Patch\n*** Update File: modify.txt\n@@\n-    let totl = 1;\n+    let total = 2;\n*** End Patch")
+        .assert()
+        .failure()
+        .stderr("Failed to apply hunk 1 of 1 (Update File: modify.txt); no files were modified.\nFailed to find expected lines in modify.txt:\n    let totl = 1;\nClosest match at line 2 (96% similar):\n    let total = 1;\n");
+    assert_eq!(
+        fs::read_to_string(&target_path)?,
+        "fn main() {\n    let total = 1;\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_cli_applies_near_miss_with_fuzz() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let target_path = tmp.path().join("modify.txt");
+    fs::write(&target_path, "fn main() {\n    let total = 1;\n}\n")?;
+
+    run_apply_patch_in_dir(
+        tmp.path(),
+        "*** Begin Patch\n*** Update File: modify.txt\n@@\n fn main(){\n-    let totl = 1;\n+    let total = 2;\n*** End Patch",
+    )?
+    .success()
+    .stdout("Success. Updated the following files:\nM modify.txt\n");
+    assert_eq!(
+        fs::read_to_string(&target_path)?,
+        "fn main() {\n    let total = 2;\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_apply_patch_cli_chained_hunks_see_staged_contents() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+
+    run_apply_patch_in_dir(
+        tmp.path(),
+        "*** Begin Patch\n*** Add File: nested/new.txt\n+one\n*** Update File: nested/new.txt\n*** Move to: moved.txt\n@@\n-one\n+two\n*** End Patch",
+    )?
+    .success()
+    .stdout("Success. Updated the following files:\nA nested/new.txt\nM moved.txt\n");
+
+    assert!(!tmp.path().join("nested/new.txt").exists());
+    assert_eq!(fs::read_to_string(tmp.path().join("moved.txt"))?, "two\n");
 
     Ok(())
 }
@@ -8,8 +8,10 @@ use std::path::PathBuf;
 use codex_common::CliConfigOverrides;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::exec::SandboxType as CoreSandboxType;
 use codex_core::exec_env::create_env;
 use codex_core::landlock::spawn_command_under_linux_sandbox;
+use codex_core::network_proxy::NetworkProxy;
 #[cfg(target_os = "macos")]
 use codex_core::seatbelt::spawn_command_under_seatbelt;
 use codex_core::spawn::StdioPolicy;
@@ -210,6 +212,21 @@ async fn run_command_under_sandbox(
     #[cfg(not(target_os = "macos"))]
     let _ = log_denials;
 
+    // Lets the command reach the policy's `network_allowlist`; the proxy runs
+    // until the command exits.
+    let network_proxy = match sandbox_type {
+        #[cfg(target_os = "macos")]
+        SandboxType::Seatbelt => {
+            NetworkProxy::start_for_policy(&config.sandbox_policy, CoreSandboxType::MacosSeatbelt)
+                .await
+        }
+        SandboxType::Landlock => {
+            NetworkProxy::start_for_policy(&config.sandbox_policy, CoreSandboxType::LinuxSeccomp)
+                .await
+        }
+        SandboxType::Windows => None,
+    };
+
     let mut child = match sandbox_type {
         #[cfg(target_os = "macos")]
         SandboxType::Seatbelt => {
@@ -220,6 +237,7 @@ async fn run_command_under_sandbox(
                 sandbox_policy_cwd.as_path(),
                 stdio_policy,
                 env,
+                network_proxy.as_ref().map(NetworkProxy::endpoint),
             )
             .await?
         }
@@ -236,6 +254,7 @@ async fn run_command_under_sandbox(
                 sandbox_policy_cwd.as_path(),
                 stdio_policy,
                 env,
+                network_proxy.as_ref().map(NetworkProxy::endpoint),
             )
             .await?
         }
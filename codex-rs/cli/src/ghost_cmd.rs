@@ -0,0 +1,428 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::Local;
+use codex_git::GHOST_REF_PREFIX;
+use codex_git::GhostRef;
+use codex_git::GhostRetention;
+use codex_git::ghost_ref_disk_usage;
+use codex_git::ghost_refs_disk_usage;
+use codex_git::list_ghost_refs;
+use codex_git::prune_ghost_refs;
+
+/// Inspect and prune the ghost snapshots Codex records for undo.
+///
+/// Snapshots are kept under `refs/codex/ghost/<conversation>/<n>` so that
+/// `git gc` does not delete them.
+///
+/// Subcommands:
+/// - `list`  — list recorded snapshots (with `--json`)
+/// - `show`  — show one snapshot and the files it captured
+/// - `prune` — delete snapshots by age and/or total size
+#[derive(Debug, clap::Parser)]
+pub struct GhostCli {
+    /// Repository to operate on. Defaults to the current directory.
+    #[arg(long = "repo", short = 'C', value_name = "DIR", global = true)]
+    pub repo: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub subcommand: GhostSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum GhostSubcommand {
+    /// List recorded ghost snapshots, oldest first.
+    List(ListArgs),
+
+    /// Show a recorded ghost snapshot.
+    Show(ShowArgs),
+
+    /// Delete ghost snapshots outside the given retention limits.
+    Prune(PruneArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Output the snapshots as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Full ref name, `<conversation>/<n>`, or a commit id prefix.
+    #[arg(value_name = "SNAPSHOT")]
+    pub snapshot: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct PruneArgs {
+    /// Prune snapshots older than this, e.g. `30m`, `12h`, `7d` or `2w`.
+    #[arg(long = "older-than", value_name = "AGE", value_parser = parse_age)]
+    pub older_than: Option<Duration>,
+
+    /// Keep the newest snapshots up to this total size, e.g. `500M` or `2G`.
+    #[arg(long = "max-size", value_name = "SIZE", value_parser = parse_size)]
+    pub max_size: Option<u64>,
+
+    /// Print what would be pruned without deleting anything.
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+}
+
+impl GhostCli {
+    pub fn run(self) -> Result<()> {
+        let GhostCli { repo, subcommand } = self;
+        let repo = match repo {
+            Some(repo) => repo,
+            None => std::env::current_dir().context("failed to resolve current directory")?,
+        };
+
+        match subcommand {
+            GhostSubcommand::List(args) => run_list(&repo, args)?,
+            GhostSubcommand::Show(args) => run_show(&repo, args)?,
+            GhostSubcommand::Prune(args) => run_prune(&repo, args)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn run_list(repo: &Path, list_args: ListArgs) -> Result<()> {
+    let refs = list_ghost_refs(repo).context("failed to list ghost snapshots")?;
+    let sizes = refs
+        .iter()
+        .map(|ghost_ref| ghost_ref_disk_usage(repo, ghost_ref, &[]))
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to measure ghost snapshots")?;
+
+    if list_args.json {
+        let json_entries: Vec<_> = refs
+            .iter()
+            .zip(&sizes)
+            .map(|(ghost_ref, size)| {
+                serde_json::json!({
+                    "ref": ghost_ref.name,
+                    "conversation_id": ghost_ref.conversation_id,
+                    "index": ghost_ref.index,
+                    "commit": ghost_ref.commit_id,
+                    "created_at": ghost_ref.created_at,
+                    "size": size,
+                })
+            })
+            .collect();
+        let output = serde_json::to_string_pretty(&json_entries)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if refs.is_empty() {
+        println!("No ghost snapshots recorded under {GHOST_REF_PREFIX}.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 5]> = refs
+        .iter()
+        .zip(&sizes)
+        .map(|(ghost_ref, size)| {
+            [
+                ghost_ref.conversation_id.clone(),
+                ghost_ref.index.to_string(),
+                short_id(&ghost_ref.commit_id),
+                format_timestamp(ghost_ref.created_at),
+                size.map_or_else(|| "unknown".to_string(), format_size),
+            ]
+        })
+        .collect();
+
+    let mut widths = [
+        "Conversation".len(),
+        "#".len(),
+        "Commit".len(),
+        "Created".len(),
+    ];
+    for row in &rows {
+        for (i, cell) in row.iter().take(widths.len()).enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    println!(
+        "{:<conv_w$}  {:>idx_w$}  {:<commit_w$}  {:<created_w$}  Size",
+        "Conversation",
+        "#",
+        "Commit",
+        "Created",
+        conv_w = widths[0],
+        idx_w = widths[1],
+        commit_w = widths[2],
+        created_w = widths[3],
+    );
+    for row in &rows {
+        println!(
+            "{:<conv_w$}  {:>idx_w$}  {:<commit_w$}  {:<created_w$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            row[4],
+            conv_w = widths[0],
+            idx_w = widths[1],
+            commit_w = widths[2],
+            created_w = widths[3],
+        );
+    }
+    // Snapshots share most of their objects, so the total is measured over
+    // all of them rather than summed.
+    let total = ghost_refs_disk_usage(repo, &refs).context("failed to measure ghost snapshots")?;
+    match total {
+        Some(total) => println!(
+            "\n{} snapshot(s), {} not shared with branches or tags.",
+            rows.len(),
+            format_size(total)
+        ),
+        None => println!(
+            "\n{} snapshot(s). Measuring their size needs git 2.38 or newer.",
+            rows.len()
+        ),
+    }
+
+    Ok(())
+}
+
+fn run_show(repo: &Path, show_args: ShowArgs) -> Result<()> {
+    let refs = list_ghost_refs(repo).context("failed to list ghost snapshots")?;
+    let ghost_ref = find_ghost_ref(&refs, &show_args.snapshot)?;
+    let size = ghost_ref_disk_usage(repo, ghost_ref, &[]).context("failed to measure snapshot")?;
+
+    println!("{}", ghost_ref.name);
+    println!("  conversation: {}", ghost_ref.conversation_id);
+    println!("  turn snapshot: {}", ghost_ref.index);
+    println!("  commit: {}", ghost_ref.commit_id);
+    println!("  created: {}", format_timestamp(ghost_ref.created_at));
+    match size {
+        Some(size) => println!("  size: {}", format_size(size)),
+        None => println!("  size: unknown (needs git 2.38 or newer)"),
+    }
+    println!();
+
+    let status = Command::new("git")
+        .current_dir(repo)
+        .args(["show", "--stat", "--format=", &ghost_ref.commit_id])
+        .status()
+        .context("failed to run git show")?;
+    if !status.success() {
+        bail!("git show exited with {status}");
+    }
+
+    Ok(())
+}
+
+fn run_prune(repo: &Path, prune_args: PruneArgs) -> Result<()> {
+    let PruneArgs {
+        older_than,
+        max_size,
+        dry_run,
+    } = prune_args;
+    if older_than.is_none() && max_size.is_none() {
+        bail!("at least one of --older-than or --max-size must be provided");
+    }
+
+    let retention = GhostRetention {
+        max_age: older_than,
+        max_total_size: max_size,
+    };
+    let pruned =
+        prune_ghost_refs(repo, &retention, dry_run).context("failed to prune ghost snapshots")?;
+
+    if pruned.is_empty() {
+        println!("No ghost snapshots to prune.");
+        return Ok(());
+    }
+    let verb = if dry_run { "Would prune" } else { "Pruned" };
+    for ghost_ref in &pruned {
+        println!(
+            "{verb} {} ({}, {})",
+            ghost_ref.name,
+            short_id(&ghost_ref.commit_id),
+            format_timestamp(ghost_ref.created_at)
+        );
+    }
+    if !dry_run {
+        println!(
+            "Pruned {} snapshot(s). Run `git gc` to reclaim the space.",
+            pruned.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn find_ghost_ref<'a>(refs: &'a [GhostRef], query: &str) -> Result<&'a GhostRef> {
+    let query = query.trim();
+    // An empty commit id prefix would match every snapshot.
+    if query.is_empty() {
+        bail!("snapshot must not be empty");
+    }
+    let matches: Vec<&GhostRef> = refs
+        .iter()
+        .filter(|ghost_ref| {
+            ghost_ref.name == query
+                || format!("{}/{}", ghost_ref.conversation_id, ghost_ref.index) == query
+                || ghost_ref.commit_id.starts_with(query)
+        })
+        .collect();
+    match matches.as_slice() {
+        [] => bail!("no ghost snapshot matches `{query}`"),
+        [only] => Ok(only),
+        [first, ..] if matches.iter().all(|m| m.commit_id == first.commit_id) => Ok(first),
+        _ => bail!("`{query}` matches several ghost snapshots; use the full ref name"),
+    }
+}
+
+fn parse_age(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| anyhow!("invalid age `{value}`; expected e.g. `7d`"))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("invalid age unit `{unit}`; use s, m, h, d or w"),
+    };
+    Ok(Duration::from_secs(amount.saturating_mul(seconds)))
+}
+
+fn parse_size(value: &str) -> Result<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| anyhow!("invalid size `{value}`; expected e.g. `500M`"))?;
+    let multiplier = match unit.to_ascii_uppercase().trim_end_matches('B') {
+        "" => 1,
+        "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => bail!("invalid size unit `{unit}`; use K, M or G"),
+    };
+    Ok(amount.saturating_mul(multiplier))
+}
+
+fn short_id(commit_id: &str) -> String {
+    commit_id.chars().take(7).collect()
+}
+
+fn format_timestamp(seconds: i64) -> String {
+    DateTime::from_timestamp(seconds, 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|| seconds.to_string())
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn ghost_ref(conversation_id: &str, index: u64, commit_id: &str) -> GhostRef {
+        GhostRef {
+            name: format!("{GHOST_REF_PREFIX}/{conversation_id}/{index}"),
+            conversation_id: conversation_id.to_string(),
+            index,
+            commit_id: commit_id.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn parse_age_accepts_units_and_defaults_to_days() {
+        assert_eq!(parse_age("30s").ok(), Some(Duration::from_secs(30)));
+        assert_eq!(parse_age("5m").ok(), Some(Duration::from_secs(5 * 60)));
+        assert_eq!(
+            parse_age("12h").ok(),
+            Some(Duration::from_secs(12 * 60 * 60))
+        );
+        assert_eq!(
+            parse_age(" 7d ").ok(),
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            parse_age("7").ok(),
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            parse_age("2w").ok(),
+            Some(Duration::from_secs(14 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn parse_age_rejects_invalid_values() {
+        assert!(parse_age("").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("7y").is_err());
+        assert!(parse_age("-1d").is_err());
+    }
+
+    #[test]
+    fn parse_size_accepts_binary_units() {
+        assert_eq!(parse_size("512").ok(), Some(512));
+        assert_eq!(parse_size("4K").ok(), Some(4 << 10));
+        assert_eq!(parse_size("500M").ok(), Some(500 << 20));
+        assert_eq!(parse_size("500mb").ok(), Some(500 << 20));
+        assert_eq!(parse_size("2G").ok(), Some(2 << 30));
+        assert_eq!(parse_size("10B").ok(), Some(10));
+    }
+
+    #[test]
+    fn parse_size_rejects_invalid_values() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("2T").is_err());
+    }
+
+    #[test]
+    fn find_ghost_ref_rejects_empty_query() {
+        let refs = vec![ghost_ref("conv", 1, "abc123")];
+        assert!(find_ghost_ref(&refs, "").is_err());
+        assert!(find_ghost_ref(&refs, "  ").is_err());
+        assert_eq!(find_ghost_ref(&refs, "abc").ok(), Some(&refs[0]));
+        assert_eq!(find_ghost_ref(&refs, "conv/1").ok(), Some(&refs[0]));
+    }
+}
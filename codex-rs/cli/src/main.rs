@@ -30,11 +30,17 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod ghost_cmd;
 mod mcp_cmd;
+mod otel_cmd;
+mod sessions_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::ghost_cmd::GhostCli;
 use crate::mcp_cmd::McpCli;
+use crate::otel_cmd::OtelCli;
+use crate::sessions_cmd::SessionsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -115,6 +121,15 @@ enum Subcommand {
     #[clap(visible_alias = "a")]
     Apply(ApplyCommand),
 
+    /// Inspect and prune the ghost snapshots recorded for undo.
+    Ghost(GhostCli),
+
+    /// Inspect recorded sessions, e.g. their per-turn token cost.
+    Sessions(SessionsCli),
+
+    /// Summarize telemetry written by the OTel file exporter.
+    Otel(OtelCli),
+
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
@@ -603,6 +618,19 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             run_apply_command(apply_cli, None).await?;
         }
+        Some(Subcommand::Ghost(ghost_cli)) => {
+            tokio::task::spawn_blocking(move || ghost_cli.run()).await??;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Otel(otel_cli)) => {
+            tokio::task::spawn_blocking(move || otel_cli.run()).await??;
+        }
         Some(Subcommand::ResponsesApiProxy(args)) => {
             tokio::task::spawn_blocking(move || codex_responses_api_proxy::run_main(args))
                 .await??;
@@ -758,6 +786,14 @@ fn merge_resume_cli_flags(interactive: &mut TuiCli, resume_cli: TuiCli) {
     if let Some(prompt) = resume_cli.prompt {
         interactive.prompt = Some(prompt);
     }
+    if let Some(record) = resume_cli.record {
+        interactive.record = Some(record);
+        interactive.replay = None;
+    }
+    if let Some(replay) = resume_cli.replay {
+        interactive.replay = Some(replay);
+        interactive.record = None;
+    }
 
     interactive
         .config_overrides
@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_core::config::find_codex_home;
+use codex_otel::file_exporter::otel_dir;
+use codex_otel::summary::SessionSummary;
+use codex_otel::summary::summarize_dir;
+
+/// Work with the telemetry written by the OTel file exporter
+/// (`[otel] exporter = "file"`).
+///
+/// Subcommands:
+/// - `summarize` — time spent in the model, in tools and waiting on approval
+#[derive(Debug, clap::Parser)]
+pub struct OtelCli {
+    #[command(subcommand)]
+    pub subcommand: OtelSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum OtelSubcommand {
+    /// Print a per-session breakdown of model, tool and approval time.
+    Summarize(SummarizeArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SummarizeArgs {
+    /// Directory holding the exported files. Defaults to `$CODEX_HOME/otel`.
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// Only summarize the session with this conversation id.
+    #[arg(long, value_name = "ID")]
+    pub session: Option<String>,
+
+    /// Number of slowest tools to list per session.
+    #[arg(long, default_value_t = 5)]
+    pub top: usize,
+
+    /// Output the summaries as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl OtelCli {
+    pub fn run(self) -> Result<()> {
+        match self.subcommand {
+            OtelSubcommand::Summarize(args) => run_summarize(args),
+        }
+    }
+}
+
+fn run_summarize(args: SummarizeArgs) -> Result<()> {
+    let dir = match args.dir {
+        Some(dir) => dir,
+        None => otel_dir(&find_codex_home().context("failed to resolve CODEX_HOME")?),
+    };
+    let mut summaries = summarize_dir(&dir)
+        .with_context(|| format!("failed to read telemetry in {}", dir.display()))?;
+    if let Some(session) = &args.session {
+        summaries.retain(|summary| &summary.conversation_id == session);
+    }
+    for summary in &mut summaries {
+        summary.tools.truncate(args.top);
+    }
+
+    if args.json {
+        let output = serde_json::to_string_pretty(&summaries)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if summaries.is_empty() {
+        println!(
+            "No sessions found in {}. Set `exporter = \"file\"` under `[otel]` to record them.",
+            dir.display()
+        );
+        return Ok(());
+    }
+
+    for (i, summary) in summaries.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_summary(summary);
+    }
+
+    Ok(())
+}
+
+fn print_summary(summary: &SessionSummary) {
+    match &summary.started_at {
+        Some(started_at) => println!("Session {} ({started_at})", summary.conversation_id),
+        None => println!("Session {}", summary.conversation_id),
+    }
+
+    let total = summary.model_ms + summary.tool_ms + summary.approval_ms;
+    let share = |ms: u64| {
+        if total == 0 {
+            0.0
+        } else {
+            ms as f64 / total as f64 * 100.0
+        }
+    };
+    println!(
+        "  Model     {:>10}  {:>5.1}%  {} request(s)",
+        format_ms(summary.model_ms),
+        share(summary.model_ms),
+        summary.model_requests
+    );
+    println!(
+        "  Tools     {:>10}  {:>5.1}%  {} call(s)",
+        format_ms(summary.tool_ms),
+        share(summary.tool_ms),
+        summary.tool_calls
+    );
+    println!(
+        "  Approval  {:>10}  {:>5.1}%  {} request(s)",
+        format_ms(summary.approval_ms),
+        share(summary.approval_ms),
+        summary.approvals
+    );
+
+    if summary.tools.is_empty() {
+        return;
+    }
+    println!("  Slowest tools:");
+    let width = summary
+        .tools
+        .iter()
+        .map(|tool| tool.tool_name.len())
+        .max()
+        .unwrap_or(0);
+    for tool in &summary.tools {
+        println!(
+            "    {:<width$}  max {:>10}  total {:>10}  {} call(s)",
+            tool.tool_name,
+            format_ms(tool.max_ms),
+            format_ms(tool.total_ms),
+            tool.calls
+        );
+    }
+}
+
+fn format_ms(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{ms}ms")
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, ms % 60_000 / 1_000)
+    }
+}
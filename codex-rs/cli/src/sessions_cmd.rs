@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::cost_ledger::cost_usd;
+use codex_core::cost_ledger::price_for_model;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::RolloutItem;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TurnCostEvent;
+
+/// Inspect recorded sessions.
+///
+/// Subcommands:
+/// - `cost` — show the per-turn token and cost ledger of a session
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Show token usage and cost of each turn of a session.
+    Cost(CostArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct CostArgs {
+    /// Session id, or the path of a rollout file.
+    #[arg(value_name = "SESSION")]
+    pub session: String,
+
+    /// Output the ledger as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            SessionsSubcommand::Cost(args) => run_cost(&config_overrides, args).await?,
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_cost(config_overrides: &CliConfigOverrides, cost_args: CostArgs) -> Result<()> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let path = match find_conversation_path_by_id_str(&config.codex_home, &cost_args.session)
+        .await
+        .context("failed to look up session")?
+    {
+        Some(path) => path,
+        None if PathBuf::from(&cost_args.session).is_file() => PathBuf::from(&cost_args.session),
+        None => bail!("no session found with id `{}`", cost_args.session),
+    };
+    let history = RolloutRecorder::get_rollout_history(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    // Price turns recorded without a price using the current table.
+    let entries: Vec<TurnCostEvent> = history
+        .get_rollout_items()
+        .into_iter()
+        .filter_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::TurnCost(entry)) => Some(entry),
+            _ => None,
+        })
+        .map(|mut entry| {
+            if entry.cost_usd.is_none() {
+                entry.cost_usd = price_for_model(&config.model_prices, &entry.model)
+                    .map(|price| cost_usd(price, &entry.usage));
+            }
+            entry
+        })
+        .collect();
+
+    if cost_args.json {
+        let output = serde_json::to_string_pretty(&entries)?;
+        println!("{output}");
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No turns with token usage recorded in {}.", path.display());
+        return Ok(());
+    }
+
+    let headers = [
+        "Turn",
+        "Model",
+        "Input",
+        "Cached",
+        "Output",
+        "Reasoning",
+        "Compacted",
+        "Cost",
+    ];
+    let rows: Vec<[String; 8]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.turn_id.clone(),
+                entry.model.clone(),
+                entry.usage.input_tokens.to_string(),
+                entry.usage.cached_input_tokens.to_string(),
+                entry.usage.output_tokens.to_string(),
+                entry.usage.reasoning_output_tokens.to_string(),
+                if entry.compacted { "yes" } else { "" }.to_string(),
+                format_cost(entry.cost_usd),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+    println!("{}", format_row(&headers.map(str::to_string), &widths));
+    for row in &rows {
+        println!("{}", format_row(row, &widths));
+    }
+
+    let mut total = TokenUsage::default();
+    for entry in &entries {
+        total.add_assign(&entry.usage);
+    }
+    let priced: Vec<f64> = entries.iter().filter_map(|entry| entry.cost_usd).collect();
+    let total_cost = if priced.is_empty() {
+        "unknown".to_string()
+    } else {
+        format_cost(Some(priced.iter().sum()))
+    };
+    println!(
+        "\n{} turn(s): {} input ({} cached), {} output, {total_cost}.",
+        entries.len(),
+        total.input_tokens,
+        total.cached_input_tokens,
+        total.output_tokens,
+    );
+    if priced.len() < entries.len() {
+        println!(
+            "{} turn(s) have no price; add their models to `model_prices` in config.toml.",
+            entries.len() - priced.len()
+        );
+    }
+    if let Some((before, after)) = input_per_turn_around_compaction(&entries) {
+        println!(
+            "Compaction: {before} input tokens per turn before the first compaction, {after} after."
+        );
+    }
+
+    Ok(())
+}
+
+/// Average input tokens of the turns before the first compacting turn and of
+/// the turns after it, to show what compaction saved.
+fn input_per_turn_around_compaction(entries: &[TurnCostEvent]) -> Option<(i64, i64)> {
+    let first = entries.iter().position(|entry| entry.compacted)?;
+    let average = |turns: &[TurnCostEvent]| {
+        let turns: Vec<_> = turns.iter().filter(|entry| !entry.compacted).collect();
+        if turns.is_empty() {
+            return None;
+        }
+        let sum: i64 = turns.iter().map(|entry| entry.usage.input_tokens).sum();
+        Some(sum / turns.len() as i64)
+    };
+    Some((average(&entries[..first])?, average(&entries[first + 1..])?))
+}
+
+fn format_row(cells: &[String; 8], widths: &[usize; 8]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (cell, width))| {
+            if i < 2 {
+                format!("{cell:<width$}")
+            } else {
+                format!("{cell:>width$}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn format_cost(cost_usd: Option<f64>) -> String {
+    match cost_usd {
+        Some(cost) => format!("${cost:.4}"),
+        None => "-".to_string(),
+    }
+}
@@ -11,6 +11,7 @@ use std::time::Duration;
 pub enum WireApi {
     Responses,
     Chat,
+    Messages,
     Compact,
 }
 
@@ -0,0 +1,453 @@
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::requests::headers::build_conversation_headers;
+use crate::requests::headers::insert_header;
+use crate::requests::headers::subagent_header;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use http::HeaderMap;
+use serde_json::Value;
+use serde_json::json;
+
+/// Value sent in the `anthropic-version` header unless the provider already
+/// configures one.
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Output token limit used when the caller does not set one. The Messages API
+/// requires `max_tokens` on every request.
+pub const DEFAULT_MAX_TOKENS: u64 = 8192;
+
+/// Assembled request body plus headers for Anthropic Messages streaming calls.
+pub struct MessagesRequest {
+    pub body: Value,
+    pub headers: HeaderMap,
+}
+
+pub struct MessagesRequestBuilder<'a> {
+    model: &'a str,
+    instructions: &'a str,
+    input: &'a [ResponseItem],
+    tools: &'a [Value],
+    parallel_tool_calls: bool,
+    max_tokens: u64,
+    thinking_budget: Option<u64>,
+    conversation_id: Option<String>,
+    session_source: Option<SessionSource>,
+}
+
+impl<'a> MessagesRequestBuilder<'a> {
+    pub fn new(
+        model: &'a str,
+        instructions: &'a str,
+        input: &'a [ResponseItem],
+        tools: &'a [Value],
+    ) -> Self {
+        Self {
+            model,
+            instructions,
+            input,
+            tools,
+            parallel_tool_calls: true,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            thinking_budget: None,
+            conversation_id: None,
+            session_source: None,
+        }
+    }
+
+    pub fn parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = enabled;
+        self
+    }
+
+    /// Output token limit for the visible answer. Any thinking budget is
+    /// added on top of this value.
+    pub fn max_tokens(mut self, max_tokens: u64) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Enables extended thinking with the given token budget.
+    pub fn thinking_budget(mut self, budget: Option<u64>) -> Self {
+        self.thinking_budget = budget;
+        self
+    }
+
+    pub fn conversation_id(mut self, id: Option<String>) -> Self {
+        self.conversation_id = id;
+        self
+    }
+
+    pub fn session_source(mut self, source: Option<SessionSource>) -> Self {
+        self.session_source = source;
+        self
+    }
+
+    pub fn build(self, provider: &Provider) -> Result<MessagesRequest, ApiError> {
+        let mut messages = Vec::<Value>::new();
+
+        for item in self.input {
+            match item {
+                ResponseItem::Message { role, content, .. } => {
+                    // The Messages API only knows `user` and `assistant`; system and
+                    // developer messages are folded into the user turn.
+                    let role = if role == "assistant" {
+                        "assistant"
+                    } else {
+                        "user"
+                    };
+                    for block in content.iter().filter_map(content_block) {
+                        push_block(&mut messages, role, block);
+                    }
+                }
+                ResponseItem::Reasoning {
+                    content: Some(content),
+                    encrypted_content: Some(signature),
+                    ..
+                } => {
+                    let thinking: String = content
+                        .iter()
+                        .map(|entry| match entry {
+                            ReasoningItemContent::ReasoningText { text }
+                            | ReasoningItemContent::Text { text } => text.as_str(),
+                        })
+                        .collect();
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({
+                            "type": "thinking",
+                            "thinking": thinking,
+                            "signature": signature,
+                        }),
+                    );
+                }
+                ResponseItem::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                    ..
+                } => {
+                    let input = serde_json::from_str::<Value>(arguments)
+                        .ok()
+                        .filter(Value::is_object)
+                        .unwrap_or_else(|| json!({}));
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({
+                            "type": "tool_use",
+                            "id": call_id,
+                            "name": name,
+                            "input": input,
+                        }),
+                    );
+                }
+                ResponseItem::LocalShellCall {
+                    id,
+                    call_id,
+                    action,
+                    ..
+                } => {
+                    let Some(call_id) = call_id.as_ref().or(id.as_ref()) else {
+                        continue;
+                    };
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({
+                            "type": "tool_use",
+                            "id": call_id,
+                            "name": "local_shell",
+                            "input": action,
+                        }),
+                    );
+                }
+                ResponseItem::CustomToolCall {
+                    call_id,
+                    name,
+                    input,
+                    ..
+                } => {
+                    push_block(
+                        &mut messages,
+                        "assistant",
+                        json!({
+                            "type": "tool_use",
+                            "id": call_id,
+                            "name": name,
+                            "input": {"input": input},
+                        }),
+                    );
+                }
+                ResponseItem::FunctionCallOutput { call_id, output } => {
+                    push_block(&mut messages, "user", tool_result_block(call_id, output));
+                }
+                ResponseItem::CustomToolCallOutput { call_id, output } => {
+                    push_block(
+                        &mut messages,
+                        "user",
+                        json!({
+                            "type": "tool_result",
+                            "tool_use_id": call_id,
+                            "content": output,
+                        }),
+                    );
+                }
+                ResponseItem::Reasoning { .. }
+                | ResponseItem::WebSearchCall { .. }
+                | ResponseItem::GhostSnapshot { .. }
+                | ResponseItem::Compaction { .. }
+                | ResponseItem::Other => {}
+            }
+        }
+
+        let max_tokens = self.max_tokens + self.thinking_budget.unwrap_or(0);
+        let mut payload = json!({
+            "model": self.model,
+            "system": self.instructions,
+            "messages": messages,
+            "max_tokens": max_tokens,
+            "stream": true,
+        });
+        if let Some(obj) = payload.as_object_mut() {
+            if !self.tools.is_empty() {
+                obj.insert("tools".to_string(), json!(self.tools));
+                obj.insert(
+                    "tool_choice".to_string(),
+                    json!({
+                        "type": "auto",
+                        "disable_parallel_tool_use": !self.parallel_tool_calls,
+                    }),
+                );
+            }
+            if let Some(budget) = self.thinking_budget {
+                obj.insert(
+                    "thinking".to_string(),
+                    json!({"type": "enabled", "budget_tokens": budget}),
+                );
+            }
+        }
+
+        let mut headers = build_conversation_headers(self.conversation_id);
+        if let Some(subagent) = subagent_header(&self.session_source) {
+            insert_header(&mut headers, "x-openai-subagent", &subagent);
+        }
+        if !provider.headers.contains_key("anthropic-version") {
+            insert_header(&mut headers, "anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        Ok(MessagesRequest {
+            body: payload,
+            headers,
+        })
+    }
+}
+
+/// Appends a content block to the conversation, merging it into the previous
+/// message when the role matches. The Messages API expects user and assistant
+/// turns to alternate, and tool results to follow their `tool_use` directly.
+fn push_block(messages: &mut Vec<Value>, role: &str, block: Value) {
+    if let Some(last) = messages.last_mut()
+        && last.get("role").and_then(Value::as_str) == Some(role)
+        && let Some(content) = last.get_mut("content").and_then(Value::as_array_mut)
+    {
+        content.push(block);
+        return;
+    }
+    messages.push(json!({"role": role, "content": [block]}));
+}
+
+fn content_block(item: &ContentItem) -> Option<Value> {
+    match item {
+        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+            // Empty text blocks are rejected by the API.
+            (!text.is_empty()).then(|| json!({"type": "text", "text": text}))
+        }
+        ContentItem::InputImage { image_url } => Some(image_block(image_url)),
+    }
+}
+
+fn image_block(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((meta, data)) = rest.split_once(',')
+        && let Some(media_type) = meta.strip_suffix(";base64")
+    {
+        return json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        });
+    }
+    json!({
+        "type": "image",
+        "source": {"type": "url", "url": image_url},
+    })
+}
+
+fn tool_result_block(call_id: &str, output: &FunctionCallOutputPayload) -> Value {
+    let content = match &output.content_items {
+        Some(items) => json!(
+            items
+                .iter()
+                .map(|item| match item {
+                    FunctionCallOutputContentItem::InputText { text } => {
+                        json!({"type": "text", "text": text})
+                    }
+                    FunctionCallOutputContentItem::InputImage { image_url } => {
+                        image_block(image_url)
+                    }
+                })
+                .collect::<Vec<_>>()
+        ),
+        None => json!(output.content),
+    };
+    let mut block = json!({
+        "type": "tool_result",
+        "tool_use_id": call_id,
+        "content": content,
+    });
+    if output.success == Some(false)
+        && let Some(obj) = block.as_object_mut()
+    {
+        obj.insert("is_error".to_string(), json!(true));
+    }
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::RetryConfig;
+    use crate::provider::WireApi;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn provider() -> Provider {
+        Provider {
+            name: "anthropic".to_string(),
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            query_params: None,
+            wire: WireApi::Messages,
+            headers: HeaderMap::new(),
+            retry: RetryConfig {
+                max_attempts: 1,
+                base_delay: Duration::from_millis(10),
+                retry_429: false,
+                retry_5xx: true,
+                retry_transport: true,
+            },
+            stream_idle_timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn user_text(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn maps_tool_calls_and_outputs_to_content_blocks() {
+        let input = vec![
+            user_text("list files"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: "use ls".to_string(),
+                }]),
+                encrypted_content: Some("sig-1".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "README.md".to_string(),
+                    content_items: None,
+                    success: Some(false),
+                },
+            },
+            user_text("thanks"),
+        ];
+        let tools = vec![json!({"name": "shell", "input_schema": {"type": "object"}})];
+
+        let req = MessagesRequestBuilder::new("claude-test", "be brief", &input, &tools)
+            .parallel_tool_calls(false)
+            .max_tokens(1000)
+            .thinking_budget(Some(2000))
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body,
+            json!({
+                "model": "claude-test",
+                "system": "be brief",
+                "max_tokens": 3000,
+                "stream": true,
+                "tools": tools,
+                "tool_choice": {"type": "auto", "disable_parallel_tool_use": true},
+                "thinking": {"type": "enabled", "budget_tokens": 2000},
+                "messages": [
+                    {"role": "user", "content": [{"type": "text", "text": "list files"}]},
+                    {"role": "assistant", "content": [
+                        {"type": "thinking", "thinking": "use ls", "signature": "sig-1"},
+                        {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                    ]},
+                    {"role": "user", "content": [
+                        {"type": "tool_result", "tool_use_id": "toolu_1", "content": "README.md", "is_error": true},
+                        {"type": "text", "text": "thanks"},
+                    ]},
+                ],
+            })
+        );
+        assert_eq!(
+            req.headers.get("anthropic-version"),
+            Some(&HeaderValue::from_static(ANTHROPIC_VERSION))
+        );
+    }
+
+    #[test]
+    fn converts_data_url_images_to_base64_sources() {
+        let input = vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![
+                ContentItem::InputImage {
+                    image_url: "data:image/png;base64,AAAA".to_string(),
+                },
+                ContentItem::InputImage {
+                    image_url: "https://example.com/cat.png".to_string(),
+                },
+            ],
+        }];
+
+        let req = MessagesRequestBuilder::new("claude-test", "", &input, &[])
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body["messages"][0]["content"],
+            json!([
+                {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}},
+                {"type": "image", "source": {"type": "url", "url": "https://example.com/cat.png"}},
+            ])
+        );
+        assert_eq!(req.body.get("tools"), None);
+        assert_eq!(req.body.get("thinking"), None);
+    }
+}
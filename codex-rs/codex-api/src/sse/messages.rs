@@ -0,0 +1,574 @@
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::telemetry::SseTelemetry;
+use codex_client::StreamResponse;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+pub(crate) fn spawn_messages_stream(
+    stream_response: StreamResponse,
+    idle_timeout: Duration,
+    telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        process_messages_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+    });
+    ResponseStream { rx_event }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    message: Option<MessageStart>,
+    index: Option<u64>,
+    content_block: Option<Value>,
+    delta: Option<Value>,
+    usage: Option<Usage>,
+    error: Option<MessagesError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    #[serde(default)]
+    id: String,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Usage {
+    input_tokens: Option<i64>,
+    cache_creation_input_tokens: Option<i64>,
+    cache_read_input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesError {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// In-flight content block, keyed by its `index` in the message.
+enum BlockState {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input_json: String,
+    },
+}
+
+/// Token counts accumulated across `message_start` and `message_delta`.
+///
+/// The Messages API reports uncached, cache-write and cache-read input tokens
+/// separately; `TokenUsage::input_tokens` is their sum.
+#[derive(Default)]
+struct UsageTotals {
+    input_tokens: i64,
+    cache_creation_input_tokens: i64,
+    cache_read_input_tokens: i64,
+    output_tokens: i64,
+}
+
+impl UsageTotals {
+    fn update(&mut self, usage: &Usage) {
+        if let Some(v) = usage.input_tokens {
+            self.input_tokens = v;
+        }
+        if let Some(v) = usage.cache_creation_input_tokens {
+            self.cache_creation_input_tokens = v;
+        }
+        if let Some(v) = usage.cache_read_input_tokens {
+            self.cache_read_input_tokens = v;
+        }
+        if let Some(v) = usage.output_tokens {
+            self.output_tokens = v;
+        }
+    }
+
+    fn to_token_usage(&self) -> TokenUsage {
+        let input_tokens =
+            self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens;
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens: self.cache_read_input_tokens,
+            output_tokens: self.output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + self.output_tokens,
+        }
+    }
+}
+
+pub async fn process_messages_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    idle_timeout: Duration,
+    telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
+) where
+    S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut response_id = String::new();
+    let mut usage = UsageTotals::default();
+    let mut blocks: HashMap<u64, BlockState> = HashMap::new();
+
+    loop {
+        let start = Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        if let Some(t) = telemetry.as_ref() {
+            t.on_sse_poll(&response, start.elapsed());
+        }
+        let sse = match response {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event.send(Err(ApiError::Stream(e.to_string()))).await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream(
+                        "stream closed before message_stop".into(),
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("SSE event: {}", sse.data);
+
+        if sse.data.trim().is_empty() {
+            continue;
+        }
+
+        let event: MessagesEvent = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(err) => {
+                debug!(
+                    "Failed to parse Messages SSE event: {err}, data: {}",
+                    &sse.data
+                );
+                continue;
+            }
+        };
+
+        match event.kind.as_str() {
+            "message_start" => {
+                if let Some(message) = event.message {
+                    response_id = message.id;
+                    if let Some(start_usage) = &message.usage {
+                        usage.update(start_usage);
+                    }
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            "content_block_start" => {
+                let (Some(index), Some(block)) = (event.index, event.content_block) else {
+                    continue;
+                };
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let item = ResponseItem::Message {
+                            id: None,
+                            role: "assistant".to_string(),
+                            content: vec![],
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        let mut state = BlockState::Text(String::new());
+                        if let Some(text) = block.get("text").and_then(Value::as_str)
+                            && !text.is_empty()
+                        {
+                            apply_text_delta(&tx_event, &mut state, text).await;
+                        }
+                        blocks.insert(index, state);
+                    }
+                    Some("thinking") => {
+                        let item = ResponseItem::Reasoning {
+                            id: String::new(),
+                            summary: Vec::new(),
+                            content: Some(vec![]),
+                            encrypted_content: None,
+                        };
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        blocks.insert(
+                            index,
+                            BlockState::Thinking {
+                                text: String::new(),
+                                signature: String::new(),
+                            },
+                        );
+                    }
+                    Some("tool_use") => {
+                        let id = block
+                            .get("id")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string();
+                        let name = block
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string();
+                        blocks.insert(
+                            index,
+                            BlockState::ToolUse {
+                                id,
+                                name,
+                                input_json: String::new(),
+                            },
+                        );
+                    }
+                    other => {
+                        debug!("Ignoring Messages content block of type {other:?}");
+                    }
+                }
+            }
+            "content_block_delta" => {
+                let (Some(index), Some(delta)) = (event.index, event.delta) else {
+                    continue;
+                };
+                let Some(state) = blocks.get_mut(&index) else {
+                    continue;
+                };
+                match (delta.get("type").and_then(Value::as_str), state) {
+                    (Some("text_delta"), state @ BlockState::Text(_)) => {
+                        if let Some(text) = delta.get("text").and_then(Value::as_str) {
+                            apply_text_delta(&tx_event, state, text).await;
+                        }
+                    }
+                    (Some("thinking_delta"), BlockState::Thinking { text, .. }) => {
+                        if let Some(thinking) = delta.get("thinking").and_then(Value::as_str) {
+                            text.push_str(thinking);
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::ReasoningContentDelta {
+                                    delta: thinking.to_string(),
+                                    content_index: 0,
+                                }))
+                                .await;
+                        }
+                    }
+                    (Some("signature_delta"), BlockState::Thinking { signature, .. }) => {
+                        if let Some(part) = delta.get("signature").and_then(Value::as_str) {
+                            signature.push_str(part);
+                        }
+                    }
+                    (Some("input_json_delta"), BlockState::ToolUse { input_json, .. }) => {
+                        if let Some(part) = delta.get("partial_json").and_then(Value::as_str) {
+                            input_json.push_str(part);
+                        }
+                    }
+                    (kind, _) => {
+                        debug!("Ignoring Messages delta of type {kind:?} for block {index}");
+                    }
+                }
+            }
+            "content_block_stop" => {
+                let Some(state) = event.index.and_then(|index| blocks.remove(&index)) else {
+                    continue;
+                };
+                let item = finish_block(state);
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+            }
+            "message_delta" => {
+                if let Some(delta_usage) = &event.usage {
+                    usage.update(delta_usage);
+                }
+                let stop_reason = event
+                    .delta
+                    .as_ref()
+                    .and_then(|delta| delta.get("stop_reason"))
+                    .and_then(Value::as_str);
+                if stop_reason == Some("model_context_window_exceeded") {
+                    let _ = tx_event.send(Err(ApiError::ContextWindowExceeded)).await;
+                    return;
+                }
+            }
+            "message_stop" => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(usage.to_token_usage()),
+                    }))
+                    .await;
+                return;
+            }
+            "error" => {
+                let error = event.error.unwrap_or(MessagesError {
+                    kind: String::new(),
+                    message: "error event received".to_string(),
+                });
+                let _ = tx_event.send(Err(map_stream_error(error))).await;
+                return;
+            }
+            "ping" => {}
+            other => {
+                debug!("Ignoring Messages SSE event {other}");
+            }
+        }
+    }
+}
+
+async fn apply_text_delta(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    state: &mut BlockState,
+    delta: &str,
+) {
+    if let BlockState::Text(text) = state {
+        text.push_str(delta);
+        let _ = tx_event
+            .send(Ok(ResponseEvent::OutputTextDelta(delta.to_string())))
+            .await;
+    }
+}
+
+fn finish_block(state: BlockState) -> ResponseItem {
+    match state {
+        BlockState::Text(text) => ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text }],
+        },
+        // The signature is required to send the thinking block back on the next
+        // request, so it travels in `encrypted_content`.
+        BlockState::Thinking { text, signature } => ResponseItem::Reasoning {
+            id: String::new(),
+            summary: Vec::new(),
+            content: Some(vec![ReasoningItemContent::ReasoningText { text }]),
+            encrypted_content: (!signature.is_empty()).then_some(signature),
+        },
+        BlockState::ToolUse {
+            id,
+            name,
+            input_json,
+        } => ResponseItem::FunctionCall {
+            id: None,
+            name,
+            arguments: if input_json.trim().is_empty() {
+                "{}".to_string()
+            } else {
+                input_json
+            },
+            call_id: id,
+        },
+    }
+}
+
+fn map_stream_error(error: MessagesError) -> ApiError {
+    match error.kind.as_str() {
+        "invalid_request_error" if error.message.contains("prompt is too long") => {
+            ApiError::ContextWindowExceeded
+        }
+        "overloaded_error" | "api_error" | "rate_limit_error" => ApiError::Retryable {
+            message: error.message,
+            delay: None,
+        },
+        _ => ApiError::Stream(error.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_util::io::ReaderStream;
+
+    fn build_body(events: &[Value]) -> String {
+        let mut body = String::new();
+        for e in events {
+            let kind = e["type"].as_str().unwrap_or_default();
+            body.push_str(&format!("event: {kind}\ndata: {e}\n\n"));
+        }
+        body
+    }
+
+    async fn collect_events(body: &str) -> Vec<Result<ResponseEvent, ApiError>> {
+        let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
+            .map_err(|err| codex_client::TransportError::Network(err.to_string()));
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_messages_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+            None,
+        ));
+
+        let mut out = Vec::new();
+        while let Some(ev) = rx.recv().await {
+            out.push(ev);
+        }
+        out
+    }
+
+    fn message_start() -> Value {
+        json!({
+            "type": "message_start",
+            "message": {
+                "id": "msg_1",
+                "usage": {
+                    "input_tokens": 10,
+                    "cache_creation_input_tokens": 5,
+                    "cache_read_input_tokens": 100,
+                    "output_tokens": 1
+                }
+            }
+        })
+    }
+
+    fn message_end(stop_reason: &str) -> Vec<Value> {
+        vec![
+            json!({
+                "type": "message_delta",
+                "delta": {"stop_reason": stop_reason},
+                "usage": {"output_tokens": 42}
+            }),
+            json!({"type": "message_stop"}),
+        ]
+    }
+
+    #[tokio::test]
+    async fn streams_text_and_reports_usage() {
+        let mut events = vec![
+            message_start(),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "ping"}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hel"}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "lo"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+        ];
+        events.extend(message_end("end_turn"));
+
+        let events = collect_events(&build_body(&events)).await;
+        let events: Vec<ResponseEvent> = events
+            .into_iter()
+            .map(|ev| ev.expect("stream error"))
+            .collect();
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(first),
+                ResponseEvent::OutputTextDelta(second),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }),
+                ResponseEvent::Completed { response_id, token_usage: Some(usage) },
+            ] if first == "Hel"
+                && second == "lo"
+                && content == &vec![ContentItem::OutputText { text: "Hello".to_string() }]
+                && response_id == "msg_1"
+                && usage.input_tokens == 115
+                && usage.cached_input_tokens == 100
+                && usage.output_tokens == 42
+                && usage.total_tokens == 157
+        );
+    }
+
+    #[tokio::test]
+    async fn emits_thinking_and_tool_use_blocks() {
+        let mut events = vec![
+            message_start(),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "look around"}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig-1"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+        ];
+        events.extend(message_end("tool_use"));
+
+        let events = collect_events(&build_body(&events)).await;
+        let items: Vec<ResponseItem> = events
+            .into_iter()
+            .filter_map(|ev| match ev.expect("stream error") {
+                ResponseEvent::OutputItemDone(item) => Some(item),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            items,
+            vec![
+                ResponseItem::Reasoning {
+                    id: String::new(),
+                    summary: Vec::new(),
+                    content: Some(vec![ReasoningItemContent::ReasoningText {
+                        text: "look around".to_string(),
+                    }]),
+                    encrypted_content: Some("sig-1".to_string()),
+                },
+                ResponseItem::FunctionCall {
+                    id: None,
+                    name: "shell".to_string(),
+                    arguments: "{\"command\":[\"ls\"]}".to_string(),
+                    call_id: "toolu_1".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn maps_error_events() {
+        let events = collect_events(&build_body(&[
+            message_start(),
+            json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+        ]))
+        .await;
+
+        assert_matches!(
+            &events[..],
+            [
+                Ok(ResponseEvent::Created),
+                Err(ApiError::Retryable { message, delay: None }),
+            ] if message == "Overloaded"
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_when_stream_ends_before_message_stop() {
+        let events = collect_events(&build_body(&[message_start()])).await;
+
+        assert_matches!(
+            &events[..],
+            [Ok(ResponseEvent::Created), Err(ApiError::Stream(message))]
+                if message == "stream closed before message_stop"
+        );
+    }
+}
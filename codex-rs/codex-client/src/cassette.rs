@@ -0,0 +1,681 @@
+//! Record and replay of model traffic.
+//!
+//! A cassette is a JSONL file with one [`CassetteEntry`] per request. Entries
+//! are written when a response finishes, so streamed responses land in the
+//! file in completion order; `seq` preserves the order in which the requests
+//! were issued.
+
+use crate::error::TransportError;
+use crate::request::Request;
+use crate::request::Response;
+use crate::transport::ByteStream;
+use crate::transport::HttpTransport;
+use crate::transport::StreamResponse;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use futures::StreamExt;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use http::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Digest;
+use sha2::Sha256;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::task::Context;
+use std::task::Poll;
+use tracing::warn;
+
+/// Request body fields that change between otherwise identical runs and are
+/// therefore left out of the request hash.
+const VOLATILE_BODY_FIELDS: &[&str] = &["prompt_cache_key"];
+
+/// One recorded request and the response that was served for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteEntry {
+    pub seq: u64,
+    pub method: String,
+    pub path: String,
+    pub request_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedResponse {
+    /// A complete, non-streaming response body.
+    Unary {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    },
+    /// A streamed response, chunk by chunk. `error` is set when the stream
+    /// failed part-way through.
+    Stream {
+        status: u16,
+        headers: Vec<(String, String)>,
+        chunks: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// The provider answered with a non-success status.
+    HttpError {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: Option<String>,
+    },
+    /// The request failed before any response arrived.
+    TransportError { message: String },
+}
+
+/// Hash identifying a request independently of the host it was sent to.
+pub fn request_hash(req: &Request) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(req.method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(request_path(&req.url).as_bytes());
+    hasher.update(b"\n");
+    if let Some(body) = &req.body {
+        let mut body = body.clone();
+        if let Some(obj) = body.as_object_mut() {
+            for field in VOLATILE_BODY_FIELDS {
+                obj.remove(*field);
+            }
+        }
+        hasher.update(body.to_string().as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Path and query of `url`, without scheme and host.
+fn request_path(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .find('/')
+        .map_or("/", |idx| &without_scheme[idx..])
+}
+
+fn headers_to_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn pairs_to_headers(pairs: &[(String, String)]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::try_from(value.as_str()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers
+}
+
+fn status_from_u16(status: u16) -> StatusCode {
+    StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Appends entries to a cassette file. Shared by every transport that records
+/// into the same file so sequence numbers stay global.
+pub struct CassetteRecorder {
+    file: Mutex<File>,
+    next_seq: AtomicU64,
+}
+
+impl CassetteRecorder {
+    /// Creates (or truncates) the cassette at `path`.
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+            next_seq: AtomicU64::new(0),
+        })
+    }
+
+    fn next_seq(&self) -> u64 {
+        self.next_seq.fetch_add(1, Ordering::SeqCst)
+    }
+
+    fn write(&self, entry: &CassetteEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("failed to serialize cassette entry {}: {err}", entry.seq);
+                return;
+            }
+        };
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Err(err) = writeln!(file, "{line}").and_then(|()| file.flush()) {
+            warn!("failed to write cassette entry {}: {err}", entry.seq);
+        }
+    }
+}
+
+/// Request metadata captured before the response is known.
+struct PendingEntry {
+    recorder: Arc<CassetteRecorder>,
+    seq: u64,
+    method: String,
+    path: String,
+    request_hash: String,
+    request_body: Option<Value>,
+}
+
+impl PendingEntry {
+    fn new(recorder: &Arc<CassetteRecorder>, req: &Request) -> Self {
+        Self {
+            recorder: Arc::clone(recorder),
+            seq: recorder.next_seq(),
+            method: req.method.to_string(),
+            path: request_path(&req.url).to_string(),
+            request_hash: request_hash(req),
+            request_body: req.body.clone(),
+        }
+    }
+
+    fn finish(self, response: RecordedResponse) {
+        let PendingEntry {
+            recorder,
+            seq,
+            method,
+            path,
+            request_hash,
+            request_body,
+        } = self;
+        recorder.write(&CassetteEntry {
+            seq,
+            method,
+            path,
+            request_hash,
+            request_body,
+            response,
+        });
+    }
+
+    fn finish_with_error(self, err: &TransportError) {
+        let response = match err {
+            TransportError::Http {
+                status,
+                headers,
+                body,
+            } => RecordedResponse::HttpError {
+                status: status.as_u16(),
+                headers: headers.as_ref().map(headers_to_pairs).unwrap_or_default(),
+                body: body.clone(),
+            },
+            other => RecordedResponse::TransportError {
+                message: other.to_string(),
+            },
+        };
+        self.finish(response);
+    }
+}
+
+/// Transport that forwards to `inner` and records every exchange.
+pub struct RecordingTransport<T: HttpTransport> {
+    inner: T,
+    recorder: Arc<CassetteRecorder>,
+}
+
+impl<T: HttpTransport> RecordingTransport<T> {
+    pub fn new(inner: T, recorder: Arc<CassetteRecorder>) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for RecordingTransport<T> {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        let pending = PendingEntry::new(&self.recorder, &req);
+        match self.inner.execute(req).await {
+            Ok(resp) => {
+                pending.finish(RecordedResponse::Unary {
+                    status: resp.status.as_u16(),
+                    headers: headers_to_pairs(&resp.headers),
+                    body: String::from_utf8_lossy(&resp.body).into_owned(),
+                });
+                Ok(resp)
+            }
+            Err(err) => {
+                pending.finish_with_error(&err);
+                Err(err)
+            }
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        let pending = PendingEntry::new(&self.recorder, &req);
+        match self.inner.stream(req).await {
+            Ok(resp) => {
+                let recording = RecordingStream {
+                    inner: resp.bytes,
+                    status: resp.status.as_u16(),
+                    headers: headers_to_pairs(&resp.headers),
+                    chunks: Vec::new(),
+                    error: None,
+                    pending: Some(pending),
+                };
+                Ok(StreamResponse {
+                    status: resp.status,
+                    headers: resp.headers,
+                    bytes: Box::pin(recording),
+                })
+            }
+            Err(err) => {
+                pending.finish_with_error(&err);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Byte stream that keeps a copy of every chunk and writes the cassette entry
+/// once the stream ends or is dropped by the consumer.
+struct RecordingStream {
+    inner: ByteStream,
+    status: u16,
+    headers: Vec<(String, String)>,
+    chunks: Vec<String>,
+    error: Option<String>,
+    pending: Option<PendingEntry>,
+}
+
+impl RecordingStream {
+    fn finish(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.finish(RecordedResponse::Stream {
+                status: self.status,
+                headers: std::mem::take(&mut self.headers),
+                chunks: std::mem::take(&mut self.chunks),
+                error: self.error.take(),
+            });
+        }
+    }
+}
+
+impl Stream for RecordingStream {
+    type Item = Result<Bytes, TransportError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let polled = self.inner.poll_next_unpin(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(bytes))) => {
+                let chunk = String::from_utf8_lossy(bytes).into_owned();
+                self.chunks.push(chunk);
+            }
+            Poll::Ready(Some(Err(err))) => {
+                self.error = Some(err.to_string());
+                self.finish();
+            }
+            Poll::Ready(None) => self.finish(),
+            Poll::Pending => {}
+        }
+        polled
+    }
+}
+
+impl Drop for RecordingStream {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Transport that serves responses from a cassette instead of the network.
+///
+/// Each request takes the earliest unused entry with the same request hash.
+/// When the request changed (for example because a new build renders the
+/// prompt differently), it falls back to the earliest unused entry for the
+/// same method and path, i.e. plain sequence order.
+pub struct ReplayTransport {
+    state: Mutex<ReplayState>,
+}
+
+struct ReplayState {
+    entries: Vec<CassetteEntry>,
+    used: Vec<bool>,
+}
+
+impl ReplayTransport {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_jsonl(&contents)
+    }
+
+    pub fn from_jsonl(contents: &str) -> std::io::Result<Self> {
+        let mut entries = Vec::new();
+        for (idx, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: CassetteEntry = serde_json::from_str(line).map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid cassette entry on line {}: {err}", idx + 1),
+                )
+            })?;
+            entries.push(entry);
+        }
+        entries.sort_by_key(|entry| entry.seq);
+        let used = vec![false; entries.len()];
+        Ok(Self {
+            state: Mutex::new(ReplayState { entries, used }),
+        })
+    }
+
+    fn take(&self, req: &Request) -> Result<RecordedResponse, TransportError> {
+        let hash = request_hash(req);
+        let method = req.method.to_string();
+        let path = request_path(&req.url);
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| TransportError::Build("replay cassette lock poisoned".to_string()))?;
+
+        let unused = |state: &ReplayState, matches: &dyn Fn(&CassetteEntry) -> bool| {
+            state
+                .entries
+                .iter()
+                .enumerate()
+                .find(|(idx, entry)| !state.used[*idx] && matches(entry))
+                .map(|(idx, _)| idx)
+        };
+        let by_hash = unused(&state, &|entry| entry.request_hash == hash);
+        let index = match by_hash {
+            Some(idx) => Some(idx),
+            None => {
+                let idx = unused(&state, &|entry| {
+                    entry.method == method && entry.path == path
+                });
+                if let Some(idx) = idx {
+                    warn!(
+                        "replaying cassette entry {} for {method} {path} although the request changed",
+                        state.entries[idx].seq
+                    );
+                }
+                idx
+            }
+        };
+
+        let Some(index) = index else {
+            return Err(TransportError::Http {
+                status: StatusCode::NOT_FOUND,
+                headers: None,
+                body: Some(format!(
+                    "replay cassette has no recorded response left for {method} {path}"
+                )),
+            });
+        };
+        state.used[index] = true;
+        Ok(state.entries[index].response.clone())
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        match self.take(&req)? {
+            RecordedResponse::Unary {
+                status,
+                headers,
+                body,
+            } => Ok(Response {
+                status: status_from_u16(status),
+                headers: pairs_to_headers(&headers),
+                body: Bytes::from(body),
+            }),
+            RecordedResponse::Stream {
+                status,
+                headers,
+                chunks,
+                ..
+            } => Ok(Response {
+                status: status_from_u16(status),
+                headers: pairs_to_headers(&headers),
+                body: Bytes::from(chunks.concat()),
+            }),
+            RecordedResponse::HttpError {
+                status,
+                headers,
+                body,
+            } => Err(TransportError::Http {
+                status: status_from_u16(status),
+                headers: Some(pairs_to_headers(&headers)),
+                body,
+            }),
+            RecordedResponse::TransportError { message } => Err(TransportError::Network(message)),
+        }
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        let (status, headers, chunks, error) = match self.take(&req)? {
+            RecordedResponse::Stream {
+                status,
+                headers,
+                chunks,
+                error,
+            } => (status, headers, chunks, error),
+            RecordedResponse::Unary {
+                status,
+                headers,
+                body,
+            } => (status, headers, vec![body], None),
+            RecordedResponse::HttpError {
+                status,
+                headers,
+                body,
+            } => {
+                return Err(TransportError::Http {
+                    status: status_from_u16(status),
+                    headers: Some(pairs_to_headers(&headers)),
+                    body,
+                });
+            }
+            RecordedResponse::TransportError { message } => {
+                return Err(TransportError::Network(message));
+            }
+        };
+
+        let items = chunks
+            .into_iter()
+            .map(|chunk| Ok(Bytes::from(chunk)))
+            .chain(error.map(|message| Err(TransportError::Network(message))));
+        Ok(StreamResponse {
+            status: status_from_u16(status),
+            headers: pairs_to_headers(&headers),
+            bytes: Box::pin(futures::stream::iter(items.collect::<Vec<_>>())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    struct FakeTransport;
+
+    #[async_trait]
+    impl HttpTransport for FakeTransport {
+        async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+            if req.url.ends_with("/fail") {
+                return Err(TransportError::Http {
+                    status: StatusCode::TOO_MANY_REQUESTS,
+                    headers: None,
+                    body: Some("slow down".to_string()),
+                });
+            }
+            Ok(Response {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: Bytes::from_static(b"{\"ok\":true}"),
+            })
+        }
+
+        async fn stream(&self, _req: Request) -> Result<StreamResponse, TransportError> {
+            let chunks = vec![
+                Ok(Bytes::from_static(b"data: one\n\n")),
+                Ok(Bytes::from_static(b"data: two\n\n")),
+            ];
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from_static("req-1"));
+            Ok(StreamResponse {
+                status: StatusCode::OK,
+                headers,
+                bytes: Box::pin(futures::stream::iter(chunks)),
+            })
+        }
+    }
+
+    fn post(url: &str, body: Value) -> Request {
+        let mut req = Request::new(Method::POST, url.to_string());
+        req.body = Some(body);
+        req
+    }
+
+    async fn collect(resp: StreamResponse) -> Vec<String> {
+        resp.bytes
+            .map(|chunk| String::from_utf8_lossy(&chunk.expect("chunk")).into_owned())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn recorded_session_replays_in_order() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("session.jsonl");
+        let recorder = Arc::new(CassetteRecorder::create(&path).expect("recorder"));
+        let transport = RecordingTransport::new(FakeTransport, Arc::clone(&recorder));
+
+        let streamed = transport
+            .stream(post(
+                "http://127.0.0.1:1234/v1/responses",
+                json!({"input": "hi", "prompt_cache_key": "a"}),
+            ))
+            .await
+            .expect("stream");
+        assert_eq!(
+            collect(streamed).await,
+            vec!["data: one\n\n", "data: two\n\n"]
+        );
+        let err = transport
+            .execute(post("http://127.0.0.1:1234/v1/fail", json!({})))
+            .await
+            .expect_err("http error");
+        assert!(matches!(err, TransportError::Http { .. }));
+
+        let replay = ReplayTransport::open(&path).expect("replay");
+        // Host and volatile fields differ, the hash still matches.
+        let replayed = replay
+            .stream(post(
+                "http://localhost:9999/v1/responses",
+                json!({"input": "hi", "prompt_cache_key": "b"}),
+            ))
+            .await
+            .expect("replayed stream");
+        assert_eq!(
+            replayed.headers.get("x-request-id"),
+            Some(&HeaderValue::from_static("req-1"))
+        );
+        assert_eq!(
+            collect(replayed).await,
+            vec!["data: one\n\n", "data: two\n\n"]
+        );
+        match replay
+            .execute(post("http://localhost:9999/v1/fail", json!({})))
+            .await
+        {
+            Err(TransportError::Http { status, body, .. }) => {
+                assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+                assert_eq!(body.as_deref(), Some("slow down"));
+            }
+            other => panic!("expected recorded http error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_prefers_hash_and_falls_back_to_sequence() {
+        let entry = |seq: u64, body: Value, chunk: &str| {
+            let req = post("http://host/v1/responses", body.clone());
+            CassetteEntry {
+                seq,
+                method: "POST".to_string(),
+                path: "/v1/responses".to_string(),
+                request_hash: request_hash(&req),
+                request_body: Some(body),
+                response: RecordedResponse::Stream {
+                    status: 200,
+                    headers: Vec::new(),
+                    chunks: vec![chunk.to_string()],
+                    error: None,
+                },
+            }
+        };
+        let contents = [
+            entry(0, json!({"turn": 1}), "first"),
+            entry(1, json!({"turn": 2}), "second"),
+        ]
+        .iter()
+        .map(|entry| serde_json::to_string(entry).expect("serialize"))
+        .collect::<Vec<_>>()
+        .join("\n");
+        let replay = ReplayTransport::from_jsonl(&contents).expect("replay");
+
+        let second = replay
+            .stream(post("http://host/v1/responses", json!({"turn": 2})))
+            .await
+            .expect("hash match");
+        assert_eq!(collect(second).await, vec!["second"]);
+
+        let changed = replay
+            .stream(post("http://host/v1/responses", json!({"turn": "changed"})))
+            .await
+            .expect("sequence fallback");
+        assert_eq!(collect(changed).await, vec!["first"]);
+
+        match replay
+            .stream(post("http://host/v1/responses", json!({"turn": 3})))
+            .await
+        {
+            Err(TransportError::Http { status, .. }) => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+            }
+            Err(other) => panic!("expected exhausted cassette, got {other:?}"),
+            Ok(_) => panic!("expected exhausted cassette"),
+        }
+    }
+}
@@ -28,6 +28,17 @@ pub trait HttpTransport: Send + Sync {
     async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError>;
 }
 
+#[async_trait]
+impl<T: HttpTransport + ?Sized> HttpTransport for std::sync::Arc<T> {
+    async fn execute(&self, req: Request) -> Result<Response, TransportError> {
+        (**self).execute(req).await
+    }
+
+    async fn stream(&self, req: Request) -> Result<StreamResponse, TransportError> {
+        (**self).stream(req).await
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ReqwestTransport {
     client: CodexHttpClient,
@@ -18,8 +18,15 @@ pub struct ApprovalPreset {
 
 /// Built-in list of approval presets that pair approval and sandbox policy.
 ///
+/// `read_deny` and `network_allowlist` are the lists configured under
+/// `[sandbox_workspace_write]`, so that picking the workspace-write preset
+/// does not drop them.
+///
 /// Keep this UI-agnostic so it can be reused by both TUI and MCP server.
-pub fn builtin_approval_presets() -> Vec<ApprovalPreset> {
+pub fn builtin_approval_presets(
+    read_deny: &[String],
+    network_allowlist: &[String],
+) -> Vec<ApprovalPreset> {
     vec![
         ApprovalPreset {
             id: "read-only",
@@ -33,7 +40,14 @@ pub fn builtin_approval_presets() -> Vec<ApprovalPreset> {
             label: "Agent",
             description: "Read and edit files, and run commands.",
             approval: AskForApproval::OnRequest,
-            sandbox: SandboxPolicy::new_workspace_write_policy(),
+            sandbox: SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![],
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+                network_allowlist: network_allowlist.to_vec(),
+                read_deny: read_deny.to_vec(),
+            },
         },
         ApprovalPreset {
             id: "full-access",
@@ -44,3 +58,31 @@ pub fn builtin_approval_presets() -> Vec<ApprovalPreset> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_preset_keeps_configured_read_deny_and_network_allowlist() {
+        let read_deny = vec!["~/.ssh".to_string()];
+        let network_allowlist = vec!["crates.io".to_string()];
+        let presets = builtin_approval_presets(&read_deny, &network_allowlist);
+
+        let sandbox = |id: &str| {
+            presets
+                .iter()
+                .find(|preset| preset.id == id)
+                .map(|preset| preset.sandbox.clone())
+        };
+        let auto = sandbox("auto");
+        assert_eq!(
+            auto.as_ref().map(SandboxPolicy::read_deny),
+            Some(read_deny.as_slice())
+        );
+        assert_eq!(
+            auto.as_ref().map(SandboxPolicy::network_allowlist),
+            Some(network_allowlist.as_slice())
+        );
+    }
+}
@@ -36,6 +36,36 @@ pub async fn ensure_oss_provider_ready(
     Ok(())
 }
 
+/// Makes `model` ready on the local `provider_id` server before a running
+/// session switches to it. Ollama pulls the model when it is missing and LM
+/// Studio loads it; status lines are passed to `on_progress`.
+pub async fn ensure_oss_model_ready(
+    provider_id: &str,
+    model: &str,
+    config: &Config,
+    mut on_progress: impl FnMut(String) + Send + 'static,
+) -> Result<(), std::io::Error> {
+    match provider_id {
+        LMSTUDIO_OSS_PROVIDER_ID => {
+            let client = codex_lmstudio::LMStudioClient::try_from_provider(config).await?;
+            on_progress(format!("Loading {model} in LM Studio"));
+            client.load_model(model).await?;
+        }
+        OLLAMA_OSS_PROVIDER_ID => {
+            let client = codex_ollama::OllamaClient::try_from_oss_provider(config).await?;
+            let models = client.fetch_models().await?;
+            if !models.iter().any(|m| m == model) {
+                let mut reporter = codex_ollama::TuiProgressReporter::new(on_progress);
+                client.pull_with_reporter(model, &mut reporter).await?;
+            }
+        }
+        _ => {
+            // Not a local provider; nothing to prepare.
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
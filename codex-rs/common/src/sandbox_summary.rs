@@ -9,6 +9,8 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            network_allowlist,
+            read_deny: _,
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -29,6 +31,11 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !network_allowlist.is_empty() {
+                summary.push_str(&format!(
+                    " (network allowlist: {})",
+                    network_allowlist.join(", ")
+                ));
             }
             summary
         }
@@ -98,8 +98,16 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, mode } => FileChange::Add {
                 content: content.clone(),
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddBinary { contents, mode } => FileChange::AddBinary {
+                size: contents.len() as u64,
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddSymlink { target } => FileChange::AddSymlink {
+                target: target.clone(),
             },
             ApplyPatchFileChange::Delete { content } => FileChange::Delete {
                 content: content.clone(),
@@ -107,10 +115,14 @@ pub(crate) fn convert_apply_patch_to_protocol(
             ApplyPatchFileChange::Update {
                 unified_diff,
                 move_path,
+                mode,
                 new_content: _new_content,
+                fuzz_score,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
+                mode: *mode,
+                fuzz_score: *fuzz_score,
             },
         };
         result.insert(path.clone(), protocol_change);
@@ -137,7 +149,8 @@ mod tests {
         assert_eq!(
             got.get(&p),
             Some(&FileChange::Add {
-                content: "hello".to_string()
+                content: "hello".to_string(),
+                mode: None,
             })
         );
     }
@@ -6,9 +6,10 @@ use codex_api::AggregateStreamExt;
 use codex_api::ChatClient as ApiChatClient;
 use codex_api::CompactClient as ApiCompactClient;
 use codex_api::CompactionInput as ApiCompactionInput;
+use codex_api::MessagesClient as ApiMessagesClient;
+use codex_api::MessagesOptions as ApiMessagesOptions;
 use codex_api::Prompt as ApiPrompt;
 use codex_api::RequestTelemetry;
-use codex_api::ReqwestTransport;
 use codex_api::ResponseStream as ApiResponseStream;
 use codex_api::ResponsesClient as ApiResponsesClient;
 use codex_api::ResponsesOptions as ApiResponsesOptions;
@@ -17,6 +18,7 @@ use codex_api::TransportError;
 use codex_api::common::Reasoning;
 use codex_api::create_text_param_for_request;
 use codex_api::error::ApiError;
+use codex_api::requests::messages::DEFAULT_MAX_TOKENS as DEFAULT_MESSAGES_MAX_TOKENS;
 use codex_app_server_protocol::AuthMode;
 use codex_otel::otel_manager::OtelManager;
 use codex_protocol::ConversationId;
@@ -42,14 +44,16 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::model_cassette::model_transport;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_models::model_family::ModelFamily;
+use crate::tokenizer::Tokenizer;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
+use crate::tools::spec::create_tools_json_for_messages_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
 #[derive(Debug, Clone)]
@@ -107,8 +111,8 @@ impl ModelClient {
         &self.provider
     }
 
-    /// Streams a single model turn using either the Responses or Chat
-    /// Completions wire API, depending on the configured provider.
+    /// Streams a single model turn using the Responses, Chat Completions or
+    /// Messages wire API, depending on the configured provider.
     ///
     /// For Chat providers, the underlying stream is optionally aggregated
     /// based on the `show_raw_agent_reasoning` flag in the config.
@@ -130,6 +134,7 @@ impl ModelClient {
                     ))
                 }
             }
+            WireApi::Messages => self.stream_messages_api(prompt).await,
         }
     }
 
@@ -159,7 +164,7 @@ impl ModelClient {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = model_transport(&self.config)?;
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -186,6 +191,68 @@ impl ModelClient {
         }
     }
 
+    /// Streams a turn via the Anthropic Messages API.
+    ///
+    /// An explicitly configured reasoning effort enables extended thinking
+    /// with a matching token budget. Like Chat, `output_schema` is not
+    /// supported.
+    async fn stream_messages_api(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        if prompt.output_schema.is_some() {
+            return Err(CodexErr::UnsupportedOperation(
+                "output_schema is not supported for Messages API".to_string(),
+            ));
+        }
+
+        let auth_manager = self.auth_manager.clone();
+        let model_family = self.get_model_family();
+        let instructions = prompt.get_full_instructions(&model_family).into_owned();
+        let tools_json = create_tools_json_for_messages_api(&prompt.tools)?;
+        let api_prompt = build_api_prompt(prompt, instructions, tools_json);
+        let (max_tokens, thinking_budget) = messages_output_limits(
+            &self.get_model(),
+            self.effort.and_then(thinking_budget_for_effort),
+        );
+        let conversation_id = self.conversation_id.to_string();
+        let session_source = self.session_source.clone();
+
+        let mut refreshed = false;
+        loop {
+            let auth = auth_manager.as_ref().and_then(|m| m.auth());
+            let api_provider = self
+                .provider
+                .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
+            let transport = model_transport(&self.config)?;
+            let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
+            let client = ApiMessagesClient::new(transport, api_provider, api_auth)
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+
+            let options = ApiMessagesOptions {
+                max_tokens: Some(max_tokens),
+                thinking_budget,
+                conversation_id: Some(conversation_id.clone()),
+                session_source: Some(session_source.clone()),
+            };
+
+            let stream_result = client
+                .stream_prompt(&self.get_model(), &api_prompt, options)
+                .await;
+
+            match stream_result {
+                Ok(stream) => {
+                    return Ok(map_response_stream(stream, self.otel_manager.clone()));
+                }
+                Err(ApiError::Transport(TransportError::Http { status, .. }))
+                    if status == StatusCode::UNAUTHORIZED =>
+                {
+                    handle_unauthorized(status, &mut refreshed, &auth_manager, &auth).await?;
+                    continue;
+                }
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
+    }
+
     /// Streams a turn via the OpenAI Responses API.
     ///
     /// Handles SSE fixtures, reasoning summaries, verbosity, and the
@@ -248,7 +315,7 @@ impl ModelClient {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = model_transport(&self.config)?;
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiResponsesClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -304,6 +371,11 @@ impl ModelClient {
         self.model_family.clone()
     }
 
+    /// Returns the tokenizer of the current model family.
+    pub fn get_tokenizer(&self) -> Tokenizer {
+        self.model_family.tokenizer
+    }
+
     /// Returns the current reasoning effort setting.
     pub fn get_reasoning_effort(&self) -> Option<ReasoningEffortConfig> {
         self.effort
@@ -332,7 +404,7 @@ impl ModelClient {
             .provider
             .to_api_provider(auth.as_ref().map(|a| a.mode))?;
         let api_auth = auth_provider_from_auth(auth.clone(), &self.provider).await?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport = model_transport(&self.config)?;
         let request_telemetry = self.build_request_telemetry();
         let client = ApiCompactClient::new(transport, api_provider, api_auth)
             .with_telemetry(Some(request_telemetry));
@@ -385,6 +457,52 @@ impl ModelClient {
     }
 }
 
+/// Maps a reasoning effort onto an extended-thinking token budget for the
+/// Messages API. `none` and `minimal` leave thinking disabled.
+fn thinking_budget_for_effort(effort: ReasoningEffortConfig) -> Option<u64> {
+    match effort {
+        ReasoningEffortConfig::None | ReasoningEffortConfig::Minimal => None,
+        ReasoningEffortConfig::Low => Some(1_024),
+        ReasoningEffortConfig::Medium => Some(4_096),
+        ReasoningEffortConfig::High => Some(16_384),
+        ReasoningEffortConfig::XHigh => Some(32_768),
+    }
+}
+
+/// Output token ceilings of Anthropic models by slug prefix, most specific
+/// first. The Messages API rejects a `max_tokens` above the model's ceiling.
+const MESSAGES_MAX_OUTPUT_TOKENS: &[(&str, u64)] = &[
+    ("claude-3-5-", 8_192),
+    ("claude-3-7-sonnet", 64_000),
+    ("claude-3-", 4_096),
+    ("claude-opus-4-5", 64_000),
+    ("claude-opus-4", 32_000),
+    ("claude-sonnet-4", 64_000),
+    ("claude-haiku-4", 64_000),
+];
+
+/// Smallest extended-thinking budget the Messages API accepts.
+const MIN_THINKING_BUDGET: u64 = 1_024;
+
+/// Splits `model`'s output ceiling between the visible answer and the
+/// thinking budget; the request's `max_tokens` is their sum. A budget that no
+/// longer fits disables thinking. Models without a known ceiling get the
+/// default answer limit plus the full budget.
+fn messages_output_limits(model: &str, thinking_budget: Option<u64>) -> (u64, Option<u64>) {
+    let Some(ceiling) = MESSAGES_MAX_OUTPUT_TOKENS
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, ceiling)| *ceiling)
+    else {
+        return (DEFAULT_MESSAGES_MAX_TOKENS, thinking_budget);
+    };
+    let max_tokens = DEFAULT_MESSAGES_MAX_TOKENS.min(ceiling);
+    let thinking_budget = thinking_budget
+        .map(|budget| budget.min(ceiling - max_tokens))
+        .filter(|budget| *budget >= MIN_THINKING_BUDGET);
+    (max_tokens, thinking_budget)
+}
+
 /// Adapts the core `Prompt` type into the `codex-api` payload shape.
 fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value>) -> ApiPrompt {
     ApiPrompt {
@@ -535,3 +653,36 @@ impl SseTelemetry for ApiTelemetry {
         self.otel_manager.log_sse_event(result, duration);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn messages_output_limits_fit_the_model_ceiling() {
+        // Unknown models keep the default answer limit and the full budget.
+        assert_eq!(
+            messages_output_limits("custom-model", Some(32_768)),
+            (DEFAULT_MESSAGES_MAX_TOKENS, Some(32_768))
+        );
+        // The budget shrinks to what is left under the ceiling...
+        assert_eq!(
+            messages_output_limits("claude-opus-4-1-20250805", Some(32_768)),
+            (8_192, Some(23_808))
+        );
+        assert_eq!(
+            messages_output_limits("claude-sonnet-4-5", Some(16_384)),
+            (8_192, Some(16_384))
+        );
+        // ...and thinking is dropped when too little is left.
+        assert_eq!(
+            messages_output_limits("claude-3-5-haiku-latest", Some(1_024)),
+            (8_192, None)
+        );
+        assert_eq!(
+            messages_output_limits("claude-3-opus-20240229", None),
+            (4_096, None)
+        );
+    }
+}
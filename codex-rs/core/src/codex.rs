@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::Instant;
 
 use crate::AuthManager;
 use crate::SandboxState;
@@ -24,6 +25,7 @@ use crate::parse_turn_item;
 use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
+use crate::tasks::RedoEntry;
 use crate::terminal;
 use crate::truncate::TruncationPolicy;
 use crate::user_notification::UserNotifier;
@@ -33,11 +35,13 @@ use async_channel::Sender;
 use codex_protocol::ConversationId;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::items::TurnItem;
+use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
+use codex_protocol::protocol::ReviewFinding;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
@@ -61,6 +65,7 @@ use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::Instrument;
 use tracing::debug;
@@ -91,6 +96,7 @@ use crate::exec_policy::ExecPolicyUpdateError;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::network_proxy::NetworkProxyManager;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
@@ -102,6 +108,7 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::Op;
+use crate::protocol::ProviderFallbackEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -117,7 +124,12 @@ use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnEndSnapshotItem;
 use crate::protocol::WarningEvent;
+use crate::read_deny::ReadDenyManager;
+use crate::read_deny::ReadDenyMatcher;
+use crate::repo_map::RepoMap;
+use crate::repo_map::load_repo_map;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
@@ -134,6 +146,7 @@ use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::UndoTarget;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
@@ -148,6 +161,12 @@ use crate::user_notification::UserNotification;
 use crate::util::backoff;
 use codex_async_utils::OrCancelExt;
 use codex_execpolicy::Policy as ExecPolicy;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostCommit;
+use codex_git::GitToolingError;
+use codex_git::create_ghost_commit;
+use codex_git::record_ghost_ref;
+use codex_otel::metrics::WorktreeDriftNotice;
 use codex_otel::otel_manager::OtelManager;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::models::ContentItem;
@@ -174,6 +193,7 @@ pub struct Codex {
 pub struct CodexSpawnOk {
     pub codex: Codex,
     pub conversation_id: ConversationId,
+    pub(crate) session: Arc<Session>,
 }
 
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
@@ -269,6 +289,8 @@ impl Codex {
             original_config_do_not_use: Arc::clone(&config),
             exec_policy,
             session_source,
+            fallbacks_used: 0,
+            provider_override: None,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -292,7 +314,7 @@ impl Codex {
         let conversation_id = session.conversation_id;
 
         // This task will run until Op::Shutdown is received.
-        tokio::spawn(submission_loop(session, config, rx_sub));
+        tokio::spawn(submission_loop(Arc::clone(&session), config, rx_sub));
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
@@ -302,6 +324,7 @@ impl Codex {
         Ok(CodexSpawnOk {
             codex,
             conversation_id,
+            session,
         })
     }
 
@@ -351,6 +374,13 @@ pub(crate) struct Session {
     next_internal_sub_id: AtomicU64,
 
     pending_compaction_preview: Mutex<Option<CompactionPreview>>,
+    /// Undos that `Op::Redo` can reapply, most recent last.
+    redo_stack: Mutex<Vec<RedoEntry>>,
+    /// Repository map added to the initial context when `Feature::RepoMap`
+    /// is enabled; reloaded after compaction.
+    repo_map: std::sync::RwLock<Option<String>>,
+    /// Turn-end snapshot being captured in the background.
+    turn_end_capture: Mutex<Option<JoinHandle<()>>>,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -384,6 +414,12 @@ impl TurnContext {
             .map_or_else(|| self.cwd.clone(), |p| self.cwd.join(p))
     }
 
+    /// Matcher for the sandbox policy's `read_deny` list, used by the file
+    /// tools that read outside of the sandbox.
+    pub(crate) fn read_deny_matcher(&self) -> ReadDenyMatcher {
+        ReadDenyMatcher::new(self.sandbox_policy.read_deny(), &self.cwd)
+    }
+
     pub(crate) fn compact_prompt(&self) -> &str {
         self.compact_prompt
             .as_deref()
@@ -435,6 +471,14 @@ pub(crate) struct SessionConfiguration {
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
+
+    /// Number of entries of `fallback_providers` the session has switched
+    /// through; `provider` is the last one used.
+    fallbacks_used: usize,
+
+    /// Key of the provider chosen with `Op::OverrideTurnContext`, replacing
+    /// `model_provider_id` as the start of the fallback chain.
+    provider_override: Option<String>,
 }
 
 impl SessionConfiguration {
@@ -458,8 +502,38 @@ impl SessionConfiguration {
         if let Some(cwd) = updates.cwd.clone() {
             next_configuration.cwd = cwd;
         }
+        if let Some(provider_id) = updates.model_provider.as_ref() {
+            match next_configuration
+                .original_config_do_not_use
+                .model_providers
+                .get(provider_id)
+            {
+                Some(provider) => {
+                    next_configuration.provider = provider.clone();
+                    next_configuration.provider_override = (*provider_id
+                        != next_configuration
+                            .original_config_do_not_use
+                            .model_provider_id)
+                        .then(|| provider_id.clone());
+                    next_configuration.fallbacks_used = 0;
+                }
+                None => warn!("ignoring unknown model provider override: {provider_id}"),
+            }
+        }
         next_configuration
     }
+
+    /// Key of the provider currently in use in the `model_providers` map.
+    fn provider_id(&self) -> &str {
+        let config = &self.original_config_do_not_use;
+        match self.fallbacks_used.checked_sub(1) {
+            Some(index) => config.fallback_providers[index].model_provider_id.as_str(),
+            None => self
+                .provider_override
+                .as_deref()
+                .unwrap_or(config.model_provider_id.as_str()),
+        }
+    }
 }
 
 #[derive(Default, Clone)]
@@ -471,6 +545,8 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) reasoning_effort: Option<Option<ReasoningEffortConfig>>,
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
+    /// Key of `model_providers` to switch the session to.
+    pub(crate) model_provider: Option<String>,
 }
 
 impl Session {
@@ -550,6 +626,9 @@ impl Session {
             return None;
         }
 
+        self.services
+            .otel_manager
+            .worktree_drift_warning(WorktreeDriftNotice::SoftPause);
         Some(Self::format_worktree_change_soft_pause_message(
             &external_paths,
         ))
@@ -615,6 +694,9 @@ impl Session {
             return;
         }
 
+        self.services
+            .otel_manager
+            .worktree_drift_warning(WorktreeDriftNotice::Warning);
         let message = worktree_change_notice::format_warning_message(&external_paths);
         self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
             .await;
@@ -661,6 +743,148 @@ impl Session {
         *guard = None;
     }
 
+    pub(crate) async fn push_redo(&self, entry: RedoEntry) {
+        self.redo_stack.lock().await.push(entry);
+    }
+
+    /// Pops the most recent undo if it can still be redone, i.e. the history
+    /// is exactly as that undo left it. Otherwise the stack is stale and is
+    /// cleared.
+    pub(crate) async fn pop_redo(&self, history: &[ResponseItem]) -> Option<RedoEntry> {
+        let mut stack = self.redo_stack.lock().await;
+        match stack.pop() {
+            Some(entry) if entry.history_after == history => Some(entry),
+            _ => {
+                stack.clear();
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn redo_available(&self, history: &[ResponseItem]) -> bool {
+        self.redo_stack
+            .lock()
+            .await
+            .last()
+            .is_some_and(|entry| entry.history_after == history)
+    }
+
+    /// Captures the workspace as this turn left it, so undo can tell the
+    /// agent's edits apart from edits made outside the session afterwards.
+    /// The capture runs in the background so the turn completes right away;
+    /// the next turn's start snapshot and undo wait for it, which keeps the
+    /// next turn's edits out of it. Edits made outside the session while it
+    /// runs are attributed to this turn.
+    async fn capture_turn_end_snapshot(self: &Arc<Self>, turn_context: &Arc<TurnContext>) {
+        if !self.enabled(Feature::GhostCommit) {
+            return;
+        }
+        let sess = Arc::clone(self);
+        let turn_context = Arc::clone(turn_context);
+        let handle = tokio::spawn(async move {
+            sess.record_turn_end_snapshot(&turn_context).await;
+        });
+        // A capture still running from an earlier turn keeps going detached.
+        *self.turn_end_capture.lock().await = Some(handle);
+    }
+
+    async fn record_turn_end_snapshot(&self, turn_context: &TurnContext) {
+        // The turn-start snapshot is recorded asynchronously.
+        turn_context.tool_call_gate.wait_ready().await;
+        let history = self.clone_history().await.get_history();
+        let Some(start_id) = history.iter().rev().find_map(|item| match item {
+            ResponseItem::GhostSnapshot { ghost_commit, .. } => Some(ghost_commit.id().to_string()),
+            _ => None,
+        }) else {
+            return;
+        };
+        // Keep the first capture if this turn had no snapshot of its own.
+        if self.state.lock().await.has_turn_end_snapshot(&start_id) {
+            return;
+        }
+
+        let repo_path = turn_context.cwd.clone();
+        let conversation_id = self.conversation_id.to_string();
+        match tokio::task::spawn_blocking(move || {
+            let end = create_ghost_commit(&CreateGhostCommitOptions::new(&repo_path))?;
+            if let Err(err) = record_ghost_ref(&repo_path, &conversation_id, &end) {
+                warn!("failed to record turn-end snapshot ref: {err}");
+            }
+            Ok::<_, GitToolingError>(end)
+        })
+        .await
+        {
+            Ok(Ok(end)) => {
+                let recorded = self
+                    .state
+                    .lock()
+                    .await
+                    .record_turn_end_snapshot(start_id.clone(), end.clone());
+                if recorded {
+                    self.persist_rollout_items(&[RolloutItem::TurnEndSnapshot(
+                        TurnEndSnapshotItem {
+                            turn_start_id: start_id,
+                            ghost_commit: end,
+                        },
+                    )])
+                    .await;
+                }
+            }
+            Ok(Err(err)) => warn!("failed to capture turn-end snapshot: {err}"),
+            Err(err) => warn!("turn-end snapshot task panicked: {err}"),
+        }
+    }
+
+    /// Waits for the turn-end snapshot still being captured, if any.
+    pub(crate) async fn wait_for_turn_end_snapshot(&self) {
+        let pending = self.turn_end_capture.lock().await.take();
+        if let Some(handle) = pending
+            && let Err(err) = handle.await
+        {
+            warn!("turn-end snapshot task panicked: {err}");
+        }
+    }
+
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
+    pub(crate) async fn turn_end_snapshots(&self) -> HashMap<String, GhostCommit> {
+        self.state.lock().await.turn_end_snapshots()
+    }
+
+    pub(crate) async fn record_review_findings(
+        &self,
+        review_id: String,
+        findings: Vec<ReviewFinding>,
+    ) {
+        self.state
+            .lock()
+            .await
+            .record_review_findings(review_id, findings);
+    }
+
+    /// Resolves the findings a [`Op::FixReviewFindings`] refers to: the
+    /// review `review_id` (or the latest one) and the 1-based finding
+    /// `numbers` in it, all of them when empty. Returns the review id and the
+    /// numbered findings, or a message explaining why nothing matches.
+    pub(crate) async fn select_review_findings(
+        &self,
+        review_id: Option<&str>,
+        numbers: &[usize],
+    ) -> Result<(String, Vec<(usize, ReviewFinding)>), String> {
+        let review = self.state.lock().await.review_findings(review_id);
+        match review {
+            Some((review_id, all)) => {
+                select_review_findings(&all, numbers).map(|selected| (review_id, selected))
+            }
+            None => Err(match review_id {
+                Some(review_id) => format!("No completed review with id {review_id}."),
+                None => "No review findings to fix. Run /review first.".to_string(),
+            }),
+        }
+    }
+
     /// Don't expand the number of mutated arguments on config. We are in the process of getting rid of it.
     fn build_per_turn_config(session_configuration: &SessionConfiguration) -> Config {
         // todo(aibrahim): store this state somewhere else so we don't need to mut config
@@ -781,10 +1005,21 @@ impl Session {
             config.mcp_servers.iter(),
             config.mcp_oauth_credentials_store_mode,
         );
+        let repo_map_fut = async {
+            if config.features.enabled(Feature::RepoMap) {
+                load_repo_map(&config.codex_home, &session_configuration.cwd).await
+            } else {
+                None
+            }
+        };
 
         // Join all independent futures.
-        let (rollout_recorder, (history_log_id, history_entry_count), auth_statuses) =
-            tokio::join!(rollout_fut, history_meta_fut, auth_statuses_fut);
+        let (rollout_recorder, (history_log_id, history_entry_count), auth_statuses, repo_map) = tokio::join!(
+            rollout_fut,
+            history_meta_fut,
+            auth_statuses_fut,
+            repo_map_fut
+        );
 
         let rollout_recorder = rollout_recorder.map_err(|e| {
             error!("failed to initialize rollout recorder: {e:#}");
@@ -859,6 +1094,8 @@ impl Session {
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills: skills.clone(),
+            network_proxy: NetworkProxyManager::default(),
+            read_deny: ReadDenyManager::default(),
         };
 
         let sess = Arc::new(Session {
@@ -871,6 +1108,9 @@ impl Session {
             next_internal_sub_id: AtomicU64::new(0),
 
             pending_compaction_preview: Mutex::new(None),
+            redo_stack: Mutex::new(Vec::new()),
+            repo_map: std::sync::RwLock::new(repo_map),
+            turn_end_capture: Mutex::new(None),
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
@@ -1009,6 +1249,8 @@ impl Session {
                     self.record_into_history(&reconstructed_history, &turn_context)
                         .await;
                 }
+                self.restore_review_findings(&rollout_items).await;
+                self.restore_turn_end_snapshots(&rollout_items).await;
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
@@ -1020,6 +1262,35 @@ impl Session {
         }
     }
 
+    /// Rebuilds the findings of earlier reviews from their recorded
+    /// `ExitedReviewMode` events so they can still be fixed.
+    async fn restore_review_findings(&self, rollout_items: &[RolloutItem]) {
+        let mut state = self.state.lock().await;
+        for item in rollout_items {
+            if let RolloutItem::EventMsg(EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: Some(output),
+                review_id: Some(review_id),
+            })) = item
+            {
+                state.record_review_findings(review_id.clone(), output.findings.clone());
+            }
+        }
+    }
+
+    /// Reloads the turn-end snapshots recorded by the resumed session.
+    async fn restore_turn_end_snapshots(&self, rollout_items: &[RolloutItem]) {
+        let mut state = self.state.lock().await;
+        for item in rollout_items {
+            if let RolloutItem::TurnEndSnapshot(TurnEndSnapshotItem {
+                turn_start_id,
+                ghost_commit,
+            }) = item
+            {
+                state.record_turn_end_snapshot(turn_start_id.clone(), ghost_commit.clone());
+            }
+        }
+    }
+
     pub(crate) async fn update_settings(&self, updates: SessionSettingsUpdate) {
         let mut state = self.state.lock().await;
 
@@ -1351,10 +1622,14 @@ impl Session {
                             self.build_initial_context(turn_context),
                             &user_messages,
                             &compacted.message,
+                            turn_context.client.get_tokenizer(),
                         );
                         history.replace(rebuilt);
                     }
                 }
+                RolloutItem::HistoryRewritten(rewritten) => {
+                    history.replace(rewritten.replacement_history.clone());
+                }
                 _ => {}
             }
         }
@@ -1434,6 +1709,9 @@ impl Session {
                 .into(),
             );
         }
+        if let Some(text) = self.repo_map.read().ok().and_then(|map| map.clone()) {
+            items.push(RepoMap { text }.into());
+        }
         items.push(ResponseItem::from(EnvironmentContext::new(
             Some(turn_context.cwd.clone()),
             Some(turn_context.approval_policy),
@@ -1443,6 +1721,29 @@ impl Session {
         items
     }
 
+    /// Reloads the repository map for the turn's working directory so the
+    /// next [`Self::build_initial_context`] reflects the current commit.
+    pub(crate) async fn refresh_repo_map(&self, turn_context: &TurnContext) {
+        if !self.features.enabled(Feature::RepoMap) {
+            return;
+        }
+        let config = turn_context.client.config();
+        let repo_map = load_repo_map(&config.codex_home, &turn_context.cwd).await;
+        if let Ok(mut guard) = self.repo_map.write() {
+            *guard = repo_map;
+        }
+    }
+
+    /// Replaces the repository map messages in `items` with the current map,
+    /// adding it at the start when `items` has none.
+    pub(crate) fn replace_repo_map(&self, items: &mut Vec<ResponseItem>) {
+        let position = items.iter().position(RepoMap::is_repo_map_item);
+        items.retain(|item| !RepoMap::is_repo_map_item(item));
+        if let Some(text) = self.repo_map.read().ok().and_then(|map| map.clone()) {
+            items.insert(position.unwrap_or(0), RepoMap { text }.into());
+        }
+    }
+
     pub(crate) async fn persist_rollout_items(&self, items: &[RolloutItem]) {
         let recorder = {
             let guard = self.services.rollout.lock().await;
@@ -1472,11 +1773,39 @@ impl Session {
                     token_usage,
                     turn_context.client.get_model_context_window(),
                 );
+                state.record_turn_usage(
+                    &turn_context.sub_id,
+                    &turn_context.client.get_model(),
+                    token_usage,
+                );
             }
         }
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Note in the cost ledger that the conversation was compacted during
+    /// the current turn.
+    pub(crate) async fn mark_turn_compacted(&self, turn_context: &TurnContext) {
+        let mut state = self.state.lock().await;
+        state.mark_turn_compacted(&turn_context.sub_id);
+    }
+
+    /// Price the usage of the finished turn and record it in the rollout.
+    pub(crate) async fn record_turn_cost(&self, turn_context: &TurnContext) {
+        let pending = {
+            let mut state = self.state.lock().await;
+            state.take_turn_cost(&turn_context.sub_id)
+        };
+        let config = turn_context.client.config();
+        let Some(entry) = pending
+            .and_then(|pending| pending.finish(turn_context.sub_id.clone(), &config.model_prices))
+        else {
+            return;
+        };
+        self.send_event(turn_context, EventMsg::TurnCost(entry))
+            .await;
+    }
+
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
         let Some(estimated_total_tokens) = self
             .clone_history()
@@ -1527,6 +1856,14 @@ impl Session {
             let state = self.state.lock().await;
             state.token_info_and_rate_limits()
         };
+        if let Some(info) = info.as_ref()
+            && let Some(context_window) = info.model_context_window
+        {
+            turn_context.client.get_otel_manager().record_context_fill(
+                info.last_token_usage.tokens_in_context_window(),
+                context_window,
+            );
+        }
         let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
         self.send_event(turn_context, event).await;
     }
@@ -1585,6 +1922,64 @@ impl Session {
         self.send_event(turn_context, event).await;
     }
 
+    /// Switches the session to the next configured fallback provider when
+    /// `err` is one of the `fallback_on` triggers. Returns the turn context to
+    /// retry the turn with, or `None` when the error should be surfaced.
+    async fn fail_over_provider(
+        &self,
+        turn_context: &TurnContext,
+        err: &CodexErr,
+    ) -> Option<Arc<TurnContext>> {
+        let (from_provider, from_model, to_provider, reason) = {
+            let mut state = self.state.lock().await;
+            let configuration = &mut state.session_configuration;
+            let config = Arc::clone(&configuration.original_config_do_not_use);
+            let reason = err
+                .fallback_trigger()
+                .filter(|trigger| config.fallback_on.contains(trigger))?;
+            let fallback = config
+                .fallback_providers
+                .get(configuration.fallbacks_used)?;
+            let from_provider = configuration.provider_id().to_string();
+            let from_model = configuration.model.clone();
+            configuration.fallbacks_used += 1;
+            configuration.provider = fallback.model_provider.clone();
+            if let Some(model) = &fallback.model {
+                configuration.model = model.clone();
+            }
+            (
+                from_provider,
+                from_model,
+                fallback.model_provider_id.clone(),
+                reason,
+            )
+        };
+
+        let next_turn_context = self
+            .new_turn_with_sub_id(
+                turn_context.sub_id.clone(),
+                SessionSettingsUpdate {
+                    final_output_json_schema: Some(turn_context.final_output_json_schema.clone()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        warn!(
+            "switching from provider {from_provider} to {to_provider} after {reason} failure: {err}"
+        );
+        let event = EventMsg::ProviderFallback(ProviderFallbackEvent {
+            from_provider,
+            from_model,
+            to_provider,
+            to_model: next_turn_context.client.get_model(),
+            reason,
+            message: err.to_string(),
+            model_context_window: next_turn_context.client.get_model_context_window(),
+        });
+        self.send_event(&next_turn_context, event).await;
+        Some(next_turn_context)
+    }
+
     async fn maybe_start_ghost_snapshot(
         self: &Arc<Self>,
         turn_context: Arc<TurnContext>,
@@ -1745,9 +2140,11 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 model,
                 effort,
                 summary,
+                model_provider,
             } => {
                 handlers::override_turn_context(
                     &sess,
+                    sub.id.clone(),
                     SessionSettingsUpdate {
                         cwd,
                         approval_policy,
@@ -1755,6 +2152,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                         model,
                         reasoning_effort: effort,
                         reasoning_summary: summary,
+                        model_provider,
                         ..Default::default()
                     },
                 )
@@ -1784,7 +2182,31 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
             Op::Undo => {
-                handlers::undo(&sess, sub.id.clone()).await;
+                handlers::undo(&sess, sub.id.clone(), UndoTarget::Latest).await;
+            }
+            Op::UndoTo { snapshot_id } => {
+                handlers::undo(&sess, sub.id.clone(), UndoTarget::Snapshot(snapshot_id)).await;
+            }
+            Op::UndoTurns { turns } => {
+                handlers::undo(&sess, sub.id.clone(), UndoTarget::Turns(turns)).await;
+            }
+            Op::Redo => {
+                handlers::redo(&sess, sub.id.clone()).await;
+            }
+            Op::ListUndoSnapshots => {
+                handlers::list_undo_snapshots(&sess, sub.id.clone()).await;
+            }
+            Op::ListExecSessions => {
+                handlers::list_exec_sessions(&sess, sub.id.clone()).await;
+            }
+            Op::GetExecSessionOutput { process_id } => {
+                handlers::get_exec_session_output(&sess, sub.id.clone(), process_id).await;
+            }
+            Op::WriteExecSessionStdin { process_id, input } => {
+                handlers::write_exec_session_stdin(&sess, sub.id.clone(), process_id, input).await;
+            }
+            Op::KillExecSession { process_id } => {
+                handlers::kill_exec_session(&sess, sub.id.clone(), process_id).await;
             }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
@@ -1846,6 +2268,19 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::FixReviewFindings {
+                review_id,
+                findings,
+            } => {
+                handlers::fix_review_findings(
+                    &sess,
+                    sub.id.clone(),
+                    review_id,
+                    findings,
+                    &mut previous_context,
+                )
+                .await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -1862,9 +2297,12 @@ mod handlers {
     use crate::config::Config;
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
+    use crate::review_format::format_review_fix_request;
     use crate::review_prompts::resolve_review_request;
     use crate::tasks::CompactTask;
+    use crate::tasks::RedoTask;
     use crate::tasks::RegularTask;
+    use crate::tasks::UndoTarget;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
@@ -1872,13 +2310,18 @@ mod handlers {
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::ExecSessionOutputResponseEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::ListExecSessionsResponseEvent;
+    use codex_protocol::protocol::ListUndoSnapshotsResponseEvent;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
+    use codex_protocol::protocol::ReviewFixStartedEvent;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::SessionSource;
     use codex_protocol::protocol::TurnAbortReason;
     use codex_protocol::protocol::WarningEvent;
+    use codex_protocol::protocol::review_finding_id;
 
     use codex_protocol::user_input::UserInput;
     use codex_rmcp_client::ElicitationAction;
@@ -1892,7 +2335,32 @@ mod handlers {
         sess.interrupt_task().await;
     }
 
-    pub async fn override_turn_context(sess: &Session, updates: SessionSettingsUpdate) {
+    pub async fn override_turn_context(
+        sess: &Session,
+        sub_id: String,
+        updates: SessionSettingsUpdate,
+    ) {
+        if let Some(provider_id) = updates.model_provider.as_ref() {
+            let known_provider = sess
+                .state
+                .lock()
+                .await
+                .session_configuration
+                .original_config_do_not_use
+                .model_providers
+                .contains_key(provider_id);
+            if !known_provider {
+                let event = Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("Unknown model provider `{provider_id}`."),
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                };
+                sess.send_event_raw(event).await;
+                return;
+            }
+        }
         sess.update_settings(updates).await;
     }
 
@@ -1924,6 +2392,7 @@ mod handlers {
                     reasoning_effort: Some(effort),
                     reasoning_summary: Some(summary),
                     final_output_json_schema: Some(final_output_json_schema),
+                    ..Default::default()
                 },
             ),
             Op::UserInput { items } => (items, SessionSettingsUpdate::default()),
@@ -2132,14 +2601,108 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn undo(sess: &Arc<Session>, sub_id: String) {
+    pub async fn undo(sess: &Arc<Session>, sub_id: String, target: UndoTarget) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
             .await;
-        sess.spawn_task(turn_context, Vec::new(), UndoTask::new())
+        sess.spawn_task(turn_context, Vec::new(), UndoTask::new(target))
             .await;
     }
 
+    pub async fn redo(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
+            .await;
+        sess.spawn_task(turn_context, Vec::new(), RedoTask).await;
+    }
+
+    pub async fn list_undo_snapshots(sess: &Session, sub_id: String) {
+        let history = sess.clone_history().await.get_history();
+        let redo_available = sess.redo_available(&history).await;
+        let snapshots = crate::tasks::list_undo_snapshots(&history)
+            .into_iter()
+            .map(|entry| entry.snapshot)
+            .collect();
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListUndoSnapshotsResponse(ListUndoSnapshotsResponseEvent {
+                snapshots,
+                redo_available,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn list_exec_sessions(sess: &Session, sub_id: String) {
+        let sessions = sess.services.unified_exec_manager.list_sessions().await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListExecSessionsResponse(ListExecSessionsResponseEvent { sessions }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn get_exec_session_output(sess: &Session, sub_id: String, process_id: String) {
+        let msg = match sess
+            .services
+            .unified_exec_manager
+            .session_output(&process_id)
+            .await
+        {
+            Ok(output) => EventMsg::ExecSessionOutputResponse(ExecSessionOutputResponseEvent {
+                process_id,
+                output,
+            }),
+            Err(err) => exec_session_error(err),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn write_exec_session_stdin(
+        sess: &Session,
+        sub_id: String,
+        process_id: String,
+        input: String,
+    ) {
+        if let Err(err) = sess
+            .services
+            .unified_exec_manager
+            .write_to_session(&process_id, &input)
+            .await
+        {
+            let event = Event {
+                id: sub_id,
+                msg: exec_session_error(err),
+            };
+            sess.send_event_raw(event).await;
+        }
+    }
+
+    pub async fn kill_exec_session(sess: &Session, sub_id: String, process_id: String) {
+        if let Err(err) = sess
+            .services
+            .unified_exec_manager
+            .kill_session(&process_id)
+            .await
+        {
+            let event = Event {
+                id: sub_id,
+                msg: exec_session_error(err),
+            };
+            sess.send_event_raw(event).await;
+            return;
+        }
+        list_exec_sessions(sess, sub_id).await;
+    }
+
+    fn exec_session_error(err: crate::unified_exec::UnifiedExecError) -> EventMsg {
+        EventMsg::Error(ErrorEvent {
+            message: err.to_string(),
+            codex_error_info: Some(CodexErrorInfo::Other),
+        })
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         // Manual /compact invalidates any pending preview.
         sess.clear_pending_compaction_preview().await;
@@ -2352,6 +2915,104 @@ mod handlers {
             }
         }
     }
+
+    /// Starts a regular turn seeded with the selected findings of a completed
+    /// review. `findings` holds 1-based finding numbers; empty selects all.
+    pub async fn fix_review_findings(
+        sess: &Arc<Session>,
+        sub_id: String,
+        review_id: Option<String>,
+        findings: Vec<usize>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let selected = sess
+            .select_review_findings(review_id.as_deref(), &findings)
+            .await;
+        let (review_id, selected) = match selected {
+            Ok(selected) => selected,
+            Err(message) => {
+                let turn_context = sess
+                    .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
+                    .await;
+                sess.send_event(
+                    &turn_context,
+                    EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: Some(CodexErrorInfo::Other),
+                    }),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let text = format_review_fix_request(
+            &review_id,
+            &selected
+                .iter()
+                .map(|(number, finding)| (*number, finding))
+                .collect::<Vec<_>>(),
+        );
+        let finding_ids = selected
+            .iter()
+            .map(|(number, _)| review_finding_id(&review_id, *number))
+            .collect();
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id.clone(), SessionSettingsUpdate::default())
+            .await;
+        sess.send_event(
+            &turn_context,
+            EventMsg::ReviewFixStarted(ReviewFixStartedEvent {
+                review_id,
+                finding_ids,
+            }),
+        )
+        .await;
+
+        user_input_or_turn(
+            sess,
+            sub_id,
+            Op::UserInput {
+                items: vec![UserInput::Text { text }],
+            },
+            previous_context,
+        )
+        .await;
+    }
+}
+
+/// Picks the findings numbered `numbers` (1-based) out of `all`, or every
+/// finding when `numbers` is empty.
+fn select_review_findings(
+    all: &[ReviewFinding],
+    numbers: &[usize],
+) -> Result<Vec<(usize, ReviewFinding)>, String> {
+    if all.is_empty() {
+        return Err("The review reported no findings to fix.".to_string());
+    }
+    if numbers.is_empty() {
+        return Ok(all
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(idx, f)| (idx + 1, f))
+            .collect());
+    }
+    numbers
+        .iter()
+        .map(|&number| {
+            number
+                .checked_sub(1)
+                .and_then(|idx| all.get(idx))
+                .map(|finding| (number, finding.clone()))
+                .ok_or_else(|| {
+                    format!(
+                        "The review has {} finding(s); there is no finding {number}.",
+                        all.len()
+                    )
+                })
+        })
+        .collect()
 }
 
 /// Spawn a review thread using the given prompt.
@@ -2438,6 +3099,7 @@ async fn spawn_review_thread(
     let review_request = ReviewRequest {
         target: resolved.target,
         user_facing_hint: Some(resolved.user_facing_hint),
+        paths: resolved.paths,
     };
     sess.send_event(&tc, EventMsg::EnteredReviewMode(review_request))
         .await;
@@ -2483,7 +3145,7 @@ fn skill_load_outcome_for_client(
 ///
 pub(crate) async fn run_task(
     sess: Arc<Session>,
-    turn_context: Arc<TurnContext>,
+    mut turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
     cancellation_token: CancellationToken,
 ) -> Option<String> {
@@ -2562,25 +3224,8 @@ pub(crate) async fn run_task(
                     needs_follow_up,
                     last_agent_message: turn_last_agent_message,
                 } = turn_output;
-                let limit = turn_context
-                    .client
-                    .get_model_family()
-                    .auto_compact_token_limit()
-                    .unwrap_or(i64::MAX);
-                let total_usage_tokens = sess.get_total_token_usage().await;
-                let token_limit_reached = total_usage_tokens >= limit;
-
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
-                if token_limit_reached {
-                    if should_use_remote_compact_task(
-                        sess.as_ref(),
-                        &turn_context.client.get_provider(),
-                    ) {
-                        run_inline_remote_auto_compact_task(sess.clone(), turn_context.clone())
-                            .await;
-                    } else {
-                        run_inline_auto_compact_task(sess.clone(), turn_context.clone()).await;
-                    }
+                if auto_compact_if_over_limit(&sess, &turn_context).await {
                     continue;
                 }
 
@@ -2610,6 +3255,12 @@ pub(crate) async fn run_task(
                 state.history.replace_last_turn_images("Invalid image");
             }
             Err(e) => {
+                if let Some(next_turn_context) = sess.fail_over_provider(&turn_context, &e).await {
+                    turn_context = next_turn_context;
+                    // The fallback model may have a smaller context window.
+                    auto_compact_if_over_limit(&sess, &turn_context).await;
+                    continue;
+                }
                 info!("Turn error: {e:#}");
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
@@ -2619,9 +3270,31 @@ pub(crate) async fn run_task(
         }
     }
 
+    sess.capture_turn_end_snapshot(&turn_context).await;
     last_agent_message
 }
 
+/// Compacts the conversation when its token usage reached the auto-compact
+/// limit of the turn's model. Returns whether a compaction ran.
+async fn auto_compact_if_over_limit(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) -> bool {
+    let limit = turn_context
+        .client
+        .get_model_family()
+        .auto_compact_token_limit()
+        .unwrap_or(i64::MAX);
+    let total_usage_tokens = sess.get_total_token_usage().await;
+    if total_usage_tokens < limit {
+        return false;
+    }
+
+    if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
+        run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
+    } else {
+        run_inline_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
+    }
+    true
+}
+
 #[instrument(
     skip_all,
     fields(
@@ -2787,6 +3460,7 @@ async fn try_run_turn(
     });
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    let request_started_at = Instant::now();
     let mut stream = turn_context
         .client
         .clone()
@@ -2806,6 +3480,7 @@ async fn try_run_turn(
     let mut needs_follow_up = false;
     let mut last_agent_message: Option<String> = None;
     let mut active_item: Option<TurnItem> = None;
+    let mut first_output_seen = false;
     let receiving_span = info_span!("receiving_stream");
     let outcome: CodexResult<TurnRunResult> = loop {
         let handle_responses = info_span!(
@@ -2839,6 +3514,20 @@ async fn try_run_turn(
         sess.services
             .otel_manager
             .record_responses(&handle_responses, &event);
+        if !first_output_seen
+            && !matches!(
+                event,
+                ResponseEvent::Created
+                    | ResponseEvent::RateLimits(_)
+                    | ResponseEvent::Completed { .. }
+            )
+        {
+            first_output_seen = true;
+            turn_context
+                .client
+                .get_otel_manager()
+                .record_time_to_first_token(request_started_at.elapsed());
+        }
 
         match event {
             ResponseEvent::Created => {}
@@ -3012,6 +3701,7 @@ mod tests {
     use crate::protocol::RateLimitSnapshot;
     use crate::protocol::RateLimitWindow;
     use crate::protocol::ResumedHistory;
+    use crate::state::MAX_TURN_END_SNAPSHOTS;
     use crate::state::TaskKind;
     use crate::tasks::SessionTask;
     use crate::tasks::SessionTaskContext;
@@ -3066,6 +3756,119 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn replace_repo_map_swaps_in_the_current_map() {
+        let (session, _turn_context) = make_session_and_context();
+        let user_message = |text: &str| ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        };
+        let mut items = vec![
+            user_message("instructions"),
+            RepoMap {
+                text: "old map".to_string(),
+            }
+            .into(),
+            user_message("hello"),
+        ];
+        *session.repo_map.write().expect("repo map lock") = Some("new map".to_string());
+
+        session.replace_repo_map(&mut items);
+
+        let new_map: ResponseItem = RepoMap {
+            text: "new map".to_string(),
+        }
+        .into();
+        assert_eq!(
+            items,
+            vec![
+                user_message("instructions"),
+                new_map.clone(),
+                user_message("hello")
+            ]
+        );
+
+        let mut without_map = vec![user_message("hello")];
+        session.replace_repo_map(&mut without_map);
+        assert_eq!(without_map, vec![new_map, user_message("hello")]);
+    }
+
+    #[test]
+    fn record_initial_history_restores_review_findings() {
+        let (session, _turn_context) = make_session_and_context();
+        let finding = ReviewFinding {
+            title: "Unchecked unwrap".to_string(),
+            body: "This panics on malformed input.".to_string(),
+            confidence_score: 0.8,
+            priority: 1,
+            code_location: codex_protocol::protocol::ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/tmp/parse.rs"),
+                line_range: codex_protocol::protocol::ReviewLineRange { start: 3, end: 4 },
+            },
+        };
+        let rollout_items = vec![RolloutItem::EventMsg(EventMsg::ExitedReviewMode(
+            ExitedReviewModeEvent {
+                review_output: Some(codex_protocol::protocol::ReviewOutputEvent {
+                    findings: vec![finding.clone()],
+                    ..Default::default()
+                }),
+                review_id: Some("7".to_string()),
+            },
+        ))];
+
+        tokio_test::block_on(session.record_initial_history(InitialHistory::Resumed(
+            ResumedHistory {
+                conversation_id: ConversationId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            },
+        )));
+
+        let selected = tokio_test::block_on(session.select_review_findings(Some("7"), &[1]));
+        assert_eq!(selected, Ok(("7".to_string(), vec![(1, finding)])));
+    }
+
+    #[test]
+    fn record_initial_history_restores_bounded_turn_end_snapshots() {
+        let (session, _turn_context) = make_session_and_context();
+        let ghost_commit =
+            |id: &str| GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new());
+        let rollout_items: Vec<RolloutItem> = (0..=MAX_TURN_END_SNAPSHOTS)
+            .map(|idx| {
+                RolloutItem::TurnEndSnapshot(TurnEndSnapshotItem {
+                    turn_start_id: format!("start-{idx}"),
+                    ghost_commit: ghost_commit(&format!("end-{idx}")),
+                })
+            })
+            .collect();
+
+        tokio_test::block_on(session.record_initial_history(InitialHistory::Resumed(
+            ResumedHistory {
+                conversation_id: ConversationId::default(),
+                history: rollout_items,
+                rollout_path: PathBuf::from("/tmp/resume.jsonl"),
+            },
+        )));
+
+        let turn_ends = tokio_test::block_on(session.turn_end_snapshots());
+        assert_eq!(turn_ends.len(), MAX_TURN_END_SNAPSHOTS);
+        assert!(!turn_ends.contains_key("start-0"));
+        assert_eq!(turn_ends.get("start-1").map(GhostCommit::id), Some("end-1"));
+
+        // A later capture for the same turn keeps the first one.
+        let recorded = tokio_test::block_on(async {
+            session
+                .state
+                .lock()
+                .await
+                .record_turn_end_snapshot("start-1".to_string(), ghost_commit("later"))
+        });
+        assert!(!recorded);
+    }
+
     #[test]
     fn record_initial_history_reconstructs_forked_transcript() {
         let (session, turn_context) = make_session_and_context();
@@ -3079,6 +3882,56 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn provider_override_switches_back_to_configured_provider() {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load default test config");
+        let config = Arc::new(config);
+        let model = ModelsManager::get_model_offline(config.model.as_deref());
+        let session_configuration = SessionConfiguration {
+            provider: config.model_provider.clone(),
+            model,
+            model_reasoning_effort: config.model_reasoning_effort,
+            model_reasoning_summary: config.model_reasoning_summary,
+            developer_instructions: config.developer_instructions.clone(),
+            user_instructions: config.user_instructions.clone(),
+            base_instructions: config.base_instructions.clone(),
+            compact_prompt: config.compact_prompt.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            cwd: config.cwd.clone(),
+            original_config_do_not_use: Arc::clone(&config),
+            exec_policy: Arc::new(RwLock::new(ExecPolicy::empty())),
+            session_source: SessionSource::Exec,
+            fallbacks_used: 0,
+            provider_override: None,
+        };
+
+        let local = session_configuration.apply(&SessionSettingsUpdate {
+            model: Some("qwen3-coder:30b".to_string()),
+            model_provider: Some(crate::OLLAMA_OSS_PROVIDER_ID.to_string()),
+            ..Default::default()
+        });
+        assert_eq!(local.provider_id(), crate::OLLAMA_OSS_PROVIDER_ID);
+        assert_eq!(
+            local.provider,
+            config.model_providers[crate::OLLAMA_OSS_PROVIDER_ID]
+        );
+
+        let restored = local.apply(&SessionSettingsUpdate {
+            model_provider: Some(config.model_provider_id.clone()),
+            ..Default::default()
+        });
+        assert_eq!(restored.provider_id(), config.model_provider_id);
+        assert_eq!(restored.provider, config.model_provider);
+        assert_eq!(restored.provider_override, None);
+    }
+
     #[test]
     fn set_rate_limits_retains_previous_credits() {
         let codex_home = tempfile::tempdir().expect("create temp dir");
@@ -3105,6 +3958,8 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             exec_policy: Arc::new(RwLock::new(ExecPolicy::empty())),
             session_source: SessionSource::Exec,
+            fallbacks_used: 0,
+            provider_override: None,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -3177,6 +4032,8 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             exec_policy: Arc::new(RwLock::new(ExecPolicy::empty())),
             session_source: SessionSource::Exec,
+            fallbacks_used: 0,
+            provider_override: None,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -3381,6 +4238,8 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             exec_policy: Arc::new(RwLock::new(ExecPolicy::empty())),
             session_source: SessionSource::Exec,
+            fallbacks_used: 0,
+            provider_override: None,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_family = ModelsManager::construct_model_family_offline(
@@ -3409,6 +4268,8 @@ mod tests {
             models_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills: None,
+            network_proxy: NetworkProxyManager::default(),
+            read_deny: ReadDenyManager::default(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -3432,6 +4293,9 @@ mod tests {
             next_internal_sub_id: AtomicU64::new(0),
 
             pending_compaction_preview: Mutex::new(None),
+            redo_stack: Mutex::new(Vec::new()),
+            repo_map: std::sync::RwLock::new(None),
+            turn_end_capture: Mutex::new(None),
         };
 
         (session, turn_context)
@@ -3473,6 +4337,8 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             exec_policy: Arc::new(RwLock::new(ExecPolicy::empty())),
             session_source: SessionSource::Exec,
+            fallbacks_used: 0,
+            provider_override: None,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_family = ModelsManager::construct_model_family_offline(
@@ -3501,6 +4367,8 @@ mod tests {
             models_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
             skills: None,
+            network_proxy: NetworkProxyManager::default(),
+            read_deny: ReadDenyManager::default(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -3524,6 +4392,9 @@ mod tests {
             next_internal_sub_id: AtomicU64::new(0),
 
             pending_compaction_preview: Mutex::new(None),
+            redo_stack: Mutex::new(Vec::new()),
+            repo_map: std::sync::RwLock::new(None),
+            turn_end_capture: Mutex::new(None),
         });
 
         (session, turn_context, rx_event)
@@ -3783,6 +4654,7 @@ mod tests {
             session.build_initial_context(turn_context),
             &user_messages1,
             summary1,
+            turn_context.client.get_tokenizer(),
         );
         live_history.replace(rebuilt1);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
@@ -3817,6 +4689,7 @@ mod tests {
             session.build_initial_context(turn_context),
             &user_messages2,
             summary2,
+            turn_context.client.get_tokenizer(),
         );
         live_history.replace(rebuilt2);
         rollout_items.push(RolloutItem::Compacted(CompactedItem {
@@ -1,22 +1,26 @@
 use crate::codex::Codex;
+use crate::codex::Session;
 use crate::error::Result as CodexResult;
 use crate::protocol::Event;
 use crate::protocol::Op;
 use crate::protocol::Submission;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct CodexConversation {
     codex: Codex,
     rollout_path: PathBuf,
+    session: Arc<Session>,
 }
 
 /// Conduit for the bidirectional stream of messages that compose a conversation
 /// in Codex.
 impl CodexConversation {
-    pub(crate) fn new(codex: Codex, rollout_path: PathBuf) -> Self {
+    pub(crate) fn new(codex: Codex, rollout_path: PathBuf, session: Arc<Session>) -> Self {
         Self {
             codex,
             rollout_path,
+            session,
         }
     }
 
@@ -33,6 +37,20 @@ impl CodexConversation {
         self.codex.next_event().await
     }
 
+    /// Checks that [`Op::FixReviewFindings`] with these arguments names a
+    /// completed review and existing findings, so callers can reject the
+    /// request before a turn starts. The error is a user-facing message.
+    pub async fn check_review_fix(
+        &self,
+        review_id: Option<&str>,
+        findings: &[usize],
+    ) -> Result<(), String> {
+        self.session
+            .select_review_findings(review_id, findings)
+            .await
+            .map(|_| ())
+    }
+
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::ModelProviderInfo;
 use crate::Prompt;
@@ -15,10 +16,12 @@ use crate::protocol::EventMsg;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
 use crate::protocol::WarningEvent;
+use crate::tokenizer::Tokenizer;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::truncate_text;
 use crate::util::backoff;
+use codex_otel::metrics::CompactionMode;
+use codex_otel::metrics::CompactionTrigger;
 use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
@@ -79,7 +82,14 @@ pub(crate) async fn run_inline_auto_compact_task(
     let prompt = turn_context.compact_prompt().to_string();
     let input = vec![UserInput::Text { text: prompt }];
 
-    run_compact_task_inner(sess, turn_context, input).await;
+    let started_at = Instant::now();
+    if run_compact_task_inner(sess, Arc::clone(&turn_context), input).await {
+        turn_context.client.get_otel_manager().record_compaction(
+            CompactionTrigger::Auto,
+            CompactionMode::Local,
+            started_at.elapsed(),
+        );
+    }
 }
 
 pub(crate) async fn run_compact_task(
@@ -91,14 +101,23 @@ pub(crate) async fn run_compact_task(
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, start_event).await;
-    run_compact_task_inner(sess.clone(), turn_context, input).await;
+    let started_at = Instant::now();
+    if run_compact_task_inner(sess.clone(), Arc::clone(&turn_context), input).await {
+        turn_context.client.get_otel_manager().record_compaction(
+            CompactionTrigger::Manual,
+            CompactionMode::Local,
+            started_at.elapsed(),
+        );
+    }
 }
 
+/// Returns whether the history was compacted; failures are reported to the
+/// client as events.
 async fn run_compact_task_inner(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
-) {
+) -> bool {
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
     let mut history = sess.clone_history().await;
@@ -144,7 +163,7 @@ async fn run_compact_task_inner(
                 break;
             }
             Err(CodexErr::Interrupted) => {
-                return;
+                return false;
             }
             Err(e @ CodexErr::ContextWindowExceeded) => {
                 if turn_input.len() > 1 {
@@ -160,7 +179,7 @@ async fn run_compact_task_inner(
                 sess.set_total_tokens_full(turn_context.as_ref()).await;
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
-                return;
+                return false;
             }
             Err(e) => {
                 if retries < max_retries {
@@ -177,7 +196,7 @@ async fn run_compact_task_inner(
                 } else {
                     let event = EventMsg::Error(e.to_error_event(None));
                     sess.send_event(&turn_context, event).await;
-                    return;
+                    return false;
                 }
             }
         }
@@ -187,16 +206,15 @@ async fn run_compact_task_inner(
     let summary_suffix =
         get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
-    let user_messages = collect_user_messages(&history_snapshot);
 
+    sess.refresh_repo_map(turn_context.as_ref()).await;
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
-    let ghost_snapshots: Vec<ResponseItem> = history_snapshot
-        .iter()
-        .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
-        .cloned()
-        .collect();
-    new_history.extend(ghost_snapshots);
+    let new_history = build_compacted_history_with_snapshots(
+        initial_context,
+        &history_snapshot,
+        &summary_text,
+        turn_context.client.get_tokenizer(),
+    );
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
 
@@ -205,6 +223,7 @@ async fn run_compact_task_inner(
         replacement_history: None,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
+    sess.mark_turn_compacted(&turn_context).await;
 
     let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
     sess.send_event(&turn_context, event).await;
@@ -213,6 +232,7 @@ async fn run_compact_task_inner(
         message: "Heads up: Long conversations and multiple compactions can cause the model to be less accurate. Start a new conversation when possible to keep conversations small and targeted.".to_string(),
     });
     sess.send_event(&turn_context, warning).await;
+    true
 }
 
 pub fn content_items_to_text(content: &[ContentItem]) -> Option<String> {
@@ -302,16 +322,39 @@ mod summary_tests {
     }
 }
 
+/// The history that replaces `history_snapshot` after a local compaction:
+/// the compacted history followed by the ghost snapshots, which keep `/undo`
+/// available.
+pub(crate) fn build_compacted_history_with_snapshots(
+    initial_context: Vec<ResponseItem>,
+    history_snapshot: &[ResponseItem],
+    summary_text: &str,
+    tokenizer: Tokenizer,
+) -> Vec<ResponseItem> {
+    let user_messages = collect_user_messages(history_snapshot);
+    let mut new_history =
+        build_compacted_history(initial_context, &user_messages, summary_text, tokenizer);
+    new_history.extend(
+        history_snapshot
+            .iter()
+            .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
+            .cloned(),
+    );
+    new_history
+}
+
 pub(crate) fn build_compacted_history(
     initial_context: Vec<ResponseItem>,
     user_messages: &[String],
     summary_text: &str,
+    tokenizer: Tokenizer,
 ) -> Vec<ResponseItem> {
     build_compacted_history_with_limit(
         initial_context,
         user_messages,
         summary_text,
         COMPACT_USER_MESSAGE_MAX_TOKENS,
+        tokenizer,
     )
 }
 
@@ -320,6 +363,7 @@ fn build_compacted_history_with_limit(
     user_messages: &[String],
     summary_text: &str,
     max_tokens: usize,
+    tokenizer: Tokenizer,
 ) -> Vec<ResponseItem> {
     let mut selected_messages: Vec<String> = Vec::new();
     if max_tokens > 0 {
@@ -328,12 +372,13 @@ fn build_compacted_history_with_limit(
             if remaining == 0 {
                 break;
             }
-            let tokens = approx_token_count(message);
+            let tokens = tokenizer.count(message);
             if tokens <= remaining {
                 selected_messages.push(message.clone());
                 remaining = remaining.saturating_sub(tokens);
             } else {
-                let truncated = truncate_text(message, TruncationPolicy::Tokens(remaining));
+                let truncated =
+                    truncate_text(message, TruncationPolicy::Tokens(remaining, tokenizer));
                 selected_messages.push(truncated);
                 break;
             }
@@ -503,6 +548,7 @@ mod tests {
             std::slice::from_ref(&big),
             "SUMMARY",
             max_tokens,
+            Tokenizer::O200kBase,
         );
         assert_eq!(history.len(), 2);
 
@@ -540,7 +586,12 @@ mod tests {
         let user_messages = vec!["first user message".to_string()];
         let summary_text = "summary text";
 
-        let history = build_compacted_history(initial_context, &user_messages, summary_text);
+        let history = build_compacted_history(
+            initial_context,
+            &user_messages,
+            summary_text,
+            Tokenizer::Heuristic,
+        );
         assert!(
             !history.is_empty(),
             "expected compacted history to include summary"
@@ -1,4 +1,6 @@
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -8,6 +10,8 @@ use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::RolloutItem;
 use crate::protocol::WarningEvent;
+use codex_otel::metrics::CompactionMode;
+use codex_otel::metrics::CompactionTrigger;
 use codex_protocol::models::ResponseItem;
 
 use crate::compact_preview::CompactionPreview;
@@ -21,11 +25,13 @@ pub(crate) async fn apply_compaction_preview(
         CompactionPreview::Local {
             summary_text,
             carry_over: _,
-        } => apply_local(sess, turn_context, summary_text).await,
+            summarized_in,
+        } => apply_local(sess, turn_context, summary_text, summarized_in).await,
         CompactionPreview::Remote {
             replacement_history,
             carry_over: _,
-        } => apply_remote(sess, turn_context, replacement_history).await,
+            summarized_in,
+        } => apply_remote(sess, turn_context, replacement_history, summarized_in).await,
     }
 }
 
@@ -33,18 +39,18 @@ async fn apply_local(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
     summary_text: String,
+    summarized_in: Duration,
 ) -> CodexResult<()> {
+    let started_at = Instant::now();
     let history_snapshot = sess.clone_history().await.get_history();
-    let user_messages = crate::compact::collect_user_messages(&history_snapshot);
+    sess.refresh_repo_map(turn_context.as_ref()).await;
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let mut new_history =
-        crate::compact::build_compacted_history(initial_context, &user_messages, &summary_text);
-    let ghost_snapshots: Vec<ResponseItem> = history_snapshot
-        .iter()
-        .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
-        .cloned()
-        .collect();
-    new_history.extend(ghost_snapshots);
+    let new_history = crate::compact::build_compacted_history_with_snapshots(
+        initial_context,
+        &history_snapshot,
+        &summary_text,
+        turn_context.client.get_tokenizer(),
+    );
 
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
@@ -54,6 +60,12 @@ async fn apply_local(
         replacement_history: None,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
+    sess.mark_turn_compacted(&turn_context).await;
+    turn_context.client.get_otel_manager().record_compaction(
+        CompactionTrigger::Manual,
+        CompactionMode::Local,
+        summarized_in + started_at.elapsed(),
+    );
 
     sess.send_event(
         &turn_context,
@@ -73,7 +85,9 @@ async fn apply_remote(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
     replacement_history: Vec<ResponseItem>,
+    summarized_in: Duration,
 ) -> CodexResult<()> {
+    let started_at = Instant::now();
     sess.replace_history(replacement_history.clone()).await;
     sess.recompute_token_usage(&turn_context).await;
 
@@ -83,6 +97,12 @@ async fn apply_remote(
     };
     sess.persist_rollout_items(&[RolloutItem::Compacted(compacted_item)])
         .await;
+    sess.mark_turn_compacted(&turn_context).await;
+    turn_context.client.get_otel_manager().record_compaction(
+        CompactionTrigger::Manual,
+        CompactionMode::Remote,
+        summarized_in + started_at.elapsed(),
+    );
 
     sess.send_event(
         &turn_context,
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -13,10 +14,12 @@ pub(crate) async fn run_local_compact_preview(
     turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
 ) -> CodexResult<CompactionPreview> {
+    let started_at = Instant::now();
     let output =
         generate_local_preview(Arc::clone(&sess), Arc::clone(&turn_context), input).await?;
     Ok(CompactionPreview::Local {
         summary_text: output.summary_text,
         carry_over: output.carry_over,
+        summarized_in: started_at.elapsed(),
     })
 }
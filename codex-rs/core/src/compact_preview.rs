@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::Prompt;
 use crate::client_common::ResponseEvent;
@@ -7,6 +8,7 @@ use crate::codex::TurnContext;
 use crate::codex::get_last_assistant_message_from_turn;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::tokenizer::Tokenizer;
 use crate::util::backoff;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
@@ -33,10 +35,14 @@ pub(crate) enum CompactionPreview {
     Local {
         summary_text: String,
         carry_over: CompactionCarryOverStats,
+        /// Time spent producing the summary, reported with the compaction
+        /// once the preview is applied.
+        summarized_in: Duration,
     },
     Remote {
         replacement_history: Vec<ResponseItem>,
         carry_over: CompactionCarryOverStats,
+        summarized_in: Duration,
     },
 }
 
@@ -108,7 +114,11 @@ pub(crate) async fn generate_local_preview(
         get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
     let user_messages = crate::compact::collect_user_messages(&history_snapshot);
-    let carry_over = compute_carry_over(&user_messages, COMPACT_USER_MESSAGE_MAX_TOKENS);
+    let carry_over = compute_carry_over(
+        &user_messages,
+        COMPACT_USER_MESSAGE_MAX_TOKENS,
+        turn_context.client.get_tokenizer(),
+    );
 
     Ok(CompactionPreviewOutput {
         summary_text,
@@ -119,10 +129,11 @@ pub(crate) async fn generate_local_preview(
 
 pub(crate) fn preview_from_remote_replacement_history(
     replacement_history: Vec<ResponseItem>,
+    tokenizer: Tokenizer,
 ) -> CompactionPreviewOutput {
     let summary_text = extract_remote_summary_text(&replacement_history);
     let user_messages = crate::compact::collect_user_messages(&replacement_history);
-    let carry_over = compute_carry_over(&user_messages, COMPACT_USER_MESSAGE_MAX_TOKENS);
+    let carry_over = compute_carry_over(&user_messages, COMPACT_USER_MESSAGE_MAX_TOKENS, tokenizer);
 
     CompactionPreviewOutput {
         summary_text,
@@ -152,7 +163,11 @@ pub(crate) fn extract_remote_summary_text(replacement_history: &[ResponseItem])
     }
 }
 
-fn compute_carry_over(user_messages: &[String], max_tokens: usize) -> CompactionCarryOverStats {
+fn compute_carry_over(
+    user_messages: &[String],
+    max_tokens: usize,
+    tokenizer: Tokenizer,
+) -> CompactionCarryOverStats {
     if max_tokens == 0 {
         return CompactionCarryOverStats {
             selected_count: 0,
@@ -170,7 +185,7 @@ fn compute_carry_over(user_messages: &[String], max_tokens: usize) -> Compaction
             break;
         }
 
-        let tokens = crate::truncate::approx_token_count(message);
+        let tokens = tokenizer.count(message);
         if tokens <= remaining {
             selected_count += 1;
             remaining = remaining.saturating_sub(tokens);
@@ -5,6 +5,7 @@ pub(crate) fn render_compaction_preview(preview: &CompactionPreview) -> String {
         CompactionPreview::Local {
             summary_text,
             carry_over,
+            ..
         } => render(
             summary_text,
             carry_over.percent(),
@@ -14,6 +15,7 @@ pub(crate) fn render_compaction_preview(preview: &CompactionPreview) -> String {
         CompactionPreview::Remote {
             replacement_history,
             carry_over,
+            ..
         } => {
             let summary_text =
                 crate::compact_preview::extract_remote_summary_text(replacement_history);
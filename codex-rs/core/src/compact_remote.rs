@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::Prompt;
 use crate::codex::Session;
@@ -9,13 +10,22 @@ use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::RolloutItem;
 use crate::protocol::TaskStartedEvent;
+use codex_otel::metrics::CompactionMode;
+use codex_otel::metrics::CompactionTrigger;
 use codex_protocol::models::ResponseItem;
 
 pub(crate) async fn run_inline_remote_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
 ) {
-    run_remote_compact_task_inner(&sess, &turn_context).await;
+    let started_at = Instant::now();
+    if run_remote_compact_task_inner(&sess, &turn_context).await {
+        turn_context.client.get_otel_manager().record_compaction(
+            CompactionTrigger::Auto,
+            CompactionMode::Remote,
+            started_at.elapsed(),
+        );
+    }
 }
 
 pub(crate) async fn run_remote_compact_task(sess: Arc<Session>, turn_context: Arc<TurnContext>) {
@@ -24,16 +34,30 @@ pub(crate) async fn run_remote_compact_task(sess: Arc<Session>, turn_context: Ar
     });
     sess.send_event(&turn_context, start_event).await;
 
-    run_remote_compact_task_inner(&sess, &turn_context).await;
+    let started_at = Instant::now();
+    if run_remote_compact_task_inner(&sess, &turn_context).await {
+        turn_context.client.get_otel_manager().record_compaction(
+            CompactionTrigger::Manual,
+            CompactionMode::Remote,
+            started_at.elapsed(),
+        );
+    }
 }
 
-async fn run_remote_compact_task_inner(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
+/// Returns whether the history was compacted; failures are reported to the
+/// client as an error event.
+async fn run_remote_compact_task_inner(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+) -> bool {
     if let Err(err) = run_remote_compact_task_inner_impl(sess, turn_context).await {
         let event = EventMsg::Error(
             err.to_error_event(Some("Error running remote compact task".to_string())),
         );
         sess.send_event(turn_context, event).await;
+        return false;
     }
+    true
 }
 
 async fn run_remote_compact_task_inner_impl(
@@ -64,6 +88,8 @@ async fn run_remote_compact_task_inner_impl(
     if !ghost_snapshots.is_empty() {
         new_history.extend(ghost_snapshots);
     }
+    sess.refresh_repo_map(turn_context.as_ref()).await;
+    sess.replace_repo_map(&mut new_history);
     sess.replace_history(new_history.clone()).await;
     sess.recompute_token_usage(turn_context).await;
 
@@ -73,6 +99,7 @@ async fn run_remote_compact_task_inner_impl(
     };
     sess.persist_rollout_items(&[RolloutItem::Compacted(compacted_item)])
         .await;
+    sess.mark_turn_compacted(turn_context).await;
 
     let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
     sess.send_event(turn_context, event).await;
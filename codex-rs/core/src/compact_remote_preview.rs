@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::Prompt;
 use crate::codex::Session;
@@ -22,10 +23,12 @@ pub(crate) async fn run_remote_compact_preview(
         output_schema: None,
     };
 
+    let started_at = Instant::now();
     let mut replacement_history = turn_context
         .client
         .compact_conversation_history(&prompt)
         .await?;
+    let summarized_in = started_at.elapsed();
 
     // Required to keep `/undo` available after compaction.
     let ghost_snapshots: Vec<ResponseItem> = history
@@ -39,9 +42,13 @@ pub(crate) async fn run_remote_compact_preview(
         replacement_history.extend(ghost_snapshots);
     }
 
-    let output = preview_from_remote_replacement_history(replacement_history.clone());
+    let output = preview_from_remote_replacement_history(
+        replacement_history.clone(),
+        turn_context.client.get_tokenizer(),
+    );
     Ok(CompactionPreview::Remote {
         replacement_history,
         carry_over: output.carry_over,
+        summarized_in,
     })
 }
@@ -1,7 +1,10 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::DEFAULT_FALLBACK_TRIGGERS;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::FallbackProvider;
 use crate::config::types::History;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelPrice;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
@@ -26,9 +29,12 @@ use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::read_deny::default_read_deny_patterns;
 use crate::util::resolve_path;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_apply_patch::DEFAULT_FUZZ_THRESHOLD;
+use codex_protocol::config_types::FallbackTrigger;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -103,6 +109,17 @@ pub struct Config {
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Providers to switch to, in order, when a model request fails with one
+    /// of the errors listed in `fallback_on`.
+    pub fallback_providers: Vec<crate::config::types::FallbackProvider>,
+
+    /// Classes of errors that trigger a switch to the next fallback provider.
+    pub fallback_on: Vec<FallbackTrigger>,
+
+    /// Prices used to cost each turn in the session's cost ledger, keyed by
+    /// model slug or slug prefix.
+    pub model_prices: HashMap<String, ModelPrice>,
+
     /// Approval policy for executing commands.
     pub approval_policy: AskForApproval,
 
@@ -116,6 +133,14 @@ pub struct Config {
     /// was coerced to read-only because native auto mode is unsupported.
     pub forced_auto_mode_downgraded_on_windows: bool,
 
+    /// `read_deny` patterns resolved from `[sandbox_workspace_write]`, carried
+    /// into the workspace-write preset so switching modes keeps the list.
+    pub sandbox_read_deny: Vec<String>,
+
+    /// `network_allowlist` from `[sandbox_workspace_write]`, carried into the
+    /// workspace-write preset like [`Config::sandbox_read_deny`].
+    pub sandbox_network_allowlist: Vec<String>,
+
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
     /// When `true`, `AgentReasoning` events emitted by the backend will be
@@ -207,6 +232,10 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Minimum score for `apply_patch` to apply a chunk at a fuzzy match when
+    /// its lines cannot be found verbatim. `1.0` disables fuzzy matching.
+    pub apply_patch_fuzz_threshold: f32,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -294,6 +323,10 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// When set, model requests are recorded to or replayed from a cassette
+    /// file instead of only going to the provider.
+    pub model_cassette: Option<crate::config::types::ModelCassette>,
 }
 
 impl Config {
@@ -537,6 +570,16 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Ordered providers to switch to when `model_provider` fails.
+    pub fallback_providers: Option<Vec<crate::config::types::FallbackProviderToml>>,
+
+    /// Errors that trigger a switch to the next fallback provider.
+    pub fallback_on: Option<Vec<FallbackTrigger>>,
+
+    /// USD prices per million tokens, keyed by model slug or slug prefix.
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
@@ -609,6 +652,11 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Minimum fuzzy-match score (0.0-1.0) for `apply_patch` chunks whose lines
+    /// do not match the file exactly. Defaults to 0.85; `1.0` disables fuzzy
+    /// matching.
+    pub apply_patch_fuzz_threshold: Option<f32>,
+
     /// Profile to use from the `profiles` map.
     pub profile: Option<String>,
 
@@ -754,12 +802,29 @@ pub struct SandboxPolicyResolution {
 }
 
 impl ConfigToml {
+    /// Returns the configured `read_deny` list, or the defaults for
+    /// `codex_home` when it is not set.
+    fn sandbox_read_deny(&self, codex_home: &Path) -> Vec<String> {
+        self.sandbox_workspace_write
+            .as_ref()
+            .and_then(|workspace_write| workspace_write.read_deny.clone())
+            .unwrap_or_else(|| default_read_deny_patterns(codex_home))
+    }
+
+    fn sandbox_network_allowlist(&self) -> Vec<String> {
+        self.sandbox_workspace_write
+            .as_ref()
+            .map(|workspace_write| workspace_write.network_allowlist.clone())
+            .unwrap_or_default()
+    }
+
     /// Derive the effective sandbox policy from the configuration.
     fn derive_sandbox_policy(
         &self,
         sandbox_mode_override: Option<SandboxMode>,
         profile_sandbox_mode: Option<SandboxMode>,
         resolved_cwd: &Path,
+        codex_home: &Path,
     ) -> SandboxPolicyResolution {
         let resolved_sandbox_mode = sandbox_mode_override
             .or(profile_sandbox_mode)
@@ -777,20 +842,24 @@ impl ConfigToml {
             .unwrap_or_default();
         let mut sandbox_policy = match resolved_sandbox_mode {
             SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
-            SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
-                Some(SandboxWorkspaceWrite {
+            SandboxMode::WorkspaceWrite => {
+                let SandboxWorkspaceWrite {
                     writable_roots,
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
-                }) => SandboxPolicy::WorkspaceWrite {
-                    writable_roots: writable_roots.clone(),
-                    network_access: *network_access,
-                    exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
-                    exclude_slash_tmp: *exclude_slash_tmp,
-                },
-                None => SandboxPolicy::new_workspace_write_policy(),
-            },
+                    network_allowlist,
+                    read_deny: _,
+                } = self.sandbox_workspace_write.clone().unwrap_or_default();
+                SandboxPolicy::WorkspaceWrite {
+                    writable_roots,
+                    network_access,
+                    exclude_tmpdir_env_var,
+                    exclude_slash_tmp,
+                    network_allowlist,
+                    read_deny: self.sandbox_read_deny(codex_home),
+                }
+            }
             SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
         };
         let mut forced_auto_mode_downgraded_on_windows = false;
@@ -871,6 +940,7 @@ pub struct ConfigOverrides {
     pub tools_web_search_request: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    pub model_cassette: Option<crate::config::types::ModelCassette>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -928,6 +998,7 @@ impl Config {
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
             additional_writable_roots,
+            model_cassette,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -993,7 +1064,14 @@ impl Config {
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
-        } = cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
+        } = cfg.derive_sandbox_policy(
+            sandbox_mode,
+            config_profile.sandbox_mode,
+            &resolved_cwd,
+            &codex_home,
+        );
+        let sandbox_read_deny = cfg.sandbox_read_deny(&codex_home);
+        let sandbox_network_allowlist = cfg.sandbox_network_allowlist();
         if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
             for path in additional_writable_roots {
                 if !writable_roots.iter().any(|existing| existing == &path) {
@@ -1043,6 +1121,36 @@ impl Config {
             })?
             .clone();
 
+        let fallback_providers = config_profile
+            .fallback_providers
+            .or(cfg.fallback_providers)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| {
+                let model_provider = model_providers
+                    .get(&entry.model_provider)
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!(
+                                "Fallback model provider `{}` not found",
+                                entry.model_provider
+                            ),
+                        )
+                    })?
+                    .clone();
+                Ok(FallbackProvider {
+                    model_provider_id: entry.model_provider,
+                    model_provider,
+                    model: entry.model,
+                })
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let fallback_on = config_profile
+            .fallback_on
+            .or(cfg.fallback_on)
+            .unwrap_or_else(|| DEFAULT_FALLBACK_TRIGGERS.to_vec());
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let history = cfg.history.unwrap_or_default();
@@ -1115,11 +1223,16 @@ impl Config {
             model_auto_compact_token_limit: cfg.model_auto_compact_token_limit,
             model_provider_id,
             model_provider,
+            fallback_providers,
+            fallback_on,
+            model_prices: cfg.model_prices,
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
+            sandbox_read_deny,
+            sandbox_network_allowlist,
             shell_environment_policy,
             notify: cfg.notify,
             user_instructions,
@@ -1149,6 +1262,11 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            apply_patch_fuzz_threshold: cfg
+                .apply_patch_fuzz_threshold
+                .map_or(DEFAULT_FUZZ_THRESHOLD, |threshold| {
+                    threshold.clamp(0.0, 1.0)
+                }),
             codex_home,
             history,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
@@ -1201,13 +1319,16 @@ impl Config {
                     .unwrap_or(DEFAULT_OTEL_ENVIRONMENT.to_string());
                 let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
                 let trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
+                let metrics_exporter = t.metrics_exporter.unwrap_or_else(|| exporter.clone());
                 OtelConfig {
                     log_user_prompt,
                     environment,
                     exporter,
                     trace_exporter,
+                    metrics_exporter,
                 }
             },
+            model_cassette,
         };
         Ok(config)
     }
@@ -1384,6 +1505,7 @@ network_access = false  # This should be ignored.
             sandbox_mode_override,
             None,
             &PathBuf::from("/tmp/test"),
+            Path::new("/tmp/codex-home"),
         );
         assert_eq!(
             resolution,
@@ -1407,6 +1529,7 @@ network_access = true  # This should be ignored.
             sandbox_mode_override,
             None,
             &PathBuf::from("/tmp/test"),
+            Path::new("/tmp/codex-home"),
         );
         assert_eq!(
             resolution,
@@ -1438,6 +1561,7 @@ exclude_slash_tmp = true
             sandbox_mode_override,
             None,
             &PathBuf::from("/tmp/test"),
+            Path::new("/tmp/codex-home"),
         );
         if cfg!(target_os = "windows") {
             assert_eq!(
@@ -1456,6 +1580,8 @@ exclude_slash_tmp = true
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        network_allowlist: vec![],
+                        read_deny: default_read_deny_patterns(Path::new("/tmp/codex-home")),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1472,6 +1598,8 @@ writable_roots = [
 ]
 exclude_tmpdir_env_var = true
 exclude_slash_tmp = true
+network_allowlist = ["crates.io", "localhost:8080"]
+read_deny = []
 
 [projects."/tmp/test"]
 trust_level = "trusted"
@@ -1486,6 +1614,7 @@ trust_level = "trusted"
             sandbox_mode_override,
             None,
             &PathBuf::from("/tmp/test"),
+            Path::new("/tmp/codex-home"),
         );
         if cfg!(target_os = "windows") {
             assert_eq!(
@@ -1504,6 +1633,11 @@ trust_level = "trusted"
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        network_allowlist: vec![
+                            "crates.io".to_string(),
+                            "localhost:8080".to_string(),
+                        ],
+                        read_deny: vec![],
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1650,6 +1784,63 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_fallback_providers_resolve_against_model_providers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+fallback_providers = [{ model_provider = "ollama" }]
+
+[profiles.work]
+model_provider = "openai"
+fallback_providers = [{ model_provider = "gateway", model = "gpt-5.1" }]
+fallback_on = ["server_error"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides {
+                config_profile: Some("work".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        );
+        assert_eq!(
+            config.expect_err("unknown fallback provider").to_string(),
+            "Fallback model provider `gateway` not found"
+        );
+
+        let mut cfg = cfg;
+        cfg.model_providers.insert(
+            "gateway".to_string(),
+            built_in_model_providers()["openai"].clone(),
+        );
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("work".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        let fallbacks = config
+            .fallback_providers
+            .iter()
+            .map(|fallback| {
+                (
+                    fallback.model_provider_id.as_str(),
+                    fallback.model.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fallbacks, vec![("gateway", Some("gpt-5.1"))]);
+        assert_eq!(config.fallback_on, vec![FallbackTrigger::ServerError]);
+
+        Ok(())
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2904,10 +3095,15 @@ model_verbosity = "high"
                 model_auto_compact_token_limit: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                fallback_providers: Vec::new(),
+                fallback_on: DEFAULT_FALLBACK_TRIGGERS.to_vec(),
+                model_prices: HashMap::new(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
+                sandbox_read_deny: default_read_deny_patterns(&fixture.codex_home()),
+                sandbox_network_allowlist: Vec::new(),
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
@@ -2919,6 +3115,7 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                apply_patch_fuzz_threshold: DEFAULT_FUZZ_THRESHOLD,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
                 file_opener: UriBasedFileOpener::VsCode,
@@ -2951,6 +3148,7 @@ model_verbosity = "high"
                 animations: true,
                 show_tooltips: true,
                 otel: OtelConfig::default(),
+                model_cassette: None,
             },
             o3_profile_config
         );
@@ -2978,10 +3176,15 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            fallback_providers: Vec::new(),
+            fallback_on: DEFAULT_FALLBACK_TRIGGERS.to_vec(),
+            model_prices: HashMap::new(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: default_read_deny_patterns(&fixture.codex_home()),
+            sandbox_network_allowlist: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
@@ -2993,6 +3196,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            apply_patch_fuzz_threshold: DEFAULT_FUZZ_THRESHOLD,
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3025,6 +3229,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -3067,10 +3272,15 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            fallback_providers: Vec::new(),
+            fallback_on: DEFAULT_FALLBACK_TRIGGERS.to_vec(),
+            model_prices: HashMap::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: default_read_deny_patterns(&fixture.codex_home()),
+            sandbox_network_allowlist: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
@@ -3082,6 +3292,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            apply_patch_fuzz_threshold: DEFAULT_FUZZ_THRESHOLD,
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3114,6 +3325,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -3142,10 +3354,15 @@ model_verbosity = "high"
             model_auto_compact_token_limit: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            fallback_providers: Vec::new(),
+            fallback_on: DEFAULT_FALLBACK_TRIGGERS.to_vec(),
+            model_prices: HashMap::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            sandbox_read_deny: default_read_deny_patterns(&fixture.codex_home()),
+            sandbox_network_allowlist: Vec::new(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
@@ -3157,6 +3374,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            apply_patch_fuzz_threshold: DEFAULT_FUZZ_THRESHOLD,
             codex_home: fixture.codex_home(),
             history: History::default(),
             file_opener: UriBasedFileOpener::VsCode,
@@ -3189,6 +3407,7 @@ model_verbosity = "high"
             animations: true,
             show_tooltips: true,
             otel: OtelConfig::default(),
+            model_cassette: None,
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -3355,7 +3574,12 @@ trust_level = "untrusted"
         let cfg = toml::from_str::<ConfigToml>(config_with_untrusted)
             .expect("TOML deserialization should succeed");
 
-        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        let resolution = cfg.derive_sandbox_policy(
+            None,
+            None,
+            &PathBuf::from("/tmp/test"),
+            Path::new("/tmp/codex-home"),
+        );
 
         // Verify that untrusted projects get WorkspaceWrite (or ReadOnly on Windows due to downgrade)
         if cfg!(target_os = "windows") {
@@ -15,6 +15,9 @@ pub struct ConfigProfile {
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
     pub model_provider: Option<String>,
+    /// Providers to switch to, in order, when `model_provider` fails.
+    pub fallback_providers: Option<Vec<crate::config::types::FallbackProviderToml>>,
+    pub fallback_on: Option<Vec<codex_protocol::config_types::FallbackTrigger>>,
     pub approval_policy: Option<AskForApproval>,
     pub sandbox_mode: Option<SandboxMode>,
     pub model_reasoning_effort: Option<ReasoningEffort>,
@@ -3,6 +3,8 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use crate::model_provider_info::ModelProviderInfo;
+use codex_protocol::config_types::FallbackTrigger;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -297,6 +299,8 @@ pub struct OtelTlsConfig {
 #[serde(rename_all = "kebab-case")]
 pub enum OtelExporterKind {
     None,
+    /// Write OTLP-JSON to rotating files under `~/.codex/otel/`.
+    File,
     OtlpHttp {
         endpoint: String,
         #[serde(default)]
@@ -328,6 +332,9 @@ pub struct OtelConfigToml {
 
     /// Optional trace exporter
     pub trace_exporter: Option<OtelExporterKind>,
+
+    /// Optional metrics exporter
+    pub metrics_exporter: Option<OtelExporterKind>,
 }
 
 /// Effective OTEL settings after defaults are applied.
@@ -337,6 +344,7 @@ pub struct OtelConfig {
     pub environment: String,
     pub exporter: OtelExporterKind,
     pub trace_exporter: OtelExporterKind,
+    pub metrics_exporter: OtelExporterKind,
 }
 
 impl Default for OtelConfig {
@@ -346,10 +354,54 @@ impl Default for OtelConfig {
             environment: DEFAULT_OTEL_ENVIRONMENT.to_owned(),
             exporter: OtelExporterKind::None,
             trace_exporter: OtelExporterKind::None,
+            metrics_exporter: OtelExporterKind::None,
         }
     }
 }
 
+/// Entry of the `fallback_providers` list in `config.toml`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FallbackProviderToml {
+    /// Key into the `model_providers` map.
+    pub model_provider: String,
+    /// Model to use with this provider. Defaults to the session's model.
+    pub model: Option<String>,
+}
+
+/// Fallback provider resolved against the `model_providers` map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackProvider {
+    pub model_provider_id: String,
+    pub model_provider: ModelProviderInfo,
+    pub model: Option<String>,
+}
+
+/// Errors that switch providers when `fallback_on` is not set.
+pub const DEFAULT_FALLBACK_TRIGGERS: [FallbackTrigger; 3] = [
+    FallbackTrigger::ServerError,
+    FallbackTrigger::Connection,
+    FallbackTrigger::UsageLimit,
+];
+
+/// Price of a model in USD per million tokens, from the `model_prices` table.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    /// Price of cached input tokens. Defaults to `input`.
+    pub cached_input: Option<f64>,
+    /// Price of output tokens, reasoning tokens included.
+    pub output: f64,
+}
+
+/// Record or replay model traffic through a cassette file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelCassette {
+    /// Forward requests to the provider and append every exchange to the file.
+    Record(PathBuf),
+    /// Serve responses from a previously recorded file without network access.
+    Replay(PathBuf),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
 pub enum Notifications {
@@ -424,6 +476,15 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    /// Hosts, CIDR ranges and ports reachable through the local egress proxy
+    /// when `network_access` is `false`.
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
+    /// Glob patterns for paths that must not be read. When unset,
+    /// [`crate::read_deny::default_read_deny_patterns`] apply; `[]` disables
+    /// the deny list.
+    #[serde(default)]
+    pub read_deny: Option<Vec<String>>,
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
@@ -433,6 +494,8 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
             network_access: Some(sandbox_workspace_write.network_access),
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
+            network_allowlist: sandbox_workspace_write.network_allowlist,
+            read_deny: sandbox_workspace_write.read_deny,
         }
     }
 }
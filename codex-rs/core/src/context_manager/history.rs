@@ -1,7 +1,6 @@
 use crate::codex::TurnContext;
 use crate::context_manager::normalize;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
@@ -77,13 +76,13 @@ impl ContextManager {
         history
     }
 
-    // Estimate token usage using byte-based heuristics from the truncation helpers.
-    // This is a coarse lower bound, not a tokenizer-accurate count.
+    // Estimate token usage with the model family's tokenizer. Encrypted
+    // reasoning is opaque, so its size is still derived from the payload length.
     pub(crate) fn estimate_token_count(&self, turn_context: &TurnContext) -> Option<i64> {
         let model_family = turn_context.client.get_model_family();
-        let base_tokens =
-            i64::try_from(approx_token_count(model_family.base_instructions.as_str()))
-                .unwrap_or(i64::MAX);
+        let tokenizer = model_family.tokenizer;
+        let base_tokens = i64::try_from(tokenizer.count(model_family.base_instructions.as_str()))
+            .unwrap_or(i64::MAX);
 
         let items_tokens = self.items.iter().fold(0i64, |acc, item| {
             acc + match item {
@@ -97,7 +96,7 @@ impl ContextManager {
                 } => estimate_reasoning_length(content.len()) as i64,
                 item => {
                     let serialized = serde_json::to_string(item).unwrap_or_default();
-                    i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+                    i64::try_from(tokenizer.count(&serialized)).unwrap_or(i64::MAX)
                 }
             }
         });
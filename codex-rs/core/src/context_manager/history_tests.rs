@@ -29,7 +29,7 @@ fn create_history_with_items(items: Vec<ResponseItem>) -> ContextManager {
     let mut h = ContextManager::new();
     // Use a generous but fixed token budget; tests only rely on truncation
     // behavior, not on a specific model's token limit.
-    h.record_items(items.iter(), TruncationPolicy::Tokens(10_000));
+    h.record_items(items.iter(), TruncationPolicy::tokens(10_000));
     h
 }
 
@@ -68,13 +68,13 @@ fn reasoning_with_encrypted_content(len: usize) -> ResponseItem {
 }
 
 fn truncate_exec_output(content: &str) -> String {
-    truncate::truncate_text(content, TruncationPolicy::Tokens(EXEC_FORMAT_MAX_TOKENS))
+    truncate::truncate_text(content, TruncationPolicy::tokens(EXEC_FORMAT_MAX_TOKENS))
 }
 
 #[test]
 fn filters_non_api_messages() {
     let mut h = ContextManager::default();
-    let policy = TruncationPolicy::Tokens(10_000);
+    let policy = TruncationPolicy::tokens(10_000);
     // System message is not API messages; Other is ignored.
     let system = ResponseItem::Message {
         id: None,
@@ -149,6 +149,7 @@ fn non_last_reasoning_tokens_ignore_entries_after_last_user() {
 fn get_history_for_prompt_drops_ghost_commits() {
     let items = vec![ResponseItem::GhostSnapshot {
         ghost_commit: GhostCommit::new("ghost-1".to_string(), None, Vec::new(), Vec::new()),
+        turn: None,
     }];
     let mut history = create_history_with_items(items);
     let filtered = history.get_history_for_prompt();
@@ -281,7 +282,7 @@ fn record_items_truncates_function_call_output_content() {
     let mut history = ContextManager::new();
     // Any reasonably small token budget works; the test only cares that
     // truncation happens and the marker is present.
-    let policy = TruncationPolicy::Tokens(1_000);
+    let policy = TruncationPolicy::tokens(1_000);
     let long_line = "a very long line to trigger truncation\n";
     let long_output = long_line.repeat(2_500);
     let item = ResponseItem::FunctionCallOutput {
@@ -317,7 +318,7 @@ fn record_items_truncates_function_call_output_content() {
 #[test]
 fn record_items_truncates_custom_tool_call_output_content() {
     let mut history = ContextManager::new();
-    let policy = TruncationPolicy::Tokens(1_000);
+    let policy = TruncationPolicy::tokens(1_000);
     let line = "custom output that is very long\n";
     let long_output = line.repeat(2_500);
     let item = ResponseItem::CustomToolCallOutput {
@@ -347,7 +348,7 @@ fn record_items_truncates_custom_tool_call_output_content() {
 #[test]
 fn record_items_respects_custom_token_limit() {
     let mut history = ContextManager::new();
-    let policy = TruncationPolicy::Tokens(10);
+    let policy = TruncationPolicy::tokens(10);
     let long_output = "tokenized content repeated many times ".repeat(200);
     let item = ResponseItem::FunctionCallOutput {
         call_id: "call-custom-limit".to_string(),
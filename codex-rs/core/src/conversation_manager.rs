@@ -86,10 +86,7 @@ impl ConversationManager {
         auth_manager: Arc<AuthManager>,
         models_manager: Arc<ModelsManager>,
     ) -> CodexResult<NewConversation> {
-        let CodexSpawnOk {
-            codex,
-            conversation_id,
-        } = Codex::spawn(
+        let spawned = Codex::spawn(
             config,
             auth_manager,
             models_manager,
@@ -97,14 +94,15 @@ impl ConversationManager {
             self.session_source.clone(),
         )
         .await?;
-        self.finalize_spawn(codex, conversation_id).await
+        self.finalize_spawn(spawned).await
     }
 
-    async fn finalize_spawn(
-        &self,
-        codex: Codex,
-        conversation_id: ConversationId,
-    ) -> CodexResult<NewConversation> {
+    async fn finalize_spawn(&self, spawned: CodexSpawnOk) -> CodexResult<NewConversation> {
+        let CodexSpawnOk {
+            codex,
+            conversation_id,
+            session,
+        } = spawned;
         // The first event must be `SessionInitialized`. Validate and forward it
         // to the caller so that they can display it in the conversation
         // history.
@@ -122,6 +120,7 @@ impl ConversationManager {
         let conversation = Arc::new(CodexConversation::new(
             codex,
             session_configured.rollout_path.clone(),
+            session,
         ));
         self.conversations
             .write()
@@ -171,10 +170,7 @@ impl ConversationManager {
         initial_history: InitialHistory,
         auth_manager: Arc<AuthManager>,
     ) -> CodexResult<NewConversation> {
-        let CodexSpawnOk {
-            codex,
-            conversation_id,
-        } = Codex::spawn(
+        let spawned = Codex::spawn(
             config,
             auth_manager,
             self.models_manager.clone(),
@@ -182,7 +178,7 @@ impl ConversationManager {
             self.session_source.clone(),
         )
         .await?;
-        self.finalize_spawn(codex, conversation_id).await
+        self.finalize_spawn(spawned).await
     }
 
     /// Removes the conversation from the manager's internal map, though the
@@ -212,10 +208,7 @@ impl ConversationManager {
 
         // Spawn a new conversation with the computed initial history.
         let auth_manager = self.auth_manager.clone();
-        let CodexSpawnOk {
-            codex,
-            conversation_id,
-        } = Codex::spawn(
+        let spawned = Codex::spawn(
             config,
             auth_manager,
             self.models_manager.clone(),
@@ -224,7 +217,7 @@ impl ConversationManager {
         )
         .await?;
 
-        self.finalize_spawn(codex, conversation_id).await
+        self.finalize_spawn(spawned).await
     }
 
     pub async fn list_models(&self, config: &Config) -> Vec<ModelPreset> {
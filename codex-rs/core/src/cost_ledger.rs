@@ -0,0 +1,160 @@
+//! Per-turn token and cost ledger.
+//!
+//! Usage reported by the model is summed per turn while the turn runs. When
+//! the turn finishes, the sum is priced with the `model_prices` table and
+//! recorded as a [`TurnCostEvent`] in the rollout.
+
+use std::collections::HashMap;
+
+use crate::config::types::ModelPrice;
+use crate::protocol::TokenUsage;
+use crate::protocol::TurnCostEvent;
+
+/// Usage of a turn that has not finished yet.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PendingTurnCost {
+    model: String,
+    usage: TokenUsage,
+    compacted: bool,
+}
+
+impl PendingTurnCost {
+    pub(crate) fn add_usage(&mut self, model: &str, usage: &TokenUsage) {
+        self.model = model.to_string();
+        self.usage.add_assign(usage);
+    }
+
+    pub(crate) fn mark_compacted(&mut self) {
+        self.compacted = true;
+    }
+
+    /// Close the entry, pricing it with `prices`. Returns `None` when the turn
+    /// made no model request.
+    pub(crate) fn finish(
+        self,
+        turn_id: String,
+        prices: &HashMap<String, ModelPrice>,
+    ) -> Option<TurnCostEvent> {
+        if self.model.is_empty() {
+            return None;
+        }
+        let cost_usd =
+            price_for_model(prices, &self.model).map(|price| cost_usd(price, &self.usage));
+        Some(TurnCostEvent {
+            turn_id,
+            model: self.model,
+            usage: self.usage,
+            compacted: self.compacted,
+            cost_usd,
+        })
+    }
+}
+
+/// Price of `model`: the entry keyed by the exact slug, otherwise the one with
+/// the longest key that prefixes it (so `gpt-5.1` also prices
+/// `gpt-5.1-2025-11-13`).
+pub fn price_for_model<'a>(
+    prices: &'a HashMap<String, ModelPrice>,
+    model: &str,
+) -> Option<&'a ModelPrice> {
+    if let Some(price) = prices.get(model) {
+        return Some(price);
+    }
+    prices
+        .iter()
+        .filter(|(slug, _)| model.starts_with(slug.as_str()))
+        .max_by_key(|(slug, _)| slug.len())
+        .map(|(_, price)| price)
+}
+
+/// Cost in USD of `usage` at `price`.
+pub fn cost_usd(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    let cached_input = price.cached_input.unwrap_or(price.input);
+    let cost = usage.non_cached_input() as f64 * price.input
+        + usage.cached_input() as f64 * cached_input
+        + usage.output_tokens.max(0) as f64 * price.output;
+    cost / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn prices_by_exact_slug_then_longest_prefix() {
+        let prices = HashMap::from([
+            (
+                "gpt-5".to_string(),
+                ModelPrice {
+                    input: 1.0,
+                    cached_input: None,
+                    output: 2.0,
+                },
+            ),
+            (
+                "gpt-5.1".to_string(),
+                ModelPrice {
+                    input: 3.0,
+                    cached_input: Some(0.5),
+                    output: 4.0,
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            price_for_model(&prices, "gpt-5").map(|p| p.input),
+            Some(1.0)
+        );
+        assert_eq!(
+            price_for_model(&prices, "gpt-5.1-codex").map(|p| p.input),
+            Some(3.0)
+        );
+        assert_eq!(
+            price_for_model(&prices, "gpt-5-mini").map(|p| p.input),
+            Some(1.0)
+        );
+        assert_eq!(price_for_model(&prices, "o3").map(|p| p.input), None);
+    }
+
+    #[test]
+    fn finished_turn_sums_usage_and_prices_cached_input() {
+        let prices = HashMap::from([(
+            "gpt-5.1".to_string(),
+            ModelPrice {
+                input: 1.25,
+                cached_input: Some(0.125),
+                output: 10.0,
+            },
+        )]);
+        let mut pending = PendingTurnCost::default();
+        pending.add_usage("gpt-5.1", &usage(600_000, 200_000, 50_000));
+        pending.add_usage("gpt-5.1", &usage(400_000, 200_000, 50_000));
+        pending.mark_compacted();
+
+        let entry = pending
+            .finish("turn-1".to_string(), &prices)
+            .expect("turn made requests");
+        assert_eq!(entry.usage.input_tokens, 1_000_000);
+        assert_eq!(entry.usage.cached_input_tokens, 400_000);
+        assert!(entry.compacted);
+        // 600k uncached at $1.25, 400k cached at $0.125 and 100k output at $10.
+        assert_eq!(entry.cost_usd, Some(1.8));
+
+        assert!(
+            PendingTurnCost::default()
+                .finish("turn-2".to_string(), &prices)
+                .is_none()
+        );
+    }
+}
@@ -213,6 +213,8 @@ mod tests {
             network_access,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: vec![],
+            read_deny: vec![],
         }
     }
 
@@ -258,7 +260,7 @@ mod tests {
         let context = EnvironmentContext::new(
             None,
             Some(AskForApproval::Never),
-            Some(SandboxPolicy::ReadOnly),
+            Some(SandboxPolicy::new_read_only_policy()),
             fake_shell(),
         );
 
@@ -9,6 +9,7 @@ use chrono::Local;
 use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ConversationId;
+use codex_protocol::config_types::FallbackTrigger;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::ErrorEvent;
 use codex_protocol::protocol::RateLimitSnapshot;
@@ -463,6 +464,27 @@ impl CodexErr {
         }
     }
 
+    /// Class of provider failure this error represents for `fallback_on`, if any.
+    pub fn fallback_trigger(&self) -> Option<FallbackTrigger> {
+        match self {
+            CodexErr::InternalServerError => Some(FallbackTrigger::ServerError),
+            CodexErr::UnexpectedStatus(err) if err.status.is_server_error() => {
+                Some(FallbackTrigger::ServerError)
+            }
+            CodexErr::RetryLimit(err) if err.status.is_server_error() => {
+                Some(FallbackTrigger::ServerError)
+            }
+            CodexErr::Stream(..)
+            | CodexErr::Timeout
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::ResponseStreamFailed(_) => Some(FallbackTrigger::Connection),
+            CodexErr::UsageLimitReached(_) | CodexErr::QuotaExceeded => {
+                Some(FallbackTrigger::UsageLimit)
+            }
+            _ => None,
+        }
+    }
+
     pub fn to_error_event(&self, message_prefix: Option<String>) -> ErrorEvent {
         let error_message = self.to_string();
         let message: String = match message_prefix {
@@ -862,4 +884,29 @@ mod tests {
             assert_eq!(err.to_string(), expected);
         });
     }
+
+    #[test]
+    fn fallback_trigger_classifies_provider_failures() {
+        let unexpected = |status| {
+            CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: String::new(),
+                request_id: None,
+            })
+        };
+        assert_eq!(
+            unexpected(StatusCode::BAD_GATEWAY).fallback_trigger(),
+            Some(FallbackTrigger::ServerError)
+        );
+        assert_eq!(unexpected(StatusCode::NOT_FOUND).fallback_trigger(), None);
+        assert_eq!(
+            CodexErr::Stream("disconnected".to_string(), None).fallback_trigger(),
+            Some(FallbackTrigger::Connection)
+        );
+        assert_eq!(
+            CodexErr::QuotaExceeded.fallback_trigger(),
+            Some(FallbackTrigger::UsageLimit)
+        );
+        assert_eq!(CodexErr::ContextWindowExceeded.fallback_trigger(), None);
+    }
 }
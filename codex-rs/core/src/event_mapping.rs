@@ -13,6 +13,7 @@ use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::repo_map::RepoMap;
 use crate::user_instructions::SkillInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
@@ -26,6 +27,7 @@ fn is_session_prefix(text: &str) -> bool {
 fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
     if UserInstructions::is_user_instructions(message)
         || SkillInstructions::is_skill_instructions(message)
+        || RepoMap::is_repo_map(message)
     {
         return None;
     }
@@ -216,6 +218,13 @@ mod tests {
                     text: "<user_shell_command>echo 42</user_shell_command>".to_string(),
                 }],
             },
+            ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "<repository_map>\n## Layout\n</repository_map>".to_string(),
+                }],
+            },
         ];
 
         for item in items {
@@ -20,11 +20,13 @@ use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
 use crate::get_platform_sandbox;
+use crate::network_proxy::NetworkProxy;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecOutputStream;
 use crate::protocol::SandboxPolicy;
+use crate::read_deny::ReadDeniedPaths;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
@@ -167,6 +169,15 @@ pub async fn process_exec_tool_call(
         justification,
     };
 
+    // Commands run outside a session get a proxy of their own that lives as
+    // long as the command does, and resolve their `read_deny` paths anew.
+    let network_proxy = NetworkProxy::start_for_policy(sandbox_policy, sandbox_type).await;
+    let read_denied_paths = if sandbox_type == SandboxType::LinuxSeccomp {
+        Some(ReadDeniedPaths::resolve(sandbox_policy, sandbox_cwd).await)
+    } else {
+        None
+    };
+
     let manager = SandboxManager::new();
     let exec_env = manager
         .transform(
@@ -175,6 +186,8 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
+            network_proxy.as_ref().map(NetworkProxy::endpoint),
+            read_denied_paths.as_ref(),
         )
         .map_err(CodexErr::from)?;
 
@@ -378,10 +391,13 @@ pub(crate) mod errors {
 
     impl From<SandboxTransformError> for CodexErr {
         fn from(err: SandboxTransformError) -> Self {
-            match err {
+            match &err {
                 SandboxTransformError::MissingLinuxSandboxExecutable => {
                     CodexErr::LandlockSandboxExecutableNotProvided
                 }
+                SandboxTransformError::MissingReadDeniedPaths => {
+                    CodexErr::UnsupportedOperation(err.to_string())
+                }
                 #[cfg(not(target_os = "macos"))]
                 SandboxTransformError::SeatbeltUnavailable => CodexErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
@@ -878,7 +894,13 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::new_read_only_policy(),
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
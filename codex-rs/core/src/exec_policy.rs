@@ -518,7 +518,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Features::with_defaults(),
             &command,
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -623,7 +623,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Features::with_defaults(),
             &command,
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -649,7 +649,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &features,
             &command,
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -704,7 +704,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Features::with_defaults(),
             &command,
             AskForApproval::UnlessTrusted,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -742,7 +742,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 &Features::with_defaults(),
                 &command,
                 AskForApproval::UnlessTrusted,
-                &SandboxPolicy::ReadOnly,
+                &SandboxPolicy::new_read_only_policy(),
                 SandboxPermissions::UseDefault,
             )
             .await,
@@ -764,7 +764,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Features::with_defaults(),
             &command,
             AskForApproval::OnRequest,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
@@ -793,7 +793,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             &Features::with_defaults(),
             &command,
             AskForApproval::OnRequest,
-            &SandboxPolicy::ReadOnly,
+            &SandboxPolicy::new_read_only_policy(),
             SandboxPermissions::UseDefault,
         )
         .await;
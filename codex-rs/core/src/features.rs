@@ -56,6 +56,8 @@ pub enum Feature {
     RemoteCompaction,
     /// Refresh remote models and emit AppReady once the list is available.
     RemoteModels,
+    /// List models served by local Ollama and LM Studio servers in `/model`.
+    LocalModels,
     /// Allow model to call multiple tools in parallel (only for models supporting it).
     ParallelToolCalls,
     /// Experimental skills injection (CLI flag-driven).
@@ -64,6 +66,10 @@ pub enum Feature {
     ShellSnapshot,
     /// Experimental TUI v2 (viewport) implementation.
     Tui2,
+    /// Serve `@` mentions and fuzzy file searches from a persistent, watched index.
+    FileSearchIndex,
+    /// Inject a compact map of the repository into the initial context.
+    RepoMap,
 }
 
 impl Feature {
@@ -347,6 +353,12 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::LocalModels,
+        key: "local_models",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::Skills,
         key: "skills",
@@ -365,4 +377,16 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::FileSearchIndex,
+        key: "file_search_index",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::RepoMap,
+        key: "repo_map",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
@@ -1,4 +1,7 @@
+use crate::network_proxy::NetworkProxyEndpoint;
+use crate::network_proxy::proxy_env_vars;
 use crate::protocol::SandboxPolicy;
+use crate::read_deny::ReadDeniedPaths;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use std::collections::HashMap;
@@ -13,6 +16,11 @@ use tokio::process::Child;
 /// helper accepts a list of `--sandbox-permission`/`-s` flags mirroring the
 /// public CLI. We convert the internal [`SandboxPolicy`] representation into
 /// the equivalent CLI options.
+///
+/// Pass `network_proxy` (see [`crate::network_proxy::NetworkProxy`]) to let
+/// the command reach the policy's `network_allowlist`; without it the network
+/// stays disabled.
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_command_under_linux_sandbox<P>(
     codex_linux_sandbox_exe: P,
     command: Vec<String>,
@@ -20,12 +28,23 @@ pub async fn spawn_command_under_linux_sandbox<P>(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
     stdio_policy: StdioPolicy,
-    env: HashMap<String, String>,
+    mut env: HashMap<String, String>,
+    network_proxy: Option<&NetworkProxyEndpoint>,
 ) -> std::io::Result<Child>
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    if let Some(network_proxy) = network_proxy {
+        env.extend(proxy_env_vars(network_proxy.port));
+    }
+    let read_denied_paths = ReadDeniedPaths::resolve(sandbox_policy, sandbox_policy_cwd).await;
+    let args = create_linux_sandbox_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        network_proxy,
+        &read_denied_paths,
+    );
     let arg0 = Some("codex-linux-sandbox");
     spawn_child_async(
         codex_linux_sandbox_exe.as_ref().to_path_buf(),
@@ -40,10 +59,16 @@ where
 }
 
 /// Converts the sandbox policy into the CLI invocation for `codex-linux-sandbox`.
+///
+/// When `network_proxy` is set, the helper runs the command in a network
+/// namespace where only the proxy's port is reachable instead of blocking the
+/// network outright. `read_denied_paths` are hidden from the command.
 pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy: Option<&NetworkProxyEndpoint>,
+    read_denied_paths: &ReadDeniedPaths,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -60,10 +85,24 @@ pub(crate) fn create_linux_sandbox_command_args(
         sandbox_policy_cwd,
         "--sandbox-policy".to_string(),
         sandbox_policy_json,
-        // Separator so that command arguments starting with `-` are not parsed as
-        // options of the helper itself.
-        "--".to_string(),
     ];
+    if let Some(network_proxy) = network_proxy {
+        linux_cmd.push("--network-proxy-port".to_string());
+        linux_cmd.push(network_proxy.port.to_string());
+        linux_cmd.push("--network-proxy-socket".to_string());
+        linux_cmd.push(network_proxy.socket_path.to_string_lossy().to_string());
+    }
+    for path in &read_denied_paths.paths {
+        linux_cmd.push("--read-deny-path".to_string());
+        linux_cmd.push(path.to_string_lossy().to_string());
+    }
+    for path in &read_denied_paths.carve_paths {
+        linux_cmd.push("--read-deny-carve-path".to_string());
+        linux_cmd.push(path.to_string_lossy().to_string());
+    }
+    // Separator so that command arguments starting with `-` are not parsed as
+    // options of the helper itself.
+    linux_cmd.push("--".to_string());
 
     // Append the original tool command.
     linux_cmd.extend(command);
@@ -25,6 +25,7 @@ mod command_safety;
 pub mod config;
 pub mod config_loader;
 mod context_manager;
+pub mod cost_ledger;
 pub mod custom_prompts;
 pub mod env;
 mod environment_context;
@@ -44,13 +45,17 @@ pub use mcp_connection_manager::MCP_SANDBOX_STATE_NOTIFICATION;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
+mod model_cassette;
 mod model_provider_info;
+pub mod network_proxy;
 pub mod parse_command;
 pub mod powershell;
+pub mod read_deny;
 pub mod sandboxing;
 mod stream_events_utils;
 mod text_encoding;
 pub mod token_data;
+pub mod tokenizer;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
@@ -97,7 +102,9 @@ pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
 mod function_tool;
+mod repo_map;
 mod state;
+mod symbols;
 mod tasks;
 mod user_notification;
 mod user_shell_command;
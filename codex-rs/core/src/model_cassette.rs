@@ -0,0 +1,70 @@
+//! Transport selection for model requests, including `--record` / `--replay`
+//! cassettes.
+//!
+//! A session creates several model clients (one per turn, plus sub-agents and
+//! compaction), so cassettes are shared per path for the whole process: all
+//! clients append to the same recording and consume the same replay queue.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_api::ReqwestTransport;
+use codex_client::CassetteRecorder;
+use codex_client::HttpTransport;
+use codex_client::RecordingTransport;
+use codex_client::ReplayTransport;
+
+use crate::config::Config;
+use crate::config::types::ModelCassette;
+use crate::default_client::build_reqwest_client;
+use crate::error::Result;
+
+enum CassetteHandle {
+    Recorder(Arc<CassetteRecorder>),
+    Replay(Arc<ReplayTransport>),
+}
+
+static CASSETTES: LazyLock<Mutex<HashMap<PathBuf, CassetteHandle>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Transport used for model API requests under `config`.
+pub(crate) fn model_transport(config: &Config) -> Result<Arc<dyn HttpTransport>> {
+    let reqwest = ReqwestTransport::new(build_reqwest_client());
+    let Some(cassette) = &config.model_cassette else {
+        return Ok(Arc::new(reqwest));
+    };
+
+    let mut cassettes = CASSETTES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match cassette {
+        ModelCassette::Record(path) => {
+            let recorder = match cassettes.get(path) {
+                Some(CassetteHandle::Recorder(recorder)) => Arc::clone(recorder),
+                _ => {
+                    let recorder = Arc::new(CassetteRecorder::create(path)?);
+                    cassettes.insert(
+                        path.clone(),
+                        CassetteHandle::Recorder(Arc::clone(&recorder)),
+                    );
+                    recorder
+                }
+            };
+            Ok(Arc::new(RecordingTransport::new(reqwest, recorder)))
+        }
+        ModelCassette::Replay(path) => {
+            let replay = match cassettes.get(path) {
+                Some(CassetteHandle::Replay(replay)) => Arc::clone(replay),
+                _ => {
+                    let replay = Arc::new(ReplayTransport::open(path)?);
+                    cassettes.insert(path.clone(), CassetteHandle::Replay(Arc::clone(&replay)));
+                    replay
+                }
+            };
+            Ok(replay)
+        }
+    }
+}
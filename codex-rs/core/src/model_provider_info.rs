@@ -33,9 +33,10 @@ const OPENAI_PROVIDER_NAME: &str = "OpenAI";
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
 /// itself (and a handful of others) additionally expose the more modern
-/// *Responses* API. The two protocols use different request/response shapes
-/// and *cannot* be auto-detected at runtime, therefore each provider entry
-/// must declare which one it expects.
+/// *Responses* API. Gateways in front of Anthropic models speak the
+/// *Messages* API instead. The protocols use different request/response
+/// shapes and *cannot* be auto-detected at runtime, therefore each provider
+/// entry must declare which one it expects.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WireApi {
@@ -45,6 +46,9 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// The Anthropic Messages API at `/v1/messages`.
+    Messages,
 }
 
 /// Serializable representation of a provider definition.
@@ -157,6 +161,7 @@ impl ModelProviderInfo {
             wire: match self.wire_api {
                 WireApi::Responses => ApiWireApi::Responses,
                 WireApi::Chat => ApiWireApi::Chat,
+                WireApi::Messages => ApiWireApi::Messages,
             },
             headers,
             retry,
@@ -384,6 +389,36 @@ query_params = { api-version = "2025-04-01-preview" }
         assert_eq!(expected_provider, provider);
     }
 
+    #[test]
+    fn test_deserialize_messages_model_provider_toml() {
+        let messages_provider_toml = r#"
+name = "Anthropic"
+base_url = "https://api.anthropic.com/v1"
+wire_api = "messages"
+env_http_headers = { "x-api-key" = "ANTHROPIC_API_KEY" }
+        "#;
+        let expected_provider = ModelProviderInfo {
+            name: "Anthropic".into(),
+            base_url: Some("https://api.anthropic.com/v1".into()),
+            env_key: None,
+            env_key_instructions: None,
+            experimental_bearer_token: None,
+            wire_api: WireApi::Messages,
+            query_params: None,
+            http_headers: None,
+            env_http_headers: Some(maplit::hashmap! {
+                "x-api-key".to_string() => "ANTHROPIC_API_KEY".to_string(),
+            }),
+            request_max_retries: None,
+            stream_max_retries: None,
+            stream_idle_timeout_ms: None,
+            requires_openai_auth: false,
+        };
+
+        let provider: ModelProviderInfo = toml::from_str(messages_provider_toml).unwrap();
+        assert_eq!(expected_provider, provider);
+    }
+
     #[test]
     fn test_deserialize_example_model_provider_toml() {
         let azure_provider_toml = r#"
@@ -0,0 +1,1067 @@
+//! Local egress proxy that enforces `network_allowlist` for sandboxed
+//! commands.
+//!
+//! When a `workspace-write` policy denies general network access but lists
+//! allowed destinations, sandboxed commands are pointed at this proxy through
+//! the usual `HTTP(S)_PROXY`/`ALL_PROXY` environment variables. The platform
+//! sandbox then only lets them reach the proxy: Seatbelt confines outbound
+//! traffic to its loopback port, while the Linux helper moves the command into
+//! a network namespace of its own and relays that port to the proxy's Unix
+//! socket. The proxy speaks HTTP (absolute-URI requests and `CONNECT`) and
+//! SOCKS5 on both listeners and only dials destinations that match the
+//! allowlist.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::debug;
+use tracing::warn;
+
+use crate::exec::SandboxType;
+use crate::protocol::SandboxPolicy;
+
+/// Largest HTTP request head the proxy will buffer before giving up.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// Longest chunk-size or trailer line accepted in a chunked request body.
+const MAX_CHUNK_LINE_BYTES: u64 = 4 * 1024;
+
+const SOCKS5_VERSION: u8 = 0x05;
+
+/// One parsed `network_allowlist` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AllowRule {
+    host: HostPattern,
+    /// `None` allows every port.
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    /// Exact host name, lower-cased.
+    Name(String),
+    /// `*.example.com`: any subdomain of the stored suffix.
+    Subdomains(String),
+    /// Loopback interface, either by name or address.
+    Localhost,
+    /// An IP network; a plain address is stored with a full-length prefix.
+    Cidr { network: IpAddr, prefix_len: u8 },
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub(crate) enum AllowlistEntryError {
+    #[error("empty network allowlist entry")]
+    Empty,
+    #[error("invalid port in network allowlist entry `{0}`")]
+    InvalidPort(String),
+    #[error("invalid CIDR range in network allowlist entry `{0}`")]
+    InvalidCidr(String),
+    #[error("invalid host in network allowlist entry `{0}`")]
+    InvalidHost(String),
+}
+
+/// Parsed form of the `network_allowlist` from a [`SandboxPolicy`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct NetworkAllowlist {
+    rules: Vec<AllowRule>,
+}
+
+impl NetworkAllowlist {
+    /// Parses every entry, skipping (and logging) the ones that are invalid so
+    /// a typo never widens access.
+    pub(crate) fn from_entries(entries: &[String]) -> Self {
+        let rules = entries
+            .iter()
+            .filter_map(|entry| match parse_entry(entry) {
+                Ok(rule) => Some(rule),
+                Err(err) => {
+                    warn!("ignoring network allowlist entry: {err}");
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Whether a destination given by host name (or address literal) is
+    /// allowed without looking at its resolved addresses.
+    fn allows_host(&self, host: &str, port: u16) -> bool {
+        let host = normalize_host(host);
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return self.allows_ip(ip, port);
+        }
+        self.rules.iter().any(|rule| {
+            port_matches(rule.port, port)
+                && match &rule.host {
+                    HostPattern::Name(name) => *name == host,
+                    HostPattern::Subdomains(suffix) => host
+                        .strip_suffix(suffix.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.') && prefix.len() > 1),
+                    HostPattern::Localhost => host == "localhost",
+                    HostPattern::Cidr { .. } => false,
+                }
+        })
+    }
+
+    fn allows_ip(&self, ip: IpAddr, port: u16) -> bool {
+        self.rules.iter().any(|rule| {
+            port_matches(rule.port, port)
+                && match &rule.host {
+                    HostPattern::Localhost => ip.is_loopback(),
+                    HostPattern::Cidr {
+                        network,
+                        prefix_len,
+                    } => cidr_contains(*network, *prefix_len, ip),
+                    HostPattern::Name(_) | HostPattern::Subdomains(_) => false,
+                }
+        })
+    }
+
+    /// Whether any rule can only be decided after DNS resolution.
+    fn has_address_rules(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(rule.host, HostPattern::Cidr { .. } | HostPattern::Localhost))
+    }
+}
+
+fn port_matches(rule_port: Option<u16>, port: u16) -> bool {
+    rule_port.is_none_or(|allowed| allowed == port)
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+fn cidr_contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    let ip = match (network, ip) {
+        (IpAddr::V4(_), IpAddr::V6(v6)) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        _ => ip,
+    };
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(prefix_len))
+                .unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Parses a single allowlist entry such as `crates.io`, `*.example.com:443`,
+/// `10.0.0.0/8`, `[::1]:8080` or `localhost`.
+fn parse_entry(entry: &str) -> Result<AllowRule, AllowlistEntryError> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return Err(AllowlistEntryError::Empty);
+    }
+
+    let (host, port) = if let Some(rest) = entry.strip_prefix('[') {
+        let (host, after) = rest
+            .split_once(']')
+            .ok_or_else(|| AllowlistEntryError::InvalidHost(entry.to_string()))?;
+        let port = match after {
+            "" => None,
+            _ => Some(
+                after
+                    .strip_prefix(':')
+                    .ok_or_else(|| AllowlistEntryError::InvalidPort(entry.to_string()))?,
+            ),
+        };
+        (host, port)
+    } else if entry.matches(':').count() > 1 {
+        // Bare IPv6 address or range; a port requires the bracketed form.
+        (entry, None)
+    } else {
+        match entry.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (entry, None),
+        }
+    };
+
+    let port = port
+        .map(|port| {
+            port.parse::<u16>()
+                .map_err(|_| AllowlistEntryError::InvalidPort(entry.to_string()))
+        })
+        .transpose()?;
+
+    let host = if let Some((address, prefix_len)) = host.split_once('/') {
+        let network = address
+            .parse::<IpAddr>()
+            .map_err(|_| AllowlistEntryError::InvalidCidr(entry.to_string()))?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = prefix_len
+            .parse::<u8>()
+            .ok()
+            .filter(|len| *len <= max_prefix_len)
+            .ok_or_else(|| AllowlistEntryError::InvalidCidr(entry.to_string()))?;
+        HostPattern::Cidr {
+            network,
+            prefix_len,
+        }
+    } else if let Ok(ip) = host.parse::<IpAddr>() {
+        HostPattern::Cidr {
+            network: ip,
+            prefix_len: if ip.is_ipv4() { 32 } else { 128 },
+        }
+    } else {
+        let host = normalize_host(host);
+        if host == "localhost" {
+            HostPattern::Localhost
+        } else if let Some(suffix) = host.strip_prefix("*.") {
+            if !is_valid_host_name(suffix) {
+                return Err(AllowlistEntryError::InvalidHost(entry.to_string()));
+            }
+            HostPattern::Subdomains(suffix.to_string())
+        } else if is_valid_host_name(&host) {
+            HostPattern::Name(host)
+        } else {
+            return Err(AllowlistEntryError::InvalidHost(entry.to_string()));
+        }
+    };
+
+    Ok(AllowRule { host, port })
+}
+
+fn is_valid_host_name(host: &str) -> bool {
+    !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+#[derive(Debug)]
+enum ConnectError {
+    Denied,
+    Io(io::Error),
+}
+
+/// Opens a connection to `host:port` if the allowlist permits it. Host names
+/// that are not allowed by name are resolved and checked against address
+/// rules; the connection then goes to the matching address so a later DNS
+/// answer cannot redirect it.
+async fn connect_allowed(
+    allowlist: &NetworkAllowlist,
+    host: &str,
+    port: u16,
+) -> Result<TcpStream, ConnectError> {
+    let normalized = normalize_host(host);
+    if allowlist.allows_host(&normalized, port) {
+        return TcpStream::connect((normalized.as_str(), port))
+            .await
+            .map_err(ConnectError::Io);
+    }
+    if !allowlist.has_address_rules() || normalized.parse::<IpAddr>().is_ok() {
+        return Err(ConnectError::Denied);
+    }
+
+    let addrs = tokio::net::lookup_host((normalized.as_str(), port))
+        .await
+        .map_err(ConnectError::Io)?;
+    let allowed: Vec<SocketAddr> = addrs
+        .filter(|addr| allowlist.allows_ip(addr.ip(), port))
+        .collect();
+    if allowed.is_empty() {
+        return Err(ConnectError::Denied);
+    }
+    TcpStream::connect(allowed.as_slice())
+        .await
+        .map_err(ConnectError::Io)
+}
+
+/// Where sandboxed commands can reach a running proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkProxyEndpoint {
+    /// Loopback TCP port advertised through the proxy environment variables.
+    pub port: u16,
+    /// Unix socket serving the same protocols. The Linux sandbox relays
+    /// `port` to it from inside the command's network namespace.
+    pub socket_path: PathBuf,
+}
+
+/// A running proxy bound to a loopback port and a private Unix socket.
+pub struct NetworkProxy {
+    endpoint: NetworkProxyEndpoint,
+    entries: Vec<String>,
+    tasks: Vec<JoinHandle<()>>,
+    /// Owns the directory holding the Unix socket; it is removed on drop.
+    _socket_dir: tempfile::TempDir,
+}
+
+impl NetworkProxy {
+    /// Starts a proxy that only dials destinations allowed by `entries`.
+    pub async fn start(entries: Vec<String>) -> io::Result<Self> {
+        let allowlist = Arc::new(NetworkAllowlist::from_entries(&entries));
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let port = listener.local_addr()?.port();
+        // The temporary directory is only accessible to the current user, so
+        // other users cannot connect to the socket.
+        let socket_dir = tempfile::Builder::new().prefix("codex-proxy-").tempdir()?;
+        let socket_path = socket_dir.path().join("proxy.sock");
+        #[cfg(unix)]
+        let unix_listener = tokio::net::UnixListener::bind(&socket_path)?;
+        debug!(
+            "network proxy listening on 127.0.0.1:{port} and {}",
+            socket_path.display()
+        );
+
+        let mut tasks = Vec::new();
+        let tcp_allowlist = Arc::clone(&allowlist);
+        tasks.push(tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => spawn_client(stream, peer.to_string(), &tcp_allowlist),
+                    Err(err) => warn!("network proxy accept failed: {err}"),
+                }
+            }
+        }));
+        #[cfg(unix)]
+        tasks.push(tokio::spawn(async move {
+            loop {
+                match unix_listener.accept().await {
+                    Ok((stream, _)) => spawn_client(stream, "unix socket".to_string(), &allowlist),
+                    Err(err) => warn!("network proxy accept failed: {err}"),
+                }
+            }
+        }));
+
+        Ok(Self {
+            endpoint: NetworkProxyEndpoint { port, socket_path },
+            entries,
+            tasks,
+            _socket_dir: socket_dir,
+        })
+    }
+
+    /// Starts a proxy for a single command run outside a session. Returns
+    /// `None` when the policy has no allowlist or `sandbox` cannot confine
+    /// the command to the proxy; the network then stays disabled.
+    pub async fn start_for_policy(policy: &SandboxPolicy, sandbox: SandboxType) -> Option<Self> {
+        let entries = policy.network_allowlist();
+        if entries.is_empty() || !sandbox_supports_network_proxy(sandbox) {
+            return None;
+        }
+        match Self::start(entries.to_vec()).await {
+            Ok(proxy) => Some(proxy),
+            Err(err) => {
+                warn!("failed to start network proxy; network stays disabled: {err}");
+                None
+            }
+        }
+    }
+
+    pub fn endpoint(&self) -> &NetworkProxyEndpoint {
+        &self.endpoint
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn spawn_client<S>(stream: S, peer: String, allowlist: &Arc<NetworkAllowlist>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let allowlist = Arc::clone(allowlist);
+    tokio::spawn(async move {
+        if let Err(err) = handle_client(stream, &allowlist).await {
+            debug!("network proxy connection from {peer} ended: {err}");
+        }
+    });
+}
+
+async fn handle_client<S>(mut client: S, allowlist: &NetworkAllowlist) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let first = match client.read_u8().await {
+        Ok(first) => first,
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if first == SOCKS5_VERSION {
+        handle_socks5(client, allowlist).await
+    } else {
+        handle_http(client, first, allowlist).await
+    }
+}
+
+async fn handle_http<S>(mut client: S, first: u8, allowlist: &NetworkAllowlist) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut buf = Vec::with_capacity(4096);
+    buf.push(first);
+    let head_len = loop {
+        if let Some(pos) = find_subsequence(&buf, b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() >= MAX_REQUEST_HEAD_BYTES {
+            return write_http_status(&mut client, "431 Request Header Fields Too Large").await;
+        }
+        let mut chunk = [0u8; 4096];
+        let n = client.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_len]).into_owned();
+    let body_prefix = buf[head_len..].to_vec();
+
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return write_http_status(&mut client, "400 Bad Request").await;
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let Some((host, port)) = split_host_port(target, 443) else {
+            return write_http_status(&mut client, "400 Bad Request").await;
+        };
+        let mut upstream = match connect_allowed(allowlist, &host, port).await {
+            Ok(upstream) => upstream,
+            Err(err) => return reject_http(&mut client, &host, port, err).await,
+        };
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        if !body_prefix.is_empty() {
+            upstream.write_all(&body_prefix).await?;
+        }
+        tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        return Ok(());
+    }
+
+    let Some(rest) = target.strip_prefix("http://") else {
+        return write_http_status(&mut client, "400 Bad Request").await;
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    let Some((host, port)) = split_host_port(authority, 80) else {
+        return write_http_status(&mut client, "400 Bad Request").await;
+    };
+
+    // Rewrite to origin-form and note how the body is framed, so exactly one
+    // request is forwarded per client connection.
+    let mut forwarded = format!("{method} {path} {version}\r\n");
+    let mut content_length = None;
+    let mut chunked = false;
+    for line in lines.filter(|line| !line.is_empty()) {
+        let (name, value) = line.split_once(':').unwrap_or((line, ""));
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("proxy-connection")
+            || name.eq_ignore_ascii_case("proxy-authorization")
+            || name.eq_ignore_ascii_case("connection")
+        {
+            continue;
+        }
+        if name.eq_ignore_ascii_case("content-length") {
+            match value.trim().parse::<u64>() {
+                Ok(len) => content_length = Some(len),
+                Err(_) => return write_http_status(&mut client, "400 Bad Request").await,
+            }
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        }
+        forwarded.push_str(line);
+        forwarded.push_str("\r\n");
+    }
+    forwarded.push_str("Connection: close\r\n\r\n");
+
+    let mut upstream = match connect_allowed(allowlist, &host, port).await {
+        Ok(upstream) => upstream,
+        Err(err) => return reject_http(&mut client, &host, port, err).await,
+    };
+    upstream.write_all(forwarded.as_bytes()).await?;
+    {
+        let mut body = BufReader::new((&body_prefix[..]).chain(&mut client));
+        if chunked {
+            relay_chunked_body(&mut body, &mut upstream).await?;
+        } else if let Some(len) = content_length {
+            let copied = tokio::io::copy(&mut (&mut body).take(len), &mut upstream).await?;
+            if copied < len {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+    }
+
+    // Anything the client sends after the request is never read: the
+    // connection closes once the response has been relayed, so a kept-alive
+    // client cannot follow up with a request for a host that was not
+    // checked.
+    tokio::io::copy(&mut upstream, &mut client).await?;
+    client.shutdown().await
+}
+
+/// Copies a `Transfer-Encoding: chunked` body, including its trailers, and
+/// stops right after its final line.
+async fn relay_chunked_body<R, W>(reader: &mut R, writer: &mut W) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut line = Vec::new();
+    loop {
+        read_chunk_line(reader, &mut line).await?;
+        writer.write_all(&line).await?;
+        let size = std::str::from_utf8(&line)
+            .ok()
+            .and_then(|line| {
+                let size = line.split(';').next().unwrap_or_default().trim();
+                u64::from_str_radix(size, 16).ok()
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid chunk size"))?;
+        if size == 0 {
+            break;
+        }
+        // Chunk data followed by its CRLF.
+        let expected = size.saturating_add(2);
+        let copied = tokio::io::copy(&mut (&mut *reader).take(expected), writer).await?;
+        if copied < expected {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+    // Trailer section, terminated by an empty line.
+    loop {
+        read_chunk_line(reader, &mut line).await?;
+        writer.write_all(&line).await?;
+        if line == b"\r\n" || line == b"\n" {
+            return Ok(());
+        }
+    }
+}
+
+async fn read_chunk_line<R>(reader: &mut R, line: &mut Vec<u8>) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+{
+    line.clear();
+    (&mut *reader)
+        .take(MAX_CHUNK_LINE_BYTES)
+        .read_until(b'\n', line)
+        .await?;
+    if line.last() != Some(&b'\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "truncated or oversized chunk line",
+        ));
+    }
+    Ok(())
+}
+
+async fn reject_http<S>(client: &mut S, host: &str, port: u16, err: ConnectError) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    match err {
+        ConnectError::Denied => {
+            warn!("network proxy denied connection to {host}:{port} (not in network_allowlist)");
+            write_http_status(client, "403 Forbidden").await
+        }
+        ConnectError::Io(err) => {
+            debug!("network proxy failed to reach {host}:{port}: {err}");
+            write_http_status(client, "502 Bad Gateway").await
+        }
+    }
+}
+
+async fn write_http_status<S>(client: &mut S, status: &str) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    client.write_all(response.as_bytes()).await?;
+    client.shutdown().await
+}
+
+fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Splits `host[:port]` (with optional IPv6 brackets), falling back to
+/// `default_port` when no port is given.
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        let port = match after.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None if after.is_empty() => default_port,
+            None => return None,
+        };
+        return Some((host.to_string(), port));
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => Some((host.to_string(), port.parse().ok()?)),
+        Some(_) => None,
+        None if !authority.is_empty() => Some((authority.to_string(), default_port)),
+        None => None,
+    }
+}
+
+/// Handles a SOCKS5 client whose version byte has already been read.
+async fn handle_socks5<S>(mut client: S, allowlist: &NetworkAllowlist) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Greeting: VER NMETHODS METHODS...
+    let method_count = client.read_u8().await?;
+    let mut methods = vec![0u8; usize::from(method_count)];
+    client.read_exact(&mut methods).await?;
+    if !methods.contains(&0x00) {
+        client.write_all(&[SOCKS5_VERSION, 0xff]).await?;
+        return Ok(());
+    }
+    client.write_all(&[SOCKS5_VERSION, 0x00]).await?;
+
+    // Request: VER CMD RSV ATYP DST.ADDR DST.PORT
+    let mut request = [0u8; 4];
+    client.read_exact(&mut request).await?;
+    let host = match request[3] {
+        0x01 => {
+            let mut octets = [0u8; 4];
+            client.read_exact(&mut octets).await?;
+            IpAddr::from(octets).to_string()
+        }
+        0x03 => {
+            let len = client.read_u8().await?;
+            let mut name = vec![0u8; usize::from(len)];
+            client.read_exact(&mut name).await?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        0x04 => {
+            let mut octets = [0u8; 16];
+            client.read_exact(&mut octets).await?;
+            IpAddr::from(octets).to_string()
+        }
+        _ => {
+            return write_socks5_reply(&mut client, 0x08).await;
+        }
+    };
+    let port = client.read_u16().await?;
+
+    if request[1] != 0x01 {
+        // Only CONNECT is supported; BIND and UDP ASSOCIATE are refused.
+        return write_socks5_reply(&mut client, 0x07).await;
+    }
+
+    let mut upstream = match connect_allowed(allowlist, &host, port).await {
+        Ok(upstream) => upstream,
+        Err(ConnectError::Denied) => {
+            warn!("network proxy denied connection to {host}:{port} (not in network_allowlist)");
+            return write_socks5_reply(&mut client, 0x02).await;
+        }
+        Err(ConnectError::Io(err)) => {
+            debug!("network proxy failed to reach {host}:{port}: {err}");
+            return write_socks5_reply(&mut client, 0x05).await;
+        }
+    };
+    write_socks5_reply(&mut client, 0x00).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn write_socks5_reply<S>(client: &mut S, status: u8) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    client
+        .write_all(&[SOCKS5_VERSION, status, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+        .await
+}
+
+/// Whether `sandbox` can confine a command to the proxy. The Windows sandbox
+/// cannot, so commands there keep the network disabled even when the policy
+/// has an allowlist.
+pub fn sandbox_supports_network_proxy(sandbox: SandboxType) -> bool {
+    matches!(
+        sandbox,
+        SandboxType::MacosSeatbelt | SandboxType::LinuxSeccomp
+    )
+}
+
+/// Owns the per-session proxy and restarts it when the allowlist changes
+/// between turns.
+#[derive(Default)]
+pub(crate) struct NetworkProxyManager {
+    proxy: Mutex<Option<NetworkProxy>>,
+}
+
+impl NetworkProxyManager {
+    /// Returns where sandboxed commands reach a proxy enforcing the policy's
+    /// allowlist, or `None` when the policy does not use one.
+    pub(crate) async fn endpoint_for(
+        &self,
+        policy: &SandboxPolicy,
+    ) -> Option<NetworkProxyEndpoint> {
+        let entries = policy.network_allowlist();
+        let mut guard = self.proxy.lock().await;
+        if entries.is_empty() {
+            *guard = None;
+            return None;
+        }
+        if let Some(proxy) = guard.as_ref()
+            && proxy.entries == entries
+        {
+            return Some(proxy.endpoint().clone());
+        }
+        match NetworkProxy::start(entries.to_vec()).await {
+            Ok(proxy) => {
+                let endpoint = proxy.endpoint().clone();
+                *guard = Some(proxy);
+                Some(endpoint)
+            }
+            Err(err) => {
+                warn!("failed to start network proxy; network stays disabled: {err}");
+                *guard = None;
+                None
+            }
+        }
+    }
+}
+
+/// Environment variables that point common HTTP clients at the proxy.
+pub fn proxy_env_vars(port: u16) -> HashMap<String, String> {
+    let http_proxy = format!("http://127.0.0.1:{port}");
+    let socks_proxy = format!("socks5h://127.0.0.1:{port}");
+    let mut env = HashMap::new();
+    for key in ["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"] {
+        env.insert(key.to_string(), http_proxy.clone());
+    }
+    for key in ["ALL_PROXY", "all_proxy"] {
+        env.insert(key.to_string(), socks_proxy.clone());
+    }
+    // Loopback bypasses would fail under the sandbox; send everything
+    // through the proxy instead.
+    for key in ["NO_PROXY", "no_proxy"] {
+        env.insert(key.to_string(), String::new());
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(entries: &[&str]) -> NetworkAllowlist {
+        NetworkAllowlist::from_entries(
+            &entries
+                .iter()
+                .map(|entry| (*entry).to_string())
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn parses_entries() {
+        assert_eq!(
+            parse_entry("crates.io"),
+            Ok(AllowRule {
+                host: HostPattern::Name("crates.io".to_string()),
+                port: None,
+            })
+        );
+        assert_eq!(
+            parse_entry("*.Example.com:443"),
+            Ok(AllowRule {
+                host: HostPattern::Subdomains("example.com".to_string()),
+                port: Some(443),
+            })
+        );
+        assert_eq!(
+            parse_entry("10.0.0.0/8"),
+            Ok(AllowRule {
+                host: HostPattern::Cidr {
+                    network: "10.0.0.0".parse().unwrap(),
+                    prefix_len: 8,
+                },
+                port: None,
+            })
+        );
+        assert_eq!(
+            parse_entry("[::1]:8080"),
+            Ok(AllowRule {
+                host: HostPattern::Cidr {
+                    network: "::1".parse().unwrap(),
+                    prefix_len: 128,
+                },
+                port: Some(8080),
+            })
+        );
+        assert_eq!(
+            parse_entry("localhost:3000"),
+            Ok(AllowRule {
+                host: HostPattern::Localhost,
+                port: Some(3000),
+            })
+        );
+        assert_eq!(
+            parse_entry("crates.io:http"),
+            Err(AllowlistEntryError::InvalidPort(
+                "crates.io:http".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_entry("10.0.0.0/33"),
+            Err(AllowlistEntryError::InvalidCidr("10.0.0.0/33".to_string()))
+        );
+        assert_eq!(
+            parse_entry("*"),
+            Err(AllowlistEntryError::InvalidHost("*".to_string()))
+        );
+    }
+
+    #[test]
+    fn matches_hosts_and_addresses() {
+        let list = allowlist(&[
+            "crates.io",
+            "*.internal.example:8443",
+            "10.1.0.0/16",
+            "localhost",
+        ]);
+
+        assert!(list.allows_host("crates.io", 443));
+        assert!(list.allows_host("CRATES.IO.", 80));
+        assert!(!list.allows_host("static.crates.io", 443));
+        assert!(list.allows_host("registry.internal.example", 8443));
+        assert!(!list.allows_host("registry.internal.example", 443));
+        assert!(!list.allows_host("internal.example", 8443));
+        assert!(list.allows_host("10.1.2.3", 22));
+        assert!(!list.allows_host("10.2.0.1", 22));
+        assert!(list.allows_host("localhost", 5432));
+        assert!(list.allows_host("127.0.0.1", 5432));
+        assert!(list.allows_host("[::1]", 5432));
+        assert!(list.allows_ip("::ffff:10.1.0.9".parse().unwrap(), 80));
+        assert!(!list.allows_host("example.com", 443));
+    }
+
+    #[test]
+    fn invalid_entries_are_dropped() {
+        let list = allowlist(&["", "bad host", "crates.io"]);
+        assert_eq!(list.rules.len(), 1);
+        assert!(!allowlist(&["nope:port"]).allows_host("nope", 80));
+        assert_eq!(allowlist(&[]), NetworkAllowlist::default());
+    }
+
+    #[test]
+    fn splits_authority() {
+        assert_eq!(
+            split_host_port("crates.io:443", 80),
+            Some(("crates.io".to_string(), 443))
+        );
+        assert_eq!(
+            split_host_port("crates.io", 80),
+            Some(("crates.io".to_string(), 80))
+        );
+        assert_eq!(
+            split_host_port("[::1]:8080", 80),
+            Some(("::1".to_string(), 8080))
+        );
+        assert_eq!(split_host_port("::1", 80), None);
+    }
+
+    #[tokio::test]
+    async fn connect_proxy_allows_and_denies() {
+        let upstream = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            stream.write_all(b"pong").await.unwrap();
+        });
+
+        let proxy = NetworkProxy::start(vec![format!("127.0.0.1:{upstream_port}")])
+            .await
+            .unwrap();
+
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, proxy.endpoint().port))
+            .await
+            .unwrap();
+        client
+            .write_all(format!("CONNECT 127.0.0.1:{upstream_port} HTTP/1.1\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&response),
+            "HTTP/1.1 200 Connection Established\r\n\r\npong"
+        );
+
+        let mut denied = TcpStream::connect((Ipv4Addr::LOCALHOST, proxy.endpoint().port))
+            .await
+            .unwrap();
+        denied
+            .write_all(b"CONNECT 127.0.0.1:1 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        denied.read_to_end(&mut response).await.unwrap();
+        assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 403 Forbidden"));
+    }
+
+    #[tokio::test]
+    async fn socks5_denies_unlisted_destination() {
+        let proxy = NetworkProxy::start(vec!["crates.io".to_string()])
+            .await
+            .unwrap();
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, proxy.endpoint().port))
+            .await
+            .unwrap();
+        client.write_all(&[0x05, 0x01, 0x00]).await.unwrap();
+        let mut greeting = [0u8; 2];
+        client.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(greeting, [0x05, 0x00]);
+
+        client
+            .write_all(&[0x05, 0x01, 0x00, 0x01, 127, 0, 0, 1, 0x00, 0x50])
+            .await
+            .unwrap();
+        let mut reply = [0u8; 10];
+        client.read_exact(&mut reply).await.unwrap();
+        assert_eq!(reply[1], 0x02);
+    }
+
+    #[tokio::test]
+    async fn http_proxy_forwards_one_request_per_connection() {
+        let upstream = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        let (received_tx, received_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut received = Vec::new();
+            while !received.ends_with(b"hello") {
+                let mut chunk = [0u8; 1024];
+                let n = stream.read(&mut chunk).await.unwrap();
+                assert_ne!(n, 0, "request body never arrived");
+                received.extend_from_slice(&chunk[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+            stream.shutdown().await.unwrap();
+            // The proxy closes its side once the response is relayed; nothing
+            // else from the client may arrive before that.
+            stream.read_to_end(&mut received).await.unwrap();
+            received_tx.send(received).unwrap();
+        });
+
+        let proxy = NetworkProxy::start(vec![format!("127.0.0.1:{upstream_port}")])
+            .await
+            .unwrap();
+        let mut client = TcpStream::connect((Ipv4Addr::LOCALHOST, proxy.endpoint().port))
+            .await
+            .unwrap();
+        client
+            .write_all(
+                format!(
+                    "POST http://127.0.0.1:{upstream_port}/submit HTTP/1.1\r\n\
+                     Host: 127.0.0.1:{upstream_port}\r\n\
+                     Content-Length: 5\r\n\r\n\
+                     hello\
+                     GET http://example.com/ HTTP/1.1\r\n\
+                     Host: example.com\r\n\r\n"
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+
+        assert_eq!(
+            String::from_utf8_lossy(&response),
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"
+        );
+        assert_eq!(
+            String::from_utf8(received_rx.await.unwrap()).unwrap(),
+            format!(
+                "POST /submit HTTP/1.1\r\n\
+                 Host: 127.0.0.1:{upstream_port}\r\n\
+                 Content-Length: 5\r\n\
+                 Connection: close\r\n\r\n\
+                 hello"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn relays_chunked_body_up_to_its_end() {
+        let body = b"4\r\nwiki\r\n0\r\nX-Trailer: 1\r\n\r\nGET / HTTP/1.1\r\n";
+        let mut reader = BufReader::new(&body[..]);
+        let mut relayed = Vec::new();
+        relay_chunked_body(&mut reader, &mut relayed).await.unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&relayed),
+            "4\r\nwiki\r\n0\r\nX-Trailer: 1\r\n\r\n"
+        );
+
+        let mut truncated = BufReader::new(&b"10\r\nshort"[..]);
+        assert!(
+            relay_chunked_body(&mut truncated, &mut Vec::new())
+                .await
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_applies_allowlist() {
+        let proxy = NetworkProxy::start(vec!["crates.io".to_string()])
+            .await
+            .unwrap();
+        let mut client = tokio::net::UnixStream::connect(&proxy.endpoint().socket_path)
+            .await
+            .unwrap();
+        client
+            .write_all(b"CONNECT 127.0.0.1:1 HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        client.read_to_end(&mut response).await.unwrap();
+        assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 403 Forbidden"));
+    }
+}
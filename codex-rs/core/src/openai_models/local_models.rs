@@ -0,0 +1,380 @@
+//! Discovery of models served by the local OSS providers, listed next to the
+//! built-in presets when the `local_models` feature is enabled.
+//!
+//! Ollama models come from `/api/tags`, with the context window read from
+//! `/api/show`. LM Studio models come from `/v1/models`, with the context
+//! window (and the model type, to skip embedding models) read from its
+//! `/api/v0/models` REST endpoint. Servers that are not running are skipped.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codex_protocol::openai_models::ModelPreset;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::openai_models::ReasoningEffortPreset;
+use futures::future::join_all;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::config::Config;
+use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
+use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+
+/// Upper bound for each request, so a stalled server does not hold up
+/// `/model`.
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// List the models available from the Ollama and LM Studio providers
+/// configured in `model_providers`.
+pub(crate) async fn discover_local_models(config: &Config) -> Vec<ModelPreset> {
+    let client = reqwest::Client::builder()
+        .timeout(DISCOVERY_TIMEOUT)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    let base_url = |provider_id: &str| {
+        config
+            .model_providers
+            .get(provider_id)
+            .and_then(|provider| provider.base_url.clone())
+    };
+
+    let ollama = async {
+        match base_url(OLLAMA_OSS_PROVIDER_ID) {
+            Some(base_url) => discover_ollama_models(&client, &base_url).await,
+            None => Vec::new(),
+        }
+    };
+    let lmstudio = async {
+        match base_url(LMSTUDIO_OSS_PROVIDER_ID) {
+            Some(base_url) => discover_lmstudio_models(&client, &base_url).await,
+            None => Vec::new(),
+        }
+    };
+    let (ollama, lmstudio) = tokio::join!(ollama, lmstudio);
+    ollama.into_iter().chain(lmstudio).collect()
+}
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+    #[serde(default)]
+    details: OllamaModelDetails,
+}
+
+#[derive(Default, Deserialize)]
+struct OllamaModelDetails {
+    parameter_size: Option<String>,
+    quantization_level: Option<String>,
+}
+
+async fn discover_ollama_models(client: &reqwest::Client, base_url: &str) -> Vec<ModelPreset> {
+    let host_root = host_root(base_url);
+    let tags: OllamaTags = match get_json(client, &format!("{host_root}/api/tags")).await {
+        Some(tags) => tags,
+        None => return Vec::new(),
+    };
+
+    let context_windows = join_all(
+        tags.models
+            .iter()
+            .map(|model| ollama_context_window(client, &host_root, &model.name)),
+    )
+    .await;
+    tags.models
+        .into_iter()
+        .zip(context_windows)
+        .map(|(model, context_window)| {
+            let details = [
+                model.details.parameter_size,
+                model.details.quantization_level,
+            ];
+            local_preset(
+                OLLAMA_OSS_PROVIDER_ID,
+                "Ollama",
+                model.name,
+                details.into_iter().flatten().collect(),
+                context_window,
+            )
+        })
+        .collect()
+}
+
+/// Context length from the `<architecture>.context_length` entry of
+/// `/api/show`.
+async fn ollama_context_window(
+    client: &reqwest::Client,
+    host_root: &str,
+    model: &str,
+) -> Option<i64> {
+    let response = client
+        .post(format!("{host_root}/api/show"))
+        .json(&serde_json::json!({ "model": model }))
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let show: Value = response.json().await.ok()?;
+    show.get("model_info")?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.ends_with(".context_length"))
+        .and_then(|(_, value)| value.as_i64())
+}
+
+#[derive(Deserialize)]
+struct LmStudioModels {
+    #[serde(default)]
+    data: Vec<LmStudioModel>,
+}
+
+#[derive(Deserialize)]
+struct LmStudioModel {
+    id: String,
+    /// `llm`, `vlm` or `embeddings`; only reported by `/api/v0/models`.
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    max_context_length: Option<i64>,
+}
+
+async fn discover_lmstudio_models(client: &reqwest::Client, base_url: &str) -> Vec<ModelPreset> {
+    let base_url = base_url.trim_end_matches('/');
+    let models: LmStudioModels = match get_json(client, &format!("{base_url}/models")).await {
+        Some(models) => models,
+        None => return Vec::new(),
+    };
+    let details: HashMap<String, LmStudioModel> =
+        get_json::<LmStudioModels>(client, &format!("{}/api/v0/models", host_root(base_url)))
+            .await
+            .map(|models| {
+                models
+                    .data
+                    .into_iter()
+                    .map(|model| (model.id.clone(), model))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+    models
+        .data
+        .into_iter()
+        .filter_map(|model| {
+            let details = details.get(&model.id);
+            if details.and_then(|details| details.kind.as_deref()) == Some("embeddings") {
+                return None;
+            }
+            let context_window = details.and_then(|details| details.max_context_length);
+            Some(local_preset(
+                LMSTUDIO_OSS_PROVIDER_ID,
+                "LM Studio",
+                model.id,
+                Vec::new(),
+                context_window,
+            ))
+        })
+        .collect()
+}
+
+async fn get_json<T: for<'de> Deserialize<'de>>(client: &reqwest::Client, url: &str) -> Option<T> {
+    let response = match client.get(url).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            debug!("local model discovery: {url} unreachable: {err}");
+            return None;
+        }
+    };
+    if !response.status().is_success() {
+        debug!(
+            "local model discovery: {url} returned HTTP {}",
+            response.status()
+        );
+        return None;
+    }
+    response.json().await.ok()
+}
+
+/// `http://localhost:11434/v1` -> `http://localhost:11434`.
+fn host_root(base_url: &str) -> String {
+    let trimmed = base_url.trim_end_matches('/');
+    trimmed
+        .strip_suffix("/v1")
+        .unwrap_or(trimmed)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn local_preset(
+    provider_id: &str,
+    server_name: &str,
+    model: String,
+    details: Vec<String>,
+    context_window: Option<i64>,
+) -> ModelPreset {
+    let mut description = format!("Local model served by {server_name}");
+    if !details.is_empty() {
+        description.push_str(&format!(" ({})", details.join(", ")));
+    }
+    ModelPreset {
+        id: format!("{provider_id}/{model}"),
+        model: model.clone(),
+        display_name: model,
+        description,
+        default_reasoning_effort: ReasoningEffort::Medium,
+        // Local servers do not advertise reasoning levels, so offer a single
+        // choice and let the picker switch right away.
+        supported_reasoning_efforts: vec![ReasoningEffortPreset {
+            effort: ReasoningEffort::Medium,
+            description: "Default reasoning for this model".to_string(),
+        }],
+        is_default: false,
+        upgrade: None,
+        show_in_picker: true,
+        context_window,
+        model_provider: Some(provider_id.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::new()
+    }
+
+    #[tokio::test]
+    async fn lists_ollama_tags_with_context_windows() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "models": [
+                    {
+                        "name": "qwen3-coder:30b",
+                        "details": {"parameter_size": "30.5B", "quantization_level": "Q4_K_M"}
+                    },
+                    {"name": "mistral"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/show"))
+            .and(body_json(json!({"model": "qwen3-coder:30b"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "model_info": {
+                    "general.architecture": "qwen3moe",
+                    "qwen3moe.context_length": 262144
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/show"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let models = discover_ollama_models(&client(), &format!("{}/v1", server.uri())).await;
+
+        let summary: Vec<(String, String, Option<i64>, Option<String>)> = models
+            .into_iter()
+            .map(|preset| {
+                (
+                    preset.model,
+                    preset.description,
+                    preset.context_window,
+                    preset.model_provider,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "qwen3-coder:30b".to_string(),
+                    "Local model served by Ollama (30.5B, Q4_K_M)".to_string(),
+                    Some(262_144),
+                    Some("ollama".to_string()),
+                ),
+                (
+                    "mistral".to_string(),
+                    "Local model served by Ollama".to_string(),
+                    None,
+                    Some("ollama".to_string()),
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_lmstudio_models_without_embeddings() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "openai/gpt-oss-20b"},
+                    {"id": "text-embedding-nomic-embed-text-v1.5"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v0/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "openai/gpt-oss-20b", "type": "llm", "max_context_length": 131072},
+                    {"id": "text-embedding-nomic-embed-text-v1.5", "type": "embeddings"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let models = discover_lmstudio_models(&client(), &format!("{}/v1", server.uri())).await;
+
+        let summary: Vec<(String, String, Option<i64>)> = models
+            .into_iter()
+            .map(|preset| (preset.id, preset.model, preset.context_window))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(
+                "lmstudio/openai/gpt-oss-20b".to_string(),
+                "openai/gpt-oss-20b".to_string(),
+                Some(131_072),
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn servers_without_model_endpoints_yield_no_models() {
+        let server = MockServer::start().await;
+        let base_url = format!("{}/v1", server.uri());
+
+        assert_eq!(
+            discover_ollama_models(&client(), &base_url).await,
+            Vec::new()
+        );
+        assert_eq!(
+            discover_lmstudio_models(&client(), &base_url).await,
+            Vec::new()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod cache;
+mod local_models;
 pub mod model_family;
 pub mod model_presets;
 pub mod models_manager;
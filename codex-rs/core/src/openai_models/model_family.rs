@@ -2,10 +2,12 @@ use codex_protocol::config_types::Verbosity;
 use codex_protocol::openai_models::ApplyPatchToolType;
 use codex_protocol::openai_models::ConfigShellToolType;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningSummaryFormat;
 
 use crate::config::Config;
+use crate::tokenizer::Tokenizer;
 use crate::truncate::TruncationPolicy;
 
 /// The `instructions` field in the payload sent to a model should always start
@@ -79,6 +81,10 @@ pub struct ModelFamily {
     /// Preferred shell tool type for this model family when features do not override it.
     pub shell_type: ConfigShellToolType,
 
+    /// Tokenizer used to estimate context usage and to count `truncation_policy`
+    /// token budgets.
+    pub tokenizer: Tokenizer,
+
     pub truncation_policy: TruncationPolicy,
 }
 
@@ -98,6 +104,21 @@ impl ModelFamily {
         }
         self
     }
+    /// Uses the context window a local server reported for this model, so
+    /// auto-compaction and the context meter work for models Codex does not
+    /// know about.
+    pub(super) fn with_local_context_window(mut self, local_models: &[ModelPreset]) -> Self {
+        if self.context_window.is_none()
+            && let Some(context_window) = local_models
+                .iter()
+                .find(|preset| preset.model == self.slug)
+                .and_then(|preset| preset.context_window)
+        {
+            self.context_window = Some(context_window);
+        }
+        self
+    }
+
     pub(super) fn with_remote_overrides(mut self, remote_models: Vec<ModelInfo>) -> Self {
         for model in remote_models {
             if model.slug == self.slug {
@@ -141,7 +162,8 @@ impl ModelFamily {
         self.support_verbosity = support_verbosity;
         self.default_verbosity = default_verbosity;
         self.apply_patch_tool_type = apply_patch_tool_type;
-        self.truncation_policy = truncation_policy.into();
+        self.truncation_policy =
+            TruncationPolicy::from(truncation_policy).with_tokenizer(self.tokenizer);
         self.supports_parallel_tool_calls = supports_parallel_tool_calls;
         self.context_window = context_window;
         self.reasoning_summary_format = reasoning_summary_format;
@@ -185,6 +207,7 @@ macro_rules! model_family {
             shell_type: ConfigShellToolType::Default,
             default_verbosity: None,
             default_reasoning_effort: None,
+            tokenizer: Tokenizer::O200kBase,
             truncation_policy: TruncationPolicy::Bytes(10_000),
         };
 
@@ -192,6 +215,7 @@ macro_rules! model_family {
         $(
             mf.$key = $value;
         )*
+        mf.truncation_policy = mf.truncation_policy.with_tokenizer(mf.tokenizer);
         mf
     }};
 }
@@ -244,6 +268,7 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
             slug, "gpt-3.5",
             needs_special_apply_patch_instructions: true,
             context_window: Some(16_385),
+            tokenizer: Tokenizer::Cl100kBase,
         )
     } else if slug.starts_with("test-gpt-5") {
         model_family!(
@@ -254,13 +279,15 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
             experimental_supported_tools: vec![
                 "grep_files".to_string(),
                 "list_dir".to_string(),
+                "list_symbols".to_string(),
                 "read_file".to_string(),
+                "search_code".to_string(),
                 "test_sync_tool".to_string(),
             ],
             supports_parallel_tool_calls: true,
             shell_type: ConfigShellToolType::ShellCommand,
             support_verbosity: true,
-            truncation_policy: TruncationPolicy::Tokens(10_000),
+            truncation_policy: TruncationPolicy::tokens(10_000),
         )
 
     // Experimental models.
@@ -275,7 +302,7 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
             shell_type: ConfigShellToolType::ShellCommand,
             supports_parallel_tool_calls: true,
             support_verbosity: false,
-            truncation_policy: TruncationPolicy::Tokens(10_000),
+            truncation_policy: TruncationPolicy::tokens(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
         )
     } else if slug.starts_with("exp-") {
@@ -304,7 +331,7 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
             shell_type: ConfigShellToolType::ShellCommand,
             supports_parallel_tool_calls: false,
             support_verbosity: false,
-            truncation_policy: TruncationPolicy::Tokens(10_000),
+            truncation_policy: TruncationPolicy::tokens(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
         )
     } else if slug.starts_with("gpt-5-codex")
@@ -320,7 +347,7 @@ pub(super) fn find_family_for_model(slug: &str) -> ModelFamily {
             shell_type: ConfigShellToolType::ShellCommand,
             supports_parallel_tool_calls: false,
             support_verbosity: false,
-            truncation_policy: TruncationPolicy::Tokens(10_000),
+            truncation_policy: TruncationPolicy::tokens(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
         )
     } else if slug.starts_with("gpt-5.2") {
@@ -385,6 +412,7 @@ fn derive_default_model_family(model: &str) -> ModelFamily {
         shell_type: ConfigShellToolType::Default,
         default_verbosity: None,
         default_reasoning_effort: None,
+        tokenizer: Tokenizer::Heuristic,
         truncation_policy: TruncationPolicy::Bytes(10_000),
     }
 }
@@ -527,7 +555,10 @@ mod tests {
             updated.apply_patch_tool_type,
             Some(ApplyPatchToolType::Freeform)
         );
-        assert_eq!(updated.truncation_policy, TruncationPolicy::Tokens(2_000));
+        assert_eq!(
+            updated.truncation_policy,
+            TruncationPolicy::Tokens(2_000, Tokenizer::O200kBase)
+        );
         assert!(updated.supports_parallel_tool_calls);
         assert_eq!(updated.context_window, Some(400_000));
         assert_eq!(
@@ -540,4 +571,27 @@ mod tests {
         );
         assert_eq!(updated.base_instructions, "Remote instructions");
     }
+
+    #[test]
+    fn families_pick_tokenizers() {
+        assert_eq!(
+            find_family_for_model("gpt-5.1-codex").tokenizer,
+            Tokenizer::O200kBase
+        );
+        assert_eq!(
+            find_family_for_model("gpt-4o-mini").tokenizer,
+            Tokenizer::O200kBase
+        );
+        assert_eq!(
+            find_family_for_model("gpt-3.5-turbo").tokenizer,
+            Tokenizer::Cl100kBase
+        );
+        assert_eq!(
+            find_family_for_model("llama3.1:8b").tokenizer,
+            Tokenizer::Heuristic
+        );
+
+        let codex = find_family_for_model("gpt-5.1-codex");
+        assert_eq!(codex.truncation_policy.tokenizer(), Tokenizer::O200kBase);
+    }
 }
@@ -38,6 +38,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
             is_default: true,
             upgrade: None,
             show_in_picker: true,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5.1-codex".to_string(),
@@ -67,6 +69,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: true,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5.1-codex-mini".to_string(),
@@ -92,6 +96,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: true,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5.2".to_string(),
@@ -120,6 +126,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
             is_default: false,
             upgrade: None,
             show_in_picker: true,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5.1".to_string(),
@@ -148,6 +156,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: true,
+            context_window: None,
+            model_provider: None,
         },
         // Deprecated models.
         ModelPreset {
@@ -177,6 +187,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: false,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5-codex-mini".to_string(),
@@ -201,6 +213,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT5_1_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: false,
+            context_window: None,
+            model_provider: None,
         },
         ModelPreset {
             id: "gpt-5".to_string(),
@@ -233,6 +247,8 @@ static PRESETS: Lazy<Vec<ModelPreset>> = Lazy::new(|| {
                 migration_config_key: HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG.to_string(),
             }),
             show_in_picker: false,
+            context_window: None,
+            model_provider: None,
         },
     ]
 });
@@ -15,6 +15,7 @@ use tracing::error;
 
 use super::cache;
 use super::cache::ModelsCache;
+use super::local_models::discover_local_models;
 use crate::api_bridge::auth_provider_from_auth;
 use crate::api_bridge::map_api_error;
 use crate::auth::AuthManager;
@@ -37,6 +38,9 @@ pub struct ModelsManager {
     // todo(aibrahim) merge available_models and model family creation into one struct
     available_models: RwLock<Vec<ModelPreset>>,
     remote_models: RwLock<Vec<ModelInfo>>,
+    /// Models discovered on local Ollama / LM Studio servers, listed after
+    /// `available_models`.
+    local_models: RwLock<Vec<ModelPreset>>,
     auth_manager: Arc<AuthManager>,
     etag: RwLock<Option<String>>,
     codex_home: PathBuf,
@@ -51,6 +55,7 @@ impl ModelsManager {
         Self {
             available_models: RwLock::new(builtin_model_presets(auth_manager.get_auth_mode())),
             remote_models: RwLock::new(Vec::new()),
+            local_models: RwLock::new(Vec::new()),
             auth_manager,
             etag: RwLock::new(None),
             codex_home,
@@ -66,6 +71,7 @@ impl ModelsManager {
         Self {
             available_models: RwLock::new(builtin_model_presets(auth_manager.get_auth_mode())),
             remote_models: RwLock::new(Vec::new()),
+            local_models: RwLock::new(Vec::new()),
             auth_manager,
             etag: RwLock::new(None),
             codex_home,
@@ -107,13 +113,24 @@ impl ModelsManager {
         if let Err(err) = self.refresh_available_models(config).await {
             error!("failed to refresh available models: {err}");
         }
-        self.available_models.read().await.clone()
+        self.refresh_local_models(config).await;
+        let mut models = self.available_models.read().await.clone();
+        models.extend(self.local_models.read().await.iter().cloned());
+        models
     }
 
     pub fn try_list_models(&self) -> Result<Vec<ModelPreset>, TryLockError> {
-        self.available_models
-            .try_read()
-            .map(|models| models.clone())
+        let mut models = self.available_models.try_read()?.clone();
+        models.extend(self.local_models.try_read()?.iter().cloned());
+        Ok(models)
+    }
+
+    /// Re-query the local OSS servers when `local_models` is enabled.
+    async fn refresh_local_models(&self, config: &Config) {
+        if !config.features.enabled(Feature::LocalModels) {
+            return;
+        }
+        *self.local_models.write().await = discover_local_models(config).await;
     }
 
     fn find_family_for_model(slug: &str) -> ModelFamily {
@@ -123,6 +140,7 @@ impl ModelsManager {
     /// Look up the requested model family while applying remote metadata overrides.
     pub async fn construct_model_family(&self, model: &str, config: &Config) -> ModelFamily {
         Self::find_family_for_model(model)
+            .with_local_context_window(&self.local_models.read().await)
             .with_config_overrides(config)
             .with_remote_overrides(self.remote_models.read().await.clone())
     }
@@ -260,6 +278,7 @@ mod tests {
     use crate::features::Feature;
     use crate::model_provider_info::WireApi;
     use codex_protocol::openai_models::ModelsResponse;
+    use codex_protocol::openai_models::ReasoningEffort;
     use core_test_support::responses::mount_models_once;
     use serde_json::json;
     use tempfile::tempdir;
@@ -310,6 +329,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn construct_model_family_uses_local_context_window() {
+        let codex_home = tempdir().expect("temp dir");
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load default test config");
+        let auth_manager =
+            AuthManager::from_auth_for_testing(CodexAuth::from_api_key("Test API Key"));
+        let manager = ModelsManager::new(auth_manager);
+        *manager.local_models.write().await = vec![ModelPreset {
+            id: "ollama/qwen3:8b".to_string(),
+            model: "qwen3:8b".to_string(),
+            display_name: "qwen3:8b".to_string(),
+            description: "Local model served by Ollama".to_string(),
+            default_reasoning_effort: ReasoningEffort::Medium,
+            supported_reasoning_efforts: Vec::new(),
+            is_default: false,
+            upgrade: None,
+            show_in_picker: true,
+            context_window: Some(40_960),
+            model_provider: Some("ollama".to_string()),
+        }];
+
+        let family = manager.construct_model_family("qwen3:8b", &config).await;
+        assert_eq!(family.context_window, Some(40_960));
+        assert_eq!(family.auto_compact_token_limit(), Some(36_864));
+
+        // Known models keep their own context window.
+        let family = manager.construct_model_family("gpt-5.1", &config).await;
+        assert_ne!(family.context_window, Some(40_960));
+    }
+
     #[tokio::test]
     async fn refresh_available_models_sorts_and_marks_default() {
         let server = MockServer::start().await;
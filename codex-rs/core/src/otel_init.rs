@@ -18,6 +18,7 @@ pub fn build_provider(
 ) -> Result<Option<OtelProvider>, Box<dyn Error>> {
     let to_otel_exporter = |kind: &Kind| match kind {
         Kind::None => OtelExporter::None,
+        Kind::File => OtelExporter::File,
         Kind::OtlpHttp {
             endpoint,
             headers,
@@ -63,6 +64,7 @@ pub fn build_provider(
 
     let exporter = to_otel_exporter(&config.otel.exporter);
     let trace_exporter = to_otel_exporter(&config.otel.trace_exporter);
+    let metrics_exporter = to_otel_exporter(&config.otel.metrics_exporter);
 
     OtelProvider::from(&OtelSettings {
         service_name: originator().value.to_owned(),
@@ -71,6 +73,7 @@ pub fn build_provider(
         environment: config.otel.environment.to_string(),
         exporter,
         trace_exporter,
+        metrics_exporter,
     })
 }
 
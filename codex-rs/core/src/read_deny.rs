@@ -0,0 +1,556 @@
+//! Matching for the `read_deny` list of a sandbox policy.
+//!
+//! Patterns are globs where `*` and `?` also match `/`, so `**/.env` and
+//! `*/.env` are equivalent. A leading `~/` expands to the home directory,
+//! patterns that start with a wildcard match anywhere, and any other relative
+//! pattern is resolved against the session cwd. A path is denied when it or
+//! any of its ancestors matches.
+//!
+//! The same matcher backs the file tools (`read_file`, `grep_files`,
+//! `list_dir`) and the Linux sandbox, which hides the existing denied paths
+//! from commands. Those paths are resolved once per session and policy by
+//! [`ReadDenyManager`] rather than by every sandboxed command.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_utils_absolute_path::AbsolutePathBuf;
+use ignore::WalkBuilder;
+use tokio::sync::Mutex;
+use tracing::warn;
+use wildmatch::WildMatchPattern;
+
+use crate::config::find_codex_home;
+use crate::protocol::SandboxPolicy;
+
+/// Patterns used when `[sandbox_workspace_write]` does not set `read_deny`,
+/// in addition to the `auth.json` in `CODEX_HOME` (see
+/// [`default_read_deny_patterns`]).
+pub const DEFAULT_READ_DENY_PATTERNS: &[&str] =
+    &["~/.ssh", "~/.aws", "~/.gnupg", "~/.netrc", "**/.env"];
+
+/// Upper bound on directory entries visited while looking for paths that
+/// match wildcard patterns, so huge trees do not stall the session.
+pub(crate) const MAX_SCAN_ENTRIES: usize = 20_000;
+
+/// Directories that the scan never descends into, on top of the ones ignored
+/// by `.gitignore` and friends. Entries directly inside the directories that
+/// are scanned are still matched, ignored or not.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules"];
+
+/// Returns [`DEFAULT_READ_DENY_PATTERNS`] plus the credentials file in
+/// `codex_home`, wherever `CODEX_HOME` points.
+pub fn default_read_deny_patterns(codex_home: &Path) -> Vec<String> {
+    DEFAULT_READ_DENY_PATTERNS
+        .iter()
+        .map(|pattern| (*pattern).to_string())
+        .chain(std::iter::once(
+            codex_home.join("auth.json").to_string_lossy().to_string(),
+        ))
+        .collect()
+}
+
+/// Existing paths denied by a `read_deny` list, resolved for the Linux
+/// sandbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadDeniedPaths {
+    /// Canonical paths to hide from sandboxed commands.
+    pub paths: Vec<PathBuf>,
+    /// The entries of `paths` denied by a pattern the user configured, as
+    /// opposed to one of the built-in defaults. Only these fall back to
+    /// Landlock when they cannot be hidden, because that fallback also makes
+    /// new files in every directory above them unreadable.
+    pub carve_paths: Vec<PathBuf>,
+    /// `false` when the scan ran out of its entry budget, in which case some
+    /// denied paths are missing.
+    pub complete: bool,
+}
+
+impl Default for ReadDeniedPaths {
+    fn default() -> Self {
+        Self {
+            paths: Vec::new(),
+            carve_paths: Vec::new(),
+            complete: true,
+        }
+    }
+}
+
+impl ReadDeniedPaths {
+    /// Resolves the paths denied by `policy` on a blocking thread. Patterns
+    /// that can match anywhere, like `**/.env`, are only looked up inside
+    /// `cwd`.
+    pub async fn resolve(policy: &SandboxPolicy, cwd: &Path) -> Self {
+        let patterns = policy.read_deny().to_vec();
+        if patterns.is_empty() {
+            return Self::default();
+        }
+        let cwd = cwd.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            ReadDenyMatcher::new(&patterns, &cwd).resolve_denied_paths(&[cwd.clone()])
+        })
+        .await
+        .unwrap_or_else(|err| {
+            warn!("read_deny scan failed: {err}");
+            Self {
+                complete: false,
+                ..Self::default()
+            }
+        })
+    }
+}
+
+/// Keeps the session's [`ReadDeniedPaths`] so that the workspace is only
+/// scanned again when the policy or the cwd changes.
+#[derive(Default)]
+pub(crate) struct ReadDenyManager {
+    resolved: Mutex<Option<ResolvedReadDeny>>,
+}
+
+struct ResolvedReadDeny {
+    patterns: Vec<String>,
+    cwd: PathBuf,
+    paths: Arc<ReadDeniedPaths>,
+}
+
+impl ReadDenyManager {
+    /// Returns the paths denied by `policy`, and whether they were resolved
+    /// by this call rather than taken from the cache.
+    pub(crate) async fn paths_for(
+        &self,
+        policy: &SandboxPolicy,
+        cwd: &Path,
+    ) -> (Arc<ReadDeniedPaths>, bool) {
+        let mut guard = self.resolved.lock().await;
+        if let Some(resolved) = guard.as_ref()
+            && resolved.patterns == policy.read_deny()
+            && resolved.cwd == cwd
+        {
+            return (Arc::clone(&resolved.paths), false);
+        }
+        let paths = Arc::new(ReadDeniedPaths::resolve(policy, cwd).await);
+        *guard = Some(ResolvedReadDeny {
+            patterns: policy.read_deny().to_vec(),
+            cwd: cwd.to_path_buf(),
+            paths: Arc::clone(&paths),
+        });
+        (paths, true)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DenyEntry {
+    kind: DenyKind,
+    /// Whether the pattern is one of the built-in defaults rather than one
+    /// the user configured.
+    builtin: bool,
+}
+
+#[derive(Debug, Clone)]
+enum DenyKind {
+    /// A pattern without wildcards; denies exactly this path.
+    Literal {
+        path: PathBuf,
+        /// `path` with symlinks resolved, when it exists and differs.
+        canonical: Option<PathBuf>,
+    },
+    Glob {
+        pattern: WildMatchPattern<'*', '?'>,
+        /// Longest wildcard-free directory prefix of the pattern, or `None`
+        /// when the pattern starts with a wildcard and can match anywhere.
+        base: Option<PathBuf>,
+    },
+}
+
+impl DenyEntry {
+    fn matches(&self, path: &Path) -> bool {
+        match &self.kind {
+            DenyKind::Literal {
+                path: denied,
+                canonical,
+            } => path == denied || canonical.as_deref() == Some(path),
+            DenyKind::Glob { pattern, .. } => pattern.matches(&path.to_string_lossy()),
+        }
+    }
+}
+
+/// Compiled form of a `read_deny` list.
+#[derive(Debug, Clone, Default)]
+pub struct ReadDenyMatcher {
+    entries: Vec<DenyEntry>,
+}
+
+impl ReadDenyMatcher {
+    pub fn new(patterns: &[String], cwd: &Path) -> Self {
+        let builtin = match find_codex_home() {
+            Ok(codex_home) => default_read_deny_patterns(&codex_home),
+            Err(_) => DEFAULT_READ_DENY_PATTERNS
+                .iter()
+                .map(|pattern| (*pattern).to_string())
+                .collect(),
+        };
+        Self::with_home(patterns, cwd, dirs::home_dir().as_deref(), &builtin)
+    }
+
+    fn with_home(patterns: &[String], cwd: &Path, home: Option<&Path>, builtin: &[String]) -> Self {
+        let entries = patterns
+            .iter()
+            .filter_map(|pattern| {
+                compile_entry(pattern, cwd, home).map(|kind| DenyEntry {
+                    kind,
+                    builtin: builtin.contains(pattern),
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` when `path`, or any of its ancestors, matches a deny
+    /// pattern. Both the lexically normalized path and its canonical form
+    /// are checked so `..` segments and symlinks cannot be used to sidestep
+    /// the list.
+    pub fn is_denied(&self, path: &Path) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        let normalized = AbsolutePathBuf::from_absolute_path(path)
+            .map(AbsolutePathBuf::into_path_buf)
+            .unwrap_or_else(|_| path.to_path_buf());
+        if self.matches_self_or_ancestor(&normalized) {
+            return true;
+        }
+        match path.canonicalize() {
+            Ok(canonical) if canonical != normalized => self.matches_self_or_ancestor(&canonical),
+            _ => false,
+        }
+    }
+
+    fn matches_self_or_ancestor(&self, path: &Path) -> bool {
+        path.ancestors().any(|candidate| self.matches(candidate))
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        self.entries.iter().any(|entry| entry.matches(path))
+    }
+
+    /// Whether `path` matches, and if so whether a user-configured pattern
+    /// matches it.
+    fn match_source(&self, path: &Path) -> Option<bool> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.matches(path))
+            .map(|entry| !entry.builtin)
+            .reduce(|configured, other| configured || other)
+    }
+
+    /// Lists the existing, canonicalized paths that the patterns deny.
+    ///
+    /// Literal patterns are checked directly. Wildcard patterns are resolved by
+    /// walking their wildcard-free prefix, or `search_roots` for patterns that
+    /// can match anywhere. The walk does not follow symlinks, skips ignored
+    /// and vendored directories and stops after a fixed number of entries;
+    /// [`ReadDeniedPaths::complete`] tells whether it got through.
+    pub fn resolve_denied_paths(&self, search_roots: &[PathBuf]) -> ReadDeniedPaths {
+        self.resolve_with_budget(search_roots, MAX_SCAN_ENTRIES)
+    }
+
+    fn resolve_with_budget(&self, search_roots: &[PathBuf], mut budget: usize) -> ReadDeniedPaths {
+        let mut denied = Vec::new();
+        let mut walk_roots = Vec::new();
+        for entry in &self.entries {
+            match &entry.kind {
+                DenyKind::Literal { path, .. } => {
+                    if path.symlink_metadata().is_ok() {
+                        denied.push((path.clone(), !entry.builtin));
+                    }
+                }
+                DenyKind::Glob {
+                    base: Some(base), ..
+                } => walk_roots.push(base.clone()),
+                DenyKind::Glob { base: None, .. } => {
+                    walk_roots.extend(search_roots.iter().cloned());
+                }
+            }
+        }
+
+        let mut complete = true;
+        let mut visited = HashSet::new();
+        for root in walk_roots {
+            if !self.collect_matches(&root, &mut visited, &mut denied, &mut budget) {
+                warn!(
+                    "read_deny scan stopped after {MAX_SCAN_ENTRIES} entries; some matches may be missed"
+                );
+                complete = false;
+                break;
+            }
+        }
+
+        let mut resolved: Vec<(PathBuf, bool)> = denied
+            .into_iter()
+            .filter_map(|(path, configured)| {
+                path.canonicalize().ok().map(|path| (path, configured))
+            })
+            .collect();
+        resolved.sort();
+        let mut paths: Vec<PathBuf> = resolved.iter().map(|(path, _)| path.clone()).collect();
+        paths.dedup();
+        let mut carve_paths: Vec<PathBuf> = resolved
+            .into_iter()
+            .filter_map(|(path, configured)| configured.then_some(path))
+            .collect();
+        carve_paths.dedup();
+        ReadDeniedPaths {
+            paths,
+            carve_paths,
+            complete,
+        }
+    }
+
+    /// Walks `root` and records the denied entries found in it. Returns
+    /// `false` when `budget` runs out first.
+    fn collect_matches(
+        &self,
+        root: &Path,
+        visited: &mut HashSet<PathBuf>,
+        denied: &mut Vec<(PathBuf, bool)>,
+        budget: &mut usize,
+    ) -> bool {
+        if let Some(configured) = self.match_source(root) {
+            denied.push((root.to_path_buf(), configured));
+            return true;
+        }
+        let matcher = self.clone();
+        let walker = WalkBuilder::new(root)
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let skipped = entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name));
+                !skipped && matcher.match_source(entry.path()).is_none()
+            })
+            .build();
+        for entry in walker.flatten() {
+            let is_dir = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir());
+            if !is_dir || !visited.insert(entry.path().to_path_buf()) {
+                continue;
+            }
+            // Ignore rules hide files like `.env` from the walker itself, so
+            // match every entry of the directories it reaches.
+            let Ok(read_dir) = std::fs::read_dir(entry.path()) else {
+                continue;
+            };
+            for child in read_dir.flatten() {
+                if *budget == 0 {
+                    return false;
+                }
+                *budget -= 1;
+                let path = child.path();
+                if let Some(configured) = self.match_source(&path) {
+                    denied.push((path, configured));
+                }
+            }
+        }
+        true
+    }
+}
+
+fn compile_entry(pattern: &str, cwd: &Path, home: Option<&Path>) -> Option<DenyKind> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let expanded = if pattern == "~" || pattern.starts_with("~/") {
+        let Some(home) = home else {
+            warn!("ignoring read_deny pattern `{pattern}`: home directory is unknown");
+            return None;
+        };
+        home.join(pattern.trim_start_matches('~').trim_start_matches('/'))
+    } else if pattern.starts_with(['*', '?']) {
+        let collapsed = collapse_double_star(pattern.trim_end_matches('/'));
+        return Some(DenyKind::Glob {
+            pattern: WildMatchPattern::new(&collapsed),
+            base: None,
+        });
+    } else {
+        PathBuf::from(pattern)
+    };
+
+    let path = match AbsolutePathBuf::resolve_path_against_base(&expanded, cwd) {
+        Ok(path) => path.into_path_buf(),
+        Err(err) => {
+            warn!("ignoring read_deny pattern `{pattern}`: {err}");
+            return None;
+        }
+    };
+
+    let text = path.to_string_lossy();
+    if !text.contains(['*', '?']) {
+        let canonical = path
+            .canonicalize()
+            .ok()
+            .filter(|canonical| canonical != &path);
+        return Some(DenyKind::Literal { path, canonical });
+    }
+
+    let base = path
+        .ancestors()
+        .skip(1)
+        .find(|ancestor| !ancestor.to_string_lossy().contains(['*', '?']))
+        .map(Path::to_path_buf);
+    Some(DenyKind::Glob {
+        pattern: WildMatchPattern::new(&collapse_double_star(&text)),
+        base,
+    })
+}
+
+/// `*` already spans directory separators, so `**` is only kept for
+/// readability in configs.
+fn collapse_double_star(pattern: &str) -> String {
+    let mut collapsed = pattern.to_string();
+    while collapsed.contains("**") {
+        collapsed = collapsed.replace("**", "*");
+    }
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn patterns(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| (*item).to_string()).collect()
+    }
+
+    #[test]
+    fn default_patterns_deny_home_secrets_and_env_files() {
+        let matcher = ReadDenyMatcher::with_home(
+            &default_read_deny_patterns(Path::new("/srv/codex-home")),
+            Path::new("/work/repo"),
+            Some(Path::new("/home/dev")),
+            &[],
+        );
+
+        assert!(matcher.is_denied(Path::new("/home/dev/.ssh")));
+        assert!(matcher.is_denied(Path::new("/home/dev/.ssh/id_ed25519")));
+        assert!(matcher.is_denied(Path::new("/home/dev/.aws/credentials")));
+        assert!(matcher.is_denied(Path::new("/srv/codex-home/auth.json")));
+        assert!(matcher.is_denied(Path::new("/work/repo/.env")));
+        assert!(matcher.is_denied(Path::new("/elsewhere/service/.env")));
+
+        assert!(!matcher.is_denied(Path::new("/srv/codex-home/config.toml")));
+        assert!(!matcher.is_denied(Path::new("/home/dev/.sshrc")));
+        assert!(!matcher.is_denied(Path::new("/work/repo/.env.example")));
+        assert!(!matcher.is_denied(Path::new("/work/repo/src/main.rs")));
+    }
+
+    #[test]
+    fn dot_dot_segments_do_not_bypass_the_list() {
+        let matcher = ReadDenyMatcher::with_home(
+            &patterns(&["~/.ssh"]),
+            Path::new("/work"),
+            Some(Path::new("/home/dev")),
+            &[],
+        );
+
+        assert!(matcher.is_denied(Path::new("/home/dev/projects/../.ssh/id_rsa")));
+    }
+
+    #[test]
+    fn relative_patterns_resolve_against_cwd() {
+        let matcher = ReadDenyMatcher::with_home(
+            &patterns(&["secrets", "config/*.pem"]),
+            Path::new("/work/repo"),
+            None,
+            &[],
+        );
+
+        assert!(matcher.is_denied(Path::new("/work/repo/secrets/token.txt")));
+        assert!(matcher.is_denied(Path::new("/work/repo/config/server.pem")));
+        assert!(!matcher.is_denied(Path::new("/other/secrets")));
+        assert!(!matcher.is_denied(Path::new("/work/repo/config/server.toml")));
+    }
+
+    #[test]
+    fn home_patterns_are_skipped_without_a_home_directory() {
+        let matcher =
+            ReadDenyMatcher::with_home(&patterns(&["~/.ssh"]), Path::new("/work"), None, &[]);
+
+        assert!(matcher.is_empty());
+    }
+
+    #[test]
+    fn resolve_denied_paths_finds_literals_and_globs() -> std::io::Result<()> {
+        let home = TempDir::new()?;
+        let repo = TempDir::new()?;
+        std::fs::create_dir(home.path().join(".ssh"))?;
+        std::fs::create_dir_all(repo.path().join("service/nested"))?;
+        std::fs::write(repo.path().join(".env"), "TOKEN=1")?;
+        std::fs::write(repo.path().join("service/nested/.env"), "TOKEN=2")?;
+        std::fs::write(repo.path().join("service/.env.example"), "TOKEN=")?;
+
+        let matcher = ReadDenyMatcher::with_home(
+            &patterns(&["~/.ssh", "~/.aws", "**/.env"]),
+            repo.path(),
+            Some(home.path()),
+            &patterns(&["**/.env"]),
+        );
+        let denied = matcher.resolve_denied_paths(&[repo.path().to_path_buf()]);
+
+        let mut paths = vec![
+            home.path().join(".ssh").canonicalize()?,
+            repo.path().join(".env").canonicalize()?,
+            repo.path().join("service/nested/.env").canonicalize()?,
+        ];
+        paths.sort();
+        assert_eq!(
+            denied,
+            ReadDeniedPaths {
+                paths,
+                carve_paths: vec![home.path().join(".ssh").canonicalize()?],
+                complete: true,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_denied_paths_skips_ignored_trees_and_reports_an_exhausted_budget()
+    -> std::io::Result<()> {
+        let repo = TempDir::new()?;
+        std::fs::write(repo.path().join(".gitignore"), ".env\nbuild/\n")?;
+        std::fs::write(repo.path().join(".env"), "TOKEN=1")?;
+        for dir in ["node_modules/pkg", "build/cache"] {
+            std::fs::create_dir_all(repo.path().join(dir))?;
+            std::fs::write(repo.path().join(dir).join(".env"), "TOKEN=2")?;
+        }
+        let matcher = ReadDenyMatcher::with_home(&patterns(&["**/.env"]), repo.path(), None, &[]);
+        let roots = [repo.path().to_path_buf()];
+
+        let denied = matcher.resolve_with_budget(&roots, 4);
+        assert_eq!(
+            denied,
+            ReadDeniedPaths {
+                paths: vec![repo.path().join(".env").canonicalize()?],
+                carve_paths: vec![repo.path().join(".env").canonicalize()?],
+                complete: true,
+            }
+        );
+
+        let truncated = matcher.resolve_with_budget(&roots, 3);
+        assert!(!truncated.complete);
+        Ok(())
+    }
+}
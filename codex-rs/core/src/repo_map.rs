@@ -0,0 +1,762 @@
+//! Compact map of the repository the session runs in, injected into the
+//! initial context when the `repo_map` feature is enabled.
+//!
+//! The map summarizes the directory tree, the package manifests and the
+//! public top-level symbols of each package's entry file, trimmed to a fixed
+//! token budget. Maps are generated once per commit and cached under
+//! `codex_home/repo_map`, keeping the few most recent commits of each
+//! repository.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use ignore::WalkBuilder;
+use sha1::Digest;
+use sha1::Sha1;
+use tracing::warn;
+
+use crate::git_info::collect_git_info;
+use crate::git_info::get_git_repo_root;
+use crate::symbols::SymbolLanguage;
+use crate::symbols::outline;
+use crate::truncate::TruncationPolicy;
+
+pub(crate) const REPO_MAP_OPEN_TAG: &str = "<repository_map>";
+pub(crate) const REPO_MAP_CLOSE_TAG: &str = "</repository_map>";
+
+/// Directory under `codex_home` holding cached maps.
+const REPO_MAP_DIR: &str = "repo_map";
+
+/// Maps kept per repository, newest first; older commits are regenerated
+/// if they are checked out again.
+const MAX_CACHED_MAPS_PER_REPO: usize = 5;
+
+/// Cached maps not written for this long are deleted.
+const MAX_CACHED_MAP_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Size of the rendered map.
+const TOKEN_BUDGET: usize = 2_000;
+
+/// Walking stops after this many entries so huge checkouts stay cheap.
+const MAX_WALK_ENTRIES: usize = 100_000;
+
+/// Manifests nested deeper than this are not listed.
+const MAX_MANIFEST_DEPTH: usize = 4;
+
+/// Directories listed under a single parent before the rest are elided.
+const MAX_CHILD_DIRECTORIES: usize = 25;
+
+/// Top-level files listed for the repository root.
+const MAX_ROOT_FILES: usize = 20;
+
+/// Public symbols listed per entry file.
+const MAX_SYMBOLS_PER_FILE: usize = 12;
+
+const MANIFEST_NAMES: [&str; 4] = ["Cargo.toml", "package.json", "pyproject.toml", "go.mod"];
+
+/// The repository map as it appears in the conversation.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RepoMap {
+    pub text: String,
+}
+
+impl RepoMap {
+    pub fn is_repo_map(message: &[ContentItem]) -> bool {
+        if let [ContentItem::InputText { text }] = message {
+            text.starts_with(REPO_MAP_OPEN_TAG)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_repo_map_item(item: &ResponseItem) -> bool {
+        matches!(item, ResponseItem::Message { role, content, .. } if role == "user" && Self::is_repo_map(content))
+    }
+}
+
+impl From<RepoMap> for ResponseItem {
+    fn from(map: RepoMap) -> Self {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!("{REPO_MAP_OPEN_TAG}\n{}\n{REPO_MAP_CLOSE_TAG}", map.text),
+            }],
+        }
+    }
+}
+
+/// Returns the map for the repository containing `cwd`, generating and
+/// caching it when no map exists for the current commit. Returns `None`
+/// outside a git repository.
+pub(crate) async fn load_repo_map(codex_home: &Path, cwd: &Path) -> Option<String> {
+    let root = get_git_repo_root(cwd)?;
+    let commit = collect_git_info(&root).await?.commit_hash?;
+    let cache_path = cache_path(codex_home, &root, &commit);
+
+    if let Ok(text) = tokio::fs::read_to_string(&cache_path).await {
+        return Some(text);
+    }
+
+    let generate_root = root.clone();
+    let text = match tokio::task::spawn_blocking(move || generate(&generate_root)).await {
+        Ok(text) => text,
+        Err(err) => {
+            warn!(
+                "failed to generate repository map for {}: {err}",
+                root.display()
+            );
+            return None;
+        }
+    };
+
+    if let Some(parent) = cache_path.parent()
+        && let Err(err) = tokio::fs::create_dir_all(parent).await
+    {
+        warn!("failed to create {}: {err}", parent.display());
+    } else if let Err(err) = tokio::fs::write(&cache_path, &text).await {
+        warn!(
+            "failed to cache repository map at {}: {err}",
+            cache_path.display()
+        );
+    }
+
+    let cache_dir = codex_home.join(REPO_MAP_DIR);
+    let keep = cache_path.clone();
+    if let Err(err) =
+        tokio::task::spawn_blocking(move || prune_cache(&cache_dir, &keep, SystemTime::now())).await
+    {
+        warn!("failed to prune cached repository maps: {err}");
+    }
+    Some(text)
+}
+
+/// Deletes cached maps beyond the newest [`MAX_CACHED_MAPS_PER_REPO`] of each
+/// repository and those not written for [`MAX_CACHED_MAP_AGE`], except `keep`.
+fn prune_cache(cache_dir: &Path, keep: &Path, now: SystemTime) {
+    let Ok(repos) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    for repo in repos.flatten() {
+        let Ok(maps) = std::fs::read_dir(repo.path()) else {
+            continue;
+        };
+        let mut maps: Vec<(SystemTime, PathBuf)> = maps
+            .flatten()
+            .filter_map(|map| {
+                let modified = map.metadata().and_then(|meta| meta.modified()).ok()?;
+                Some((modified, map.path()))
+            })
+            .collect();
+        maps.sort_by(|a, b| b.0.cmp(&a.0));
+        for (position, (modified, path)) in maps.iter().enumerate() {
+            let expired = now
+                .duration_since(*modified)
+                .is_ok_and(|age| age > MAX_CACHED_MAP_AGE);
+            if path != keep && (position >= MAX_CACHED_MAPS_PER_REPO || expired) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        // Only succeeds once every map of the repository is gone.
+        let _ = std::fs::remove_dir(repo.path());
+    }
+}
+
+fn cache_path(codex_home: &Path, root: &Path, commit: &str) -> PathBuf {
+    let digest = Sha1::digest(root.to_string_lossy().as_bytes());
+    let root_key: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    codex_home
+        .join(REPO_MAP_DIR)
+        .join(root_key)
+        .join(format!("{commit}.md"))
+}
+
+/// Files found while walking the repository, as `/`-separated paths
+/// relative to the root.
+#[derive(Debug, Default)]
+struct Tree {
+    /// File count per directory, including nested directories. The root is
+    /// keyed by the empty string.
+    dirs: BTreeMap<String, usize>,
+    root_files: Vec<String>,
+    manifests: Vec<String>,
+}
+
+fn walk(root: &Path) -> Tree {
+    let mut tree = Tree::default();
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    for entry in walker.flatten().take(MAX_WALK_ENTRIES) {
+        let Ok(relative) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if relative.is_empty() {
+            continue;
+        }
+        let is_dir = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir());
+        if is_dir {
+            tree.dirs.entry(relative).or_default();
+            continue;
+        }
+
+        *tree.dirs.entry(String::new()).or_default() += 1;
+        for (index, _) in relative.match_indices('/') {
+            *tree.dirs.entry(relative[..index].to_string()).or_default() += 1;
+        }
+
+        let depth = relative.matches('/').count();
+        let file_name = relative.rsplit('/').next().unwrap_or(&relative);
+        if depth < MAX_MANIFEST_DEPTH && MANIFEST_NAMES.contains(&file_name) {
+            tree.manifests.push(relative.clone());
+        }
+        if depth == 0 {
+            tree.root_files.push(relative);
+        }
+    }
+    tree.root_files.sort();
+    tree.manifests.sort();
+    tree
+}
+
+/// Renders the map for the repository at `root` within [`TOKEN_BUDGET`].
+fn generate(root: &Path) -> String {
+    render(root, &walk(root), TOKEN_BUDGET)
+}
+
+fn render(root: &Path, tree: &Tree, token_budget: usize) -> String {
+    let byte_budget = TruncationPolicy::tokens(token_budget).byte_budget();
+    let mut sections = vec![format!(
+        "Repository map of {} (directories show file counts; ignored files are skipped).",
+        root.display()
+    )];
+    let mut remaining = byte_budget.saturating_sub(sections[0].len());
+
+    // The layout gets half of the budget up front; whatever it leaves unused
+    // goes to the package and symbol sections.
+    let layout = render_layout(tree, remaining / 2);
+    remaining = remaining.saturating_sub(layout.len());
+    sections.push(layout);
+
+    let packages = read_packages(root, tree);
+    let manifests = render_manifests(&packages, remaining / 2);
+    if !manifests.is_empty() {
+        remaining = remaining.saturating_sub(manifests.len());
+        sections.push(manifests);
+    }
+
+    let symbols = render_symbols(root, &packages, remaining);
+    if !symbols.is_empty() {
+        sections.push(symbols);
+    }
+
+    sections.join("\n\n")
+}
+
+/// Lists directories level by level: each additional level of depth is only
+/// included when the whole level fits in `byte_budget`.
+fn render_layout(tree: &Tree, byte_budget: usize) -> String {
+    let header = "## Layout".to_string();
+    let mut root_line = format!("./ ({} files)", tree.dirs.get("").copied().unwrap_or(0));
+    if !tree.root_files.is_empty() {
+        let shown: Vec<&str> = tree
+            .root_files
+            .iter()
+            .take(MAX_ROOT_FILES)
+            .map(String::as_str)
+            .collect();
+        root_line.push_str(&format!(": {}", shown.join(", ")));
+        if tree.root_files.len() > MAX_ROOT_FILES {
+            root_line.push_str(&format!(
+                ", +{} more",
+                tree.root_files.len() - MAX_ROOT_FILES
+            ));
+        }
+    }
+
+    // Each directory line is tagged with its depth, so every candidate depth
+    // is measured from one rendering instead of walking the tree per level.
+    let entries = layout_entries(tree);
+    let max_depth = entries.iter().map(|(depth, _)| *depth).max().unwrap_or(0);
+    let mut bytes_per_depth = vec![0usize; max_depth + 1];
+    for (depth, line) in &entries {
+        bytes_per_depth[*depth] += line.len() + 1;
+    }
+    let mut used = header.len() + 1 + root_line.len() + 1;
+    let mut shown_depth = 0;
+    for (depth, bytes) in bytes_per_depth.iter().enumerate().skip(1) {
+        used += bytes;
+        if used > byte_budget {
+            break;
+        }
+        shown_depth = depth;
+    }
+
+    let mut lines = vec![header, root_line];
+    lines.extend(
+        entries
+            .into_iter()
+            .filter(|(depth, _)| *depth <= shown_depth)
+            .map(|(_, line)| line),
+    );
+    lines.join("\n")
+}
+
+/// Directory lines of the layout in display order, each with its depth
+/// (1 for top-level directories).
+fn layout_entries(tree: &Tree) -> Vec<(usize, String)> {
+    let mut children: HashMap<&str, Vec<(&str, usize)>> = HashMap::new();
+    for (dir, count) in &tree.dirs {
+        if dir.is_empty() {
+            continue;
+        }
+        let parent = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+        children
+            .entry(parent)
+            .or_default()
+            .push((dir.as_str(), *count));
+    }
+    // Largest directories first so elision drops the least informative ones.
+    for siblings in children.values_mut() {
+        siblings.sort_by(|(a_dir, a_count), (b_dir, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_dir.cmp(b_dir))
+        });
+    }
+
+    let mut entries = Vec::new();
+    push_layout_entries(&children, "", 1, &mut entries);
+    entries
+}
+
+fn push_layout_entries(
+    children: &HashMap<&str, Vec<(&str, usize)>>,
+    parent: &str,
+    depth: usize,
+    entries: &mut Vec<(usize, String)>,
+) {
+    let Some(siblings) = children.get(parent) else {
+        return;
+    };
+    let indent = "  ".repeat(depth);
+    for (dir, count) in siblings.iter().take(MAX_CHILD_DIRECTORIES) {
+        let name = dir.rsplit('/').next().unwrap_or(dir);
+        entries.push((depth, format!("{indent}{name}/ ({count} files)")));
+        push_layout_entries(children, dir, depth + 1, entries);
+    }
+    if siblings.len() > MAX_CHILD_DIRECTORIES {
+        entries.push((
+            depth,
+            format!(
+                "{indent}+{} more directories",
+                siblings.len() - MAX_CHILD_DIRECTORIES
+            ),
+        ));
+    }
+}
+
+/// A package declared by a manifest.
+#[derive(Debug, PartialEq)]
+struct Package {
+    manifest: String,
+    name: Option<String>,
+    description: Option<String>,
+    /// Entry file relative to the repository root, when one exists.
+    entry: Option<String>,
+}
+
+fn read_packages(root: &Path, tree: &Tree) -> Vec<Package> {
+    tree.manifests
+        .iter()
+        .filter_map(|manifest| {
+            let contents = std::fs::read_to_string(root.join(manifest)).ok()?;
+            let file_name = manifest.rsplit('/').next().unwrap_or(manifest);
+            let (name, description) = parse_manifest(file_name, &contents);
+            let dir = manifest
+                .strip_suffix(file_name)
+                .unwrap_or_default()
+                .to_string();
+            let entry = entry_candidates(file_name, name.as_deref())
+                .into_iter()
+                .map(|candidate| format!("{dir}{candidate}"))
+                .find(|candidate| root.join(candidate).is_file());
+            Some(Package {
+                manifest: manifest.clone(),
+                name,
+                description,
+                entry,
+            })
+        })
+        .collect()
+}
+
+/// Package name and description declared by a manifest.
+fn parse_manifest(file_name: &str, contents: &str) -> (Option<String>, Option<String>) {
+    let string_at =
+        |value: Option<&toml::Value>| value.and_then(toml::Value::as_str).map(ToString::to_string);
+    match file_name {
+        "Cargo.toml" => {
+            let Ok(value) = toml::from_str::<toml::Value>(contents) else {
+                return (None, None);
+            };
+            let package = value.get("package");
+            let name = string_at(package.and_then(|package| package.get("name")));
+            let description = string_at(package.and_then(|package| package.get("description")));
+            if name.is_none()
+                && let Some(members) = value
+                    .get("workspace")
+                    .and_then(|workspace| workspace.get("members"))
+                    .and_then(toml::Value::as_array)
+            {
+                return (
+                    None,
+                    Some(format!("workspace with {} members", members.len())),
+                );
+            }
+            (name, description)
+        }
+        "pyproject.toml" => {
+            let Ok(value) = toml::from_str::<toml::Value>(contents) else {
+                return (None, None);
+            };
+            let project = value
+                .get("project")
+                .or_else(|| value.get("tool").and_then(|tool| tool.get("poetry")));
+            (
+                string_at(project.and_then(|project| project.get("name"))),
+                string_at(project.and_then(|project| project.get("description"))),
+            )
+        }
+        "package.json" => {
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(contents) else {
+                return (None, None);
+            };
+            let string_at = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+            (string_at("name"), string_at("description"))
+        }
+        "go.mod" => (
+            contents
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))
+                .map(|module| module.trim().to_string()),
+            None,
+        ),
+        _ => (None, None),
+    }
+}
+
+/// Entry files whose public symbols represent the package, in preference
+/// order, relative to the manifest's directory.
+fn entry_candidates(manifest: &str, name: Option<&str>) -> Vec<String> {
+    match manifest {
+        "Cargo.toml" => vec!["src/lib.rs".to_string(), "src/main.rs".to_string()],
+        "package.json" => ["src/index.ts", "src/index.tsx", "index.ts", "index.tsx"]
+            .map(String::from)
+            .to_vec(),
+        "pyproject.toml" => name
+            .map(|name| {
+                let module = name.replace('-', "_");
+                vec![
+                    format!("src/{module}/__init__.py"),
+                    format!("{module}/__init__.py"),
+                ]
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn render_manifests(packages: &[Package], byte_budget: usize) -> String {
+    let mut lines = vec!["## Packages".to_string()];
+    let mut used = lines[0].len() + 1;
+    for package in packages {
+        let mut line = format!("- {}", package.manifest);
+        if let Some(name) = &package.name {
+            line.push_str(&format!(": {name}"));
+        }
+        if let Some(description) = &package.description {
+            line.push_str(&format!(" ({description})"));
+        }
+        if used + line.len() + 1 > byte_budget {
+            break;
+        }
+        used += line.len() + 1;
+        lines.push(line);
+    }
+    if lines.len() == 1 {
+        return String::new();
+    }
+    lines.join("\n")
+}
+
+fn render_symbols(root: &Path, packages: &[Package], byte_budget: usize) -> String {
+    let mut lines = vec!["## Public symbols".to_string()];
+    let mut used = lines[0].len() + 1;
+    for entry in packages.iter().filter_map(|package| package.entry.as_ref()) {
+        let names = public_symbols(&root.join(entry));
+        if names.is_empty() {
+            continue;
+        }
+        let mut line = format!(
+            "- {entry}: {}",
+            names
+                .iter()
+                .take(MAX_SYMBOLS_PER_FILE)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if names.len() > MAX_SYMBOLS_PER_FILE {
+            line.push_str(&format!(", +{} more", names.len() - MAX_SYMBOLS_PER_FILE));
+        }
+        if used + line.len() + 1 > byte_budget {
+            break;
+        }
+        used += line.len() + 1;
+        lines.push(line);
+    }
+    if lines.len() == 1 {
+        return String::new();
+    }
+    lines.join("\n")
+}
+
+/// Top-level symbols of `path` visible outside the file, as `kind name`.
+fn public_symbols(path: &Path) -> Vec<String> {
+    let Some(language) = SymbolLanguage::from_path(path) else {
+        return Vec::new();
+    };
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let Ok(symbols) = outline(&source, language) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = source.lines().collect();
+    symbols
+        .iter()
+        .filter(|symbol| symbol.depth == 0 && !symbol.name.is_empty())
+        .filter(|symbol| match language {
+            SymbolLanguage::Rust => symbol.kind != "impl" && symbol.signature.starts_with("pub "),
+            SymbolLanguage::TypeScript | SymbolLanguage::Tsx => lines
+                .get(symbol.start_line - 1)
+                .is_some_and(|line| line.trim_start().starts_with("export ")),
+            SymbolLanguage::Python => !symbol.name.starts_with('_'),
+        })
+        .map(|symbol| format!("{} {}", symbol.kind, symbol.name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, contents: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("create dirs");
+        std::fs::write(path, contents).expect("write file");
+    }
+
+    fn sample_repo() -> TempDir {
+        let dir = TempDir::new().expect("tempdir");
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"core\", \"cli\"]\n",
+        );
+        write(
+            root,
+            "core/Cargo.toml",
+            "[package]\nname = \"demo-core\"\ndescription = \"Core library\"\n",
+        );
+        write(
+            root,
+            "core/src/lib.rs",
+            "pub mod config;\nmod private;\npub fn run() {}\nfn helper() {}\npub(crate) struct Hidden;\npub struct Session;\nimpl Session {}\n",
+        );
+        write(root, "core/src/config.rs", "");
+        write(root, "cli/Cargo.toml", "[package]\nname = \"demo-cli\"\n");
+        write(root, "cli/src/main.rs", "fn main() {}\n");
+        write(root, "README.md", "# Demo\n");
+        write(root, ".gitignore", "target/\n");
+        write(root, "target/debug/out.bin", "");
+        dir
+    }
+
+    #[test]
+    fn renders_layout_packages_and_public_symbols() {
+        let repo = sample_repo();
+        let root = repo.path();
+        let text = render(root, &walk(root), TOKEN_BUDGET);
+
+        let expected = format!(
+            "Repository map of {} (directories show file counts; ignored files are skipped).
+
+## Layout
+./ (8 files): .gitignore, Cargo.toml, README.md
+  core/ (3 files)
+    src/ (2 files)
+  cli/ (2 files)
+    src/ (1 files)
+
+## Packages
+- Cargo.toml (workspace with 2 members)
+- cli/Cargo.toml: demo-cli
+- core/Cargo.toml: demo-core (Core library)
+
+## Public symbols
+- core/src/lib.rs: mod config, fn run, struct Session",
+            root.display()
+        );
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn layout_drops_deeper_levels_to_fit_budget() {
+        let repo = sample_repo();
+        let tree = walk(repo.path());
+
+        let full = render_layout(&tree, usize::MAX);
+        assert!(full.contains("    src/ (2 files)"));
+
+        let shallow = render_layout(&tree, full.len() - 1);
+        assert_eq!(
+            shallow,
+            "## Layout\n./ (8 files): .gitignore, Cargo.toml, README.md\n  core/ (3 files)\n  cli/ (2 files)"
+        );
+    }
+
+    #[test]
+    fn layout_elides_directories_beyond_the_child_limit() {
+        let mut tree = Tree::default();
+        tree.dirs.insert(String::new(), 30);
+        for index in 0..30 {
+            tree.dirs.insert(format!("dir{index:02}"), 1);
+        }
+        tree.dirs.insert("dir00/nested".to_string(), 1);
+
+        let layout = render_layout(&tree, usize::MAX);
+        let lines: Vec<&str> = layout.lines().collect();
+        assert_eq!(lines[2], "  dir00/ (1 files)");
+        assert_eq!(lines[3], "    nested/ (1 files)");
+        assert_eq!(lines.last(), Some(&"  +5 more directories"));
+        assert_eq!(lines.len(), 2 + 25 + 1 + 1);
+    }
+
+    #[test]
+    fn prune_cache_keeps_recent_maps_per_repository() {
+        let cache_dir = TempDir::new().expect("cache dir");
+        let now = SystemTime::now();
+        let touch = |relative: &str, age_days: u64| {
+            let path = cache_dir.path().join(relative);
+            write(cache_dir.path(), relative, "map");
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| {
+                    file.set_modified(now - Duration::from_secs(age_days * 24 * 60 * 60))
+                })
+                .expect("set mtime");
+            path
+        };
+        let recent: Vec<PathBuf> = (0..7)
+            .map(|age| touch(&format!("repo/{age}.md"), age))
+            .collect();
+        let stale = touch("old-repo/commit.md", 60);
+
+        prune_cache(cache_dir.path(), &recent[6], now);
+
+        let exists: Vec<bool> = recent.iter().map(|path| path.exists()).collect();
+        assert_eq!(exists, vec![true, true, true, true, true, false, true]);
+        assert!(!stale.exists());
+        assert!(!cache_dir.path().join("old-repo").exists());
+    }
+
+    #[test]
+    fn repo_map_message_is_recognized() {
+        let item: ResponseItem = RepoMap {
+            text: "## Layout".to_string(),
+        }
+        .into();
+        let ResponseItem::Message { content, .. } = item else {
+            panic!("expected message");
+        };
+        assert!(RepoMap::is_repo_map(&content));
+        assert!(!RepoMap::is_repo_map(&[ContentItem::InputText {
+            text: "hello".to_string(),
+        }]));
+    }
+
+    #[tokio::test]
+    async fn load_repo_map_caches_per_commit() {
+        let repo = sample_repo();
+        let codex_home = TempDir::new().expect("codex home");
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo.path())
+                .env("GIT_CONFIG_GLOBAL", "/dev/null")
+                .env("GIT_CONFIG_NOSYSTEM", "1")
+                .status()
+                .expect("run git");
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "init",
+        ]);
+
+        let cwd = repo.path().join("core");
+        let text = load_repo_map(codex_home.path(), &cwd)
+            .await
+            .expect("repo map");
+        assert!(text.contains("core/Cargo.toml: demo-core"));
+
+        let root = get_git_repo_root(&cwd).expect("git root");
+        let commit = collect_git_info(&root)
+            .await
+            .and_then(|info| info.commit_hash)
+            .expect("commit");
+        let cached = cache_path(codex_home.path(), &root, &commit);
+        assert_eq!(std::fs::read_to_string(&cached).expect("cached"), text);
+
+        std::fs::write(&cached, "cached map").expect("overwrite cache");
+        assert_eq!(
+            load_repo_map(codex_home.path(), &cwd).await.as_deref(),
+            Some("cached map")
+        );
+    }
+
+    #[tokio::test]
+    async fn load_repo_map_is_none_outside_git() {
+        let dir = TempDir::new().expect("tempdir");
+        let codex_home = TempDir::new().expect("codex home");
+        assert_eq!(load_repo_map(codex_home.path(), dir.path()).await, None);
+    }
+}
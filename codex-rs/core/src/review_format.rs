@@ -1,5 +1,6 @@
 use crate::protocol::ReviewFinding;
 use crate::protocol::ReviewOutputEvent;
+use crate::protocol::review_finding_id;
 
 // Note: We keep this module UI-agnostic. It returns plain strings that
 // higher layers (e.g., TUI) may style as needed.
@@ -57,6 +58,30 @@ pub fn format_review_findings_block(
     lines.join("\n")
 }
 
+/// Format the user message that asks the agent to fix review findings.
+///
+/// `findings` pairs each finding with its 1-based number in the review, so
+/// the message names the same finding ids clients and the rollout use.
+pub fn format_review_fix_request(review_id: &str, findings: &[(usize, &ReviewFinding)]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if findings.len() > 1 {
+        lines.push("Fix the following code review findings.".to_string());
+    } else {
+        lines.push("Fix the following code review finding.".to_string());
+    }
+
+    for (number, item) in findings {
+        lines.push(String::new());
+        let id = review_finding_id(review_id, *number);
+        lines.push(format!("[{id}] {} — {}", item.title, format_location(item)));
+        for body_line in item.body.lines() {
+            lines.push(format!("  {body_line}"));
+        }
+    }
+
+    lines.join("\n")
+}
+
 /// Render a human-readable review summary suitable for a user-facing message.
 ///
 /// Returns either the explanation, the formatted findings block, or both
@@ -80,3 +105,35 @@ pub fn render_review_output_text(output: &ReviewOutputEvent) -> String {
         sections.join("\n\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn fix_request_names_finding_ids_and_locations() {
+        let finding = ReviewFinding {
+            title: "[P1] Off by one".to_string(),
+            body: "The loop skips the last item.\nUse `..=`.".to_string(),
+            confidence_score: 0.9,
+            priority: 1,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+        };
+
+        assert_eq!(
+            format_review_fix_request("review-1", &[(3, &finding)]),
+            "Fix the following code review finding.\n\
+             \n\
+             [review-1#3] [P1] Off by one — /repo/src/lib.rs:10-12\n  \
+             The loop skips the last item.\n  \
+             Use `..=`."
+        );
+    }
+}
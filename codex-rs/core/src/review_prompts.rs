@@ -8,6 +8,7 @@ pub struct ResolvedReviewRequest {
     pub target: ReviewTarget,
     pub prompt: String,
     pub user_facing_hint: String,
+    pub paths: Vec<String>,
 }
 
 const UNCOMMITTED_PROMPT: &str = "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.";
@@ -19,12 +20,19 @@ const COMMIT_PROMPT_WITH_TITLE: &str = "Review the code changes introduced by co
 const COMMIT_PROMPT: &str =
     "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings.";
 
+const RANGE_PROMPT: &str = "Review the code changes between {base} and {head}. Run `git diff {base}..{head}` to inspect them. Provide prioritized, actionable findings.";
+
+const PATHS_PROMPT: &str = " Only review changes to the following paths and ignore everything else (e.g. pass `-- {paths}` to `git diff`): {paths}.";
+
 pub fn resolve_review_request(
     request: ReviewRequest,
     cwd: &Path,
 ) -> anyhow::Result<ResolvedReviewRequest> {
     let target = request.target;
-    let prompt = review_prompt(&target, cwd)?;
+    let mut prompt = review_prompt(&target, cwd)?;
+    if !request.paths.is_empty() {
+        prompt.push_str(&PATHS_PROMPT.replace("{paths}", &request.paths.join(" ")));
+    }
     let user_facing_hint = request
         .user_facing_hint
         .unwrap_or_else(|| user_facing_hint(&target));
@@ -33,6 +41,7 @@ pub fn resolve_review_request(
         target,
         prompt,
         user_facing_hint,
+        paths: request.paths,
     })
 }
 
@@ -57,6 +66,9 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
                 Ok(COMMIT_PROMPT.replace("{sha}", sha))
             }
         }
+        ReviewTarget::Range { base, head } => {
+            Ok(RANGE_PROMPT.replace("{base}", base).replace("{head}", head))
+        }
         ReviewTarget::Custom { instructions } => {
             let prompt = instructions.trim();
             if prompt.is_empty() {
@@ -79,6 +91,7 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
                 format!("commit {short_sha}")
             }
         }
+        ReviewTarget::Range { base, head } => format!("changes in {base}..{head}"),
         ReviewTarget::Custom { instructions } => instructions.trim().to_string(),
     }
 }
@@ -88,6 +101,7 @@ impl From<ResolvedReviewRequest> for ReviewRequest {
         ReviewRequest {
             target: resolved.target,
             user_facing_hint: Some(resolved.user_facing_hint),
+            paths: resolved.paths,
         }
     }
 }
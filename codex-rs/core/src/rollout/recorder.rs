@@ -251,6 +251,12 @@ impl RolloutRecorder {
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
+                    RolloutItem::TurnEndSnapshot(item) => {
+                        items.push(RolloutItem::TurnEndSnapshot(item));
+                    }
+                    RolloutItem::HistoryRewritten(item) => {
+                        items.push(RolloutItem::HistoryRewritten(item));
+                    }
                 },
                 Err(e) => {
                     warn!("failed to parse rollout line: {v:?}, error: {e}");
@@ -186,11 +186,21 @@ fn is_write_patch_constrained_to_writable_paths(
 
     for (path, change) in action.changes() {
         match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
+            ApplyPatchFileChange::Add { .. }
+            | ApplyPatchFileChange::AddBinary { .. }
+            | ApplyPatchFileChange::Delete { .. } => {
                 if !is_path_writable(path) {
                     return false;
                 }
             }
+            ApplyPatchFileChange::AddSymlink { target } => {
+                // Later writes through the link land on its target, so the
+                // target must be writable too.
+                let link_dir = path.parent().unwrap_or(path);
+                if !is_path_writable(path) || !is_path_writable(&link_dir.join(target)) {
+                    return false;
+                }
+            }
             ApplyPatchFileChange::Update { move_path, .. } => {
                 if !is_path_writable(path) {
                     return false;
@@ -234,6 +244,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: vec![],
+            read_deny: vec![],
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -255,6 +267,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: vec![],
+            read_deny: vec![],
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -262,4 +276,53 @@ mod tests {
             &cwd,
         ));
     }
+
+    #[test]
+    fn test_symlink_target_must_be_writable() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let parent = cwd.parent().unwrap().to_path_buf();
+        let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            network_allowlist: vec![],
+            read_deny: vec![],
+        };
+        let link = cwd.join("latest");
+
+        let inside = ApplyPatchAction::new_add_symlink_for_test(&link, Path::new("releases/v2"));
+        assert!(is_write_patch_constrained_to_writable_paths(
+            &inside,
+            &policy_workspace_only,
+            &cwd,
+        ));
+
+        let relative_escape =
+            ApplyPatchAction::new_add_symlink_for_test(&link, Path::new("../outside.txt"));
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &relative_escape,
+            &policy_workspace_only,
+            &cwd,
+        ));
+
+        let absolute_escape =
+            ApplyPatchAction::new_add_symlink_for_test(&link, &parent.join("outside.txt"));
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &absolute_escape,
+            &policy_workspace_only,
+            &cwd,
+        ));
+
+        let link_outside = ApplyPatchAction::new_add_symlink_for_test(
+            &parent.join("latest"),
+            &cwd.join("inner.txt"),
+        );
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &link_outside,
+            &policy_workspace_only,
+            &cwd,
+        ));
+    }
 }
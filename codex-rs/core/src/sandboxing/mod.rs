@@ -12,7 +12,11 @@ use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
 use crate::landlock::create_linux_sandbox_command_args;
+use crate::network_proxy::NetworkProxyEndpoint;
+use crate::network_proxy::proxy_env_vars;
+use crate::network_proxy::sandbox_supports_network_proxy;
 use crate::protocol::SandboxPolicy;
+use crate::read_deny::ReadDeniedPaths;
 #[cfg(target_os = "macos")]
 use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
 #[cfg(target_os = "macos")]
@@ -59,6 +63,8 @@ pub enum SandboxPreference {
 pub(crate) enum SandboxTransformError {
     #[error("missing codex-linux-sandbox executable path")]
     MissingLinuxSandboxExecutable,
+    #[error("read_deny paths were not resolved for the Linux sandbox")]
+    MissingReadDeniedPaths,
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
@@ -98,6 +104,8 @@ impl SandboxManager {
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         codex_linux_sandbox_exe: Option<&PathBuf>,
+        network_proxy: Option<&NetworkProxyEndpoint>,
+        read_denied_paths: Option<&ReadDeniedPaths>,
     ) -> Result<ExecEnv, SandboxTransformError> {
         let mut env = spec.env;
         if !policy.has_full_network_access() {
@@ -107,6 +115,13 @@ impl SandboxManager {
             );
         }
 
+        // The egress proxy only matters when the platform sandbox confines
+        // the command to it; everywhere else the network stays disabled.
+        let network_proxy = network_proxy.filter(|_| sandbox_supports_network_proxy(sandbox));
+        if let Some(network_proxy) = network_proxy {
+            env.extend(proxy_env_vars(network_proxy.port));
+        }
+
         let mut command = Vec::with_capacity(1 + spec.args.len());
         command.push(spec.program);
         command.append(&mut spec.args);
@@ -117,8 +132,12 @@ impl SandboxManager {
             SandboxType::MacosSeatbelt => {
                 let mut seatbelt_env = HashMap::new();
                 seatbelt_env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-                let mut args =
-                    create_seatbelt_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_seatbelt_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy.map(|network_proxy| network_proxy.port),
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(MACOS_PATH_TO_SEATBELT_EXECUTABLE.to_string());
                 full_command.append(&mut args);
@@ -129,8 +148,15 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let read_denied_paths =
+                    read_denied_paths.ok_or(SandboxTransformError::MissingReadDeniedPaths)?;
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy,
+                    read_denied_paths,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
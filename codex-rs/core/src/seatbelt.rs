@@ -6,6 +6,8 @@ use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+use crate::network_proxy::NetworkProxyEndpoint;
+use crate::network_proxy::proxy_env_vars;
 use crate::protocol::SandboxPolicy;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::StdioPolicy;
@@ -20,6 +22,9 @@ const MACOS_SEATBELT_NETWORK_POLICY: &str = include_str!("seatbelt_network_polic
 /// already has root access.
 pub(crate) const MACOS_PATH_TO_SEATBELT_EXECUTABLE: &str = "/usr/bin/sandbox-exec";
 
+/// Pass `network_proxy` (see [`crate::network_proxy::NetworkProxy`]) to let
+/// the command reach the policy's `network_allowlist`; without it the network
+/// stays disabled.
 pub async fn spawn_command_under_seatbelt(
     command: Vec<String>,
     command_cwd: PathBuf,
@@ -27,8 +32,17 @@ pub async fn spawn_command_under_seatbelt(
     sandbox_policy_cwd: &Path,
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
+    network_proxy: Option<&NetworkProxyEndpoint>,
 ) -> std::io::Result<Child> {
-    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    if let Some(network_proxy) = network_proxy {
+        env.extend(proxy_env_vars(network_proxy.port));
+    }
+    let args = create_seatbelt_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        network_proxy.map(|network_proxy| network_proxy.port),
+    );
     let arg0 = None;
     env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
     spawn_child_async(
@@ -47,6 +61,7 @@ pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_port: Option<u16>,
 ) -> Vec<String> {
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
@@ -113,9 +128,12 @@ pub(crate) fn create_seatbelt_command_args(
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
-        MACOS_SEATBELT_NETWORK_POLICY
+        MACOS_SEATBELT_NETWORK_POLICY.to_string()
+    } else if let Some(port) = network_proxy_port {
+        // Only the local egress proxy is reachable; it enforces the allowlist.
+        format!("(allow network-outbound (remote ip \"localhost:{port}\"))")
     } else {
-        ""
+        String::new()
     };
 
     let full_policy = format!(
@@ -196,12 +214,15 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            network_allowlist: vec![],
+            read_deny: vec![],
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             &cwd,
+            None,
         );
 
         // Build the expected policy text using a raw string for readability.
@@ -272,12 +293,15 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: vec![],
+            read_deny: vec![],
         };
 
         let args = create_seatbelt_command_args(
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             root_with_git.as_path(),
+            None,
         );
 
         let tmpdir_env_var = std::env::var("TMPDIR")
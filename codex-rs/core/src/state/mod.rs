@@ -3,6 +3,8 @@ mod session;
 mod turn;
 
 pub(crate) use service::SessionServices;
+#[cfg(test)]
+pub(crate) use session::MAX_TURN_END_SNAPSHOTS;
 pub(crate) use session::SessionState;
 pub(crate) use turn::ActiveTurn;
 pub(crate) use turn::RunningTask;
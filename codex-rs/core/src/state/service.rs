@@ -3,7 +3,9 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::network_proxy::NetworkProxyManager;
 use crate::openai_models::models_manager::ModelsManager;
+use crate::read_deny::ReadDenyManager;
 use crate::skills::SkillLoadOutcome;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
@@ -26,4 +28,6 @@ pub(crate) struct SessionServices {
     pub(crate) otel_manager: OtelManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
     pub(crate) skills: Option<SkillLoadOutcome>,
+    pub(crate) network_proxy: NetworkProxyManager,
+    pub(crate) read_deny: ReadDenyManager,
 }
@@ -1,15 +1,24 @@
 //! Session-wide mutable state.
 
+use std::collections::HashMap;
+
+use codex_git::GhostCommit;
 use codex_protocol::models::ResponseItem;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::cost_ledger::PendingTurnCost;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::ReviewFinding;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::truncate::TruncationPolicy;
 use crate::worktree_change_notice::WorktreeSnapshot;
 
+/// Turn-end snapshots kept per session. Undo treats older turns as ending
+/// where the next one started.
+pub(crate) const MAX_TURN_END_SNAPSHOTS: usize = 100;
+
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
@@ -18,6 +27,14 @@ pub(crate) struct SessionState {
     pub(crate) last_worktree_snapshot: Option<WorktreeSnapshot>,
     pub(crate) cached_repo_root_for_worktree_notice: Option<std::path::PathBuf>,
     pub(crate) worktree_change_pause_bypass_once: bool,
+    /// Snapshots of the workspace as each recent turn left it, oldest first,
+    /// keyed by the id of the ghost snapshot taken when that turn started.
+    turn_end_snapshots: Vec<(String, GhostCommit)>,
+    /// Findings of each completed review, oldest first, keyed by the id of
+    /// the review turn.
+    pub(crate) review_findings: Vec<(String, Vec<ReviewFinding>)>,
+    /// Usage of the turns that have not finished yet, keyed by turn id.
+    pub(crate) pending_turn_costs: HashMap<String, PendingTurnCost>,
 }
 
 impl SessionState {
@@ -31,6 +48,9 @@ impl SessionState {
             last_worktree_snapshot: None,
             cached_repo_root_for_worktree_notice: None,
             worktree_change_pause_bypass_once: false,
+            turn_end_snapshots: Vec::new(),
+            review_findings: Vec::new(),
+            pending_turn_costs: HashMap::new(),
         }
     }
 
@@ -88,6 +108,73 @@ impl SessionState {
     pub(crate) fn get_total_token_usage(&self) -> i64 {
         self.history.get_total_token_usage()
     }
+
+    // Cost ledger helpers
+    pub(crate) fn record_turn_usage(&mut self, turn_id: &str, model: &str, usage: &TokenUsage) {
+        self.pending_turn_costs
+            .entry(turn_id.to_string())
+            .or_default()
+            .add_usage(model, usage);
+    }
+
+    pub(crate) fn mark_turn_compacted(&mut self, turn_id: &str) {
+        self.pending_turn_costs
+            .entry(turn_id.to_string())
+            .or_default()
+            .mark_compacted();
+    }
+
+    pub(crate) fn take_turn_cost(&mut self, turn_id: &str) -> Option<PendingTurnCost> {
+        self.pending_turn_costs.remove(turn_id)
+    }
+
+    // Undo helpers
+    /// Records the end snapshot of the turn started by `start_id`, keeping
+    /// only the most recent [`MAX_TURN_END_SNAPSHOTS`]. Returns `false` when
+    /// that turn already has one.
+    pub(crate) fn record_turn_end_snapshot(&mut self, start_id: String, end: GhostCommit) -> bool {
+        if self.has_turn_end_snapshot(&start_id) {
+            return false;
+        }
+        if self.turn_end_snapshots.len() >= MAX_TURN_END_SNAPSHOTS {
+            self.turn_end_snapshots.remove(0);
+        }
+        self.turn_end_snapshots.push((start_id, end));
+        true
+    }
+
+    pub(crate) fn has_turn_end_snapshot(&self, start_id: &str) -> bool {
+        self.turn_end_snapshots.iter().any(|(id, _)| id == start_id)
+    }
+
+    pub(crate) fn turn_end_snapshots(&self) -> HashMap<String, GhostCommit> {
+        self.turn_end_snapshots.iter().cloned().collect()
+    }
+
+    // Review helpers
+    pub(crate) fn record_review_findings(
+        &mut self,
+        review_id: String,
+        findings: Vec<ReviewFinding>,
+    ) {
+        self.review_findings.retain(|(id, _)| id != &review_id);
+        self.review_findings.push((review_id, findings));
+    }
+
+    /// Findings of the review `review_id`, or of the most recent review.
+    pub(crate) fn review_findings(
+        &self,
+        review_id: Option<&str>,
+    ) -> Option<(String, Vec<ReviewFinding>)> {
+        match review_id {
+            Some(review_id) => self
+                .review_findings
+                .iter()
+                .find(|(id, _)| id == review_id)
+                .cloned(),
+            None => self.review_findings.last().cloned(),
+        }
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
@@ -0,0 +1,442 @@
+//! Syntax-aware outlines of source files, backed by tree-sitter.
+//!
+//! Used by `read_file`'s symbol mode and the `list_symbols` tool to find the
+//! functions, types and modules declared in a file without relying on
+//! indentation.
+
+use std::path::Path;
+
+use tree_sitter::Language;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+/// Longest signature kept in an outline entry, in bytes.
+const MAX_SIGNATURE_LENGTH: usize = 200;
+
+/// Languages with a bundled grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SymbolLanguage {
+    Rust,
+    TypeScript,
+    Tsx,
+    Python,
+}
+
+impl SymbolLanguage {
+    /// Picks the grammar from the file extension.
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension {
+            "rs" => Some(Self::Rust),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "py" | "pyi" => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+        }
+    }
+
+    /// Kind label for `node`, when it declares a symbol.
+    fn symbol_kind(self, node: Node<'_>) -> Option<&'static str> {
+        let kind = match (self, node.kind()) {
+            (Self::Rust, "function_item" | "function_signature_item") => "fn",
+            (Self::Rust, "struct_item") => "struct",
+            (Self::Rust, "enum_item") => "enum",
+            (Self::Rust, "union_item") => "union",
+            (Self::Rust, "trait_item") => "trait",
+            (Self::Rust, "impl_item") => "impl",
+            (Self::Rust, "mod_item") => "mod",
+            (Self::Rust, "macro_definition") => "macro",
+            (Self::Rust, "const_item") => "const",
+            (Self::Rust, "static_item") => "static",
+            (Self::Rust, "type_item") => "type",
+            (Self::Python, "function_definition") => "def",
+            (Self::Python, "class_definition") => "class",
+            (
+                Self::TypeScript | Self::Tsx,
+                "function_declaration" | "generator_function_declaration",
+            ) => "function",
+            (Self::TypeScript | Self::Tsx, "class_declaration" | "abstract_class_declaration") => {
+                "class"
+            }
+            (Self::TypeScript | Self::Tsx, "interface_declaration") => "interface",
+            (Self::TypeScript | Self::Tsx, "type_alias_declaration") => "type",
+            (Self::TypeScript | Self::Tsx, "enum_declaration") => "enum",
+            (
+                Self::TypeScript | Self::Tsx,
+                "method_definition" | "method_signature" | "abstract_method_signature",
+            ) => "method",
+            (Self::TypeScript | Self::Tsx, "internal_module" | "module") => "namespace",
+            (Self::TypeScript | Self::Tsx, "lexical_declaration" | "variable_declaration") => {
+                let value = declared_function(node)?.child_by_field_name("value")?;
+                if value.kind() == "class" {
+                    "class"
+                } else {
+                    "function"
+                }
+            }
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// Whether symbols nested in `kind` are listed. Function bodies are not
+    /// descended into; their contents belong to the function.
+    fn is_container(self, kind: &str) -> bool {
+        matches!(
+            kind,
+            "impl" | "trait" | "mod" | "class" | "interface" | "namespace"
+        )
+    }
+
+    fn separator(self) -> &'static str {
+        match self {
+            Self::Rust => "::",
+            Self::TypeScript | Self::Tsx | Self::Python => ".",
+        }
+    }
+}
+
+/// A declaration found in a source file. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Symbol {
+    pub kind: &'static str,
+    pub name: String,
+    /// Names of the enclosing symbols followed by `name`.
+    pub path: Vec<String>,
+    /// The declaration up to its body, on one line.
+    pub signature: String,
+    /// First line of the doc comments, attributes or decorators attached to
+    /// the declaration; `start_line` when there are none.
+    pub header_line: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Nesting depth; top-level symbols are at depth 0.
+    pub depth: usize,
+}
+
+impl Symbol {
+    /// Name qualified with the enclosing symbols, e.g. `Config::load`.
+    pub(crate) fn qualified_name(&self, language: SymbolLanguage) -> String {
+        self.path.join(language.separator())
+    }
+}
+
+/// Lists the symbols declared in `source`, in source order.
+pub(crate) fn outline(source: &str, language: SymbolLanguage) -> Result<Vec<Symbol>, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .map_err(|err| format!("failed to load grammar: {err}"))?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| "failed to parse file".to_string())?;
+
+    let mut symbols = Vec::new();
+    collect(
+        tree.root_node(),
+        source,
+        language,
+        &mut Vec::new(),
+        &mut symbols,
+    );
+    Ok(symbols)
+}
+
+/// Symbols whose qualified name ends with `query`. The query may use either
+/// `::` or `.` between segments, e.g. `new`, `Config::new` or `Config.new`.
+pub(crate) fn find_by_name<'a>(symbols: &'a [Symbol], query: &str) -> Vec<&'a Symbol> {
+    let segments: Vec<&str> = query
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
+        return Vec::new();
+    }
+    symbols
+        .iter()
+        .filter(|symbol| {
+            symbol.path.len() >= segments.len()
+                && symbol.path[symbol.path.len() - segments.len()..]
+                    .iter()
+                    .zip(&segments)
+                    .all(|(name, segment)| name == segment)
+        })
+        .collect()
+}
+
+/// The innermost symbol whose range, including its header, contains `line`.
+pub(crate) fn enclosing(symbols: &[Symbol], line: usize) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .filter(|symbol| symbol.header_line <= line && line <= symbol.end_line)
+        .max_by_key(|symbol| symbol.depth)
+}
+
+fn collect(
+    node: Node<'_>,
+    source: &str,
+    language: SymbolLanguage,
+    parents: &mut Vec<String>,
+    out: &mut Vec<Symbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let Some(kind) = language.symbol_kind(child) else {
+            collect(child, source, language, parents, out);
+            continue;
+        };
+        let name = symbol_name(child, kind, source);
+        let mut path = parents.clone();
+        path.push(name.clone());
+        out.push(Symbol {
+            kind,
+            name: name.clone(),
+            path,
+            signature: signature(child, source),
+            header_line: header_row(child) + 1,
+            start_line: child.start_position().row + 1,
+            end_line: end_row(child) + 1,
+            depth: parents.len(),
+        });
+        if language.is_container(kind) {
+            parents.push(name);
+            collect(child, source, language, parents, out);
+            parents.pop();
+        }
+    }
+}
+
+fn symbol_name(node: Node<'_>, kind: &str, source: &str) -> String {
+    let text = |node: Node<'_>| source[node.byte_range()].to_string();
+    if kind == "impl" {
+        // Members of `impl Trait for Type` are addressed through `Type`.
+        return node
+            .child_by_field_name("type")
+            .map(|ty| base_type_name(&text(ty)))
+            .unwrap_or_default();
+    }
+    if let Some(declarator) = declared_function(node) {
+        return declarator
+            .child_by_field_name("name")
+            .map(text)
+            .unwrap_or_default();
+    }
+    node.child_by_field_name("name")
+        .map(text)
+        .unwrap_or_default()
+}
+
+/// `Foo<T>` and `crate::Foo` both name `Foo`.
+fn base_type_name(ty: &str) -> String {
+    let without_generics = ty.split('<').next().unwrap_or(ty);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+        .trim()
+        .to_string()
+}
+
+/// For `const f = () => ...`, the declarator binding a function or class.
+fn declared_function(node: Node<'_>) -> Option<Node<'_>> {
+    if !matches!(node.kind(), "lexical_declaration" | "variable_declaration") {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let declarators: Vec<Node<'_>> = node
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "variable_declarator")
+        .collect();
+    match declarators.as_slice() {
+        [declarator] => {
+            let value = declarator.child_by_field_name("value")?;
+            matches!(
+                value.kind(),
+                "arrow_function" | "function_expression" | "function" | "class"
+            )
+            .then_some(*declarator)
+        }
+        _ => None,
+    }
+}
+
+/// The declaration text before its body, collapsed onto one line.
+fn signature(node: Node<'_>, source: &str) -> String {
+    let end = match node.child_by_field_name("body") {
+        Some(body) => body.start_byte(),
+        None => {
+            let text = &source[node.byte_range()];
+            node.start_byte() + text.find('\n').unwrap_or(text.len())
+        }
+    };
+    let collapsed = source[node.start_byte()..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let trimmed = collapsed.trim_end_matches([':', '{', ' ']);
+    codex_utils_string::take_bytes_at_char_boundary(trimmed, MAX_SIGNATURE_LENGTH).to_string()
+}
+
+/// First row of `node` including decorators, export keywords and the doc
+/// comments or attributes directly above it.
+fn header_row(node: Node<'_>) -> usize {
+    let mut first = node;
+    while let Some(parent) = first.parent()
+        && matches!(parent.kind(), "decorated_definition" | "export_statement")
+    {
+        first = parent;
+    }
+
+    let mut row = first.start_position().row;
+    let mut previous = first.prev_named_sibling();
+    while let Some(sibling) = previous {
+        let attached = matches!(
+            sibling.kind(),
+            "line_comment" | "block_comment" | "comment" | "attribute_item" | "decorator"
+        ) && end_row(sibling) + 1 >= row;
+        if !attached {
+            break;
+        }
+        row = sibling.start_position().row;
+        previous = sibling.prev_named_sibling();
+    }
+    row
+}
+
+/// Last row holding text of `node`; nodes ending with a newline report the
+/// following row as their end.
+fn end_row(node: Node<'_>) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entries(symbols: &[Symbol], language: SymbolLanguage) -> Vec<(String, usize, usize)> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    format!("{} {}", symbol.kind, symbol.qualified_name(language)),
+                    symbol.header_line,
+                    symbol.end_line,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn outlines_rust_items_with_doc_comments() {
+        let source = r#"use std::fmt;
+
+/// A point.
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.x)
+    }
+}
+
+mod tests {
+    fn helper() {}
+}
+"#;
+        let symbols = outline(source, SymbolLanguage::Rust).expect("outline");
+        assert_eq!(
+            entries(&symbols, SymbolLanguage::Rust),
+            vec![
+                ("struct Point".to_string(), 3, 7),
+                ("impl Point".to_string(), 9, 13),
+                ("fn Point::fmt".to_string(), 10, 12),
+                ("mod tests".to_string(), 15, 17),
+                ("fn tests::helper".to_string(), 16, 16),
+            ]
+        );
+        assert_eq!(
+            symbols[2].signature,
+            "fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result"
+        );
+        assert_eq!(symbols[1].signature, "impl fmt::Display for Point");
+    }
+
+    #[test]
+    fn outlines_python_classes_and_decorated_functions() {
+        let source = "class Greeter:\n    @staticmethod\n    def hello(name):\n        return name\n\n\ndef main():\n    pass\n";
+        let symbols = outline(source, SymbolLanguage::Python).expect("outline");
+        assert_eq!(
+            entries(&symbols, SymbolLanguage::Python),
+            vec![
+                ("class Greeter".to_string(), 1, 4),
+                ("def Greeter.hello".to_string(), 2, 4),
+                ("def main".to_string(), 7, 8),
+            ]
+        );
+        assert_eq!(symbols[1].signature, "def hello(name)");
+    }
+
+    #[test]
+    fn outlines_typescript_exports_methods_and_arrow_functions() {
+        let source = "export class Store {\n  get(key: string): string {\n    return key;\n  }\n}\n\nconst load = async () => {\n  return 1;\n};\n\ninterface Options {\n  verbose: boolean;\n}\n";
+        let symbols = outline(source, SymbolLanguage::TypeScript).expect("outline");
+        assert_eq!(
+            entries(&symbols, SymbolLanguage::TypeScript),
+            vec![
+                ("class Store".to_string(), 1, 5),
+                ("method Store.get".to_string(), 2, 4),
+                ("function load".to_string(), 7, 9),
+                ("interface Options".to_string(), 11, 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_symbols_by_qualified_name_and_line() {
+        let source =
+            "struct A;\nimpl A {\n    fn new() -> Self {\n        A\n    }\n}\nfn new() {}\n";
+        let symbols = outline(source, SymbolLanguage::Rust).expect("outline");
+
+        let names = |found: Vec<&Symbol>| {
+            found
+                .into_iter()
+                .map(|symbol| symbol.qualified_name(SymbolLanguage::Rust))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(find_by_name(&symbols, "new")), vec!["A::new", "new"]);
+        assert_eq!(names(find_by_name(&symbols, "A.new")), vec!["A::new"]);
+        assert_eq!(
+            names(find_by_name(&symbols, "B::new")),
+            Vec::<String>::new()
+        );
+
+        assert_eq!(
+            enclosing(&symbols, 4).map(|symbol| symbol.qualified_name(SymbolLanguage::Rust)),
+            Some("A::new".to_string())
+        );
+        assert_eq!(
+            enclosing(&symbols, 2).map(|symbol| symbol.qualified_name(SymbolLanguage::Rust)),
+            Some("A".to_string())
+        );
+    }
+}
@@ -4,12 +4,14 @@ use crate::protocol::WarningEvent;
 use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::undo::current_snapshot_turn;
 use async_trait::async_trait;
 use codex_git::CreateGhostCommitOptions;
 use codex_git::GhostSnapshotReport;
 use codex_git::GitToolingError;
 use codex_git::capture_ghost_snapshot_report;
 use codex_git::create_ghost_commit;
+use codex_git::record_ghost_ref;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
 use codex_utils_readiness::Readiness;
@@ -72,6 +74,9 @@ impl SessionTask for GhostSnapshotTask {
             let cancelled = tokio::select! {
                 _ = cancellation_token.cancelled() => true,
                 _ = async {
+                    // Let the previous turn's end snapshot finish first so it
+                    // cannot pick up this turn's edits.
+                    session.session.wait_for_turn_end_snapshot().await;
                     let repo_path = ctx_for_task.cwd.clone();
                     // First, compute a snapshot report so we can warn about
                     // large untracked directories before running the heavier
@@ -95,18 +100,30 @@ impl SessionTask for GhostSnapshotTask {
                             }
 
                     // Required to run in a dedicated blocking pool.
+                    let conversation_id = session.session.conversation_id().to_string();
                     match tokio::task::spawn_blocking(move || {
                         let options = CreateGhostCommitOptions::new(&repo_path);
-                        create_ghost_commit(&options)
+                        let ghost_commit = create_ghost_commit(&options)?;
+                        // Keep the snapshot reachable so `git gc` does not
+                        // break undo; losing the ref is not fatal.
+                        if let Err(err) =
+                            record_ghost_ref(&repo_path, &conversation_id, &ghost_commit)
+                        {
+                            warn!("failed to record ghost snapshot ref: {err}");
+                        }
+                        Ok::<_, GitToolingError>(ghost_commit)
                     })
                     .await
                     {
                         Ok(Ok(ghost_commit)) => {
                             info!("ghost snapshot blocking task finished");
+                            let history = session.session.clone_history().await.get_history();
+                            let turn = current_snapshot_turn(&history);
                             session
                                 .session
                                 .record_conversation_items(&ctx, &[ResponseItem::GhostSnapshot {
                                     ghost_commit: ghost_commit.clone(),
+                                    turn: Some(turn),
                                 }])
                                 .await;
                             info!("ghost commit captured: {}", ghost_commit.id());
@@ -7,6 +7,7 @@ mod user_shell;
 
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use tokio::select;
@@ -33,7 +34,11 @@ pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
+pub(crate) use undo::RedoEntry;
+pub(crate) use undo::RedoTask;
+pub(crate) use undo::UndoTarget;
 pub(crate) use undo::UndoTask;
+pub(crate) use undo::list_undo_snapshots;
 pub(crate) use user_shell::UserShellCommandTask;
 
 const GRACEFULL_INTERRUPTION_TIMEOUT_MS: u64 = 100;
@@ -125,6 +130,7 @@ impl Session {
             let task_cancellation_token = cancellation_token.child_token();
             tokio::spawn(async move {
                 let ctx_for_finish = Arc::clone(&ctx);
+                let started_at = Instant::now();
                 let last_agent_message = task_for_run
                     .run(
                         Arc::clone(&session_ctx),
@@ -135,6 +141,10 @@ impl Session {
                     .await;
                 session_ctx.clone_session().flush_rollout().await;
                 if !task_cancellation_token.is_cancelled() {
+                    ctx_for_finish
+                        .client
+                        .get_otel_manager()
+                        .record_turn_duration(started_at.elapsed());
                     // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
                     let sess = session_ctx.clone_session();
                     sess.on_task_finished(ctx_for_finish, last_agent_message)
@@ -174,6 +184,8 @@ impl Session {
         }
         drop(active);
 
+        self.record_turn_cost(turn_context.as_ref()).await;
+
         // Notify the UI first so turn completion is never delayed by best-effort work
         // like git snapshotting.
         let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
@@ -231,6 +243,8 @@ impl Session {
             .abort(session_ctx, Arc::clone(&task.turn_context))
             .await;
 
+        self.record_turn_cost(task.turn_context.as_ref()).await;
+
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
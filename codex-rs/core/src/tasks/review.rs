@@ -222,10 +222,18 @@ pub(crate) async fn exit_review_mode(
             }],
         )
         .await;
+    if let Some(output) = &review_output {
+        session
+            .record_review_findings(ctx.sub_id.clone(), output.findings.clone())
+            .await;
+    }
     session
         .send_event(
             ctx.as_ref(),
-            EventMsg::ExitedReviewMode(ExitedReviewModeEvent { review_output }),
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output,
+                review_id: Some(ctx.sub_id.clone()),
+            }),
         )
         .await;
     session
@@ -1,14 +1,30 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact::is_summary_message;
+use crate::parse_turn_item;
 use crate::protocol::EventMsg;
+use crate::protocol::HistoryRewrittenItem;
+use crate::protocol::RolloutItem;
 use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoSnapshot;
 use crate::protocol::UndoStartedEvent;
 use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
-use codex_git::restore_ghost_commit;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostCommit;
+use codex_git::GitToolingError;
+use codex_git::create_ghost_commit;
+use codex_git::ghost_commit_exists;
+use codex_git::revert_ghost_changes;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::GhostSnapshotTurn;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
 use tokio_util::sync::CancellationToken;
@@ -16,11 +32,116 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-pub(crate) struct UndoTask;
+/// Which ghost snapshot an undo restores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum UndoTarget {
+    /// The snapshot taken at the start of the most recent turn.
+    Latest,
+    /// A specific snapshot, by ghost commit id.
+    Snapshot(String),
+    /// The snapshot of the given number of most recent turns.
+    Turns(u32),
+}
+
+/// A ghost snapshot found in the conversation history.
+pub(crate) struct UndoSnapshotEntry {
+    /// Position of the `GhostSnapshot` item in the history.
+    pub(crate) index: usize,
+    pub(crate) ghost_commit: GhostCommit,
+    pub(crate) snapshot: UndoSnapshot,
+}
+
+/// What `RedoTask` needs to reapply an undo.
+pub(crate) struct RedoEntry {
+    /// Snapshot of the workspace taken just before the undo.
+    pub(crate) before: GhostCommit,
+    /// Snapshot of the workspace as the undo left it.
+    pub(crate) after: GhostCommit,
+    /// History before the undo, restored on redo.
+    pub(crate) history_before: Vec<ResponseItem>,
+    /// History as the undo left it; the undo can only be redone while the
+    /// history is unchanged.
+    pub(crate) history_after: Vec<ResponseItem>,
+}
+
+/// Result of reversing the undone turns in the workspace.
+struct UndoOutcome {
+    before: GhostCommit,
+    after: GhostCommit,
+    conflicts: Vec<PathBuf>,
+}
+
+/// Why the undone turns could not be reversed.
+enum UndoError {
+    /// The ghost commit with this id is no longer in the object database.
+    MissingSnapshot(String),
+    Git(GitToolingError),
+}
+
+impl From<GitToolingError> for UndoError {
+    fn from(err: GitToolingError) -> Self {
+        Self::Git(err)
+    }
+}
+
+/// Lists the ghost snapshots in `items`, oldest first, each labelled with the
+/// turn (and user prompt) it was taken for. Snapshots carry their turn; ones
+/// from older rollouts are recorded right after the user message that starts
+/// their turn, so that message labels them.
+pub(crate) fn list_undo_snapshots(items: &[ResponseItem]) -> Vec<UndoSnapshotEntry> {
+    label_snapshots(items).0
+}
+
+/// The turn a snapshot taken now belongs to: the one started by the latest
+/// user message in `items`.
+pub(crate) fn current_snapshot_turn(items: &[ResponseItem]) -> GhostSnapshotTurn {
+    label_snapshots(items).1
+}
+
+/// Walks `items` counting turns. A snapshot's recorded turn resets the count,
+/// so the user messages that compaction keeps ahead of the snapshots, and the
+/// summary, do not shift the turns that follow.
+fn label_snapshots(items: &[ResponseItem]) -> (Vec<UndoSnapshotEntry>, GhostSnapshotTurn) {
+    let mut current = GhostSnapshotTurn {
+        number: 0,
+        prompt: None,
+    };
+    let mut entries = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        if let ResponseItem::GhostSnapshot { ghost_commit, turn } = item {
+            if let Some(turn) = turn {
+                current = turn.clone();
+            }
+            entries.push(UndoSnapshotEntry {
+                index,
+                ghost_commit: ghost_commit.clone(),
+                snapshot: UndoSnapshot {
+                    id: ghost_commit.id().to_string(),
+                    turn: current.number,
+                    prompt: current.prompt.clone(),
+                },
+            });
+        } else if let Some(TurnItem::UserMessage(message)) = parse_turn_item(item) {
+            let text = message.message();
+            if is_summary_message(&text) {
+                continue;
+            }
+            current = GhostSnapshotTurn {
+                number: current.number + 1,
+                prompt: (!text.trim().is_empty()).then_some(text),
+            };
+        }
+    }
+    (entries, current)
+}
+
+pub(crate) struct UndoTask {
+    target: UndoTarget,
+}
 
 impl UndoTask {
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn new(target: UndoTarget) -> Self {
+        Self { target }
     }
 }
 
@@ -52,61 +173,287 @@ impl SessionTask for UndoTask {
                 EventMsg::UndoCompleted(UndoCompletedEvent {
                     success: false,
                     message: Some("Undo cancelled.".to_string()),
+                    conflicts: Vec::new(),
                 }),
             )
             .await;
             return None;
         }
 
-        let mut history = sess.clone_history().await;
-        let mut items = history.get_history();
+        let items = sess.clone_history().await.get_history();
         let mut completed = UndoCompletedEvent {
             success: false,
             message: None,
+            conflicts: Vec::new(),
         };
 
-        let Some((idx, ghost_commit)) =
-            items
+        let snapshots = list_undo_snapshots(&items);
+        let position = match &self.target {
+            UndoTarget::Latest => snapshots.len().checked_sub(1),
+            UndoTarget::Snapshot(id) => snapshots
                 .iter()
-                .enumerate()
-                .rev()
-                .find_map(|(idx, item)| match item {
-                    ResponseItem::GhostSnapshot { ghost_commit } => {
-                        Some((idx, ghost_commit.clone()))
-                    }
-                    _ => None,
+                .position(|entry| entry.ghost_commit.id() == id),
+            UndoTarget::Turns(0) => None,
+            UndoTarget::Turns(turns) => snapshots.len().checked_sub(*turns as usize),
+        };
+        let Some(position) = position else {
+            completed.message = Some(match &self.target {
+                UndoTarget::Latest => "No ghost snapshot available to undo.".to_string(),
+                UndoTarget::Snapshot(id) => format!("No ghost snapshot {id} available to undo."),
+                UndoTarget::Turns(0) => "Nothing to undo: turns must be at least 1.".to_string(),
+                UndoTarget::Turns(turns) => format!(
+                    "Cannot undo {turns} turns: only {} have a ghost snapshot.",
+                    snapshots.len()
+                ),
+            });
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+        let target = &snapshots[position];
+        let undone_turns = snapshots.len() - position;
+
+        let commit_id = target.ghost_commit.id().to_string();
+        let starts: Vec<GhostCommit> = snapshots[position..]
+            .iter()
+            .map(|entry| entry.ghost_commit.clone())
+            .collect();
+        sess.wait_for_turn_end_snapshot().await;
+        let turn_ends = sess.turn_end_snapshots().await;
+        let repo_path = ctx.cwd.clone();
+        let undo_result =
+            tokio::task::spawn_blocking(move || undo_turns(&repo_path, &starts, &turn_ends)).await;
+
+        match undo_result {
+            Ok(Ok(outcome)) => {
+                let history_after = history_without_turns(&items, target.index);
+                sess.replace_history(history_after.clone()).await;
+                persist_history(&sess, &history_after).await;
+                sess.push_redo(RedoEntry {
+                    before: outcome.before,
+                    after: outcome.after,
+                    history_before: items.clone(),
+                    history_after,
                 })
-        else {
-            completed.message = Some("No ghost snapshot available to undo.".to_string());
+                .await;
+                let short_id: String = commit_id.chars().take(7).collect();
+                info!(commit_id = commit_id, "Undo restored ghost snapshot");
+                let mut message = if undone_turns == 1 {
+                    format!("Undo restored snapshot {short_id}.")
+                } else {
+                    format!(
+                        "Undo restored snapshot {short_id} from turn {}, undoing {undone_turns} turns.",
+                        target.snapshot.turn
+                    )
+                };
+                message.push_str(&conflicts_note(&outcome.conflicts));
+                completed.success = true;
+                completed.message = Some(message);
+                completed.conflicts = outcome.conflicts;
+            }
+            Ok(Err(UndoError::MissingSnapshot(missing_id))) => {
+                let short_id: String = missing_id.chars().take(7).collect();
+                let message = format!(
+                    "Snapshot {short_id} is missing from the repository, so undo cannot restore it. It was probably removed by `git gc` or `codex ghost prune`."
+                );
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Ok(Err(UndoError::Git(err))) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                error!("{message}");
+                completed.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+/// `items` without the turns undone by restoring the snapshot at
+/// `target_index`: everything from the user message that started the
+/// target's turn onwards is dropped. Earlier snapshots are kept even when they
+/// sit after that message, as compaction moves them to the end of the history.
+/// A snapshot that compaction moved has no turn left to cut, since the
+/// compacted history stands for it, so only the snapshot and what follows it
+/// are dropped.
+fn history_without_turns(items: &[ResponseItem], target_index: usize) -> Vec<ResponseItem> {
+    let turn_start = items[..target_index]
+        .iter()
+        .rposition(|item| is_compaction_boundary(item) || is_user_turn_start(item))
+        .filter(|&idx| !is_compaction_boundary(&items[idx]))
+        .unwrap_or(target_index);
+    items
+        .iter()
+        .enumerate()
+        .filter(|(idx, item)| {
+            *idx < turn_start
+                || (*idx < target_index && matches!(item, ResponseItem::GhostSnapshot { .. }))
+        })
+        .map(|(_, item)| item.clone())
+        .collect()
+}
+
+fn is_user_turn_start(item: &ResponseItem) -> bool {
+    matches!(parse_turn_item(item), Some(TurnItem::UserMessage(_)))
+}
+
+/// The summary of a local compaction or the item ending a remote one.
+fn is_compaction_boundary(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Compaction { .. } => true,
+        _ => match parse_turn_item(item) {
+            Some(TurnItem::UserMessage(message)) => is_summary_message(&message.message()),
+            _ => false,
+        },
+    }
+}
+
+/// Records the rewritten history in the rollout so a resumed session picks up
+/// the undo (or redo) instead of replaying the original turns.
+async fn persist_history(sess: &Session, history: &[ResponseItem]) {
+    sess.persist_rollout_items(&[RolloutItem::HistoryRewritten(HistoryRewrittenItem {
+        replacement_history: history.to_vec(),
+    })])
+    .await;
+}
+
+/// Reverses the agent's changes from each turn started by `starts`, newest
+/// first, keeping edits made outside the session. A turn's changes are the
+/// difference between its start snapshot and the workspace as it left it;
+/// when the end was not captured (e.g. the turn was interrupted), the next
+/// turn's start or, for the last turn, the current workspace stands in. The
+/// same applies to an end snapshot that is no longer in the repository,
+/// whereas a missing start snapshot aborts the undo before anything changes.
+fn undo_turns(
+    repo_path: &Path,
+    starts: &[GhostCommit],
+    turn_ends: &HashMap<String, GhostCommit>,
+) -> Result<UndoOutcome, UndoError> {
+    for start in starts {
+        if !ghost_commit_exists(repo_path, start.id())? {
+            return Err(UndoError::MissingSnapshot(start.id().to_string()));
+        }
+    }
+
+    let before = create_ghost_commit(&CreateGhostCommitOptions::new(repo_path))?;
+    let mut conflicts = Vec::new();
+    for (idx, start) in starts.iter().enumerate().rev() {
+        let end = match turn_ends.get(start.id()) {
+            Some(end) if ghost_commit_exists(repo_path, end.id())? => end,
+            _ => starts.get(idx + 1).unwrap_or(&before),
+        };
+        let report = revert_ghost_changes(repo_path, start, end)?;
+        conflicts.extend(report.conflicted);
+    }
+    conflicts.sort();
+    conflicts.dedup();
+    let after = create_ghost_commit(&CreateGhostCommitOptions::new(repo_path))?;
+    Ok(UndoOutcome {
+        before,
+        after,
+        conflicts,
+    })
+}
+
+/// Sentence appended to the undo/redo message when files were left alone.
+fn conflicts_note(conflicts: &[PathBuf]) -> String {
+    if conflicts.is_empty() {
+        return String::new();
+    }
+    let paths = conflicts
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" Kept edits made outside the session in: {paths}.")
+}
+
+/// Reapplies the most recent undo. Progress is reported with the undo events.
+pub(crate) struct RedoTask;
+
+#[async_trait]
+impl SessionTask for RedoTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some("Redo in progress...".to_string()),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+            conflicts: Vec::new(),
+        };
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("Redo cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let items = sess.clone_history().await.get_history();
+        let Some(entry) = sess.pop_redo(&items).await else {
+            completed.message = Some("Nothing to redo.".to_string());
             sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
                 .await;
             return None;
         };
 
-        let commit_id = ghost_commit.id().to_string();
+        let commit_id = entry.before.id().to_string();
+        let before = entry.before.clone();
+        let after = entry.after.clone();
         let repo_path = ctx.cwd.clone();
-        let restore_result =
-            tokio::task::spawn_blocking(move || restore_ghost_commit(&repo_path, &ghost_commit))
+        let redo_result =
+            tokio::task::spawn_blocking(move || revert_ghost_changes(&repo_path, &before, &after))
                 .await;
 
-        match restore_result {
-            Ok(Ok(())) => {
-                items.remove(idx);
-                sess.replace_history(items).await;
+        match redo_result {
+            Ok(Ok(report)) => {
+                persist_history(&sess, &entry.history_before).await;
+                sess.replace_history(entry.history_before).await;
                 let short_id: String = commit_id.chars().take(7).collect();
-                info!(commit_id = commit_id, "Undo restored ghost snapshot");
+                info!(commit_id = commit_id, "Redo restored ghost snapshot");
                 completed.success = true;
-                completed.message = Some(format!("Undo restored snapshot {short_id}."));
+                completed.message = Some(format!(
+                    "Redo restored snapshot {short_id}.{}",
+                    conflicts_note(&report.conflicted)
+                ));
+                completed.conflicts = report.conflicted;
             }
             Ok(Err(err)) => {
                 let message = format!("Failed to restore snapshot {commit_id}: {err}");
                 warn!("{message}");
                 completed.message = Some(message);
+                // Keep the entry so the redo can be retried.
+                sess.push_redo(entry).await;
             }
             Err(err) => {
                 let message = format!("Failed to restore snapshot {commit_id}: {err}");
                 error!("{message}");
                 completed.message = Some(message);
+                sess.push_redo(entry).await;
             }
         }
 
@@ -115,3 +462,165 @@ impl SessionTask for UndoTask {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compact::SUMMARY_PREFIX;
+    use crate::compact::build_compacted_history_with_snapshots;
+    use crate::tokenizer::Tokenizer;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn ghost_snapshot(id: &str) -> ResponseItem {
+        ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+            turn: None,
+        }
+    }
+
+    fn labelled_snapshot(id: &str, number: u32, prompt: &str) -> ResponseItem {
+        ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+            turn: Some(GhostSnapshotTurn {
+                number,
+                prompt: Some(prompt.to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn list_undo_snapshots_labels_turns_and_prompts() {
+        let items = vec![
+            message(
+                "user",
+                "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>",
+            ),
+            message("user", "add a readme"),
+            ghost_snapshot("first"),
+            message("assistant", "done"),
+            message("user", "fix the build"),
+            ghost_snapshot("second"),
+        ];
+
+        let listed: Vec<(usize, UndoSnapshot)> = list_undo_snapshots(&items)
+            .into_iter()
+            .map(|entry| (entry.index, entry.snapshot))
+            .collect();
+
+        assert_eq!(
+            listed,
+            vec![
+                (
+                    2,
+                    UndoSnapshot {
+                        id: "first".to_string(),
+                        turn: 1,
+                        prompt: Some("add a readme".to_string()),
+                    },
+                ),
+                (
+                    5,
+                    UndoSnapshot {
+                        id: "second".to_string(),
+                        turn: 2,
+                        prompt: Some("fix the build".to_string()),
+                    },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn history_without_turns_drops_undone_turns() {
+        let items = vec![
+            message("user", "add a readme"),
+            ghost_snapshot("first"),
+            message("assistant", "done"),
+            message("user", "fix the build"),
+            ghost_snapshot("second"),
+            message("assistant", "fixed"),
+        ];
+
+        assert_eq!(
+            history_without_turns(&items, 4),
+            vec![
+                message("user", "add a readme"),
+                ghost_snapshot("first"),
+                message("assistant", "done"),
+            ]
+        );
+        assert_eq!(history_without_turns(&items, 1), Vec::new());
+    }
+
+    #[test]
+    fn snapshots_keep_their_turns_across_compaction() {
+        let mut items = vec![message("user", "add a readme")];
+        items.push(labelled_snapshot(
+            "first",
+            current_snapshot_turn(&items).number,
+            "add a readme",
+        ));
+        items.push(message("assistant", "done"));
+        items.push(message("user", "fix the build"));
+        items.push(labelled_snapshot(
+            "second",
+            current_snapshot_turn(&items).number,
+            "fix the build",
+        ));
+        items.push(message("assistant", "fixed"));
+
+        let summary_text = format!("{SUMMARY_PREFIX}\nadded a readme and fixed the build");
+        let mut items = build_compacted_history_with_snapshots(
+            Vec::new(),
+            &items,
+            &summary_text,
+            Tokenizer::Heuristic,
+        );
+        let compacted_len = items.len();
+        items.push(message("user", "add tests"));
+        assert_eq!(
+            current_snapshot_turn(&items),
+            GhostSnapshotTurn {
+                number: 3,
+                prompt: Some("add tests".to_string()),
+            }
+        );
+        items.push(labelled_snapshot("third", 3, "add tests"));
+
+        let listed: Vec<(u32, Option<String>)> = list_undo_snapshots(&items)
+            .into_iter()
+            .map(|entry| (entry.snapshot.turn, entry.snapshot.prompt))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (1, Some("add a readme".to_string())),
+                (2, Some("fix the build".to_string())),
+                (3, Some("add tests".to_string())),
+            ]
+        );
+
+        // Undoing the latest turn drops just that turn.
+        assert_eq!(
+            history_without_turns(&items, compacted_len + 1),
+            items[..compacted_len].to_vec()
+        );
+        // A turn folded into the summary keeps the summary and the snapshots
+        // before its own.
+        assert_eq!(
+            history_without_turns(&items, compacted_len - 1),
+            items[..compacted_len - 1].to_vec()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Token counting for context estimates, compaction carry-over and
+//! token-based truncation.
+//!
+//! Model families pick a [`Tokenizer`]; families whose vocabulary is known use
+//! the bundled BPE tables, everything else falls back to a bytes-per-token
+//! heuristic.
+
+use tiktoken_rs::CoreBPE;
+
+use crate::truncate::APPROX_BYTES_PER_TOKEN;
+use crate::truncate::approx_token_count;
+
+/// Generous bound on the bytes a single BPE token covers. Only this many bytes
+/// per requested token are encoded when looking for a prefix or suffix, so
+/// truncating a huge output does not tokenize all of it twice.
+pub(crate) const MAX_BYTES_PER_TOKEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Tokenizer {
+    /// `o200k_base`, used by GPT-4o, GPT-4.1, the o-series, GPT-5 and
+    /// gpt-oss models.
+    O200kBase,
+    /// `cl100k_base`, used by GPT-3.5 and GPT-4.
+    Cl100kBase,
+    /// Roughly four bytes per token, for models with an unknown vocabulary.
+    #[default]
+    Heuristic,
+}
+
+impl Tokenizer {
+    /// Number of tokens `text` encodes to.
+    pub fn count(self, text: &str) -> usize {
+        match self.bpe() {
+            Some(bpe) => bpe.encode_ordinary(text).len(),
+            None => approx_token_count(text),
+        }
+    }
+
+    /// Byte offset ending the longest prefix of `text` that fits in
+    /// `max_tokens` tokens, on a char boundary.
+    pub(crate) fn prefix_end(self, text: &str, max_tokens: usize) -> usize {
+        let Some(bpe) = self.bpe() else {
+            return floor_char_boundary(text, max_tokens.saturating_mul(APPROX_BYTES_PER_TOKEN));
+        };
+        let window_end = floor_char_boundary(text, max_tokens.saturating_mul(MAX_BYTES_PER_TOKEN));
+        let tokens = bpe.encode_ordinary(&text[..window_end]);
+        let kept = &tokens[..tokens.len().min(max_tokens)];
+        floor_char_boundary(text, decoded_len(bpe, kept))
+    }
+
+    /// Byte offset starting the longest suffix of `text` that fits in
+    /// `max_tokens` tokens, on a char boundary.
+    pub(crate) fn suffix_start(self, text: &str, max_tokens: usize) -> usize {
+        let len = text.len();
+        let Some(bpe) = self.bpe() else {
+            return ceil_char_boundary(
+                text,
+                len.saturating_sub(max_tokens.saturating_mul(APPROX_BYTES_PER_TOKEN)),
+            );
+        };
+        let window_start = ceil_char_boundary(
+            text,
+            len.saturating_sub(max_tokens.saturating_mul(MAX_BYTES_PER_TOKEN)),
+        );
+        let tokens = bpe.encode_ordinary(&text[window_start..]);
+        let kept = &tokens[tokens.len().saturating_sub(max_tokens)..];
+        ceil_char_boundary(text, len.saturating_sub(decoded_len(bpe, kept)))
+    }
+
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        match self {
+            Tokenizer::O200kBase => Some(tiktoken_rs::o200k_base_singleton()),
+            Tokenizer::Cl100kBase => Some(tiktoken_rs::cl100k_base_singleton()),
+            Tokenizer::Heuristic => None,
+        }
+    }
+}
+
+/// Number of bytes `tokens` decode to. The tokens were just produced by the
+/// same encoder, so decoding cannot fail; an error keeps nothing.
+fn decoded_len(bpe: &CoreBPE, tokens: &[u32]) -> usize {
+    bpe.decode_bytes(tokens).map_or(0, |bytes| bytes.len())
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bpe_counts_differ_from_the_heuristic() {
+        let prose = "The quick brown fox jumps over the lazy dog.";
+        assert_eq!(Tokenizer::O200kBase.count(prose), 10);
+        assert_eq!(Tokenizer::Cl100kBase.count(prose), 10);
+        assert_eq!(Tokenizer::Heuristic.count(prose), 11);
+
+        // Dense text is where the heuristic is furthest off.
+        let cjk = "上下文窗口的令牌计数";
+        assert!(Tokenizer::O200kBase.count(cjk) > Tokenizer::Heuristic.count(cjk) / 2);
+        assert_eq!(Tokenizer::O200kBase.count(""), 0);
+    }
+
+    #[test]
+    fn prefix_and_suffix_respect_token_budgets() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let tokenizer = Tokenizer::O200kBase;
+
+        let prefix_end = tokenizer.prefix_end(text, 3);
+        assert_eq!(&text[..prefix_end], "alpha beta gamma");
+        let suffix_start = tokenizer.suffix_start(text, 2);
+        assert_eq!(&text[suffix_start..], " eta theta");
+
+        assert_eq!(tokenizer.prefix_end(text, 1_000), text.len());
+        assert_eq!(tokenizer.suffix_start(text, 1_000), 0);
+        assert_eq!(tokenizer.prefix_end(text, 0), 0);
+        assert_eq!(tokenizer.suffix_start(text, 0), text.len());
+    }
+
+    #[test]
+    fn prefix_and_suffix_land_on_char_boundaries() {
+        let text = "😀😀😀😀😀😀";
+        for tokenizer in [Tokenizer::O200kBase, Tokenizer::Heuristic] {
+            for budget in 0..8 {
+                assert!(text.is_char_boundary(tokenizer.prefix_end(text, budget)));
+                assert!(text.is_char_boundary(tokenizer.suffix_start(text, budget)));
+            }
+        }
+    }
+}
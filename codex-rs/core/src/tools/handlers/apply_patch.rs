@@ -79,7 +79,8 @@ impl ToolHandler for ApplyPatchHandler {
         // Avoid building temporary ExecParams/command vectors; derive directly from inputs.
         let cwd = turn.cwd.clone();
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
-        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        let fuzz_threshold = turn.client.config().apply_patch_fuzz_threshold;
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd, fuzz_threshold) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
                     .await
@@ -111,6 +112,7 @@ impl ToolHandler for ApplyPatchHandler {
                             timeout_ms: None,
                             user_explicitly_approved: apply.user_explicitly_approved_this_action,
                             codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                            fuzz_threshold,
                         };
 
                         let mut orchestrator = ToolOrchestrator::new();
@@ -170,7 +172,8 @@ pub(crate) async fn intercept_apply_patch(
     call_id: &str,
     tool_name: &str,
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
-    match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd) {
+    let fuzz_threshold = turn.client.config().apply_patch_fuzz_threshold;
+    match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd, fuzz_threshold) {
         codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
             session
                 .record_model_warning(
@@ -202,6 +205,7 @@ pub(crate) async fn intercept_apply_patch(
                         timeout_ms,
                         user_explicitly_approved: apply.user_explicitly_approved_this_action,
                         codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                        fuzz_threshold,
                     };
 
                     let mut orchestrator = ToolOrchestrator::new();
@@ -274,13 +278,16 @@ Your patch language is a stripped‑down, file‑oriented diff format designed t
 
 Within that envelope, you get a sequence of file operations.
 You MUST include a header to specify the action you are taking.
-Each operation starts with one of three headers:
+Each operation starts with one of these headers:
 
 *** Add File: <path> - create a new file. Every following line is a + line (the initial contents).
+*** Add Binary File: <path> - create a binary file. Every following line is a + line of base64 data.
+*** Add Symlink: <path> -> <target> - create a symbolic link. Nothing follows.
 *** Delete File: <path> - remove an existing file. Nothing follows.
 *** Update File: <path> - patch an existing file in place (optionally with a rename).
 
 May be immediately followed by *** Move to: <new path> if you want to rename the file.
+An Add File, Add Binary File or Update File header (after any Move to) may be followed by *** Set Mode: <octal> (e.g. 755) to set the file's permission bits (at most 777); an Update File that only sets a mode needs no hunks.
 Then one or more “hunks”, each introduced by @@ (optionally followed by a hunk header).
 Within a hunk each line starts with:
 
@@ -306,11 +313,14 @@ The full grammar definition is below:
 Patch := Begin { FileOp } End
 Begin := "*** Begin Patch" NEWLINE
 End := "*** End Patch" NEWLINE
-FileOp := AddFile | DeleteFile | UpdateFile
-AddFile := "*** Add File: " path NEWLINE { "+" line NEWLINE }
+FileOp := AddFile | AddBinaryFile | AddSymlink | DeleteFile | UpdateFile
+AddFile := "*** Add File: " path NEWLINE [ SetMode ] { "+" line NEWLINE }
+AddBinaryFile := "*** Add Binary File: " path NEWLINE [ SetMode ] { "+" base64 NEWLINE }
+AddSymlink := "*** Add Symlink: " path " -> " target NEWLINE
 DeleteFile := "*** Delete File: " path NEWLINE
-UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] { Hunk }
+UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] [ SetMode ] { Hunk }
 MoveTo := "*** Move to: " newPath NEWLINE
+SetMode := "*** Set Mode: " octalMode NEWLINE
 Hunk := "@@" [ header ] NEWLINE { HunkLine } [ "*** End of File" NEWLINE ]
 HunkLine := (" " | "-" | "+") text NEWLINE
 
@@ -7,6 +7,7 @@ use tokio::process::Command;
 use tokio::time::timeout;
 
 use crate::function_tool::FunctionCallError;
+use crate::read_deny::ReadDenyMatcher;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -76,6 +77,14 @@ impl ToolHandler for GrepFilesHandler {
 
         verify_path_exists(&search_path).await?;
 
+        let read_deny = turn.read_deny_matcher();
+        if read_deny.is_denied(&search_path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to `{}` is blocked by the sandbox read_deny list",
+                search_path.display()
+            )));
+        }
+
         let include = args.include.as_deref().map(str::trim).and_then(|val| {
             if val.is_empty() {
                 None
@@ -84,8 +93,15 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        let search_results = run_rg_search(
+            pattern,
+            include.as_deref(),
+            &search_path,
+            limit,
+            &turn.cwd,
+            &read_deny,
+        )
+        .await?;
 
         if search_results.is_empty() {
             Ok(ToolOutput::Function {
@@ -116,6 +132,7 @@ async fn run_rg_search(
     search_path: &Path,
     limit: usize,
     cwd: &Path,
+    read_deny: &ReadDenyMatcher,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut command = Command::new("rg");
     command
@@ -144,7 +161,7 @@ async fn run_rg_search(
         })?;
 
     match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
+        Some(0) => Ok(parse_results(&output.stdout, limit, read_deny)),
         Some(1) => Ok(Vec::new()),
         _ => {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -155,14 +172,16 @@ async fn run_rg_search(
     }
 }
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
+/// Collects up to `limit` paths from rg output, dropping any that the
+/// `read_deny` list covers.
+fn parse_results(stdout: &[u8], limit: usize, read_deny: &ReadDenyMatcher) -> Vec<String> {
     let mut results = Vec::new();
     for line in stdout.split(|byte| *byte == b'\n') {
         if line.is_empty() {
             continue;
         }
         if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
+            if text.is_empty() || read_deny.is_denied(Path::new(text)) {
                 continue;
             }
             results.push(text.to_string());
@@ -183,7 +202,7 @@ mod tests {
     #[test]
     fn parses_basic_results() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
+        let parsed = parse_results(stdout, 10, &ReadDenyMatcher::default());
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -193,7 +212,7 @@ mod tests {
     #[test]
     fn parse_truncates_after_limit() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
+        let parsed = parse_results(stdout, 2, &ReadDenyMatcher::default());
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -211,7 +230,8 @@ mod tests {
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
         std::fs::write(dir.join("other.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
+        let results =
+            run_rg_search("alpha", None, dir, 10, dir, &ReadDenyMatcher::default()).await?;
         assert_eq!(results.len(), 2);
         assert!(results.iter().any(|path| path.ends_with("match_one.txt")));
         assert!(results.iter().any(|path| path.ends_with("match_two.txt")));
@@ -228,7 +248,15 @@ mod tests {
         std::fs::write(dir.join("match_one.rs"), "alpha beta gamma").unwrap();
         std::fs::write(dir.join("match_two.txt"), "alpha delta").unwrap();
 
-        let results = run_rg_search("alpha", Some("*.rs"), dir, 10, dir).await?;
+        let results = run_rg_search(
+            "alpha",
+            Some("*.rs"),
+            dir,
+            10,
+            dir,
+            &ReadDenyMatcher::default(),
+        )
+        .await?;
         assert_eq!(results.len(), 1);
         assert!(results.iter().all(|path| path.ends_with("match_one.rs")));
         Ok(())
@@ -245,11 +273,30 @@ mod tests {
         std::fs::write(dir.join("two.txt"), "alpha two").unwrap();
         std::fs::write(dir.join("three.txt"), "alpha three").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 2, dir).await?;
+        let results =
+            run_rg_search("alpha", None, dir, 2, dir, &ReadDenyMatcher::default()).await?;
         assert_eq!(results.len(), 2);
         Ok(())
     }
 
+    #[tokio::test]
+    async fn run_search_skips_read_denied_files() -> anyhow::Result<()> {
+        if !rg_available() {
+            return Ok(());
+        }
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::create_dir(dir.join("credentials")).unwrap();
+        std::fs::write(dir.join("credentials/token.txt"), "alpha secret").unwrap();
+        std::fs::write(dir.join("visible.txt"), "alpha visible").unwrap();
+
+        let read_deny = ReadDenyMatcher::new(&["credentials".to_string()], dir);
+        let results = run_rg_search("alpha", None, dir, 10, dir, &read_deny).await?;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ends_with("visible.txt"));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn run_search_handles_no_matches() -> anyhow::Result<()> {
         if !rg_available() {
@@ -259,7 +306,8 @@ mod tests {
         let dir = temp.path();
         std::fs::write(dir.join("one.txt"), "omega").unwrap();
 
-        let results = run_rg_search("alpha", None, dir, 5, dir).await?;
+        let results =
+            run_rg_search("alpha", None, dir, 5, dir, &ReadDenyMatcher::default()).await?;
         assert!(results.is_empty());
         Ok(())
     }
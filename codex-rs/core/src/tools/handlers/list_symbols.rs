@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::symbols::Symbol;
+use crate::symbols::SymbolLanguage;
+use crate::symbols::outline;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct ListSymbolsHandler;
+
+const INDENTATION_SPACES: usize = 2;
+
+#[derive(Deserialize)]
+struct ListSymbolsArgs {
+    file_path: String,
+    /// Maximum nesting depth to list; 1 lists top-level symbols only.
+    #[serde(default)]
+    depth: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for ListSymbolsHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "list_symbols handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ListSymbolsArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let ListSymbolsArgs { file_path, depth } = args;
+
+        if depth == Some(0) {
+            return Err(FunctionCallError::RespondToModel(
+                "depth must be greater than zero".to_string(),
+            ));
+        }
+
+        let path = PathBuf::from(&file_path);
+        if !path.is_absolute() {
+            return Err(FunctionCallError::RespondToModel(
+                "file_path must be an absolute path".to_string(),
+            ));
+        }
+
+        if turn.read_deny_matcher().is_denied(&path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to `{}` is blocked by the sandbox read_deny list",
+                path.display()
+            )));
+        }
+
+        let language = SymbolLanguage::from_path(&path).ok_or_else(|| {
+            FunctionCallError::RespondToModel(
+                "list_symbols supports Rust, TypeScript and Python files".to_string(),
+            )
+        })?;
+        let bytes = tokio::fs::read(&path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+        })?;
+        let source = String::from_utf8_lossy(&bytes);
+        let symbols = outline(&source, language).map_err(FunctionCallError::RespondToModel)?;
+
+        let mut output = vec![format!("Absolute path: {}", path.display())];
+        output.extend(format_outline(&symbols, depth));
+        if output.len() == 1 {
+            output.push("No symbols found.".to_string());
+        }
+        Ok(ToolOutput::Function {
+            content: output.join("\n"),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Renders one line per symbol, indented by nesting depth, with the line
+/// range that `read_file` accepts as `offset`/`limit` or `anchor_line`.
+fn format_outline(symbols: &[Symbol], depth: Option<usize>) -> Vec<String> {
+    symbols
+        .iter()
+        .filter(|symbol| depth.is_none_or(|depth| symbol.depth < depth))
+        .map(|symbol| {
+            let indent = " ".repeat(symbol.depth * INDENTATION_SPACES);
+            format!(
+                "{indent}L{}-{} {}",
+                symbol.start_line, symbol.end_line, symbol.signature
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn formats_nested_outline_with_depth_limit() {
+        let source = "class Store:\n    def get(self, key):\n        return key\n\n\ndef main():\n    pass\n";
+        let symbols = outline(source, SymbolLanguage::Python).expect("parse");
+
+        assert_eq!(
+            format_outline(&symbols, None),
+            vec![
+                "L1-3 class Store".to_string(),
+                "  L2-3 def get(self, key)".to_string(),
+                "L6-7 def main()".to_string(),
+            ]
+        );
+        assert_eq!(
+            format_outline(&symbols, Some(1)),
+            vec![
+                "L1-3 class Store".to_string(),
+                "L6-7 def main()".to_string()
+            ]
+        );
+    }
+}
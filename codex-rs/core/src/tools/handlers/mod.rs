@@ -1,10 +1,12 @@
 pub mod apply_patch;
 mod grep_files;
 mod list_dir;
+mod list_symbols;
 mod mcp;
 mod mcp_resource;
 mod plan;
 mod read_file;
+mod search_code;
 mod shell;
 mod test_sync;
 mod unified_exec;
@@ -15,10 +17,12 @@ pub use plan::PLAN_TOOL;
 pub use apply_patch::ApplyPatchHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
+pub use list_symbols::ListSymbolsHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
+pub use search_code::SearchCodeHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
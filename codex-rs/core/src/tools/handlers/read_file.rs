@@ -31,12 +31,16 @@ struct ReadFileArgs {
     /// Maximum number of lines to return; defaults to 2000.
     #[serde(default = "defaults::limit")]
     limit: usize,
-    /// Determines whether the handler reads a simple slice or indentation-aware block.
+    /// Determines whether the handler reads a simple slice, an indentation-aware
+    /// block, or a whole declaration.
     #[serde(default)]
     mode: ReadMode,
     /// Optional indentation configuration used when `mode` is `Indentation`.
     #[serde(default)]
     indentation: Option<IndentationArgs>,
+    /// Optional symbol lookup used when `mode` is `Symbol`.
+    #[serde(default)]
+    symbol: Option<SymbolArgs>,
 }
 
 #[derive(Deserialize)]
@@ -44,6 +48,18 @@ struct ReadFileArgs {
 enum ReadMode {
     Slice,
     Indentation,
+    Symbol,
+}
+
+/// Selects the declaration returned by symbol-aware reads.
+#[derive(Deserialize, Clone, Default)]
+struct SymbolArgs {
+    /// Name of the symbol, optionally qualified (`Config::load`, `Store.get`).
+    #[serde(default)]
+    name: Option<String>,
+    /// Line inside the symbol when no name is given; defaults to `offset`.
+    #[serde(default)]
+    anchor_line: Option<usize>,
 }
 /// Additional configuration for indentation-aware reads.
 #[derive(Deserialize, Clone)]
@@ -96,7 +112,7 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -119,6 +135,7 @@ impl ToolHandler for ReadFileHandler {
             limit,
             mode,
             indentation,
+            symbol,
         } = args;
 
         if offset == 0 {
@@ -140,12 +157,23 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        if turn.read_deny_matcher().is_denied(&path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to `{}` is blocked by the sandbox read_deny list",
+                path.display()
+            )));
+        }
+
         let collected = match mode {
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
                 indentation::read_block(&path, offset, limit, indentation).await?
             }
+            ReadMode::Symbol => {
+                let symbol = symbol.unwrap_or_default();
+                symbol::read_symbol(&path, offset, limit, symbol).await?
+            }
         };
         Ok(ToolOutput::Function {
             content: collected.join("\n"),
@@ -431,6 +459,107 @@ mod indentation {
     }
 }
 
+mod symbol {
+    use crate::function_tool::FunctionCallError;
+    use crate::symbols::Symbol;
+    use crate::symbols::SymbolLanguage;
+    use crate::symbols::enclosing;
+    use crate::symbols::find_by_name;
+    use crate::symbols::outline;
+    use crate::tools::handlers::read_file::SymbolArgs;
+    use crate::tools::handlers::read_file::format_line;
+    use std::path::Path;
+
+    const MAX_SUGGESTED_SYMBOLS: usize = 20;
+
+    /// Reads the declarations named by `options.name`, or the innermost one
+    /// around the anchor line, including their doc comments and attributes.
+    pub async fn read_symbol(
+        path: &Path,
+        offset: usize,
+        limit: usize,
+        options: SymbolArgs,
+    ) -> Result<Vec<String>, FunctionCallError> {
+        let language = SymbolLanguage::from_path(path).ok_or_else(|| {
+            FunctionCallError::RespondToModel(
+                "symbol mode supports Rust, TypeScript and Python files; use slice or \
+                 indentation mode for other files"
+                    .to_string(),
+            )
+        })?;
+        let bytes = tokio::fs::read(path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+        })?;
+        let source = String::from_utf8_lossy(&bytes);
+        let symbols = outline(&source, language).map_err(FunctionCallError::RespondToModel)?;
+
+        let selected: Vec<&Symbol> = match options.name.as_deref() {
+            Some(name) => {
+                let found = find_by_name(&symbols, name);
+                if found.is_empty() {
+                    let available: Vec<String> = symbols
+                        .iter()
+                        .take(MAX_SUGGESTED_SYMBOLS)
+                        .map(|symbol| symbol.qualified_name(language))
+                        .collect();
+                    let mut message = format!("no symbol named `{name}` in {}", path.display());
+                    if !available.is_empty() {
+                        message.push_str(&format!(
+                            "; declared symbols include {}",
+                            available.join(", ")
+                        ));
+                    }
+                    return Err(FunctionCallError::RespondToModel(message));
+                }
+                // Drop matches nested in another match, e.g. methods of a
+                // matching impl, since they are returned with it.
+                found
+                    .iter()
+                    .filter(|symbol| {
+                        !found.iter().any(|outer| {
+                            outer.depth < symbol.depth
+                                && outer.header_line <= symbol.header_line
+                                && symbol.end_line <= outer.end_line
+                        })
+                    })
+                    .copied()
+                    .collect()
+            }
+            None => {
+                let anchor_line = options.anchor_line.unwrap_or(offset);
+                if anchor_line == 0 {
+                    return Err(FunctionCallError::RespondToModel(
+                        "anchor_line must be a 1-indexed line number".to_string(),
+                    ));
+                }
+                let symbol = enclosing(&symbols, anchor_line).ok_or_else(|| {
+                    FunctionCallError::RespondToModel(format!(
+                        "no symbol encloses line {anchor_line}"
+                    ))
+                })?;
+                vec![symbol]
+            }
+        };
+
+        let lines: Vec<&str> = source.split('\n').collect();
+        let mut collected = Vec::new();
+        for symbol in selected {
+            if !collected.is_empty() {
+                collected.push(String::new());
+            }
+            for number in symbol.header_line..=symbol.end_line {
+                if collected.len() >= limit {
+                    return Ok(collected);
+                }
+                let line = lines.get(number - 1).copied().unwrap_or_default();
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                collected.push(format!("L{number}: {}", format_line(line.as_bytes())));
+            }
+        }
+        Ok(collected)
+    }
+}
+
 fn format_line(bytes: &[u8]) -> String {
     let decoded = String::from_utf8_lossy(bytes);
     if decoded.len() > MAX_LINE_LENGTH {
@@ -495,6 +624,7 @@ mod defaults {
 mod tests {
     use super::indentation::read_block;
     use super::slice::read;
+    use super::symbol::read_symbol;
     use super::*;
     use pretty_assertions::assert_eq;
     use tempfile::NamedTempFile;
@@ -996,4 +1126,126 @@ private:
         );
         Ok(())
     }
+
+    fn rust_temp_file(contents: &str) -> anyhow::Result<tempfile::NamedTempFile> {
+        use std::io::Write as _;
+        let mut temp = tempfile::Builder::new().suffix(".rs").tempfile()?;
+        temp.write_all(contents.as_bytes())?;
+        Ok(temp)
+    }
+
+    const RUST_SOURCE: &str = "use std::fmt;
+
+/// A point.
+#[derive(Debug)]
+struct Point {
+    x: i32,
+}
+
+impl Point {
+    /// Creates a point.
+    fn new(x: i32) -> Self {
+        Point { x }
+    }
+}
+
+fn new() {}
+";
+
+    #[tokio::test]
+    async fn symbol_mode_reads_named_symbol_with_docs() -> anyhow::Result<()> {
+        let temp = rust_temp_file(RUST_SOURCE)?;
+        let options = SymbolArgs {
+            name: Some("Point::new".to_string()),
+            anchor_line: None,
+        };
+
+        let lines = read_symbol(temp.path(), 1, 200, options).await?;
+        assert_eq!(
+            lines,
+            vec![
+                "L10:     /// Creates a point.".to_string(),
+                "L11:     fn new(x: i32) -> Self {".to_string(),
+                "L12:         Point { x }".to_string(),
+                "L13:     }".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn symbol_mode_returns_every_match_for_unqualified_name() -> anyhow::Result<()> {
+        let temp = rust_temp_file(RUST_SOURCE)?;
+        let options = SymbolArgs {
+            name: Some("new".to_string()),
+            anchor_line: None,
+        };
+
+        let lines = read_symbol(temp.path(), 1, 200, options).await?;
+        assert_eq!(
+            lines,
+            vec![
+                "L10:     /// Creates a point.".to_string(),
+                "L11:     fn new(x: i32) -> Self {".to_string(),
+                "L12:         Point { x }".to_string(),
+                "L13:     }".to_string(),
+                String::new(),
+                "L16: fn new() {}".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn symbol_mode_reads_enclosing_symbol_for_anchor() -> anyhow::Result<()> {
+        let temp = rust_temp_file(RUST_SOURCE)?;
+
+        let lines = read_symbol(temp.path(), 6, 200, SymbolArgs::default()).await?;
+        assert_eq!(
+            lines,
+            vec![
+                "L3: /// A point.".to_string(),
+                "L4: #[derive(Debug)]".to_string(),
+                "L5: struct Point {".to_string(),
+                "L6:     x: i32,".to_string(),
+                "L7: }".to_string(),
+            ]
+        );
+
+        let err = read_symbol(temp.path(), 1, 200, SymbolArgs::default())
+            .await
+            .expect_err("no symbol encloses the use declaration");
+        assert_eq!(
+            err,
+            FunctionCallError::RespondToModel("no symbol encloses line 1".to_string())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn symbol_mode_rejects_unknown_symbols_and_languages() -> anyhow::Result<()> {
+        let temp = rust_temp_file(RUST_SOURCE)?;
+        let options = SymbolArgs {
+            name: Some("Missing".to_string()),
+            anchor_line: None,
+        };
+        let err = read_symbol(temp.path(), 1, 200, options)
+            .await
+            .expect_err("unknown symbol");
+        assert!(matches!(
+            err,
+            FunctionCallError::RespondToModel(message)
+                if message.ends_with("declared symbols include Point, Point, Point::new, new")
+        ));
+
+        let text = NamedTempFile::new()?;
+        let err = read_symbol(text.path(), 1, 200, SymbolArgs::default())
+            .await
+            .expect_err("unsupported language");
+        assert!(matches!(
+            err,
+            FunctionCallError::RespondToModel(message) if message.starts_with("symbol mode supports")
+        ));
+        Ok(())
+    }
 }
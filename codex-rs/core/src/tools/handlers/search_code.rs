@@ -0,0 +1,621 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use regex::Regex;
+use regex::RegexBuilder;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::read_deny::ReadDenyMatcher;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SearchCodeHandler;
+
+const DEFAULT_MAX_RESULTS: usize = 50;
+const MAX_RESULTS_LIMIT: usize = 500;
+const DEFAULT_CONTEXT_LINES: usize = 2;
+const MAX_CONTEXT_LINES: usize = 10;
+const MAX_LINE_LENGTH: usize = 500;
+/// Files larger than this are skipped; they are almost always generated.
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+/// A NUL byte in this prefix marks the file as binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+/// How much of the tree one search may read before it stops.
+const SCAN_LIMITS: ScanLimits = ScanLimits {
+    max_files: 20_000,
+    max_bytes: 256 * 1024 * 1024,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct ScanLimits {
+    max_files: usize,
+    max_bytes: u64,
+}
+
+fn default_context_lines() -> usize {
+    DEFAULT_CONTEXT_LINES
+}
+
+fn default_max_results() -> usize {
+    DEFAULT_MAX_RESULTS
+}
+
+#[derive(Deserialize)]
+struct SearchCodeArgs {
+    pattern: String,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    include: Option<String>,
+    #[serde(default = "default_context_lines")]
+    context_lines: usize,
+    #[serde(default)]
+    case_insensitive: bool,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+/// Matches found in one file, with the surrounding context already cut into
+/// hunks. Only the first `max_results` matches keep their lines.
+#[derive(Debug)]
+struct FileMatches {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    match_count: usize,
+    hunks: Vec<Vec<SearchLine>>,
+}
+
+/// Matches of one walk; `complete` is false when it hit [`ScanLimits`].
+#[derive(Debug)]
+struct SearchScan {
+    files: Vec<FileMatches>,
+    complete: bool,
+}
+
+/// Sets the flag when the tool call is dropped, e.g. because the turn was
+/// interrupted, so the blocking walk stops too.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct SearchLine {
+    number: usize,
+    text: String,
+    is_match: bool,
+}
+
+#[async_trait]
+impl ToolHandler for SearchCodeHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            payload,
+            turn,
+            tracker,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "search_code handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SearchCodeArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        if args.pattern.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "pattern must not be empty".to_string(),
+            ));
+        }
+
+        if args.max_results == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "max_results must be greater than zero".to_string(),
+            ));
+        }
+
+        let regex = RegexBuilder::new(&args.pattern)
+            .case_insensitive(args.case_insensitive)
+            .build()
+            .map_err(|err| FunctionCallError::RespondToModel(format!("invalid pattern: {err}")))?;
+
+        let search_path = turn.resolve_path(args.path.clone());
+        tokio::fs::metadata(&search_path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "unable to access `{}`: {err}",
+                search_path.display()
+            ))
+        })?;
+
+        let read_deny = turn.read_deny_matcher();
+        if read_deny.is_denied(&search_path) {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "access to `{}` is blocked by the sandbox read_deny list",
+                search_path.display()
+            )));
+        }
+
+        let include = args
+            .include
+            .as_deref()
+            .map(str::trim)
+            .filter(|include| !include.is_empty())
+            .map(str::to_string);
+        let context_lines = args.context_lines.min(MAX_CONTEXT_LINES);
+        let max_results = args.max_results.min(MAX_RESULTS_LIMIT);
+
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let _cancel_on_drop = CancelOnDrop(Arc::clone(&cancel_flag));
+        let SearchScan {
+            mut files,
+            complete,
+        } = tokio::task::spawn_blocking(move || {
+            search_files(
+                &search_path,
+                &regex,
+                include.as_deref(),
+                context_lines,
+                max_results,
+                &read_deny,
+                SCAN_LIMITS,
+                &cancel_flag,
+            )
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("search_code failed: {err}")))??;
+
+        if files.is_empty() {
+            let mut content = "No matches found.".to_string();
+            if !complete {
+                content.push_str(&format!(" {}", incomplete_scan_note()));
+            }
+            return Ok(ToolOutput::Function {
+                content,
+                content_items: None,
+                success: Some(false),
+            });
+        }
+
+        let touched = tracker.lock().await.touched_paths();
+        rank_files(&mut files, &touched);
+        let mut content = render_results(
+            &files,
+            &turn.cwd,
+            max_results,
+            turn.truncation_policy.byte_budget(),
+        );
+        if !complete {
+            content.push_str(&format!("\n\n{}", incomplete_scan_note()));
+        }
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Walks `search_path` honoring `.gitignore` and friends and collects the
+/// matches of `regex` in every readable text file. The walk stops early once
+/// it has read `limits` worth of files, and fails once `cancel_flag` is set.
+#[allow(clippy::too_many_arguments)]
+fn search_files(
+    search_path: &Path,
+    regex: &Regex,
+    include: Option<&str>,
+    context_lines: usize,
+    max_results: usize,
+    read_deny: &ReadDenyMatcher,
+    limits: ScanLimits,
+    cancel_flag: &AtomicBool,
+) -> Result<SearchScan, FunctionCallError> {
+    let mut walk_builder = WalkBuilder::new(search_path);
+    walk_builder
+        // Allow hidden entries, but never descend into the git directory.
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        // Don't require git to be present to apply git-related ignore rules.
+        .require_git(false);
+    if let Some(include) = include {
+        let mut override_builder = OverrideBuilder::new(search_path);
+        override_builder.add(include).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("invalid include glob: {err}"))
+        })?;
+        let overrides = override_builder.build().map_err(|err| {
+            FunctionCallError::RespondToModel(format!("invalid include glob: {err}"))
+        })?;
+        walk_builder.overrides(overrides);
+    }
+
+    let mut files = Vec::new();
+    let mut scanned_files = 0;
+    let mut scanned_bytes = 0;
+    for entry in walk_builder.build().flatten() {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err(FunctionCallError::RespondToModel(
+                "search_code was cancelled".to_string(),
+            ));
+        }
+        let is_file = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file());
+        if !is_file || read_deny.is_denied(entry.path()) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        if scanned_files == limits.max_files || scanned_bytes + metadata.len() > limits.max_bytes {
+            return Ok(SearchScan {
+                files,
+                complete: false,
+            });
+        }
+        scanned_files += 1;
+        scanned_bytes += metadata.len();
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+        if sniff.contains(&0) {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&bytes);
+        if let Some(mut matches) = search_text(&text, regex, context_lines, max_results) {
+            matches.path = entry.into_path();
+            matches.modified = metadata.modified().ok();
+            files.push(matches);
+        }
+    }
+    Ok(SearchScan {
+        files,
+        complete: true,
+    })
+}
+
+fn incomplete_scan_note() -> String {
+    format!(
+        "[search stopped after reading {} files or {} MiB; narrow the path or include glob to search the rest]",
+        SCAN_LIMITS.max_files,
+        SCAN_LIMITS.max_bytes / (1024 * 1024)
+    )
+}
+
+/// Finds the matching lines of `text` and groups them with `context_lines`
+/// of context on each side, merging hunks that touch.
+fn search_text(
+    text: &str,
+    regex: &Regex,
+    context_lines: usize,
+    max_results: usize,
+) -> Option<FileMatches> {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let matched: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(index, _)| index)
+        .collect();
+    if matched.is_empty() {
+        return None;
+    }
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &index in matched.iter().take(max_results) {
+        let start = index.saturating_sub(context_lines);
+        let end = (index + context_lines).min(lines.len() - 1);
+        match ranges.last_mut() {
+            Some((_, last_end)) if start <= *last_end + 1 => *last_end = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    let matched_set: HashSet<usize> = matched.iter().take(max_results).copied().collect();
+    let hunks = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            (start..=end)
+                .map(|index| SearchLine {
+                    number: index + 1,
+                    text: take_bytes_at_char_boundary(lines[index], MAX_LINE_LENGTH).to_string(),
+                    is_match: matched_set.contains(&index),
+                })
+                .collect()
+        })
+        .collect();
+
+    Some(FileMatches {
+        path: PathBuf::new(),
+        modified: None,
+        match_count: matched.len(),
+        hunks,
+    })
+}
+
+/// Orders files edited during this turn first, then the most recently
+/// modified, then by path so the output is stable.
+fn rank_files(files: &mut [FileMatches], touched: &[PathBuf]) {
+    files.sort_by(|a, b| {
+        let a_key = (!touched.contains(&a.path), Reverse(a.modified));
+        let b_key = (!touched.contains(&b.path), Reverse(b.modified));
+        a_key.cmp(&b_key).then_with(|| a.path.cmp(&b.path))
+    });
+}
+
+/// Renders matches grouped per file. Matching lines use `L{n}:` like
+/// `read_file` and context lines use `L{n}-`. Output stops after
+/// `max_results` matches or `byte_budget` bytes, whichever comes first.
+fn render_results(
+    files: &[FileMatches],
+    cwd: &Path,
+    max_results: usize,
+    byte_budget: usize,
+) -> String {
+    let total_matches: usize = files.iter().map(|file| file.match_count).sum();
+    let mut output = String::new();
+    let mut shown_matches = 0;
+
+    'files: for file in files {
+        let display_path = file.path.strip_prefix(cwd).unwrap_or(&file.path);
+        let header = format!("{}\n", display_path.display());
+        if shown_matches == max_results || output.len() + header.len() > byte_budget {
+            break;
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&header);
+        for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+            for (line_index, line) in hunk.iter().enumerate() {
+                if line.is_match && shown_matches == max_results {
+                    break 'files;
+                }
+                let hunk_separator = if hunk_index > 0 && line_index == 0 {
+                    "--\n"
+                } else {
+                    ""
+                };
+                let separator = if line.is_match { ':' } else { '-' };
+                let rendered = format!(
+                    "{hunk_separator}L{}{separator} {}\n",
+                    line.number, line.text
+                );
+                if output.len() + rendered.len() > byte_budget {
+                    break 'files;
+                }
+                output.push_str(&rendered);
+                if line.is_match {
+                    shown_matches += 1;
+                }
+            }
+        }
+    }
+
+    let omitted = total_matches - shown_matches;
+    if omitted > 0 {
+        output.push_str(&format!(
+            "\n[{omitted} more matches not shown; narrow the pattern, path or include glob]"
+        ));
+    }
+    output.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn regex(pattern: &str) -> Regex {
+        Regex::new(pattern).expect("valid regex")
+    }
+
+    #[test]
+    fn merges_adjacent_context_into_one_hunk() {
+        let text = "a\nneedle one\nb\nneedle two\nc\nd\ne\nf\nneedle three\n";
+        let matches = search_text(text, &regex("needle"), 1, 10).expect("matches");
+
+        assert_eq!(matches.match_count, 3);
+        let numbers: Vec<Vec<(usize, bool)>> = matches
+            .hunks
+            .iter()
+            .map(|hunk| {
+                hunk.iter()
+                    .map(|line| (line.number, line.is_match))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                vec![(1, false), (2, true), (3, false), (4, true), (5, false)],
+                vec![(8, false), (9, true)],
+            ]
+        );
+    }
+
+    #[test]
+    fn search_respects_gitignore_and_skips_binary_files() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        std::fs::write(dir.join(".gitignore"), "target/\n")?;
+        std::fs::create_dir(dir.join("target"))?;
+        std::fs::write(dir.join("target/out.rs"), "fn needle() {}\n")?;
+        std::fs::write(dir.join("blob.bin"), b"needle\0\x01")?;
+        std::fs::write(dir.join("lib.rs"), "fn needle() {}\n")?;
+
+        let scan = search_files(
+            dir,
+            &regex("needle"),
+            None,
+            0,
+            10,
+            &ReadDenyMatcher::default(),
+            SCAN_LIMITS,
+            &AtomicBool::new(false),
+        )?;
+        assert!(scan.complete);
+        let paths: Vec<PathBuf> = scan.files.into_iter().map(|file| file.path).collect();
+        assert_eq!(paths, vec![dir.join("lib.rs")]);
+        Ok(())
+    }
+
+    #[test]
+    fn search_applies_include_glob_and_read_deny() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        std::fs::create_dir(dir.join("secrets"))?;
+        std::fs::write(dir.join("secrets/key.rs"), "needle\n")?;
+        std::fs::write(dir.join("notes.md"), "needle\n")?;
+        std::fs::write(dir.join("main.rs"), "needle\n")?;
+
+        let read_deny = ReadDenyMatcher::new(&["secrets".to_string()], dir);
+        let scan = search_files(
+            dir,
+            &regex("needle"),
+            Some("*.rs"),
+            0,
+            10,
+            &read_deny,
+            SCAN_LIMITS,
+            &AtomicBool::new(false),
+        )?;
+        let paths: Vec<PathBuf> = scan.files.into_iter().map(|file| file.path).collect();
+        assert_eq!(paths, vec![dir.join("main.rs")]);
+        Ok(())
+    }
+
+    #[test]
+    fn search_stops_at_scan_limits_and_on_cancel() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(dir.join(name), "needle\n")?;
+        }
+        let search = |limits: ScanLimits, cancel_flag: &AtomicBool| {
+            search_files(
+                dir,
+                &regex("needle"),
+                None,
+                0,
+                10,
+                &ReadDenyMatcher::default(),
+                limits,
+                cancel_flag,
+            )
+        };
+
+        let by_files = search(
+            ScanLimits {
+                max_files: 2,
+                max_bytes: u64::MAX,
+            },
+            &AtomicBool::new(false),
+        )?;
+        assert_eq!((by_files.files.len(), by_files.complete), (2, false));
+
+        let by_bytes = search(
+            ScanLimits {
+                max_files: usize::MAX,
+                max_bytes: 7,
+            },
+            &AtomicBool::new(false),
+        )?;
+        assert_eq!((by_bytes.files.len(), by_bytes.complete), (1, false));
+
+        let cancelled = search(SCAN_LIMITS, &AtomicBool::new(true));
+        assert!(cancelled.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn ranks_touched_then_recent_files_first() {
+        let now = SystemTime::now();
+        let file = |name: &str, age: u64| FileMatches {
+            path: PathBuf::from("/repo").join(name),
+            modified: Some(now - Duration::from_secs(age)),
+            match_count: 1,
+            hunks: Vec::new(),
+        };
+        let mut files = vec![
+            file("old.rs", 300),
+            file("new.rs", 10),
+            file("edited.rs", 600),
+        ];
+
+        rank_files(&mut files, &[PathBuf::from("/repo/edited.rs")]);
+        let names: Vec<&str> = files
+            .iter()
+            .map(|file| {
+                file.path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        assert_eq!(names, vec!["edited.rs", "new.rs", "old.rs"]);
+    }
+
+    #[test]
+    fn render_groups_by_file_and_reports_omitted_matches() {
+        let text = "one\nneedle a\ntwo\n";
+        let mut first = search_text(text, &regex("needle"), 1, 10).expect("matches");
+        first.path = PathBuf::from("/repo/src/a.rs");
+        let mut second =
+            search_text("needle b\nneedle c\n", &regex("needle"), 0, 10).expect("matches");
+        second.path = PathBuf::from("/repo/src/b.rs");
+        let files = vec![first, second];
+
+        assert_eq!(
+            render_results(&files, Path::new("/repo"), 10, usize::MAX),
+            "src/a.rs\nL1- one\nL2: needle a\nL3- two\n\nsrc/b.rs\nL1: needle b\nL2: needle c"
+        );
+        assert_eq!(
+            render_results(&files, Path::new("/repo"), 2, usize::MAX),
+            "src/a.rs\nL1- one\nL2: needle a\nL3- two\n\nsrc/b.rs\nL1: needle b\n\n\
+             [1 more matches not shown; narrow the pattern, path or include glob]"
+        );
+        assert_eq!(
+            render_results(&files, Path::new("/repo"), 10, 30),
+            "src/a.rs\nL1- one\nL2: needle a\n\n\
+             [2 more matches not shown; narrow the pattern, path or include glob]"
+        );
+    }
+}
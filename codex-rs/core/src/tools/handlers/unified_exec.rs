@@ -1,7 +1,9 @@
+use crate::bash::extract_bash_command;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandSource;
+use crate::protocol::ExecSessionInfo;
 use crate::protocol::TerminalInteractionEvent;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::Shell;
@@ -17,6 +19,7 @@ use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::unified_exec::ExecCommandRequest;
 use crate::unified_exec::UnifiedExecContext;
+use crate::unified_exec::UnifiedExecError;
 use crate::unified_exec::UnifiedExecResponse;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::unified_exec::WriteStdinRequest;
@@ -44,12 +47,17 @@ struct ExecCommandArgs {
     sandbox_permissions: SandboxPermissions,
     #[serde(default)]
     justification: Option<String>,
+    #[serde(default)]
+    session_name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct WriteStdinArgs {
     // The model is trained on `session_id`.
-    session_id: i32,
+    #[serde(default)]
+    session_id: Option<i32>,
+    #[serde(default)]
+    session_name: Option<String>,
     #[serde(default)]
     chars: String,
     #[serde(default = "default_write_stdin_yield_time_ms")]
@@ -137,8 +145,12 @@ impl ToolHandler for UnifiedExecHandler {
                     max_output_tokens,
                     sandbox_permissions,
                     justification,
+                    session_name,
                     ..
                 } = args;
+                let session_name = session_name
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty());
 
                 if sandbox_permissions.requires_escalated_permissions()
                     && !matches!(
@@ -198,6 +210,7 @@ impl ToolHandler for UnifiedExecHandler {
                             workdir,
                             sandbox_permissions,
                             justification,
+                            session_name,
                         },
                         &context,
                     )
@@ -212,21 +225,49 @@ impl ToolHandler for UnifiedExecHandler {
                         "failed to parse write_stdin arguments: {err:?}"
                     ))
                 })?;
-                let response = manager
-                    .write_stdin(WriteStdinRequest {
-                        process_id: &args.session_id.to_string(),
-                        input: &args.chars,
-                        yield_time_ms: args.yield_time_ms,
-                        max_output_tokens: args.max_output_tokens,
-                    })
-                    .await
-                    .map_err(|err| {
-                        FunctionCallError::RespondToModel(format!("write_stdin failed: {err:?}"))
-                    })?;
+                let process_id = match (args.session_id, args.session_name.as_deref()) {
+                    (Some(session_id), _) => Ok(session_id.to_string()),
+                    (None, Some(name)) => manager.process_id_for_name(name).await,
+                    (None, None) => {
+                        return Err(FunctionCallError::RespondToModel(
+                            "write_stdin requires session_id or session_name".to_string(),
+                        ));
+                    }
+                };
+                let response = match process_id {
+                    Ok(process_id) => manager
+                        .write_stdin(WriteStdinRequest {
+                            process_id: &process_id,
+                            input: &args.chars,
+                            yield_time_ms: args.yield_time_ms,
+                            max_output_tokens: args.max_output_tokens,
+                        })
+                        .await
+                        .map(|response| (process_id, response)),
+                    Err(err) => Err(err),
+                };
+                let (process_id, response) = match response {
+                    Ok(resolved) => resolved,
+                    Err(
+                        err @ (UnifiedExecError::UnknownSessionId { .. }
+                        | UnifiedExecError::UnknownSessionName { .. }),
+                    ) => {
+                        let sessions = manager.list_sessions().await;
+                        return Err(FunctionCallError::RespondToModel(format!(
+                            "write_stdin failed: {err}. {}",
+                            describe_running_sessions(&sessions)
+                        )));
+                    }
+                    Err(err) => {
+                        return Err(FunctionCallError::RespondToModel(format!(
+                            "write_stdin failed: {err:?}"
+                        )));
+                    }
+                };
 
                 let interaction = TerminalInteractionEvent {
                     call_id: response.event_call_id.clone(),
-                    process_id: args.session_id.to_string(),
+                    process_id,
                     stdin: args.chars.clone(),
                 };
                 session
@@ -280,7 +321,12 @@ fn format_response(response: &UnifiedExecResponse) -> String {
 
     if let Some(process_id) = &response.process_id {
         // Training still uses "session ID".
-        sections.push(format!("Process running with session ID {process_id}"));
+        match &response.session_name {
+            Some(name) => sections.push(format!(
+                "Process running with session ID {process_id} (session name: {name})"
+            )),
+            None => sections.push(format!("Process running with session ID {process_id}")),
+        }
     }
 
     if let Some(original_token_count) = response.original_token_count {
@@ -293,6 +339,27 @@ fn format_response(response: &UnifiedExecResponse) -> String {
     sections.join("\n")
 }
 
+/// Lists the running sessions so the model can recover an id it lost, for
+/// example after compaction.
+fn describe_running_sessions(sessions: &[ExecSessionInfo]) -> String {
+    if sessions.is_empty() {
+        return "No sessions are running.".to_string();
+    }
+    let described: Vec<String> = sessions
+        .iter()
+        .map(|session| {
+            let command = extract_bash_command(&session.command)
+                .map(|(_, script)| script.to_string())
+                .unwrap_or_else(|| session.command.join(" "));
+            match &session.name {
+                Some(name) => format!("{} ({name}): `{command}`", session.process_id),
+                None => format!("{}: `{command}`", session.process_id),
+            }
+        })
+        .collect();
+    format!("Running sessions: {}", described.join("; "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -8,6 +8,8 @@ retry without sandbox on denial (no re‑approval thanks to caching).
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::network_proxy::sandbox_supports_network_proxy;
+use crate::read_deny::MAX_SCAN_ENTRIES;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
@@ -18,7 +20,11 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::WarningEvent;
+use std::time::Duration;
+use std::time::Instant;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -56,7 +62,13 @@ impl ToolOrchestrator {
         });
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
-                otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
+                otel.tool_decision(
+                    otel_tn,
+                    otel_ci,
+                    &ReviewDecision::Approved,
+                    otel_cfg,
+                    Duration::ZERO,
+                );
             }
             ExecApprovalRequirement::Forbidden { reason } => {
                 return Err(ToolError::Rejected(reason));
@@ -68,9 +80,17 @@ impl ToolOrchestrator {
                     call_id: &tool_ctx.call_id,
                     retry_reason: reason,
                 };
+                otel.approval_requested(otel_tn);
+                let requested_at = Instant::now();
                 let decision = tool.start_approval_async(req, approval_ctx).await;
 
-                otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                otel.tool_decision(
+                    otel_tn,
+                    otel_ci,
+                    &decision,
+                    otel_user.clone(),
+                    requested_at.elapsed(),
+                );
 
                 match decision {
                     ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -92,6 +112,40 @@ impl ToolOrchestrator {
                 .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference()),
         };
 
+        let network_proxy = if sandbox_supports_network_proxy(initial_sandbox) {
+            tool_ctx
+                .session
+                .services
+                .network_proxy
+                .endpoint_for(&turn_ctx.sandbox_policy)
+                .await
+        } else {
+            None
+        };
+
+        // The Linux sandbox hides the `read_deny` paths, which are resolved
+        // once per policy rather than by every command.
+        let read_denied_paths = if initial_sandbox == crate::exec::SandboxType::LinuxSeccomp {
+            let (paths, resolved) = tool_ctx
+                .session
+                .services
+                .read_deny
+                .paths_for(&turn_ctx.sandbox_policy, &turn_ctx.cwd)
+                .await;
+            if resolved && !paths.complete {
+                let message = format!(
+                    "Stopped looking for read_deny matches after {MAX_SCAN_ENTRIES} workspace entries; sandboxed commands can still read matches in the rest of the workspace. Use patterns anchored to a directory to cover them."
+                );
+                tool_ctx
+                    .session
+                    .send_event(turn_ctx, EventMsg::Warning(WarningEvent { message }))
+                    .await;
+            }
+            Some(paths)
+        } else {
+            None
+        };
+
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox().
         let initial_attempt = SandboxAttempt {
@@ -100,6 +154,8 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            network_proxy: network_proxy.as_ref(),
+            read_denied_paths: read_denied_paths.as_deref(),
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -108,6 +164,7 @@ impl ToolOrchestrator {
                 Ok(out)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+                otel.sandbox_denied(otel_tn);
                 if !tool.escalate_on_failure() {
                     return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
                         output,
@@ -131,8 +188,16 @@ impl ToolOrchestrator {
                         retry_reason: Some(reason_msg),
                     };
 
+                    otel.approval_requested(otel_tn);
+                    let requested_at = Instant::now();
                     let decision = tool.start_approval_async(req, approval_ctx).await;
-                    otel.tool_decision(otel_tn, otel_ci, &decision, otel_user);
+                    otel.tool_decision(
+                        otel_tn,
+                        otel_ci,
+                        &decision,
+                        otel_user,
+                        requested_at.elapsed(),
+                    );
 
                     match decision {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -150,6 +215,8 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    network_proxy: None,
+                    read_denied_paths: None,
                 };
 
                 // Second attempt.
@@ -18,6 +18,7 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
+use codex_apply_patch::FUZZ_THRESHOLD_ENV_VAR;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 use futures::future::BoxFuture;
@@ -31,6 +32,9 @@ pub struct ApplyPatchRequest {
     pub timeout_ms: Option<u64>,
     pub user_explicitly_approved: bool,
     pub codex_exe: Option<PathBuf>,
+    /// Forwarded to the child process so it applies the patch with the same
+    /// fuzzy matching that verification used.
+    pub fuzz_threshold: f32,
 }
 
 #[derive(Default)]
@@ -62,7 +66,10 @@ impl ApplyPatchRuntime {
             cwd: req.cwd.clone(),
             expiration: req.timeout_ms.into(),
             // Run apply_patch with a minimal environment for determinism and to avoid leaks.
-            env: HashMap::new(),
+            env: HashMap::from([(
+                FUZZ_THRESHOLD_ENV_VAR.to_string(),
+                req.fuzz_threshold.to_string(),
+            )]),
             sandbox_permissions: SandboxPermissions::UseDefault,
             justification: None,
         })
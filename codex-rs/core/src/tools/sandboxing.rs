@@ -7,7 +7,9 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
+use crate::network_proxy::NetworkProxyEndpoint;
 use crate::protocol::SandboxPolicy;
+use crate::read_deny::ReadDeniedPaths;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
@@ -237,6 +239,12 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    /// The session's egress proxy when the policy carries a
+    /// `network_allowlist`.
+    pub network_proxy: Option<&'a NetworkProxyEndpoint>,
+    /// The session's resolved `read_deny` paths; set whenever `sandbox` is
+    /// the Linux sandbox.
+    pub read_denied_paths: Option<&'a ReadDeniedPaths>,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -250,6 +258,8 @@ impl<'a> SandboxAttempt<'a> {
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
+            self.network_proxy,
+            self.read_denied_paths,
         )
     }
 }
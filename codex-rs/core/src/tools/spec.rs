@@ -1,5 +1,6 @@
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::error::CodexErr;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::openai_models::model_family::ModelFamily;
@@ -192,6 +193,15 @@ fn create_exec_command_tool() -> ToolSpec {
             ),
         },
     );
+    properties.insert(
+        "session_name".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional name for the session if the command keeps running, e.g. \"dev-server\". Pass it to write_stdin as session_name to find the session again later."
+                    .to_string(),
+            ),
+        },
+    );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "exec_command".to_string(),
@@ -215,6 +225,14 @@ fn create_write_stdin_tool() -> ToolSpec {
             description: Some("Identifier of the running unified exec session.".to_string()),
         },
     );
+    properties.insert(
+        "session_name".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Name given to the session in exec_command; use instead of session_id.".to_string(),
+            ),
+        },
+    );
     properties.insert(
         "chars".to_string(),
         JsonSchema::String {
@@ -246,7 +264,7 @@ fn create_write_stdin_tool() -> ToolSpec {
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["session_id".to_string()]),
+            required: None,
             additional_properties: Some(false.into()),
         },
     })
@@ -524,6 +542,72 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_search_code_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "pattern".to_string(),
+        JsonSchema::String {
+            description: Some("Regular expression to search for in file contents.".to_string()),
+        },
+    );
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Directory or file to search. Defaults to the session's working directory."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "include".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional glob that limits which files are searched (e.g. \"*.rs\" or \
+                 \"src/**/*.ts\")."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "context_lines".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Lines of context to show around each match (defaults to 2, at most 10)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "case_insensitive".to_string(),
+        JsonSchema::Boolean {
+            description: Some("Match without regard to case.".to_string()),
+        },
+    );
+    properties.insert(
+        "max_results".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of matching lines to return (defaults to 50).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "search_code".to_string(),
+        description: "Searches file contents with a regular expression and returns matching \
+                      lines with context, grouped per file. Respects .gitignore and ranks files \
+                      edited this turn and recently modified files first."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["pattern".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -550,8 +634,9 @@ fn create_read_file_tool() -> ToolSpec {
         "mode".to_string(),
         JsonSchema::String {
             description: Some(
-                "Optional mode selector: \"slice\" for simple ranges (default) or \"indentation\" \
-                 to expand around an anchor line."
+                "Optional mode selector: \"slice\" for simple ranges (default), \"indentation\" \
+                 to expand around an anchor line, or \"symbol\" to read a whole declaration in \
+                 Rust, TypeScript or Python files."
                     .to_string(),
             ),
         },
@@ -608,10 +693,39 @@ fn create_read_file_tool() -> ToolSpec {
         },
     );
 
+    let mut symbol_properties = BTreeMap::new();
+    symbol_properties.insert(
+        "name".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Symbol to read, optionally qualified (e.g. \"Config::load\" or \"Store.get\")."
+                    .to_string(),
+            ),
+        },
+    );
+    symbol_properties.insert(
+        "anchor_line".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "When no name is given, read the innermost symbol containing this line \
+                 (defaults to offset)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "symbol".to_string(),
+        JsonSchema::Object {
+            properties: symbol_properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    );
+
     ToolSpec::Function(ResponsesApiTool {
         name: "read_file".to_string(),
         description:
-            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
+            "Reads a local file with 1-indexed line numbers, supporting slice, indentation-aware block and symbol modes."
                 .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -667,6 +781,37 @@ fn create_list_dir_tool() -> ToolSpec {
     })
 }
 
+fn create_list_symbols_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "file_path".to_string(),
+        JsonSchema::String {
+            description: Some("Absolute path to a Rust, TypeScript or Python file.".to_string()),
+        },
+    );
+    properties.insert(
+        "depth".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "The maximum nesting depth to list; 1 lists top-level symbols only.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "list_symbols".to_string(),
+        description:
+            "Outlines the functions, types and other declarations in a source file with their line ranges."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["file_path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_list_mcp_resources_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -825,6 +970,38 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
     Ok(tools_json)
 }
 
+pub(crate) fn create_tools_json_for_messages_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    // The Messages API only understands function tools, described by `name`,
+    // `description` and an `input_schema`. Built-in tools are sent as their
+    // function equivalents, whose calls the same handlers accept.
+    tools
+        .iter()
+        .map(|tool| {
+            let function = match tool {
+                ToolSpec::Function(_) => tool.clone(),
+                ToolSpec::LocalShell {} => create_shell_tool(),
+                ToolSpec::Freeform(freeform) if freeform.name == "apply_patch" => {
+                    create_apply_patch_json_tool()
+                }
+                ToolSpec::Freeform(_) | ToolSpec::WebSearch {} => {
+                    return Err(CodexErr::UnsupportedOperation(format!(
+                        "the `{}` tool is not supported for Messages API",
+                        tool.name()
+                    )));
+                }
+            };
+            let json = serde_json::to_value(&function)?;
+            Ok(json!({
+                "name": json.get("name").cloned().unwrap_or_default(),
+                "description": json.get("description").cloned().unwrap_or_default(),
+                "input_schema": json.get("parameters").cloned().unwrap_or_default(),
+            }))
+        })
+        .collect()
+}
+
 pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
@@ -979,10 +1156,12 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::ApplyPatchHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::ListSymbolsHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::SearchCodeHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
     use crate::tools::handlers::TestSyncHandler;
@@ -1061,6 +1240,16 @@ pub(crate) fn build_specs(
         builder.register_handler("grep_files", grep_files_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .iter()
+        .any(|tool| tool == "search_code")
+    {
+        let search_code_handler = Arc::new(SearchCodeHandler);
+        builder.push_spec_with_parallel_support(create_search_code_tool(), true);
+        builder.register_handler("search_code", search_code_handler);
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"read_file".to_string())
@@ -1080,6 +1269,16 @@ pub(crate) fn build_specs(
         builder.register_handler("list_dir", list_dir_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .iter()
+        .any(|tool| tool == "list_symbols")
+    {
+        let list_symbols_handler = Arc::new(ListSymbolsHandler);
+        builder.push_spec_with_parallel_support(create_list_symbols_tool(), true);
+        builder.register_handler("list_symbols", list_symbols_handler);
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"test_sync_tool".to_string())
@@ -1539,6 +1738,16 @@ mod tests {
                 .any(|tool| tool_name(&tool.spec) == "grep_files")
         );
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "list_dir"));
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "list_symbols")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "search_code")
+        );
     }
 
     #[test]
@@ -2157,5 +2366,44 @@ Examples of valid command strings:
                 }
             })]
         );
+
+        let messages_json = create_tools_json_for_messages_api(&tools).unwrap();
+
+        assert_eq!(
+            messages_json,
+            vec![json!({
+                "name": "demo",
+                "description": "A demo tool",
+                "input_schema": {
+                    "type": "object",
+                    "properties": {
+                        "foo": { "type": "string" }
+                    },
+                },
+            })]
+        );
+    }
+
+    #[test]
+    fn messages_api_tools_convert_built_in_tools_to_functions() {
+        let tools = vec![ToolSpec::LocalShell {}, create_apply_patch_freeform_tool()];
+
+        let messages_json = create_tools_json_for_messages_api(&tools).unwrap();
+
+        let names: Vec<&str> = messages_json
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["shell", "apply_patch"]);
+        assert_eq!(
+            messages_json[1]["input_schema"]["required"],
+            json!(["input"])
+        );
+
+        let err = create_tools_json_for_messages_api(&[ToolSpec::WebSearch {}]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unsupported operation: the `web_search` tool is not supported for Messages API"
+        );
     }
 }
@@ -3,36 +3,60 @@
 //! used across the core crate.
 
 use crate::config::Config;
+use crate::tokenizer::MAX_BYTES_PER_TOKEN;
+use crate::tokenizer::Tokenizer;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 
-const APPROX_BYTES_PER_TOKEN: usize = 4;
+pub(crate) const APPROX_BYTES_PER_TOKEN: usize = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
     Bytes(usize),
-    Tokens(usize),
+    /// A token limit, counted with the model family's tokenizer.
+    Tokens(usize, Tokenizer),
 }
 
 impl From<TruncationPolicyConfig> for TruncationPolicy {
     fn from(config: TruncationPolicyConfig) -> Self {
         match config.mode {
             TruncationMode::Bytes => Self::Bytes(config.limit as usize),
-            TruncationMode::Tokens => Self::Tokens(config.limit as usize),
+            TruncationMode::Tokens => Self::tokens(config.limit as usize),
         }
     }
 }
 
 impl TruncationPolicy {
+    /// A token limit counted with the byte heuristic.
+    pub const fn tokens(limit: usize) -> Self {
+        Self::Tokens(limit, Tokenizer::Heuristic)
+    }
+
+    /// Count `Tokens` limits with `tokenizer`; `Bytes` limits are unchanged.
+    pub fn with_tokenizer(self, tokenizer: Tokenizer) -> Self {
+        match self {
+            TruncationPolicy::Bytes(bytes) => TruncationPolicy::Bytes(bytes),
+            TruncationPolicy::Tokens(tokens, _) => TruncationPolicy::Tokens(tokens, tokenizer),
+        }
+    }
+
+    /// The tokenizer token budgets are counted with.
+    pub fn tokenizer(&self) -> Tokenizer {
+        match self {
+            TruncationPolicy::Bytes(_) => Tokenizer::Heuristic,
+            TruncationPolicy::Tokens(_, tokenizer) => *tokenizer,
+        }
+    }
+
     /// Scale the underlying budget by `multiplier`, rounding up to avoid under-budgeting.
     pub fn mul(self, multiplier: f64) -> Self {
         match self {
             TruncationPolicy::Bytes(bytes) => {
                 TruncationPolicy::Bytes((bytes as f64 * multiplier).ceil() as usize)
             }
-            TruncationPolicy::Tokens(tokens) => {
-                TruncationPolicy::Tokens((tokens as f64 * multiplier).ceil() as usize)
+            TruncationPolicy::Tokens(tokens, tokenizer) => {
+                TruncationPolicy::Tokens((tokens as f64 * multiplier).ceil() as usize, tokenizer)
             }
         }
     }
@@ -48,11 +72,11 @@ impl TruncationPolicy {
                     Self::Bytes(family_bytes)
                 }
             }
-            TruncationPolicy::Tokens(family_tokens) => {
+            TruncationPolicy::Tokens(family_tokens, tokenizer) => {
                 if let Some(token_limit) = config_token_limit {
-                    Self::Tokens(token_limit)
+                    Self::Tokens(token_limit, tokenizer)
                 } else {
-                    Self::Tokens(family_tokens)
+                    Self::Tokens(family_tokens, tokenizer)
                 }
             }
         }
@@ -68,7 +92,7 @@ impl TruncationPolicy {
             TruncationPolicy::Bytes(bytes) => {
                 usize::try_from(approx_tokens_from_byte_count(*bytes)).unwrap_or(usize::MAX)
             }
-            TruncationPolicy::Tokens(tokens) => *tokens,
+            TruncationPolicy::Tokens(tokens, _) => *tokens,
         }
     }
 
@@ -80,7 +104,7 @@ impl TruncationPolicy {
     pub fn byte_budget(&self) -> usize {
         match self {
             TruncationPolicy::Bytes(bytes) => *bytes,
-            TruncationPolicy::Tokens(tokens) => approx_bytes_for_tokens(*tokens),
+            TruncationPolicy::Tokens(tokens, _) => approx_bytes_for_tokens(*tokens),
         }
     }
 }
@@ -89,15 +113,18 @@ pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -
     if content.len() <= policy.byte_budget() {
         return content.to_string();
     }
-    let total_lines = content.lines().count();
     let result = truncate_text(content, policy);
+    if result == content {
+        return result;
+    }
+    let total_lines = content.lines().count();
     format!("Total output lines: {total_lines}\n\n{result}")
 }
 
 pub(crate) fn truncate_text(content: &str, policy: TruncationPolicy) -> String {
     match policy {
         TruncationPolicy::Bytes(_) => truncate_with_byte_estimate(content, policy),
-        TruncationPolicy::Tokens(_) => {
+        TruncationPolicy::Tokens(..) => {
             let (truncated, _) = truncate_with_token_budget(content, policy);
             truncated
         }
@@ -113,7 +140,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
     let mut out: Vec<FunctionCallOutputContentItem> = Vec::with_capacity(items.len());
     let mut remaining_budget = match policy {
         TruncationPolicy::Bytes(_) => policy.byte_budget(),
-        TruncationPolicy::Tokens(_) => policy.token_budget(),
+        TruncationPolicy::Tokens(..) => policy.token_budget(),
     };
     let mut omitted_text_items = 0usize;
 
@@ -127,7 +154,7 @@ pub(crate) fn truncate_function_output_items_with_policy(
 
                 let cost = match policy {
                     TruncationPolicy::Bytes(_) => text.len(),
-                    TruncationPolicy::Tokens(_) => approx_token_count(text),
+                    TruncationPolicy::Tokens(_, tokenizer) => tokenizer.count(text),
                 };
 
                 if cost <= remaining_budget {
@@ -136,7 +163,9 @@ pub(crate) fn truncate_function_output_items_with_policy(
                 } else {
                     let snippet_policy = match policy {
                         TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(remaining_budget),
-                        TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(remaining_budget),
+                        TruncationPolicy::Tokens(_, tokenizer) => {
+                            TruncationPolicy::Tokens(remaining_budget, tokenizer)
+                        }
                     };
                     let snippet = truncate_text(text, snippet_policy);
                     if snippet.is_empty() {
@@ -173,6 +202,10 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
         return (String::new(), None);
     }
     let max_tokens = policy.token_budget();
+    let tokenizer = policy.tokenizer();
+    if tokenizer != Tokenizer::Heuristic {
+        return truncate_with_tokenizer(s, max_tokens, tokenizer);
+    }
 
     let byte_len = s.len();
     if max_tokens > 0 && byte_len <= approx_bytes_for_tokens(max_tokens) {
@@ -189,6 +222,54 @@ fn truncate_with_token_budget(s: &str, policy: TruncationPolicy) -> (String, Opt
     }
 }
 
+/// Like [`truncate_with_token_budget`], but keeps as many whole tokens of the
+/// beginning and end as `tokenizer` fits in the budget. The number of tokens
+/// removed is exact unless the string is too long to possibly fit, in which
+/// case the middle is not tokenized and its count is estimated.
+fn truncate_with_tokenizer(
+    s: &str,
+    max_tokens: usize,
+    tokenizer: Tokenizer,
+) -> (String, Option<u64>) {
+    // Every token covers at least one byte, so short strings need no counting.
+    if max_tokens > 0 && s.len() <= max_tokens {
+        return (s.to_string(), None);
+    }
+    // Past this length the string cannot fit, so only the kept windows are
+    // tokenized and the removed middle is estimated from its byte length.
+    let exact_total = if s.len() > max_tokens.saturating_mul(MAX_BYTES_PER_TOKEN) {
+        None
+    } else {
+        let total = tokenizer.count(s);
+        if total <= max_tokens {
+            return (s.to_string(), None);
+        }
+        Some(total)
+    };
+
+    let (left_budget, right_budget) = split_budget(max_tokens);
+    let prefix_end = tokenizer.prefix_end(s, left_budget);
+    let suffix_start = tokenizer.suffix_start(s, right_budget).max(prefix_end);
+    let prefix = &s[..prefix_end];
+    let suffix = &s[suffix_start..];
+    let kept = tokenizer
+        .count(prefix)
+        .saturating_add(tokenizer.count(suffix));
+    let removed = match exact_total {
+        Some(total) => total.saturating_sub(kept),
+        None => approx_token_count(&s[prefix_end..suffix_start]),
+    };
+    let total = kept.saturating_add(removed);
+    let marker = format_truncation_marker(
+        TruncationPolicy::Tokens(max_tokens, tokenizer),
+        u64::try_from(removed).unwrap_or(u64::MAX),
+    );
+    (
+        assemble_truncated_output(prefix, suffix, &marker),
+        Some(u64::try_from(total).unwrap_or(u64::MAX)),
+    )
+}
+
 /// Truncate a string using a byte budget derived from the token budget, without
 /// performing any real tokenization. This keeps the logic purely byte-based and
 /// uses a bytes placeholder in the truncated output.
@@ -269,7 +350,7 @@ fn split_string(s: &str, beginning_bytes: usize, end_bytes: usize) -> (usize, &s
 
 fn format_truncation_marker(policy: TruncationPolicy, removed_count: u64) -> String {
     match policy {
-        TruncationPolicy::Tokens(_) => format!("…{removed_count} tokens truncated…"),
+        TruncationPolicy::Tokens(..) => format!("…{removed_count} tokens truncated…"),
         TruncationPolicy::Bytes(_) => format!("…{removed_count} chars truncated…"),
     }
 }
@@ -285,7 +366,7 @@ fn removed_units_for_source(
     removed_chars: usize,
 ) -> u64 {
     match policy {
-        TruncationPolicy::Tokens(_) => approx_tokens_from_byte_count(removed_bytes),
+        TruncationPolicy::Tokens(..) => approx_tokens_from_byte_count(removed_bytes),
         TruncationPolicy::Bytes(_) => u64::try_from(removed_chars).unwrap_or(u64::MAX),
     }
 }
@@ -323,6 +404,7 @@ mod tests {
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
     use super::truncate_with_token_budget;
+    use crate::tokenizer::Tokenizer;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
 
@@ -377,17 +459,34 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 1\n\nex…3 tokens truncated…ut",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(1)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(1)),
         );
     }
 
+    #[test]
+    fn bpe_truncation_of_long_output_tokenizes_only_the_kept_windows() {
+        let content = "word ".repeat(10_000);
+        let policy = TruncationPolicy::Tokens(100, Tokenizer::O200kBase);
+
+        let (truncated, total) = truncate_with_token_budget(&content, policy);
+
+        assert!(truncated.starts_with("word word"), "{truncated}");
+        assert!(truncated.ends_with("word "), "{truncated}");
+        assert!(truncated.contains("tokens truncated"), "{truncated}");
+        assert!(truncated.len() < 1_000, "{truncated}");
+        // The middle is estimated from its byte length, which overcounts
+        // this text's one-token-per-word encoding.
+        let total = total.expect("long output is truncated");
+        assert!(total >= 10_000, "unexpected total {total}");
+    }
+
     #[test]
     fn truncate_tokens_under_limit_returns_original() {
         let content = "example output";
 
         assert_eq!(
             content,
-            formatted_truncate_text(content, TruncationPolicy::Tokens(10)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(10)),
         );
     }
 
@@ -407,7 +506,7 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 1\n\nthis is an…10 tokens truncated… truncated",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(5)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(5)),
         );
     }
 
@@ -439,7 +538,7 @@ mod tests {
 
         assert_eq!(
             "Total output lines: 2\n\nthis is an example o…11 tokens truncated…also some other line",
-            formatted_truncate_text(content, TruncationPolicy::Tokens(10)),
+            formatted_truncate_text(content, TruncationPolicy::tokens(10)),
         );
     }
 
@@ -447,7 +546,7 @@ mod tests {
     fn truncate_with_token_budget_returns_original_when_under_limit() {
         let s = "short output";
         let limit = 100;
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(limit));
+        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::tokens(limit));
         assert_eq!(out, s);
         assert_eq!(original, None);
     }
@@ -455,7 +554,7 @@ mod tests {
     #[test]
     fn truncate_with_token_budget_reports_truncation_at_zero_limit() {
         let s = "abcdef";
-        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::Tokens(0));
+        let (out, original) = truncate_with_token_budget(s, TruncationPolicy::tokens(0));
         assert_eq!(out, "…2 tokens truncated…");
         assert_eq!(original, Some(2));
     }
@@ -463,7 +562,7 @@ mod tests {
     #[test]
     fn truncate_middle_tokens_handles_utf8_content() {
         let s = "😀😀😀😀😀😀😀😀😀😀\nsecond line with text\n";
-        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::Tokens(8));
+        let (out, tokens) = truncate_with_token_budget(s, TruncationPolicy::tokens(8));
         assert_eq!(out, "😀😀😀😀…8 tokens truncated… line with text\n");
         assert_eq!(tokens, Some(16));
     }
@@ -499,7 +598,7 @@ mod tests {
         ];
 
         let output =
-            truncate_function_output_items_with_policy(&items, TruncationPolicy::Tokens(limit));
+            truncate_function_output_items_with_policy(&items, TruncationPolicy::tokens(limit));
 
         // Expect: t1 (full), t2 (full), image, t3 (truncated), summary mentioning 2 omitted.
         assert_eq!(output.len(), 5);
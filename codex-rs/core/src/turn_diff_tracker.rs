@@ -296,6 +296,12 @@ impl TurnDiffTracker {
 
         // Fast path: identical bytes or both missing.
         if left_bytes == right_bytes.as_deref() {
+            // Like git, a mode-only change has no index line or hunks.
+            if left_present && baseline_mode != current_mode {
+                aggregated.push_str(&format!("diff --git a/{left_display} b/{right_display}\n"));
+                aggregated.push_str(&format!("old mode {baseline_mode}\n"));
+                aggregated.push_str(&format!("new mode {current_mode}\n"));
+            }
             return aggregated;
         }
 
@@ -522,6 +528,7 @@ mod tests {
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -552,6 +559,8 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -627,6 +636,8 @@ index {left_oid}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -667,6 +678,8 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -689,6 +702,8 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".into(),
                 move_path: Some(dest.clone()),
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv);
@@ -729,6 +744,8 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_a);
@@ -809,6 +826,8 @@ index {left_oid_b}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -833,6 +852,40 @@ Binary files differ
         assert_eq!(diff, expected);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn mode_only_update_reports_mode_change() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("run.sh");
+        fs::write(&file, "echo hi\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        let update_changes = HashMap::from([(
+            file.clone(),
+            FileChange::Update {
+                unified_diff: "".to_owned(),
+                move_path: None,
+                fuzz_score: None,
+                mode: Some(0o755),
+            },
+        )]);
+        acc.on_patch_begin(&update_changes);
+
+        // Apply the mode change on disk
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        let expected = r#"diff --git a/<TMP>/run.sh b/<TMP>/run.sh
+old mode 100644
+new mode 100755
+"#;
+        assert_eq!(diff, expected);
+    }
+
     #[test]
     fn filenames_with_spaces_add_and_update() {
         let mut acc = TurnDiffTracker::new();
@@ -845,6 +898,7 @@ Binary files differ
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -875,6 +929,8 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -8,6 +8,10 @@ pub(crate) enum UnifiedExecError {
     // Called "session" in the model's training.
     #[error("Unknown session id {process_id}")]
     UnknownSessionId { process_id: String },
+    #[error("Unknown session name {name}")]
+    UnknownSessionName { name: String },
+    #[error("a running session is already named {name}")]
+    DuplicateSessionName { name: String },
     #[error("failed to write to stdin")]
     WriteToStdin,
     #[error("missing command line for unified exec request")]
@@ -26,6 +26,7 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::SystemTime;
 
 use rand::Rng;
 use rand::rng;
@@ -96,6 +97,8 @@ pub(crate) struct ExecCommandRequest {
     pub workdir: Option<PathBuf>,
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
+    /// Name the model gave the session so it can find it again later.
+    pub session_name: Option<String>,
 }
 
 #[derive(Debug)]
@@ -118,6 +121,7 @@ pub(crate) struct UnifiedExecResponse {
     pub exit_code: Option<i32>,
     pub original_token_count: Option<usize>,
     pub session_command: Option<Vec<String>>,
+    pub session_name: Option<String>,
 }
 
 #[derive(Default)]
@@ -131,6 +135,12 @@ impl SessionStore {
         self.reserved_sessions_id.remove(session_id);
         self.sessions.remove(session_id)
     }
+
+    fn find_by_name(&self, name: &str) -> Option<&SessionEntry> {
+        self.sessions
+            .values()
+            .find(|entry| entry.name.as_deref() == Some(name) && !entry.session.has_exited())
+    }
 }
 
 pub(crate) struct UnifiedExecSessionManager {
@@ -152,6 +162,9 @@ struct SessionEntry {
     call_id: String,
     process_id: String,
     command: Vec<String>,
+    name: Option<String>,
+    cwd: PathBuf,
+    started_at: SystemTime,
     last_used: tokio::time::Instant,
 }
 
@@ -199,6 +212,16 @@ mod tests {
         turn: &Arc<TurnContext>,
         cmd: &str,
         yield_time_ms: u64,
+    ) -> Result<UnifiedExecResponse, UnifiedExecError> {
+        exec_named_command(session, turn, cmd, yield_time_ms, None).await
+    }
+
+    async fn exec_named_command(
+        session: &Arc<Session>,
+        turn: &Arc<TurnContext>,
+        cmd: &str,
+        yield_time_ms: u64,
+        session_name: Option<&str>,
     ) -> Result<UnifiedExecResponse, UnifiedExecError> {
         let context =
             UnifiedExecContext::new(Arc::clone(session), Arc::clone(turn), "call".to_string());
@@ -220,6 +243,7 @@ mod tests {
                     workdir: None,
                     sandbox_permissions: SandboxPermissions::UseDefault,
                     justification: None,
+                    session_name: session_name.map(str::to_string),
                 },
                 &context,
             )
@@ -475,4 +499,57 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn named_sessions_can_be_listed_read_and_killed() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn();
+        let manager = &session.services.unified_exec_manager;
+
+        let server = exec_named_command(
+            &session,
+            &turn,
+            "echo listening; cat",
+            2_500,
+            Some("dev-server"),
+        )
+        .await?;
+        let process_id = server.process_id.clone().expect("expected process id");
+        assert_eq!(server.session_name.as_deref(), Some("dev-server"));
+        assert_eq!(manager.process_id_for_name("dev-server").await?, process_id);
+
+        let duplicate = exec_named_command(&session, &turn, "cat", 1_000, Some("dev-server")).await;
+        assert!(matches!(
+            duplicate,
+            Err(UnifiedExecError::DuplicateSessionName { .. })
+        ));
+
+        let sessions = manager.list_sessions().await;
+        assert_eq!(sessions.len(), 1);
+        let info = &sessions[0];
+        assert_eq!(info.process_id, process_id);
+        assert_eq!(info.name.as_deref(), Some("dev-server"));
+        assert!(info.pid.is_some());
+        assert_eq!(info.last_output.as_deref(), Some("listening"));
+
+        manager.write_to_session(&process_id, "ping\n").await?;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let output = manager.session_output(&process_id).await?;
+        assert!(output.contains("listening"), "output: {output:?}");
+        assert!(output.contains("ping"), "output: {output:?}");
+
+        manager.kill_session(&process_id).await?;
+        assert!(manager.list_sessions().await.is_empty());
+        assert!(matches!(
+            manager.process_id_for_name("dev-server").await,
+            Err(UnifiedExecError::UnknownSessionName { .. })
+        ));
+        assert!(matches!(
+            manager.kill_session(&process_id).await,
+            Err(UnifiedExecError::UnknownSessionId { .. })
+        ));
+
+        Ok(())
+    }
 }
@@ -19,6 +19,7 @@ use crate::truncate::formatted_truncate_text;
 use codex_utils_pty::ExecCommandSession;
 use codex_utils_pty::SpawnedPty;
 
+use super::CommandTranscript;
 use super::UNIFIED_EXEC_OUTPUT_MAX_BYTES;
 use super::UNIFIED_EXEC_OUTPUT_MAX_TOKENS;
 use super::UnifiedExecError;
@@ -81,6 +82,8 @@ pub(crate) struct UnifiedExecSession {
     cancellation_token: CancellationToken,
     output_drained: Arc<Notify>,
     output_task: JoinHandle<()>,
+    /// Everything the command printed, including output polled by the model.
+    transcript: Arc<Mutex<CommandTranscript>>,
     sandbox_type: SandboxType,
 }
 
@@ -94,13 +97,16 @@ impl UnifiedExecSession {
         let output_notify = Arc::new(Notify::new());
         let cancellation_token = CancellationToken::new();
         let output_drained = Arc::new(Notify::new());
+        let transcript = Arc::new(Mutex::new(CommandTranscript::default()));
         let mut receiver = initial_output_rx;
         let buffer_clone = Arc::clone(&output_buffer);
         let notify_clone = Arc::clone(&output_notify);
+        let transcript_clone = Arc::clone(&transcript);
         let output_task = tokio::spawn(async move {
             loop {
                 match receiver.recv().await {
                     Ok(chunk) => {
+                        transcript_clone.lock().await.append(&chunk);
                         let mut guard = buffer_clone.lock().await;
                         guard.push_chunk(chunk);
                         drop(guard);
@@ -119,6 +125,7 @@ impl UnifiedExecSession {
             cancellation_token,
             output_drained,
             output_task,
+            transcript,
             sandbox_type,
         }
     }
@@ -155,6 +162,14 @@ impl UnifiedExecSession {
         self.session.exit_code()
     }
 
+    pub(super) fn pid(&self) -> Option<u32> {
+        self.session.pid()
+    }
+
+    pub(super) fn transcript(&self) -> Arc<Mutex<CommandTranscript>> {
+        Arc::clone(&self.transcript)
+    }
+
     pub(super) fn terminate(&self) {
         self.session.terminate();
         self.cancellation_token.cancel();
@@ -205,7 +220,7 @@ impl UnifiedExecSession {
         if is_likely_sandbox_denied(sandbox_type, &exec_output) {
             let snippet = formatted_truncate_text(
                 text,
-                TruncationPolicy::Tokens(UNIFIED_EXEC_OUTPUT_MAX_TOKENS),
+                TruncationPolicy::tokens(UNIFIED_EXEC_OUTPUT_MAX_TOKENS),
             );
             let message = if snippet.is_empty() {
                 format!("Session exited with code {exit_code}")
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
@@ -17,6 +19,7 @@ use crate::exec_env::create_env;
 use crate::exec_policy::create_exec_approval_requirement_for_command;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::EventMsg;
+use crate::protocol::ExecSessionInfo;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::orchestrator::ToolOrchestrator;
@@ -24,7 +27,6 @@ use crate::tools::runtimes::unified_exec::UnifiedExecRequest as UnifiedExecToolR
 use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
 use crate::tools::sandboxing::ToolCtx;
 use crate::truncate::TruncationPolicy;
-use crate::truncate::approx_token_count;
 use crate::truncate::formatted_truncate_text;
 
 use super::CommandTranscript;
@@ -75,8 +77,12 @@ struct PreparedSessionHandles {
     turn_ref: Arc<TurnContext>,
     command: Vec<String>,
     process_id: String,
+    name: Option<String>,
 }
 
+/// Longest `last_output` line reported by [`UnifiedExecSessionManager::list_sessions`].
+const LAST_OUTPUT_MAX_CHARS: usize = 200;
+
 impl UnifiedExecSessionManager {
     pub(crate) async fn allocate_process_id(&self) -> String {
         loop {
@@ -122,6 +128,13 @@ impl UnifiedExecSessionManager {
             .clone()
             .unwrap_or_else(|| context.turn.cwd.clone());
 
+        if let Some(name) = &request.session_name
+            && self.session_store.lock().await.find_by_name(name).is_some()
+        {
+            self.release_process_id(&request.process_id).await;
+            return Err(UnifiedExecError::DuplicateSessionName { name: name.clone() });
+        }
+
         let session = self
             .open_session_with_sandbox(
                 &request.command,
@@ -166,7 +179,9 @@ impl UnifiedExecSessionManager {
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
+        let tokenizer = context.turn.client.get_tokenizer();
+        let output =
+            formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens, tokenizer));
         let exit_code = session.exit_code();
         let has_exited = session.has_exited() || exit_code.is_some();
         let chunk_id = generate_chunk_id();
@@ -204,6 +219,7 @@ impl UnifiedExecSessionManager {
                 cwd.clone(),
                 start,
                 process_id,
+                request.session_name.clone(),
                 Arc::clone(&transcript),
             )
             .await;
@@ -211,7 +227,7 @@ impl UnifiedExecSessionManager {
             Self::emit_waiting_status(&context.session, &context.turn, &request.command).await;
         };
 
-        let original_token_count = approx_token_count(&text);
+        let original_token_count = tokenizer.count(&text);
         let response = UnifiedExecResponse {
             event_call_id: context.call_id.clone(),
             chunk_id,
@@ -226,6 +242,7 @@ impl UnifiedExecSessionManager {
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(request.command.clone()),
+            session_name: request.session_name,
         };
 
         Ok(response)
@@ -246,7 +263,7 @@ impl UnifiedExecSessionManager {
             turn_ref,
             command: session_command,
             process_id,
-            ..
+            name: session_name,
         } = self.prepare_session_handles(process_id.as_str()).await?;
 
         if !request.input.is_empty() {
@@ -270,8 +287,10 @@ impl UnifiedExecSessionManager {
         let wall_time = Instant::now().saturating_duration_since(start);
 
         let text = String::from_utf8_lossy(&collected).to_string();
-        let output = formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens));
-        let original_token_count = approx_token_count(&text);
+        let tokenizer = turn_ref.client.get_tokenizer();
+        let output =
+            formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens, tokenizer));
+        let original_token_count = tokenizer.count(&text);
         let chunk_id = generate_chunk_id();
 
         // After polling, refresh_session_state tells us whether the PTY is
@@ -306,6 +325,7 @@ impl UnifiedExecSessionManager {
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(session_command.clone()),
+            session_name,
         };
 
         if response.process_id.is_some() {
@@ -369,6 +389,7 @@ impl UnifiedExecSessionManager {
             turn_ref: Arc::clone(&entry.turn_ref),
             command: entry.command.clone(),
             process_id: entry.process_id.clone(),
+            name: entry.name.clone(),
         })
     }
 
@@ -391,6 +412,7 @@ impl UnifiedExecSessionManager {
         cwd: PathBuf,
         started_at: Instant,
         process_id: String,
+        name: Option<String>,
         transcript: Arc<tokio::sync::Mutex<CommandTranscript>>,
     ) {
         let entry = SessionEntry {
@@ -400,6 +422,11 @@ impl UnifiedExecSessionManager {
             call_id: context.call_id.clone(),
             process_id: process_id.clone(),
             command: command.to_vec(),
+            name,
+            cwd: cwd.clone(),
+            started_at: SystemTime::now()
+                .checked_sub(started_at.elapsed())
+                .unwrap_or_else(SystemTime::now),
             last_used: started_at,
         };
         let number_sessions = {
@@ -630,6 +657,103 @@ impl UnifiedExecSessionManager {
             .map(|(process_id, _, _)| process_id)
     }
 
+    /// Resolves a session name given to `exec_command` to its process id.
+    pub(crate) async fn process_id_for_name(&self, name: &str) -> Result<String, UnifiedExecError> {
+        let store = self.session_store.lock().await;
+        store
+            .find_by_name(name)
+            .map(|entry| entry.process_id.clone())
+            .ok_or_else(|| UnifiedExecError::UnknownSessionName {
+                name: name.to_string(),
+            })
+    }
+
+    /// Sessions whose command is still running, oldest first.
+    pub(crate) async fn list_sessions(&self) -> Vec<ExecSessionInfo> {
+        let entries: Vec<(ExecSessionInfo, Arc<tokio::sync::Mutex<CommandTranscript>>)> = {
+            let store = self.session_store.lock().await;
+            store
+                .sessions
+                .values()
+                .filter(|entry| !entry.session.has_exited())
+                .map(|entry| {
+                    let started_at = entry
+                        .started_at
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs() as i64)
+                        .unwrap_or_default();
+                    let info = ExecSessionInfo {
+                        process_id: entry.process_id.clone(),
+                        name: entry.name.clone(),
+                        command: entry.command.clone(),
+                        cwd: entry.cwd.clone(),
+                        pid: entry.session.pid(),
+                        started_at,
+                        last_output: None,
+                    };
+                    (info, entry.session.transcript())
+                })
+                .collect()
+        };
+
+        let mut sessions = Vec::with_capacity(entries.len());
+        for (mut info, transcript) in entries {
+            info.last_output = last_output_line(&transcript.lock().await.data);
+            sessions.push(info);
+        }
+        sessions.sort_by(|a, b| {
+            a.started_at
+                .cmp(&b.started_at)
+                .then_with(|| a.process_id.cmp(&b.process_id))
+        });
+        sessions
+    }
+
+    /// Everything the session has printed so far, up to
+    /// `UNIFIED_EXEC_OUTPUT_MAX_BYTES`.
+    pub(crate) async fn session_output(
+        &self,
+        process_id: &str,
+    ) -> Result<String, UnifiedExecError> {
+        let transcript = {
+            let store = self.session_store.lock().await;
+            let entry = store.sessions.get(process_id).ok_or_else(|| {
+                UnifiedExecError::UnknownSessionId {
+                    process_id: process_id.to_string(),
+                }
+            })?;
+            entry.session.transcript()
+        };
+        let transcript = transcript.lock().await;
+        Ok(String::from_utf8_lossy(&transcript.data).into_owned())
+    }
+
+    /// Writes `input` on behalf of the user. Unlike `write_stdin`, the output
+    /// is left in the buffer for the model's next poll.
+    pub(crate) async fn write_to_session(
+        &self,
+        process_id: &str,
+        input: &str,
+    ) -> Result<(), UnifiedExecError> {
+        let handles = self.prepare_session_handles(process_id).await?;
+        Self::send_input(&handles.writer_tx, input.as_bytes()).await
+    }
+
+    /// Terminates one session. Its exit watcher still reports the end of the
+    /// command.
+    pub(crate) async fn kill_session(&self, process_id: &str) -> Result<(), UnifiedExecError> {
+        let entry = self
+            .session_store
+            .lock()
+            .await
+            .remove(process_id)
+            .ok_or_else(|| UnifiedExecError::UnknownSessionId {
+                process_id: process_id.to_string(),
+            })?;
+        entry.session.terminate();
+        Ok(())
+    }
+
     pub(crate) async fn terminate_all_sessions(&self) {
         let entries: Vec<SessionEntry> = {
             let mut sessions = self.session_store.lock().await;
@@ -645,6 +769,22 @@ impl UnifiedExecSessionManager {
     }
 }
 
+/// The last non-empty line of `data`, shortened to `LAST_OUTPUT_MAX_CHARS`.
+fn last_output_line(data: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(data);
+    let line = text
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    if line.chars().count() <= LAST_OUTPUT_MAX_CHARS {
+        return Some(line.to_string());
+    }
+    let mut shortened: String = line.chars().take(LAST_OUTPUT_MAX_CHARS).collect();
+    shortened.push('…');
+    Some(shortened)
+}
+
 enum SessionStatus {
     Alive {
         exit_code: Option<i32>,
@@ -694,6 +834,20 @@ mod tests {
         assert_eq!(env.get("PATH"), Some(&"/usr/bin".to_string()));
     }
 
+    #[test]
+    fn last_output_line_skips_trailing_blank_lines() {
+        assert_eq!(
+            last_output_line(b"compiling\r\nready on :3000\r\n\r\n"),
+            Some("ready on :3000".to_string())
+        );
+        assert_eq!(last_output_line(b"\n  \n"), None);
+
+        let long = "x".repeat(LAST_OUTPUT_MAX_CHARS + 10);
+        let shortened = last_output_line(long.as_bytes()).expect("line");
+        assert_eq!(shortened.chars().count(), LAST_OUTPUT_MAX_CHARS + 1);
+        assert!(shortened.ends_with('…'));
+    }
+
     #[test]
     fn pruning_prefers_exited_sessions_outside_recently_used() {
         let now = Instant::now();
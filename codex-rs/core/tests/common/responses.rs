@@ -545,6 +545,80 @@ pub fn sse_failed(id: &str, code: &str, message: &str) -> String {
     })])
 }
 
+/// Build an Anthropic Messages SSE stream body from a list of JSON events.
+///
+/// Unlike [`sse`], every event carries its payload as `data`, including the
+/// single-field `message_stop` event.
+pub fn sse_messages(events: Vec<Value>) -> String {
+    use std::fmt::Write as _;
+    let mut out = String::new();
+    for ev in events {
+        let kind = ev.get("type").and_then(|v| v.as_str()).unwrap();
+        write!(&mut out, "event: {kind}\ndata: {ev}\n\n").unwrap();
+    }
+    out
+}
+
+/// Convenience: Messages `message_start` event with a specific id.
+pub fn ev_messages_start(id: &str) -> Value {
+    serde_json::json!({
+        "type": "message_start",
+        "message": {
+            "id": id,
+            "type": "message",
+            "role": "assistant",
+            "content": [],
+            "usage": {"input_tokens": 0, "output_tokens": 0}
+        }
+    })
+}
+
+/// Convenience: the start/delta/stop events for a single Messages text block.
+pub fn ev_messages_text_block(index: u64, text: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "text", "text": ""}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "text_delta", "text": text}
+        }),
+        serde_json::json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+/// Convenience: the start/delta/stop events for a single Messages `tool_use` block.
+pub fn ev_messages_tool_use_block(index: u64, id: &str, name: &str, input: &Value) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "content_block_start",
+            "index": index,
+            "content_block": {"type": "tool_use", "id": id, "name": name, "input": {}}
+        }),
+        serde_json::json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": {"type": "input_json_delta", "partial_json": input.to_string()}
+        }),
+        serde_json::json!({"type": "content_block_stop", "index": index}),
+    ]
+}
+
+/// Convenience: the closing `message_delta` and `message_stop` events.
+pub fn ev_messages_stop(stop_reason: &str) -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "message_delta",
+            "delta": {"stop_reason": stop_reason},
+            "usage": {"output_tokens": 0}
+        }),
+        serde_json::json!({"type": "message_stop"}),
+    ]
+}
+
 pub fn sse_response(body: String) -> ResponseTemplate {
     ResponseTemplate::new(200)
         .insert_header("content-type", "text/event-stream")
@@ -585,6 +659,14 @@ fn base_mock() -> (MockBuilder, ResponseMock) {
     (mock, response_mock)
 }
 
+fn messages_mock() -> (MockBuilder, ResponseMock) {
+    let response_mock = ResponseMock::new();
+    let mock = Mock::given(method("POST"))
+        .and(path_regex(".*/messages$"))
+        .and(response_mock.clone());
+    (mock, response_mock)
+}
+
 fn compact_mock() -> (MockBuilder, ResponseMock) {
     let response_mock = ResponseMock::new();
     let mock = Mock::given(method("POST"))
@@ -623,6 +705,15 @@ pub async fn mount_sse_once(server: &MockServer, body: String) -> ResponseMock {
     response_mock
 }
 
+pub async fn mount_messages_sse_once(server: &MockServer, body: String) -> ResponseMock {
+    let (mock, response_mock) = messages_mock();
+    mock.respond_with(sse_response(body))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+    response_mock
+}
+
 pub async fn mount_compact_json_once_match<M>(
     server: &MockServer,
     matcher: M,
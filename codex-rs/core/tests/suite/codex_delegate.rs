@@ -62,7 +62,7 @@ async fn codex_delegate_forwards_exec_approval_and_proceeds_on_approval() {
     // routes ExecApprovalRequest via the parent.
     let mut builder = test_codex().with_model("gpt-5.1").with_config(|config| {
         config.approval_policy = AskForApproval::OnRequest;
-        config.sandbox_policy = SandboxPolicy::ReadOnly;
+        config.sandbox_policy = SandboxPolicy::new_read_only_policy();
     });
     let test = builder.build(&server).await.expect("build test codex");
 
@@ -74,6 +74,7 @@ async fn codex_delegate_forwards_exec_approval_and_proceeds_on_approval() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -139,7 +140,7 @@ async fn codex_delegate_forwards_patch_approval_and_proceeds_on_decision() {
     let mut builder = test_codex().with_model("gpt-5.1").with_config(|config| {
         config.approval_policy = AskForApproval::OnRequest;
         // Use a restricted sandbox so patch approval is required
-        config.sandbox_policy = SandboxPolicy::ReadOnly;
+        config.sandbox_policy = SandboxPolicy::new_read_only_policy();
         config.include_apply_patch_tool = true;
     });
     let test = builder.build(&server).await.expect("build test codex");
@@ -151,6 +152,7 @@ async fn codex_delegate_forwards_patch_approval_and_proceeds_on_decision() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -207,6 +209,7 @@ async fn codex_delegate_ignores_legacy_deltas() {
                     instructions: "Please review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
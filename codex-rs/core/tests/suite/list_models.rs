@@ -89,6 +89,8 @@ fn gpt_5_1_codex_max() -> ModelPreset {
         is_default: true,
         upgrade: None,
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     }
 }
 
@@ -116,6 +118,8 @@ fn gpt_5_1_codex() -> ModelPreset {
         is_default: false,
         upgrade: Some(gpt_5_1_codex_max_upgrade()),
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     }
 }
 
@@ -139,6 +143,8 @@ fn gpt_5_1_codex_mini() -> ModelPreset {
         is_default: false,
         upgrade: Some(gpt_5_1_codex_max_upgrade()),
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     }
 }
 
@@ -172,6 +178,8 @@ fn gpt_5_2() -> ModelPreset {
         is_default: false,
         upgrade: None,
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     }
 }
 
@@ -199,6 +207,8 @@ fn gpt_5_1() -> ModelPreset {
         is_default: false,
         upgrade: Some(gpt_5_1_codex_max_upgrade()),
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     }
 }
 
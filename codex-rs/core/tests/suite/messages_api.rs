@@ -0,0 +1,124 @@
+use codex_core::WireApi;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_messages_start;
+use core_test_support::responses::ev_messages_stop;
+use core_test_support::responses::ev_messages_text_block;
+use core_test_support::responses::ev_messages_tool_use_block;
+use core_test_support::responses::mount_messages_sse_once;
+use core_test_support::responses::sse_messages;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn messages_wire_api_round_trips_tool_use() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.model_provider.wire_api = WireApi::Messages;
+    });
+    let TestCodex { codex, .. } = builder.build(&server).await?;
+
+    let plan = json!({"plan": [{"step": "inspect", "status": "in_progress"}]});
+    let mut first = vec![ev_messages_start("msg_1")];
+    first.extend(ev_messages_text_block(0, "Planning first."));
+    first.extend(ev_messages_tool_use_block(
+        1,
+        "toolu_1",
+        "update_plan",
+        &plan,
+    ));
+    first.extend(ev_messages_stop("tool_use"));
+    let first_mock = mount_messages_sse_once(&server, sse_messages(first)).await;
+
+    let mut second = vec![ev_messages_start("msg_2")];
+    second.extend(ev_messages_text_block(0, "All done."));
+    second.extend(ev_messages_stop("end_turn"));
+    let second_mock = mount_messages_sse_once(&server, sse_messages(second)).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "make a plan".into(),
+            }],
+        })
+        .await?;
+
+    let plan_update = wait_for_event(&codex, |ev| matches!(ev, EventMsg::PlanUpdate(_))).await;
+    let EventMsg::PlanUpdate(plan_update) = plan_update else {
+        unreachable!("matched above");
+    };
+    assert_eq!(plan_update.plan.len(), 1);
+
+    let last_message = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(complete) = last_message else {
+        unreachable!("matched above");
+    };
+    assert_eq!(complete.last_agent_message.as_deref(), Some("All done."));
+
+    let first_request = first_mock.single_request();
+    assert_eq!(first_request.path(), "/v1/messages");
+    assert_eq!(
+        first_request.header("anthropic-version").as_deref(),
+        Some("2023-06-01")
+    );
+    let body = first_request.body_json();
+    assert_eq!(body["stream"], json!(true));
+    assert!(body["system"].as_str().is_some_and(|s| !s.is_empty()));
+    let tools = body["tools"].as_array().expect("tools array");
+    let update_plan = tools
+        .iter()
+        .find(|tool| tool["name"] == "update_plan")
+        .expect("update_plan tool");
+    assert_eq!(update_plan["input_schema"]["type"], "object");
+    let last_user = body["messages"]
+        .as_array()
+        .and_then(|messages| messages.last())
+        .expect("user message");
+    assert_eq!(last_user["role"], "user");
+    assert!(
+        last_user["content"]
+            .as_array()
+            .expect("content blocks")
+            .iter()
+            .any(|block| block["text"] == "make a plan")
+    );
+
+    let body = second_mock.single_request().body_json();
+    let messages = body["messages"].as_array().expect("messages array");
+    let (assistant, tool_result) = match &messages[messages.len() - 2..] {
+        [assistant, tool_result] => (assistant, tool_result),
+        other => panic!("unexpected trailing messages: {other:?}"),
+    };
+    assert_eq!(
+        assistant,
+        &json!({
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Planning first."},
+                {"type": "tool_use", "id": "toolu_1", "name": "update_plan", "input": plan},
+            ],
+        })
+    );
+    assert_eq!(tool_result["role"], "user");
+    let result_block = &tool_result["content"][0];
+    assert_eq!(result_block["type"], "tool_result");
+    assert_eq!(result_block["tool_use_id"], "toolu_1");
+    assert!(
+        result_block["content"]
+            .as_str()
+            .is_some_and(|s| !s.is_empty())
+    );
+    assert_eq!(result_block.get("is_error"), None::<&Value>);
+
+    Ok(())
+}
@@ -35,10 +35,13 @@ mod json_result;
 mod list_dir;
 mod list_models;
 mod live_cli;
+mod messages_api;
+mod model_cassette;
 mod model_overrides;
 mod model_tools;
 mod otel;
 mod prompt_caching;
+mod provider_fallback;
 mod quota_exceeded;
 mod read_file;
 mod remote_models;
@@ -58,6 +61,7 @@ mod tool_harness;
 mod tool_parallelism;
 mod tools;
 mod truncation;
+mod turn_cost;
 mod undo;
 mod unified_exec;
 mod user_notification;
@@ -0,0 +1,81 @@
+use codex_core::config::types::ModelCassette;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+async fn run_turn(codex: &codex_core::CodexConversation) -> anyhow::Result<Option<String>> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "say hello".into(),
+            }],
+        })
+        .await?;
+    let complete = wait_for_event(codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(complete) = complete else {
+        unreachable!("matched above");
+    };
+    Ok(complete.last_agent_message)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replayed_session_does_not_reach_the_provider() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let cassette_dir = tempfile::tempdir()?;
+    let cassette = cassette_dir.path().join("session.jsonl");
+
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "hello from the recording"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let record_path = cassette.clone();
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_cassette = Some(ModelCassette::Record(record_path));
+        })
+        .build(&server)
+        .await?;
+    assert_eq!(
+        run_turn(&codex).await?.as_deref(),
+        Some("hello from the recording")
+    );
+    let recorded_requests = server.received_requests().await.unwrap_or_default().len();
+    assert!(std::fs::read_to_string(&cassette)?.contains("hello from the recording"));
+
+    let replay_path = cassette.clone();
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_cassette = Some(ModelCassette::Replay(replay_path));
+        })
+        .build(&server)
+        .await?;
+    assert_eq!(
+        run_turn(&codex).await?.as_deref(),
+        Some("hello from the recording")
+    );
+    assert_eq!(
+        server.received_requests().await.unwrap_or_default().len(),
+        recorded_requests
+    );
+
+    Ok(())
+}
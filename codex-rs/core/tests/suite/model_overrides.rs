@@ -3,7 +3,17 @@ use codex_core::ConversationManager;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::user_input::UserInput;
 use core_test_support::load_default_config_for_test;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
 use core_test_support::wait_for_event;
 use pretty_assertions::assert_eq;
 use tempfile::TempDir;
@@ -40,6 +50,7 @@ async fn override_turn_context_does_not_persist_when_config_exists() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
             summary: None,
+            model_provider: None,
         })
         .await
         .expect("submit override");
@@ -82,6 +93,7 @@ async fn override_turn_context_does_not_create_config_file() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::Medium)),
             summary: None,
+            model_provider: None,
         })
         .await
         .expect("submit override");
@@ -94,3 +106,95 @@ async fn override_turn_context_does_not_create_config_file() {
         "override should not create config.toml"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn override_turn_context_switches_model_provider() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let primary = start_mock_server().await;
+    let local = start_mock_server().await;
+    let local_mock = mount_sse_once(
+        &local,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "answered locally"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let local_uri = local.uri();
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            let mut local_provider = config.model_provider.clone();
+            local_provider.name = "local".to_string();
+            local_provider.base_url = Some(format!("{local_uri}/v1"));
+            config
+                .model_providers
+                .insert("local".to_string(), local_provider);
+        })
+        .build(&primary)
+        .await?;
+
+    codex
+        .submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: Some("qwen3-coder:30b".to_string()),
+            effort: None,
+            summary: None,
+            model_provider: Some("local".to_string()),
+        })
+        .await?;
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    let complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(complete) = complete else {
+        unreachable!("matched above");
+    };
+
+    assert_eq!(
+        complete.last_agent_message.as_deref(),
+        Some("answered locally")
+    );
+    assert_eq!(
+        local_mock.single_request().body_json()["model"],
+        "qwen3-coder:30b"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn override_turn_context_rejects_unknown_model_provider() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let TestCodex { codex, .. } = test_codex().build(&server).await?;
+
+    codex
+        .submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: None,
+            effort: None,
+            summary: None,
+            model_provider: Some("missing".to_string()),
+        })
+        .await?;
+    let error = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+    let EventMsg::Error(error) = error else {
+        unreachable!("matched above");
+    };
+
+    assert_eq!(error.message, "Unknown model provider `missing`.");
+
+    Ok(())
+}
@@ -322,10 +322,13 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() -> an
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                network_allowlist: vec![],
+                read_deny: vec![],
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
             summary: Some(ReasoningSummary::Detailed),
+            model_provider: None,
         })
         .await?;
 
@@ -405,6 +408,7 @@ async fn override_before_first_turn_emits_environment_context() -> anyhow::Resul
             model: None,
             effort: None,
             summary: None,
+            model_provider: None,
         })
         .await?;
 
@@ -512,6 +516,8 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                network_allowlist: vec![],
+                read_deny: vec![],
             },
             model: "o3".to_string(),
             effort: Some(ReasoningEffort::High),
@@ -0,0 +1,114 @@
+use codex_core::config::types::FallbackProvider;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::config_types::FallbackTrigger;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path_regex;
+
+async fn submit_and_wait(codex: &codex_core::CodexConversation, text: &str) -> Option<String> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text { text: text.into() }],
+        })
+        .await
+        .expect("submit");
+    let complete = wait_for_event(codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(complete) = complete else {
+        unreachable!("matched above");
+    };
+    complete.last_agent_message
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn server_errors_switch_to_fallback_provider_for_rest_of_session() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let primary = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path_regex(".*/responses$"))
+        .respond_with(ResponseTemplate::new(502).set_body_string("bad gateway"))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    let backup = start_mock_server().await;
+    let reply = |id: &str, text: &str| {
+        sse(vec![
+            ev_response_created(id),
+            ev_assistant_message(&format!("msg-{id}"), text),
+            ev_completed(id),
+        ])
+    };
+    let backup_mock = mount_sse_sequence(
+        &backup,
+        vec![
+            reply("resp-1", "answered by backup"),
+            reply("resp-2", "still on backup"),
+        ],
+    )
+    .await;
+
+    let backup_uri = backup.uri();
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_provider.request_max_retries = Some(0);
+            config.model_provider.stream_max_retries = Some(0);
+            let mut backup_provider = config.model_provider.clone();
+            backup_provider.name = "backup".to_string();
+            backup_provider.base_url = Some(format!("{backup_uri}/v1"));
+            config.fallback_providers = vec![FallbackProvider {
+                model_provider_id: "backup".to_string(),
+                model_provider: backup_provider,
+                model: Some("gpt-5.1".to_string()),
+            }];
+        })
+        .build(&primary)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    let fallback = wait_for_event(&codex, |ev| matches!(ev, EventMsg::ProviderFallback(_))).await;
+    let EventMsg::ProviderFallback(fallback) = fallback else {
+        unreachable!("matched above");
+    };
+    assert_eq!(fallback.to_provider, "backup");
+    assert_eq!(fallback.to_model, "gpt-5.1");
+    assert_eq!(fallback.reason, FallbackTrigger::ServerError);
+    let complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let EventMsg::TaskComplete(complete) = complete else {
+        unreachable!("matched above");
+    };
+    assert_eq!(
+        complete.last_agent_message.as_deref(),
+        Some("answered by backup")
+    );
+
+    assert_eq!(
+        submit_and_wait(&codex, "again").await.as_deref(),
+        Some("still on backup")
+    );
+    let requests = backup_mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body_json()["model"], "gpt-5.1");
+
+    Ok(())
+}
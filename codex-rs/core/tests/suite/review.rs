@@ -87,6 +87,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
                     instructions: "Please review my changes".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -207,6 +208,7 @@ async fn review_op_with_plain_text_emits_review_fallback() {
                     instructions: "Plain text review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -267,6 +269,7 @@ async fn review_filters_agent_message_related_events() {
                     instructions: "Filter streaming events".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -348,6 +351,7 @@ async fn review_does_not_emit_agent_message_on_structured_output() {
                     instructions: "check structured".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -407,6 +411,7 @@ async fn review_uses_custom_review_model_from_config() {
                     instructions: "use custom model".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -418,7 +423,8 @@ async fn review_uses_custom_review_model_from_config() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: None
+                review_output: None,
+                ..
             })
         )
     })
@@ -529,6 +535,7 @@ async fn review_input_isolated_from_parent_history() {
                     instructions: review_prompt.clone(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -539,7 +546,8 @@ async fn review_input_isolated_from_parent_history() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: None
+                review_output: None,
+                ..
             })
         )
     })
@@ -649,6 +657,7 @@ async fn review_history_surfaces_in_parent_session() {
                     instructions: "Start a review".to_string(),
                 },
                 user_facing_hint: None,
+                paths: Vec::new(),
             },
         })
         .await
@@ -658,7 +667,8 @@ async fn review_history_surfaces_in_parent_session() {
         matches!(
             ev,
             EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
-                review_output: Some(_)
+                review_output: Some(_),
+                ..
             })
         )
     })
@@ -716,6 +726,110 @@ async fn review_history_surfaces_in_parent_session() {
     server.verify().await;
 }
 
+/// Fixing review findings starts a regular turn seeded with the selected
+/// findings and records which findings it addresses.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fix_review_findings_starts_turn_with_selected_findings() {
+    skip_if_no_network!();
+
+    let review_json = serde_json::json!({
+        "findings": [
+            {
+                "title": "Unchecked unwrap",
+                "body": "This panics on malformed input.",
+                "confidence_score": 0.8,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": "/tmp/parse.rs",
+                    "line_range": {"start": 3, "end": 4}
+                }
+            }
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "One real bug.",
+        "overall_confidence_score": 0.7
+    })
+    .to_string();
+    let sse_template = r#"[
+            {"type":"response.output_item.done", "item":{
+                "type":"message", "role":"assistant",
+                "content":[{"type":"output_text","text":__REVIEW__}]
+            }},
+            {"type":"response.completed", "response": {"id": "__ID__"}}
+        ]"#;
+    let review_json_escaped = serde_json::to_string(&review_json).unwrap();
+    let sse_raw = sse_template.replace("__REVIEW__", &review_json_escaped);
+    let server = start_responses_server_with_sse(&sse_raw, 2).await;
+    let codex_home = TempDir::new().unwrap();
+    let codex = new_conversation_for_server(&server, &codex_home, |_| {}).await;
+
+    codex
+        .submit(Op::Review {
+            review_request: ReviewRequest {
+                target: ReviewTarget::UncommittedChanges,
+                user_facing_hint: None,
+                paths: Vec::new(),
+            },
+        })
+        .await
+        .unwrap();
+    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // Unknown finding numbers are rejected without starting a turn.
+    codex
+        .submit(Op::FixReviewFindings {
+            review_id: None,
+            findings: vec![2],
+        })
+        .await
+        .unwrap();
+    let error = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+    let EventMsg::Error(error) = error else {
+        unreachable!();
+    };
+    assert_eq!(
+        error.message,
+        "The review has 1 finding(s); there is no finding 2."
+    );
+
+    codex
+        .submit(Op::FixReviewFindings {
+            review_id: None,
+            findings: vec![1],
+        })
+        .await
+        .unwrap();
+    let started = wait_for_event(&codex, |ev| matches!(ev, EventMsg::ReviewFixStarted(_))).await;
+    let EventMsg::ReviewFixStarted(started) = started else {
+        unreachable!();
+    };
+    let finding_id = format!("{}#1", started.review_id);
+    assert_eq!(started.finding_ids, vec![finding_id.clone()]);
+    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = get_responses_requests(&server).await;
+    let fix_request = String::from_utf8_lossy(&requests.last().unwrap().body).into_owned();
+    assert!(
+        fix_request.contains(&format!(
+            "[{finding_id}] Unchecked unwrap — /tmp/parse.rs:3-4"
+        )),
+        "fix turn input missing finding: {fix_request}"
+    );
+
+    // The rollout ties the fix turn back to the finding ids.
+    let text = std::fs::read_to_string(codex.rollout_path()).expect("read rollout file");
+    let recorded = text.lines().any(|line| {
+        let rl: RolloutLine = serde_json::from_str(line).expect("rollout line");
+        matches!(
+            rl.item,
+            RolloutItem::EventMsg(EventMsg::ReviewFixStarted(ev)) if ev.finding_ids == [finding_id.clone()]
+        )
+    });
+    assert!(recorded, "ReviewFixStarted missing from rollout");
+
+    server.verify().await;
+}
+
 /// Start a mock Responses API server and mount the given SSE stream body.
 async fn start_responses_server_with_sse(sse_raw: &str, expected_requests: usize) -> MockServer {
     let server = MockServer::start().await;
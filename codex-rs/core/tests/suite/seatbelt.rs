@@ -80,6 +80,8 @@ async fn if_parent_of_repo_is_writable_then_dot_git_folder_is_writable() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     test_scenario
@@ -106,6 +108,8 @@ async fn if_git_repo_is_writable_root_then_dot_git_folder_is_read_only() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     test_scenario
@@ -145,7 +149,7 @@ async fn danger_full_access_allows_all_writes() {
 async fn read_only_forbids_all_writes() {
     let tmp = TempDir::new().expect("should be able to create temp dir");
     let test_scenario = create_test_scenario(&tmp);
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
 
     test_scenario
         .run_test(
@@ -171,7 +175,7 @@ async fn openpty_works_under_seatbelt() {
         return;
     }
 
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
     let command_cwd = std::env::current_dir().expect("getcwd");
     let sandbox_cwd = command_cwd.clone();
 
@@ -191,6 +195,7 @@ assert os.read(master, 4) == b"ping""#
         sandbox_cwd.as_path(),
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
+        None,
     )
     .await
     .expect("should be able to spawn python under seatbelt");
@@ -228,7 +233,7 @@ async fn java_home_finds_runtime_under_seatbelt() {
         return;
     }
 
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
     let command_cwd = std::env::current_dir().expect("getcwd");
     let sandbox_cwd = command_cwd.clone();
 
@@ -243,6 +248,7 @@ async fn java_home_finds_runtime_under_seatbelt() {
         sandbox_cwd.as_path(),
         StdioPolicy::RedirectForShellTool,
         env,
+        None,
     )
     .await
     .expect("should be able to spawn java_home under seatbelt");
@@ -299,6 +305,7 @@ async fn touch(path: &Path, policy: &SandboxPolicy) -> bool {
         sandbox_cwd.as_path(),
         StdioPolicy::RedirectForShellTool,
         HashMap::new(),
+        None,
     )
     .await
     .expect("should be able to spawn command under seatbelt");
@@ -0,0 +1,70 @@
+use codex_core::config::types::ModelPrice;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn finished_turn_records_priced_cost_entry() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed_with_tokens("resp-1", 4_000),
+        ]),
+    )
+    .await;
+
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(|config| {
+            config.model = Some("gpt-5.1".to_string());
+            config.model_prices.insert(
+                "gpt-5.1".to_string(),
+                ModelPrice {
+                    input: 2.5,
+                    cached_input: None,
+                    output: 10.0,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    let entry = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::TurnCost(_) | EventMsg::TaskComplete(_))
+    })
+    .await;
+    let EventMsg::TurnCost(entry) = entry else {
+        panic!("expected the cost entry before task completion, got {entry:?}");
+    };
+    assert_eq!(entry.model, "gpt-5.1");
+    assert_eq!(entry.usage.input_tokens, 4_000);
+    assert!(!entry.compacted);
+    assert_eq!(entry.cost_usd, Some(0.01));
+
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    Ok(())
+}
@@ -2,8 +2,11 @@
 
 use std::fs;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -11,6 +14,7 @@ use anyhow::bail;
 use codex_core::CodexConversation;
 use codex_core::features::Feature;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ListUndoSnapshotsResponseEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::UndoCompletedEvent;
 use core_test_support::responses::ev_apply_patch_function_call;
@@ -109,7 +113,11 @@ async fn run_apply_patch_turn(
 }
 
 async fn invoke_undo(codex: &Arc<CodexConversation>) -> Result<UndoCompletedEvent> {
-    codex.submit(Op::Undo).await?;
+    submit_undo_op(codex, Op::Undo).await
+}
+
+async fn submit_undo_op(codex: &Arc<CodexConversation>, op: Op) -> Result<UndoCompletedEvent> {
+    codex.submit(op).await?;
     let event = wait_for_event_match(codex, |msg| match msg {
         EventMsg::UndoCompleted(done) => Some(done.clone()),
         _ => None,
@@ -118,6 +126,26 @@ async fn invoke_undo(codex: &Arc<CodexConversation>) -> Result<UndoCompletedEven
     Ok(event)
 }
 
+/// Waits for the snapshots of how the first `turns` turns ended, which are
+/// captured in the background after each turn. Every captured turn leaves a
+/// start and an end ref.
+async fn wait_for_turn_end_snapshots(repo: &Path, turns: usize) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let refs = git_output(
+            repo,
+            &["for-each-ref", "--format=%(refname)", "refs/codex/ghost"],
+        )?;
+        if refs.lines().count() >= turns * 2 {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            bail!("turn-end snapshots were not recorded: {refs:?}");
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
 async fn expect_successful_undo(codex: &Arc<CodexConversation>) -> Result<UndoCompletedEvent> {
     let event = invoke_undo(codex).await?;
     assert!(
@@ -266,6 +294,52 @@ async fn undo_reverts_only_latest_turn() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_turns_reverts_requested_number_of_turns() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let add_patch = "*** Begin Patch\n*** Add File: story.txt\n+first version\n*** End Patch";
+    run_apply_patch_turn(
+        &harness,
+        "create story",
+        "undo-turns-one",
+        add_patch,
+        "done",
+    )
+    .await?;
+    let update_patch = "*** Begin Patch\n*** Update File: story.txt\n@@\n-first version\n+second version\n*** End Patch";
+    run_apply_patch_turn(
+        &harness,
+        "revise story",
+        "undo-turns-two",
+        update_patch,
+        "done",
+    )
+    .await?;
+    let story = harness.path("story.txt");
+    assert_eq!(fs::read_to_string(&story)?, "second version\n");
+
+    let codex = Arc::clone(&harness.test().codex);
+    let too_many = submit_undo_op(&codex, Op::UndoTurns { turns: 3 }).await?;
+    assert!(!too_many.success);
+    assert_eq!(
+        too_many.message.as_deref(),
+        Some("Cannot undo 3 turns: only 2 have a ghost snapshot.")
+    );
+
+    let undone = submit_undo_op(&codex, Op::UndoTurns { turns: 2 }).await?;
+    assert!(undone.success, "undo failed: {:?}", undone.message);
+    assert!(!story.exists());
+
+    let listed = list_undo_snapshots(&codex).await?;
+    assert!(listed.snapshots.is_empty());
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_does_not_touch_unrelated_files() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -376,6 +450,100 @@ async fn undo_sequential_turns_consumes_snapshots() -> Result<()> {
     Ok(())
 }
 
+async fn list_undo_snapshots(
+    codex: &Arc<CodexConversation>,
+) -> Result<ListUndoSnapshotsResponseEvent> {
+    codex.submit(Op::ListUndoSnapshots).await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::ListUndoSnapshotsResponse(list) => Some(list.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(event)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_to_earlier_snapshot_and_redo() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let story = harness.path("story.txt");
+    fs::write(&story, "initial\n")?;
+    git(harness.cwd(), &["add", "story.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed story"])?;
+
+    let turns = [
+        ("first change", "initial", "turn one"),
+        ("second change", "turn one", "turn two"),
+        ("third change", "turn two", "turn three"),
+    ];
+    for (idx, (prompt, before, after)) in turns.iter().enumerate() {
+        run_apply_patch_turn(
+            &harness,
+            prompt,
+            &format!("undo-to-turn-{idx}"),
+            &format!(
+                "*** Begin Patch\n*** Update File: story.txt\n@@\n-{before}\n+{after}\n*** End Patch"
+            ),
+            "ok",
+        )
+        .await?;
+    }
+    assert_eq!(fs::read_to_string(&story)?, "turn three\n");
+
+    let codex = Arc::clone(&harness.test().codex);
+    let listed = list_undo_snapshots(&codex).await?;
+    let labels: Vec<(u32, Option<&str>)> = listed
+        .snapshots
+        .iter()
+        .map(|snapshot| (snapshot.turn, snapshot.prompt.as_deref()))
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            (1, Some("first change")),
+            (2, Some("second change")),
+            (3, Some("third change")),
+        ]
+    );
+    assert!(!listed.redo_available);
+
+    let snapshot_id = listed.snapshots[0].id.clone();
+    let undone = submit_undo_op(&codex, Op::UndoTo { snapshot_id }).await?;
+    assert!(undone.success, "undo failed: {:?}", undone.message);
+    assert_eq!(fs::read_to_string(&story)?, "initial\n");
+
+    let listed = list_undo_snapshots(&codex).await?;
+    assert!(listed.snapshots.is_empty());
+    assert!(listed.redo_available);
+
+    let redone = submit_undo_op(&codex, Op::Redo).await?;
+    assert!(redone.success, "redo failed: {:?}", redone.message);
+    assert_eq!(fs::read_to_string(&story)?, "turn three\n");
+
+    // Redo restores the snapshots, so single-step undo works again.
+    expect_successful_undo(&codex).await?;
+    assert_eq!(fs::read_to_string(&story)?, "turn two\n");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn redo_without_undo_reports_failure() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    let codex = Arc::clone(&harness.test().codex);
+
+    let event = submit_undo_op(&codex, Op::Redo).await?;
+    assert!(!event.success);
+    assert_eq!(event.message.as_deref(), Some("Nothing to redo."));
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_without_snapshot_reports_failure() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -455,7 +623,7 @@ async fn undo_does_not_touch_ignored_directory_contents() -> Result<()> {
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn undo_overwrites_manual_edits_after_turn() -> Result<()> {
+async fn undo_keeps_conflicting_manual_edits_after_turn() -> Result<()> {
     skip_if_no_network!(Ok(()));
 
     let harness = undo_harness().await?;
@@ -469,20 +637,111 @@ async fn undo_overwrites_manual_edits_after_turn() -> Result<()> {
     run_apply_patch_turn(
         &harness,
         "modify tracked",
-        "undo-manual-overwrite",
+        "undo-manual-conflict",
         "*** Begin Patch\n*** Update File: tracked.txt\n@@\n-baseline\n+turn change\n*** End Patch",
         "ok",
     )
     .await?;
     assert_eq!(fs::read_to_string(&tracked)?, "turn change\n");
+    wait_for_turn_end_snapshots(harness.cwd(), 1).await?;
 
     fs::write(&tracked, "manual edit\n")?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let completed = expect_successful_undo(&codex).await?;
+
+    assert_eq!(completed.conflicts, vec![PathBuf::from("tracked.txt")]);
     assert_eq!(fs::read_to_string(&tracked)?, "manual edit\n");
 
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_preserves_external_edits_after_turn() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let tracked = harness.path("tracked.txt");
+    fs::write(&tracked, "one\ntwo\nthree\nfour\nfive\n")?;
+    let unrelated = harness.path("unrelated.txt");
+    fs::write(&unrelated, "before\n")?;
+    git(harness.cwd(), &["add", "tracked.txt", "unrelated.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "baseline"])?;
+
+    run_apply_patch_turn(
+        &harness,
+        "modify tracked",
+        "undo-external-edits",
+        "*** Begin Patch\n*** Update File: tracked.txt\n@@\n one\n-two\n+TWO\n three\n*** End Patch",
+        "ok",
+    )
+    .await?;
+    assert_eq!(
+        fs::read_to_string(&tracked)?,
+        "one\nTWO\nthree\nfour\nfive\n"
+    );
+
+    wait_for_turn_end_snapshots(harness.cwd(), 1).await?;
+
+    // A teammate edits another part of the same file and an unrelated file.
+    fs::write(&tracked, "one\nTWO\nthree\nfour\nFIVE\n")?;
+    fs::write(&unrelated, "edited outside\n")?;
+
     let codex = Arc::clone(&harness.test().codex);
-    expect_successful_undo(&codex).await?;
+    let completed = expect_successful_undo(&codex).await?;
 
-    assert_eq!(fs::read_to_string(&tracked)?, "baseline\n");
+    assert!(completed.conflicts.is_empty());
+    assert_eq!(
+        fs::read_to_string(&tracked)?,
+        "one\ntwo\nthree\nfour\nFIVE\n"
+    );
+    assert_eq!(fs::read_to_string(&unrelated)?, "edited outside\n");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_reports_snapshot_removed_by_gc() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let patch = "*** Begin Patch\n*** Add File: gc.txt\n+from turn\n*** End Patch";
+    run_apply_patch_turn(&harness, "create file", "undo-gc", patch, "ok").await?;
+    wait_for_turn_end_snapshots(harness.cwd(), 1).await?;
+
+    // Snapshots are recorded under refs/codex/ghost so gc keeps them...
+    let refs = git_output(
+        harness.cwd(),
+        &["for-each-ref", "--format=%(refname)", "refs/codex/ghost"],
+    )?;
+    assert!(
+        !refs.trim().is_empty(),
+        "expected ghost refs to be recorded"
+    );
+
+    // ...until the refs are deleted and the objects pruned.
+    for name in refs.lines() {
+        git(harness.cwd(), &["update-ref", "-d", name])?;
+    }
+    git(
+        harness.cwd(),
+        &["reflog", "expire", "--expire=now", "--all"],
+    )?;
+    git(harness.cwd(), &["gc", "--prune=now", "--quiet"])?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let completed = invoke_undo(&codex).await?;
+    assert!(!completed.success);
+    let message = completed.message.unwrap_or_default();
+    assert!(
+        message.contains("is missing from the repository"),
+        "unexpected message: {message}"
+    );
+    assert_eq!(fs::read_to_string(harness.path("gc.txt"))?, "from turn\n");
 
     Ok(())
 }
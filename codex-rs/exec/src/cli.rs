@@ -1,3 +1,4 @@
+use clap::Args;
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
@@ -66,6 +67,14 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Record every model request and response of this session to a cassette file.
+    #[arg(long = "record", value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve model responses from a cassette recorded with --record instead of the provider.
+    #[arg(long = "replay", value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -118,7 +127,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "uncommitted",
         default_value_t = false,
-        conflicts_with_all = ["base", "commit", "prompt"]
+        conflicts_with_all = ["base", "commit", "range", "prompt"]
     )]
     pub uncommitted: bool,
 
@@ -126,7 +135,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "base",
         value_name = "BRANCH",
-        conflicts_with_all = ["uncommitted", "commit", "prompt"]
+        conflicts_with_all = ["uncommitted", "commit", "range", "prompt"]
     )]
     pub base: Option<String>,
 
@@ -134,7 +143,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "commit",
         value_name = "SHA",
-        conflicts_with_all = ["uncommitted", "base", "prompt"]
+        conflicts_with_all = ["uncommitted", "base", "range", "prompt"]
     )]
     pub commit: Option<String>,
 
@@ -142,11 +151,52 @@ pub struct ReviewArgs {
     #[arg(long = "title", value_name = "TITLE", requires = "commit")]
     pub commit_title: Option<String>,
 
+    /// Review the changes between two revisions, e.g. `main..HEAD`.
+    #[arg(
+        long = "range",
+        value_name = "BASE..HEAD",
+        conflicts_with_all = ["uncommitted", "base", "commit", "prompt"]
+    )]
+    pub range: Option<String>,
+
+    /// Only review changes under this path, relative to the working
+    /// directory. May be repeated.
+    #[arg(long = "path", value_name = "PATH")]
+    pub paths: Vec<String>,
+
+    #[clap(flatten)]
+    pub report: ReviewReportArgs,
+
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
 }
 
+#[derive(Args, Debug, Default, Clone)]
+pub struct ReviewReportArgs {
+    /// Write the findings as a SARIF 2.1.0 log to this file.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Write the findings as GitHub check-run annotations (JSON) to this file.
+    #[arg(long = "annotations", value_name = "FILE")]
+    pub annotations: Option<PathBuf>,
+
+    /// Exit with status 2 when a finding has this priority or a more urgent
+    /// one (0 = P0 ... 3 = P3).
+    #[arg(
+        long = "fail-on-priority",
+        value_name = "PRIORITY",
+        value_parser = clap::value_parser!(i32).range(0..=3)
+    )]
+    pub fail_on_priority: Option<i32>,
+
+    /// Exit with status 2 when a finding has at least this confidence
+    /// (0.0-1.0). Combined with `--fail-on-priority`, both must hold.
+    #[arg(long = "min-confidence", value_name = "SCORE")]
+    pub min_confidence: Option<f32>,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -228,6 +228,19 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::StreamError(StreamErrorEvent { message, .. }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::ProviderFallback(ev) => {
+                ts_msg!(
+                    self,
+                    "{} {} ({}) -> {} ({}) after {}: {}",
+                    "provider fallback:".style(self.yellow).style(self.bold),
+                    ev.from_provider,
+                    ev.from_model,
+                    ev.to_provider,
+                    ev.to_model,
+                    ev.reason,
+                    ev.message
+                );
+            }
             EventMsg::TaskStarted(_) => {
                 // Ignore.
             }
@@ -385,17 +398,41 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 // it's easy to scan in the terminal output.
                 for (path, change) in changes.iter() {
                     match change {
-                        FileChange::Add { content } => {
-                            let header = format!(
+                        FileChange::Add { content, mode } => {
+                            let mut header = format!(
                                 "{} {}",
                                 format_file_change(change),
                                 path.to_string_lossy()
                             );
+                            if let Some(mode) = mode {
+                                header.push_str(&format!(" (mode {mode:o})"));
+                            }
                             eprintln!("{}", header.style(self.magenta));
                             for line in content.lines() {
                                 eprintln!("{}", line.style(self.green));
                             }
                         }
+                        FileChange::AddBinary { size, mode } => {
+                            let mut header = format!(
+                                "{} {} (binary, {size} bytes",
+                                format_file_change(change),
+                                path.to_string_lossy()
+                            );
+                            if let Some(mode) = mode {
+                                header.push_str(&format!(", mode {mode:o}"));
+                            }
+                            header.push(')');
+                            eprintln!("{}", header.style(self.magenta));
+                        }
+                        FileChange::AddSymlink { target } => {
+                            let header = format!(
+                                "{} {} -> {}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                target.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
                         FileChange::Delete { content } => {
                             let header = format!(
                                 "{} {}",
@@ -410,8 +447,10 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         FileChange::Update {
                             unified_diff,
                             move_path,
+                            mode,
+                            fuzz_score,
                         } => {
-                            let header = if let Some(dest) = move_path {
+                            let mut header = if let Some(dest) = move_path {
                                 format!(
                                     "{} {} -> {}",
                                     format_file_change(change),
@@ -421,6 +460,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                             } else {
                                 format!("{} {}", format_file_change(change), path.to_string_lossy())
                             };
+                            if let Some(mode) = mode {
+                                header.push_str(&format!(" (mode {mode:o})"));
+                            }
+                            if let Some(score) = fuzz_score {
+                                header.push_str(&format!(" (applied with fuzz, score {score:.2})"));
+                            }
                             eprintln!("{}", header.style(self.magenta));
 
                             // Colorize diff lines. We keep file header lines
@@ -575,10 +620,14 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListUndoSnapshotsResponse(_)
+            | EventMsg::ListExecSessionsResponse(_)
+            | EventMsg::ExecSessionOutputResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
             | EventMsg::ExitedReviewMode(_)
+            | EventMsg::ReviewFixStarted(_)
             | EventMsg::AgentMessageDelta(_)
             | EventMsg::AgentReasoningDelta(_)
             | EventMsg::AgentReasoningRawContentDelta(_)
@@ -589,6 +638,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ReasoningRawContentDelta(_)
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_)
+            | EventMsg::TurnCost(_)
             | EventMsg::SoftPause(_) => {}
         }
         CodexStatus::Running
@@ -624,7 +674,9 @@ fn escape_command(command: &[String]) -> String {
 
 fn format_file_change(change: &FileChange) -> &'static str {
     match change {
-        FileChange::Add { .. } => "A",
+        FileChange::Add { .. } | FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+            "A"
+        }
         FileChange::Delete { .. } => "D",
         FileChange::Update {
             move_path: Some(_), ..
@@ -347,7 +347,9 @@ impl EventProcessorWithJsonOutput {
 
     fn map_change_kind(&self, kind: &FileChange) -> PatchChangeKind {
         match kind {
-            FileChange::Add { .. } => PatchChangeKind::Add,
+            FileChange::Add { .. }
+            | FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. } => PatchChangeKind::Add,
             FileChange::Delete { .. } => PatchChangeKind::Delete,
             FileChange::Update { .. } => PatchChangeKind::Update,
         }
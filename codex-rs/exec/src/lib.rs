@@ -9,10 +9,12 @@ mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod review_report;
 
 pub use cli::Cli;
 pub use cli::Command;
 pub use cli::ReviewArgs;
+pub use cli::ReviewReportArgs;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_core::AuthManager;
@@ -26,6 +28,7 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::config::types::ModelCassette;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
@@ -89,6 +92,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        record,
+        replay,
         config_overrides,
     } = cli;
 
@@ -200,6 +205,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
         additional_writable_roots: add_dir,
+        model_cassette: record
+            .map(ModelCassette::Record)
+            .or(replay.map(ModelCassette::Replay)),
     };
 
     let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
@@ -300,8 +308,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             .new_conversation(config.clone())
             .await?
     };
+    let mut review_report: Option<(ReviewReportArgs, Vec<String>)> = None;
     let (initial_operation, prompt_summary) = match (command, prompt, images) {
         (Some(ExecCommand::Review(review_cli)), _, _) => {
+            if review_cli.report.is_requested() {
+                review_report = Some((review_cli.report.clone(), review_cli.paths.clone()));
+            }
             let review_request = build_review_request(review_cli)?;
             let summary = codex_core::review_prompts::user_facing_hint(&review_request.target);
             (InitialOperation::Review { review_request }, summary)
@@ -431,6 +443,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut review_output = None;
     while let Some(event) = rx.recv().await {
         if let EventMsg::ElicitationRequest(ev) = &event.msg {
             // Automatically cancel elicitation requests in exec mode.
@@ -445,6 +458,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if let EventMsg::ExitedReviewMode(ev) = &event.msg {
+            review_output = ev.review_output.clone();
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -461,6 +477,24 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
+    if let Some((report, paths)) = review_report {
+        let Some(output) = review_output else {
+            eprintln!("Review did not produce any findings to report.");
+            std::process::exit(1);
+        };
+        let findings = review_report::filter_findings(&output.findings, &config.cwd, &paths);
+        let root = get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone());
+        review_report::write_reports(&report, &output, &findings, &root)?;
+        let failing = findings
+            .iter()
+            .filter(|finding| report.fails_on(finding))
+            .count();
+        if failing > 0 {
+            eprintln!("{failing} review finding(s) meet the failure threshold.");
+            std::process::exit(review_report::FINDINGS_EXIT_CODE);
+        }
+    }
+
     Ok(())
 }
 
@@ -559,6 +593,17 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
             sha,
             title: args.commit_title,
         }
+    } else if let Some(range) = args.range {
+        let Some((base, head)) = range
+            .split_once("..")
+            .filter(|(base, head)| !base.is_empty() && !head.is_empty() && !head.starts_with('.'))
+        else {
+            anyhow::bail!("Review range must look like BASE..HEAD, got `{range}`");
+        };
+        ReviewTarget::Range {
+            base: base.to_string(),
+            head: head.to_string(),
+        }
     } else if let Some(prompt_arg) = args.prompt {
         let prompt = resolve_prompt(Some(prompt_arg)).trim().to_string();
         if prompt.is_empty() {
@@ -569,13 +614,14 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         }
     } else {
         anyhow::bail!(
-            "Specify --uncommitted, --base, --commit, or provide custom review instructions"
+            "Specify --uncommitted, --base, --commit, --range, or provide custom review instructions"
         );
     };
 
     Ok(ReviewRequest {
         target,
         user_facing_hint: None,
+        paths: args.paths,
     })
 }
 
@@ -591,6 +637,9 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            paths: Vec::new(),
+            report: ReviewReportArgs::default(),
             prompt: None,
         })
         .expect("builds uncommitted review request");
@@ -598,6 +647,7 @@ mod tests {
         let expected = ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            paths: Vec::new(),
         };
 
         assert_eq!(request, expected);
@@ -610,6 +660,9 @@ mod tests {
             base: None,
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
+            range: None,
+            paths: Vec::new(),
+            report: ReviewReportArgs::default(),
             prompt: None,
         })
         .expect("builds commit review request");
@@ -620,6 +673,7 @@ mod tests {
                 title: Some("Add review command".to_string()),
             },
             user_facing_hint: None,
+            paths: Vec::new(),
         };
 
         assert_eq!(request, expected);
@@ -632,6 +686,9 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            paths: Vec::new(),
+            report: ReviewReportArgs::default(),
             prompt: Some("  custom review instructions  ".to_string()),
         })
         .expect("builds custom review request");
@@ -641,8 +698,37 @@ mod tests {
                 instructions: "custom review instructions".to_string(),
             },
             user_facing_hint: None,
+            paths: Vec::new(),
         };
 
         assert_eq!(request, expected);
     }
+
+    #[test]
+    fn builds_range_review_request_with_paths() {
+        let args = |range: &str| ReviewArgs {
+            uncommitted: false,
+            base: None,
+            commit: None,
+            commit_title: None,
+            range: Some(range.to_string()),
+            paths: vec!["src".to_string()],
+            report: ReviewReportArgs::default(),
+            prompt: None,
+        };
+
+        let request = build_review_request(args("main..HEAD")).expect("builds range request");
+        let expected = ReviewRequest {
+            target: ReviewTarget::Range {
+                base: "main".to_string(),
+                head: "HEAD".to_string(),
+            },
+            user_facing_hint: None,
+            paths: vec!["src".to_string()],
+        };
+        assert_eq!(request, expected);
+
+        assert!(build_review_request(args("main")).is_err());
+        assert!(build_review_request(args("main...HEAD")).is_err());
+    }
 }
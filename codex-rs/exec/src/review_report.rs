@@ -0,0 +1,305 @@
+//! Machine-readable reports and CI gating for `codex exec review`.
+//!
+//! Findings come from the `ExitedReviewMode` event. They can be written as a
+//! SARIF log (for code scanning uploads) and as GitHub check-run annotations,
+//! and a priority/confidence threshold decides whether the run fails.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::cli::ReviewReportArgs;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const TOOL_NAME: &str = "codex-review";
+const TOOL_URI: &str = "https://github.com/openai/codex";
+
+/// Exit status used when at least one finding meets the failure threshold,
+/// distinct from the status 1 reported for errors.
+pub(crate) const FINDINGS_EXIT_CODE: i32 = 2;
+
+impl ReviewReportArgs {
+    /// Whether any report or gate was requested.
+    pub(crate) fn is_requested(&self) -> bool {
+        self.sarif.is_some()
+            || self.annotations.is_some()
+            || self.fail_on_priority.is_some()
+            || self.min_confidence.is_some()
+    }
+
+    /// Whether `finding` should fail the run. Without any threshold nothing
+    /// fails; with both, a finding has to meet both.
+    pub(crate) fn fails_on(&self, finding: &ReviewFinding) -> bool {
+        if self.fail_on_priority.is_none() && self.min_confidence.is_none() {
+            return false;
+        }
+        let priority_met = self
+            .fail_on_priority
+            .is_none_or(|max_priority| finding.priority <= max_priority);
+        let confidence_met = self
+            .min_confidence
+            .is_none_or(|min_confidence| finding.confidence_score >= min_confidence);
+        priority_met && confidence_met
+    }
+}
+
+/// Writes the requested SARIF and annotation files for `findings`.
+pub(crate) fn write_reports(
+    args: &ReviewReportArgs,
+    output: &ReviewOutputEvent,
+    findings: &[ReviewFinding],
+    root: &Path,
+) -> anyhow::Result<()> {
+    if let Some(path) = &args.sarif {
+        write_json(path, &to_sarif(output, findings, root))?;
+    }
+    if let Some(path) = &args.annotations {
+        write_json(path, &to_github_annotations(findings, root))?;
+    }
+    Ok(())
+}
+
+fn write_json(path: &Path, value: &Value) -> anyhow::Result<()> {
+    let contents = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, contents)
+        .with_context(|| format!("failed to write review report to {}", path.display()))
+}
+
+/// Drops the findings outside `paths`, which are relative to `cwd`. An empty
+/// filter keeps every finding.
+pub(crate) fn filter_findings(
+    findings: &[ReviewFinding],
+    cwd: &Path,
+    paths: &[String],
+) -> Vec<ReviewFinding> {
+    if paths.is_empty() {
+        return findings.to_vec();
+    }
+    let filters: Vec<PathBuf> = paths.iter().map(|path| cwd.join(path)).collect();
+    findings
+        .iter()
+        .filter(|finding| {
+            let location = cwd.join(&finding.code_location.absolute_file_path);
+            filters.iter().any(|filter| location.starts_with(filter))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Builds a SARIF 2.1.0 log with one result per finding. Locations are made
+/// relative to `root` when they are inside it.
+pub(crate) fn to_sarif(
+    output: &ReviewOutputEvent,
+    findings: &[ReviewFinding],
+    root: &Path,
+) -> Value {
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let range = &finding.code_location.line_range;
+            json!({
+                "ruleId": rule_id(finding.priority),
+                "level": sarif_level(finding.priority),
+                "message": { "text": finding_message(finding) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": relative_uri(&finding.code_location.absolute_file_path, root),
+                        },
+                        "region": {
+                            "startLine": range.start.max(1),
+                            "endLine": range.end.max(range.start).max(1),
+                        },
+                    },
+                }],
+                "properties": {
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "informationUri": TOOL_URI,
+                    "rules": (0..=3).map(|priority| json!({
+                        "id": rule_id(priority),
+                        "shortDescription": { "text": priority_description(priority) },
+                    })).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": output.overall_correctness,
+                "overallExplanation": output.overall_explanation,
+                "overallConfidence": output.overall_confidence_score,
+            },
+        }],
+    })
+}
+
+/// Builds GitHub check-run annotations, one per finding.
+pub(crate) fn to_github_annotations(findings: &[ReviewFinding], root: &Path) -> Value {
+    let annotations: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let range = &finding.code_location.line_range;
+            json!({
+                "path": relative_uri(&finding.code_location.absolute_file_path, root),
+                "start_line": range.start.max(1),
+                "end_line": range.end.max(range.start).max(1),
+                "annotation_level": annotation_level(finding.priority),
+                "title": finding.title,
+                "message": finding.body,
+            })
+        })
+        .collect();
+    Value::Array(annotations)
+}
+
+fn finding_message(finding: &ReviewFinding) -> String {
+    if finding.body.trim().is_empty() {
+        finding.title.clone()
+    } else {
+        format!("{}\n\n{}", finding.title, finding.body)
+    }
+}
+
+fn rule_id(priority: i32) -> String {
+    match priority {
+        0..=3 => format!("P{priority}"),
+        _ => "review".to_string(),
+    }
+}
+
+fn priority_description(priority: i32) -> &'static str {
+    match priority {
+        0 => "Drop everything to fix; blocks release or operations.",
+        1 => "Urgent; should be addressed in the next cycle.",
+        2 => "Normal; to be fixed eventually.",
+        _ => "Low; nice to have.",
+    }
+}
+
+fn sarif_level(priority: i32) -> &'static str {
+    match priority {
+        0 | 1 => "error",
+        2 => "warning",
+        _ => "note",
+    }
+}
+
+fn annotation_level(priority: i32) -> &'static str {
+    match priority {
+        0 | 1 => "failure",
+        2 => "warning",
+        _ => "notice",
+    }
+}
+
+fn relative_uri(path: &Path, root: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+
+    fn finding(path: &str, priority: i32, confidence_score: f32) -> ReviewFinding {
+        ReviewFinding {
+            title: format!("[P{priority}] issue in {path}"),
+            body: "details".to_string(),
+            confidence_score,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo").join(path),
+                line_range: ReviewLineRange { start: 3, end: 5 },
+            },
+        }
+    }
+
+    #[test]
+    fn thresholds_gate_on_priority_and_confidence() {
+        let urgent_unsure = finding("a.rs", 1, 0.4);
+        let minor_sure = finding("b.rs", 3, 0.9);
+
+        let none = ReviewReportArgs::default();
+        assert!(!none.fails_on(&urgent_unsure));
+
+        let priority = ReviewReportArgs {
+            fail_on_priority: Some(1),
+            ..Default::default()
+        };
+        assert!(priority.fails_on(&urgent_unsure));
+        assert!(!priority.fails_on(&minor_sure));
+
+        let both = ReviewReportArgs {
+            fail_on_priority: Some(1),
+            min_confidence: Some(0.5),
+            ..Default::default()
+        };
+        assert!(!both.fails_on(&urgent_unsure));
+        assert!(!both.fails_on(&minor_sure));
+
+        let confidence = ReviewReportArgs {
+            min_confidence: Some(0.5),
+            ..Default::default()
+        };
+        assert!(confidence.fails_on(&minor_sure));
+    }
+
+    #[test]
+    fn filters_findings_by_path_prefix() {
+        let findings = vec![finding("src/lib.rs", 1, 0.9), finding("docs/a.md", 2, 0.9)];
+        let kept = filter_findings(&findings, Path::new("/repo"), &["src".to_string()]);
+        assert_eq!(kept, vec![findings[0].clone()]);
+        assert_eq!(
+            filter_findings(&findings, Path::new("/repo"), &[]),
+            findings
+        );
+    }
+
+    #[test]
+    fn sarif_and_annotations_use_repo_relative_paths() {
+        let findings = vec![finding("src/lib.rs", 0, 0.8)];
+        let sarif = to_sarif(&ReviewOutputEvent::default(), &findings, Path::new("/repo"));
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "P0");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/lib.rs"
+        );
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 3, "endLine": 5 })
+        );
+
+        assert_eq!(
+            to_github_annotations(&findings, Path::new("/repo")),
+            json!([{
+                "path": "src/lib.rs",
+                "start_line": 3,
+                "end_line": 5,
+                "annotation_level": "failure",
+                "title": "[P0] issue in src/lib.rs",
+                "message": "details",
+            }])
+        );
+    }
+}
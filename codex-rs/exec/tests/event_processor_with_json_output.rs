@@ -79,7 +79,7 @@ fn session_configured_produces_thread_started_event() {
             model: "codex-mini-latest".to_string(),
             model_provider_id: "test-provider".to_string(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             cwd: PathBuf::from("/home/user/project"),
             reasoning_effort: None,
             history_log_id: 0,
@@ -900,6 +900,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         PathBuf::from("a/added.txt"),
         FileChange::Add {
             content: "+hello".to_string(),
+            mode: None,
         },
     );
     changes.insert(
@@ -913,6 +914,8 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         FileChange::Update {
             unified_diff: "--- c/modified.txt\n+++ c/modified.txt\n@@\n-old\n+new\n".to_string(),
             move_path: Some(PathBuf::from("c/renamed.txt")),
+            fuzz_score: None,
+            mode: None,
         },
     );
 
@@ -985,6 +988,8 @@ fn patch_apply_failure_produces_item_completed_patchapply_failed() {
         FileChange::Update {
             unified_diff: "--- file.txt\n+++ file.txt\n@@\n-old\n+new\n".to_string(),
             move_path: None,
+            fuzz_score: None,
+            mode: None,
         },
     );
 
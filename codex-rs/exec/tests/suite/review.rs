@@ -0,0 +1,122 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use serde_json::Value;
+
+fn review_output_body(file: &str) -> String {
+    let review_json = serde_json::json!({
+        "findings": [
+            {
+                "title": "[P1] Missing bounds check",
+                "body": "Index can exceed the slice length.",
+                "confidence_score": 0.9,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": file,
+                    "line_range": {"start": 4, "end": 6}
+                }
+            },
+            {
+                "title": "[P3] Nit",
+                "body": "Rename for clarity.",
+                "confidence_score": 0.3,
+                "priority": 3,
+                "code_location": {
+                    "absolute_file_path": file,
+                    "line_range": {"start": 10, "end": 10}
+                }
+            }
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "One real bug.",
+        "overall_confidence_score": 0.8
+    });
+    responses::sse(vec![
+        responses::ev_response_created("resp1"),
+        responses::ev_assistant_message("m1", &review_json.to_string()),
+        responses::ev_completed("resp1"),
+    ])
+}
+
+/// A range review writes SARIF and annotations and exits with status 2 when a
+/// finding meets the `--fail-on-priority` threshold.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_range_writes_reports_and_fails_on_priority() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let file = test.cwd_path().join("src/lib.rs");
+    let sarif_path = test.cwd_path().join("review.sarif");
+    let annotations_path = test.cwd_path().join("annotations.json");
+
+    let server = responses::start_mock_server().await;
+    let response_mock =
+        responses::mount_sse_once(&server, review_output_body(&file.to_string_lossy())).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("review")
+        .arg("--range")
+        .arg("main..feature")
+        .arg("--path")
+        .arg("src")
+        .arg("--sarif")
+        .arg(&sarif_path)
+        .arg("--annotations")
+        .arg(&annotations_path)
+        .arg("--fail-on-priority")
+        .arg("1")
+        .assert()
+        .code(2);
+
+    let request = response_mock.single_request();
+    let prompt = request.message_input_texts("user").join("\n");
+    assert!(
+        prompt.contains("git diff main..feature"),
+        "prompt: {prompt}"
+    );
+    assert!(prompt.contains("-- src"), "prompt: {prompt}");
+
+    let sarif: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path)?)?;
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["ruleId"], "P1");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/lib.rs"
+    );
+
+    let annotations: Value = serde_json::from_str(&std::fs::read_to_string(&annotations_path)?)?;
+    assert_eq!(annotations[0]["annotation_level"], "failure");
+    assert_eq!(annotations[1]["annotation_level"], "notice");
+
+    Ok(())
+}
+
+/// Findings below the thresholds do not fail the run.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_passes_when_no_finding_meets_threshold() -> anyhow::Result<()> {
+    let test = test_codex_exec();
+    let file = test.cwd_path().join("src/lib.rs");
+
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once(&server, review_output_body(&file.to_string_lossy())).await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(test.cwd_path())
+        .arg("review")
+        .arg("--range")
+        .arg("main..feature")
+        .arg("--fail-on-priority")
+        .arg("1")
+        .arg("--min-confidence")
+        .arg("0.95")
+        .assert()
+        .success();
+
+    Ok(())
+}
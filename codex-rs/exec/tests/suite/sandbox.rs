@@ -28,6 +28,7 @@ async fn spawn_command_under_sandbox(
         sandbox_cwd,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
@@ -51,6 +52,7 @@ async fn spawn_command_under_sandbox(
         sandbox_cwd,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
@@ -73,6 +75,8 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     let python_code = r#"import multiprocessing
@@ -123,7 +127,7 @@ async fn python_getpwuid_works_under_sandbox() {
         return;
     }
 
-    let policy = SandboxPolicy::ReadOnly;
+    let policy = SandboxPolicy::new_read_only_policy();
     let command_cwd = std::env::current_dir().expect("should be able to get current dir");
     let sandbox_cwd = command_cwd.clone();
 
@@ -172,6 +176,8 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
@@ -309,7 +315,7 @@ fn unix_sock_body() {
 async fn allow_unix_socketpair_recvfrom() {
     run_code_under_sandbox(
         "allow_unix_socketpair_recvfrom",
-        &SandboxPolicy::ReadOnly,
+        &SandboxPolicy::new_read_only_policy(),
         || async { unix_sock_body() },
     )
     .await
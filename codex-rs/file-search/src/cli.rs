@@ -37,6 +37,20 @@ pub struct Cli {
     #[arg(short, long, action = ArgAction::Append)]
     pub exclude: Vec<String>,
 
+    /// Search the persistent index instead of walking the tree, creating or
+    /// updating the index first.
+    #[arg(long, default_value = "false")]
+    pub index: bool,
+
+    /// Report the state of the persistent index for the directory and exit.
+    #[arg(long, default_value = "false", conflicts_with = "index")]
+    pub index_status: bool,
+
+    /// Directory holding the persistent index. Defaults to `$CODEX_HOME` or
+    /// `~/.codex`.
+    #[arg(long, value_name = "DIR")]
+    pub codex_home: Option<PathBuf>,
+
     /// Search pattern.
     pub pattern: Option<String>,
 }
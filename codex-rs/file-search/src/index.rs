@@ -0,0 +1,870 @@
+//! Persistent path index used instead of walking the tree on every search.
+//!
+//! The index stores every file path under a repository root together with
+//! the modification time of each directory. It lives under
+//! `$CODEX_HOME/file_search_index/`, keyed by the repository root. On open,
+//! directories whose mtime changed since the index was written are walked
+//! again, so files added or removed between sessions are picked up without a
+//! full rebuild. While a [`FileSearchIndex`] is shared through
+//! [`shared_index`], a `notify` watcher keeps it current. A change of the
+//! checked-out branch (the contents of `.git/HEAD`) discards the index.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::num::NonZero;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::RwLock;
+use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use ignore::WalkBuilder;
+use ignore::overrides::Override;
+use ignore::overrides::OverrideBuilder;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use notify::event::ModifyKind;
+use nucleo_matcher::Matcher;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+
+use crate::BestMatchesList;
+use crate::FileSearchResults;
+use crate::build_file_matches;
+use crate::create_pattern;
+
+/// Directory under `CODEX_HOME` that holds the index files.
+const INDEX_DIR: &str = "file_search_index";
+/// Bumped whenever the on-disk format changes; older files are rebuilt.
+const INDEX_VERSION: u32 = 1;
+/// Filesystem events are applied once the tree has been quiet this long.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+/// Longest time a change waits while the tree keeps changing.
+const MAX_WATCH_DELAY: Duration = Duration::from_secs(2);
+/// Minimum time between saves of an index changed by the watcher.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// State of the index for a directory, as reported by `--index-status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexState {
+    /// No index has been written for this repository yet.
+    Missing,
+    /// The index matches the working tree.
+    Fresh,
+    /// Some directories changed since the index was written; they are
+    /// walked again on the next indexed search.
+    Stale,
+    /// The checked-out branch changed or the format is outdated; the index
+    /// is rebuilt on the next indexed search.
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexStatus {
+    pub root: PathBuf,
+    pub index_path: PathBuf,
+    pub state: IndexState,
+    pub file_count: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stale_directories: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_head: Option<String>,
+    /// Seconds since the Unix epoch at which the index was last written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+    pub watching: bool,
+}
+
+/// The persisted form of the index. Paths are relative to `root` and use
+/// `/` as separator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexData {
+    version: u32,
+    root: PathBuf,
+    git_head: Option<String>,
+    updated_at: u64,
+    files: BTreeSet<String>,
+    /// Modification time of every indexed directory, in nanoseconds since
+    /// the Unix epoch. The root is stored under the empty string.
+    dirs: BTreeMap<String, u64>,
+}
+
+impl IndexData {
+    fn build(root: &Path) -> Self {
+        let mut data = Self {
+            version: INDEX_VERSION,
+            root: root.to_path_buf(),
+            git_head: read_git_head(root),
+            updated_at: unix_now(),
+            files: BTreeSet::new(),
+            dirs: BTreeMap::new(),
+        };
+        data.insert_tree(root);
+        data
+    }
+
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read(path)
+            .with_context(|| format!("failed to read file search index {}", path.display()))?;
+        serde_json::from_slice(&contents)
+            .with_context(|| format!("failed to parse file search index {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write to a sibling file and rename so a crash never leaves a
+        // truncated index behind.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("failed to write file search index {}", path.display()))
+    }
+
+    /// Whether the index can be updated incrementally rather than rebuilt.
+    fn is_valid_for(&self, root: &Path) -> bool {
+        self.version == INDEX_VERSION && self.root == root && self.git_head == read_git_head(root)
+    }
+
+    /// Directories whose mtime differs from the recorded one, outermost
+    /// first; nested stale directories are covered by their ancestor.
+    fn stale_dirs(&self) -> Vec<String> {
+        let mut stale: Vec<String> = Vec::new();
+        for (dir, mtime) in &self.dirs {
+            if stale.iter().any(|outer| is_within(dir, outer)) {
+                continue;
+            }
+            if dir_mtime(&self.root.join(dir)) != Some(*mtime) {
+                stale.push(dir.clone());
+            }
+        }
+        stale
+    }
+
+    /// Walks the stale directories again. Returns whether anything changed.
+    fn revalidate(&mut self) -> bool {
+        let stale = self.stale_dirs();
+        for dir in &stale {
+            self.refresh(&self.root.join(dir));
+        }
+        !stale.is_empty()
+    }
+
+    /// Brings the entries at and below `path` in line with the filesystem.
+    fn refresh(&mut self, path: &Path) {
+        let Some(relative) = relative_key(&self.root, path) else {
+            return;
+        };
+        if is_git_dir(&relative) {
+            return;
+        }
+        remove_prefixed(&mut self.files, &relative);
+        let stale_dirs: Vec<String> = prefixed(self.dirs.keys(), &relative).cloned().collect();
+        for dir in stale_dirs {
+            self.dirs.remove(&dir);
+        }
+
+        if path == self.root {
+            self.insert_tree(path);
+        } else if let Some(parent) = path.parent()
+            && let Some(parent_key) = relative_key(&self.root, parent)
+            && self.dirs.contains_key(&parent_key)
+        {
+            // Only paths inside indexed directories can be indexed. Listing
+            // the parent then applies the same ignore rules as a full walk,
+            // including the ones from `.gitignore` files above `path`.
+            let listed = walk_builder(parent)
+                .max_depth(Some(1))
+                .build()
+                .flatten()
+                .any(|entry| entry.depth() == 1 && entry.path() == path);
+            if listed {
+                self.insert_tree(path);
+            }
+            if let Some(mtime) = dir_mtime(parent) {
+                self.dirs.insert(parent_key, mtime);
+            }
+        }
+        self.updated_at = unix_now();
+    }
+
+    fn insert_tree(&mut self, path: &Path) {
+        for entry in walk_builder(path).build().flatten() {
+            let Some(key) = relative_key(&self.root, entry.path()) else {
+                continue;
+            };
+            match entry.file_type() {
+                Some(file_type) if file_type.is_dir() => {
+                    if let Some(mtime) = dir_mtime(entry.path()) {
+                        self.dirs.insert(key, mtime);
+                    }
+                }
+                Some(_) => {
+                    self.files.insert(key);
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// A repository's path index, loaded in memory and optionally kept current
+/// by a filesystem watcher.
+pub struct FileSearchIndex {
+    root: PathBuf,
+    index_path: PathBuf,
+    data: RwLock<IndexData>,
+    dirty: AtomicBool,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl FileSearchIndex {
+    /// Loads the index for the repository containing `search_directory`,
+    /// building or updating it as needed and saving it when it changed.
+    pub fn open(codex_home: &Path, search_directory: &Path) -> anyhow::Result<Self> {
+        let root = repo_root(search_directory);
+        let index_path = index_path(codex_home, &root);
+        let (data, changed) = match IndexData::load(&index_path) {
+            Ok(mut data) if data.is_valid_for(&root) => {
+                let changed = data.revalidate();
+                (data, changed)
+            }
+            _ => (IndexData::build(&root), true),
+        };
+        if changed {
+            data.save(&index_path)?;
+        }
+        Ok(Self {
+            root,
+            index_path,
+            data: RwLock::new(data),
+            dirty: AtomicBool::new(false),
+            watcher: Mutex::new(None),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Starts a watcher that applies filesystem changes to the index until
+    /// the index is dropped.
+    pub fn watch(self: &Arc<Self>) -> anyhow::Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                if let Ok(event) = event
+                    && changes_paths(&event.kind)
+                {
+                    let _ = tx.send(event.paths);
+                }
+            })?;
+        watcher.watch(&self.root, RecursiveMode::Recursive)?;
+
+        let index = Arc::downgrade(self);
+        std::thread::Builder::new()
+            .name("file-search-index-watcher".to_string())
+            .spawn(move || apply_events(index, rx))?;
+
+        #[expect(clippy::unwrap_used)]
+        let mut slot = self.watcher.lock().unwrap();
+        *slot = Some(watcher);
+        Ok(())
+    }
+
+    /// Fuzzy-matches `pattern_text` against the indexed files below
+    /// `search_directory`, returning paths relative to it like [`crate::run`].
+    pub fn search(
+        &self,
+        pattern_text: &str,
+        limit: NonZero<usize>,
+        search_directory: &Path,
+        exclude: Vec<String>,
+        cancel_flag: Arc<AtomicBool>,
+        compute_indices: bool,
+    ) -> anyhow::Result<FileSearchResults> {
+        self.ensure_current_branch();
+
+        let pattern = create_pattern(pattern_text);
+        let exclude = build_excludes(search_directory, exclude)?;
+        let mut best = BestMatchesList::new(
+            limit.get(),
+            pattern.clone(),
+            Matcher::new(nucleo_matcher::Config::DEFAULT),
+        );
+
+        let prefix = relative_key(&self.root, search_directory).ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} is outside the indexed repository {}",
+                search_directory.display(),
+                self.root.display()
+            )
+        })?;
+        #[expect(clippy::unwrap_used)]
+        let data = self.data.read().unwrap();
+        const CHECK_INTERVAL: usize = 1024;
+        for (processed, key) in prefixed(data.files.iter(), &prefix).enumerate() {
+            if processed % CHECK_INTERVAL == 0 && cancel_flag.load(Ordering::Relaxed) {
+                return Ok(FileSearchResults {
+                    matches: Vec::new(),
+                    total_match_count: 0,
+                });
+            }
+            let path = if prefix.is_empty() {
+                key.as_str()
+            } else {
+                &key[prefix.len() + 1..]
+            };
+            if !is_excluded(exclude.as_ref(), path) {
+                best.insert(path);
+            }
+        }
+        drop(data);
+
+        let total_match_count = best.num_matches;
+        let raw_matches = best.binary_heap.into_iter().map(|entry| entry.0).collect();
+        Ok(FileSearchResults {
+            matches: build_file_matches(raw_matches, &pattern, compute_indices),
+            total_match_count,
+        })
+    }
+
+    pub fn status(&self) -> IndexStatus {
+        #[expect(clippy::unwrap_used)]
+        let watching = self.watcher.lock().unwrap().is_some();
+        #[expect(clippy::unwrap_used)]
+        let data = self.data.read().unwrap();
+        status_for(&self.root, &self.index_path, Some(&data), watching)
+    }
+
+    /// Writes the index to disk if the watcher changed it since the last save.
+    pub fn save(&self) -> anyhow::Result<()> {
+        if self.dirty.swap(false, Ordering::Relaxed) {
+            #[expect(clippy::unwrap_used)]
+            let data = self.data.read().unwrap();
+            data.save(&self.index_path)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the index when another branch was checked out since it was
+    /// written. Reading `.git/HEAD` is cheap enough to do on every search and
+    /// also covers worktrees whose git directory is not being watched.
+    fn ensure_current_branch(&self) {
+        let head = read_git_head(&self.root);
+        #[expect(clippy::unwrap_used)]
+        let current = self.data.read().unwrap().git_head == head;
+        if current {
+            return;
+        }
+        let data = IndexData::build(&self.root);
+        if data.save(&self.index_path).is_err() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        #[expect(clippy::unwrap_used)]
+        let mut slot = self.data.write().unwrap();
+        *slot = data;
+    }
+
+    fn refresh_paths(&self, paths: &[PathBuf]) {
+        #[expect(clippy::unwrap_used)]
+        let mut data = self.data.write().unwrap();
+        for path in paths {
+            data.refresh(path);
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for FileSearchIndex {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+/// Returns the process-wide index for the repository containing
+/// `search_directory`, opening it and starting its watcher on first use.
+///
+/// The index stays loaded only while a caller holds the returned `Arc`;
+/// sessions keep theirs for as long as they are open, and the index is saved
+/// and its watcher stopped once the last one is dropped.
+///
+/// Fails when the watcher cannot be started, most often because it runs out
+/// of inotify watches (`fs.inotify.max_user_watches`), since the index would
+/// then miss changes for the rest of the session; callers log the error and
+/// fall back to walking the tree.
+pub fn shared_index(
+    codex_home: &Path,
+    search_directory: &Path,
+) -> anyhow::Result<Arc<FileSearchIndex>> {
+    static INDEXES: OnceLock<Mutex<HashMap<PathBuf, Weak<FileSearchIndex>>>> = OnceLock::new();
+
+    let root = repo_root(search_directory);
+    let indexes = INDEXES.get_or_init(|| Mutex::new(HashMap::new()));
+    #[expect(clippy::unwrap_used)]
+    let mut indexes = indexes.lock().unwrap();
+    if let Some(index) = indexes.get(&root).and_then(Weak::upgrade) {
+        return Ok(index);
+    }
+    indexes.retain(|_, index| index.strong_count() > 0);
+    let index = Arc::new(FileSearchIndex::open(codex_home, &root)?);
+    if let Err(err) = index.watch() {
+        let context = if is_watch_limit_error(&err) {
+            format!(
+                "not enough inotify watches to watch {}; raise fs.inotify.max_user_watches",
+                root.display()
+            )
+        } else {
+            format!("failed to watch {}", root.display())
+        };
+        return Err(err.context(context));
+    }
+    indexes.insert(root, Arc::downgrade(&index));
+    Ok(index)
+}
+
+fn is_watch_limit_error(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<notify::Error>()
+        .is_some_and(|err| matches!(err.kind, notify::ErrorKind::MaxFilesWatch))
+}
+
+/// Describes the stored index for `search_directory` without building or
+/// updating it.
+pub fn index_status(codex_home: &Path, search_directory: &Path) -> IndexStatus {
+    let root = repo_root(search_directory);
+    let index_path = index_path(codex_home, &root);
+    let data = IndexData::load(&index_path).ok();
+    status_for(&root, &index_path, data.as_ref(), false)
+}
+
+fn status_for(
+    root: &Path,
+    index_path: &Path,
+    data: Option<&IndexData>,
+    watching: bool,
+) -> IndexStatus {
+    let Some(data) = data else {
+        return IndexStatus {
+            root: root.to_path_buf(),
+            index_path: index_path.to_path_buf(),
+            state: IndexState::Missing,
+            file_count: 0,
+            stale_directories: Vec::new(),
+            git_head: read_git_head(root),
+            updated_at: None,
+            watching,
+        };
+    };
+    let (state, stale_directories) = if !data.is_valid_for(root) {
+        (IndexState::Invalid, Vec::new())
+    } else {
+        let stale = data.stale_dirs();
+        if stale.is_empty() {
+            (IndexState::Fresh, stale)
+        } else {
+            (IndexState::Stale, stale)
+        }
+    };
+    IndexStatus {
+        root: root.to_path_buf(),
+        index_path: index_path.to_path_buf(),
+        state,
+        file_count: data.files.len(),
+        stale_directories,
+        git_head: data.git_head.clone(),
+        updated_at: Some(data.updated_at),
+        watching,
+    }
+}
+
+/// Whether an event can add, remove or rename paths. Reads and content
+/// changes are ignored; the index itself causes plenty of the former.
+fn changes_paths(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Access(_) => false,
+        EventKind::Modify(modify) => matches!(modify, ModifyKind::Name(_) | ModifyKind::Any),
+        _ => true,
+    }
+}
+
+/// Collects watcher events and applies them once the tree has been quiet
+/// for [`WATCH_DEBOUNCE`], or after [`MAX_WATCH_DELAY`] while changes keep
+/// arriving. Exits when the index or its watcher is dropped.
+fn apply_events(index: Weak<FileSearchIndex>, rx: mpsc::Receiver<Vec<PathBuf>>) {
+    let mut pending: HashSet<PathBuf> = HashSet::new();
+    let mut pending_since: Option<Instant> = None;
+    let mut last_save = Instant::now();
+    loop {
+        match rx.recv_timeout(WATCH_DEBOUNCE) {
+            Ok(paths) => {
+                pending.extend(paths);
+                let since = *pending_since.get_or_insert_with(Instant::now);
+                if since.elapsed() < MAX_WATCH_DELAY {
+                    continue;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+        pending_since = None;
+        if pending.is_empty() {
+            continue;
+        }
+        let Some(index) = index.upgrade() else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = pending.drain().collect();
+        // Refreshing a directory covers everything below it.
+        paths.sort();
+        paths.dedup_by(|path, outer| path.starts_with(outer));
+        index.refresh_paths(&paths);
+        if last_save.elapsed() >= SAVE_INTERVAL {
+            let _ = index.save();
+            last_save = Instant::now();
+        }
+    }
+}
+
+/// Walker configured like [`crate::run`], except that `.git` is skipped.
+fn walk_builder(path: &Path) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(path);
+    builder
+        .hidden(false)
+        .follow_links(true)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git");
+    builder
+}
+
+fn build_excludes(
+    search_directory: &Path,
+    exclude: Vec<String>,
+) -> anyhow::Result<Option<Override>> {
+    if exclude.is_empty() {
+        return Ok(None);
+    }
+    let mut override_builder = OverrideBuilder::new(search_directory);
+    for exclude in exclude {
+        override_builder.add(&format!("!{exclude}"))?;
+    }
+    Ok(Some(override_builder.build()?))
+}
+
+/// Applies the exclude globs to `path` and each of its parent directories,
+/// mirroring how a walk would prune excluded directories.
+fn is_excluded(exclude: Option<&Override>, path: &str) -> bool {
+    let Some(exclude) = exclude else {
+        return false;
+    };
+    let path = Path::new(path);
+    if exclude.matched(path, false).is_ignore() {
+        return true;
+    }
+    path.ancestors()
+        .skip(1)
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .any(|ancestor| exclude.matched(ancestor, true).is_ignore())
+}
+
+/// The nearest ancestor of `dir` containing `.git`, or `dir` itself.
+fn repo_root(dir: &Path) -> PathBuf {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+        .unwrap_or(dir)
+}
+
+fn index_path(codex_home: &Path, root: &Path) -> PathBuf {
+    let digest = Sha1::digest(root.to_string_lossy().as_bytes());
+    let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    codex_home.join(INDEX_DIR).join(format!("{name}.json"))
+}
+
+/// Contents of `HEAD` for the repository at `root`, following the `gitdir:`
+/// indirection used by worktrees and submodules.
+fn read_git_head(root: &Path) -> Option<String> {
+    let dot_git = root.join(".git");
+    let git_dir = if dot_git.is_file() {
+        let contents = std::fs::read_to_string(&dot_git).ok()?;
+        let target = contents.trim().strip_prefix("gitdir:")?.trim();
+        root.join(target)
+    } else {
+        dot_git
+    };
+    std::fs::read_to_string(git_dir.join("HEAD"))
+        .ok()
+        .map(|head| head.trim().to_string())
+}
+
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Option<Vec<&str>> = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect();
+    Some(parts?.join("/"))
+}
+
+fn is_git_dir(key: &str) -> bool {
+    key == ".git" || key.starts_with(".git/")
+}
+
+/// Whether `key` is `prefix` or lies below it.
+fn is_within(key: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || key == prefix
+        || (key.starts_with(prefix) && key.as_bytes().get(prefix.len()) == Some(&b'/'))
+}
+
+/// Yields the keys of a sorted set that are `prefix` or lie below it.
+fn prefixed<'a, I>(keys: I, prefix: &'a str) -> impl Iterator<Item = &'a String> + 'a
+where
+    I: IntoIterator<Item = &'a String>,
+    I::IntoIter: 'a,
+{
+    keys.into_iter()
+        .skip_while(move |key| key.as_str() < prefix)
+        .take_while(move |key| key.starts_with(prefix))
+        .filter(move |key| is_within(key, prefix))
+}
+
+fn remove_prefixed(files: &mut BTreeSet<String>, prefix: &str) {
+    let removed: Vec<String> = files
+        .range(prefix.to_string()..)
+        .take_while(|key| key.starts_with(prefix))
+        .filter(|key| is_within(key, prefix))
+        .cloned()
+        .collect();
+    for key in removed {
+        files.remove(&key);
+    }
+}
+
+fn dir_mtime(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let nanos = modified.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    u64::try_from(nanos).ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn repo() -> anyhow::Result<TempDir> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        std::fs::create_dir_all(root.join(".git"))?;
+        std::fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n")?;
+        std::fs::write(root.join(".gitignore"), "target/\n")?;
+        std::fs::create_dir_all(root.join("src/nested"))?;
+        std::fs::create_dir_all(root.join("target"))?;
+        std::fs::write(root.join("src/lib.rs"), "")?;
+        std::fs::write(root.join("src/nested/mod.rs"), "")?;
+        std::fs::write(root.join("target/out.rs"), "")?;
+        Ok(temp)
+    }
+
+    fn files(index: &FileSearchIndex) -> Vec<String> {
+        let data = index.data.read().unwrap();
+        data.files.iter().cloned().collect()
+    }
+
+    fn search(index: &FileSearchIndex, pattern: &str, dir: &Path) -> Vec<String> {
+        let results = index
+            .search(
+                pattern,
+                NonZero::new(10).expect("non-zero"),
+                dir,
+                Vec::new(),
+                Arc::new(AtomicBool::new(false)),
+                false,
+            )
+            .expect("search");
+        results.matches.into_iter().map(|m| m.path).collect()
+    }
+
+    #[test]
+    fn builds_index_honoring_gitignore_and_persists_it() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+
+        let index = FileSearchIndex::open(home.path(), repo.path())?;
+        assert_eq!(
+            files(&index),
+            vec![".gitignore", "src/lib.rs", "src/nested/mod.rs"]
+        );
+        assert!(index.index_path.exists());
+        assert_eq!(index.status().state, IndexState::Fresh);
+
+        let status = index_status(home.path(), &repo.path().join("src"));
+        assert_eq!(status.state, IndexState::Fresh);
+        assert_eq!(status.file_count, 3);
+        assert_eq!(status.git_head.as_deref(), Some("ref: refs/heads/main"));
+        Ok(())
+    }
+
+    #[test]
+    fn search_is_relative_to_search_directory_and_applies_excludes() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        let index = FileSearchIndex::open(home.path(), repo.path())?;
+
+        let root = index.root().to_path_buf();
+        assert_eq!(
+            search(&index, "mod", &root.join("src")),
+            vec!["nested/mod.rs"]
+        );
+
+        let results = index.search(
+            "rs",
+            NonZero::new(10).expect("non-zero"),
+            &root,
+            vec!["nested".to_string()],
+            Arc::new(AtomicBool::new(false)),
+            false,
+        )?;
+        let paths: Vec<String> = results.matches.into_iter().map(|m| m.path).collect();
+        assert_eq!(paths, vec!["src/lib.rs"]);
+        Ok(())
+    }
+
+    #[test]
+    fn reopening_rewalks_changed_directories() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        drop(FileSearchIndex::open(home.path(), repo.path())?);
+
+        std::fs::write(repo.path().join("src/nested/added.rs"), "")?;
+        std::fs::remove_file(repo.path().join("src/lib.rs"))?;
+        // Some filesystems only record mtimes at a coarse granularity.
+        let root = repo_root(repo.path());
+        let mut data = IndexData::load(&index_path(home.path(), &root))?;
+        for mtime in data.dirs.values_mut() {
+            *mtime = 0;
+        }
+        data.save(&index_path(home.path(), &root))?;
+        assert_eq!(
+            index_status(home.path(), repo.path()).state,
+            IndexState::Stale
+        );
+
+        let index = FileSearchIndex::open(home.path(), repo.path())?;
+        assert_eq!(
+            files(&index),
+            vec![".gitignore", "src/nested/added.rs", "src/nested/mod.rs"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn refresh_applies_additions_removals_and_ignore_rules() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        let index = FileSearchIndex::open(home.path(), repo.path())?;
+        let root = index.root().to_path_buf();
+
+        std::fs::create_dir_all(root.join("docs"))?;
+        std::fs::write(root.join("docs/guide.md"), "")?;
+        std::fs::write(root.join("target/new.rs"), "")?;
+        std::fs::remove_dir_all(root.join("src/nested"))?;
+        index.refresh_paths(&[
+            root.join("docs"),
+            root.join("target/new.rs"),
+            root.join("src/nested"),
+        ]);
+
+        assert_eq!(
+            files(&index),
+            vec![".gitignore", "docs/guide.md", "src/lib.rs"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn branch_switch_rebuilds_index() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        let index = FileSearchIndex::open(home.path(), repo.path())?;
+        let root = index.root().to_path_buf();
+
+        // Simulate a checkout that the watcher did not report.
+        std::fs::write(root.join("src/feature.rs"), "")?;
+        std::fs::write(root.join(".git/HEAD"), "ref: refs/heads/feature\n")?;
+        assert_eq!(index_status(home.path(), &root).state, IndexState::Invalid);
+
+        assert_eq!(search(&index, "feature", &root), vec!["src/feature.rs"]);
+        assert_eq!(index_status(home.path(), &root).state, IndexState::Fresh);
+        Ok(())
+    }
+
+    #[test]
+    fn shared_index_is_released_with_its_last_holder() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        let first = shared_index(home.path(), repo.path())?;
+        let second = shared_index(home.path(), &repo.path().join("src"))?;
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let released = Arc::downgrade(&first);
+        drop(first);
+        drop(second);
+        assert!(released.upgrade().is_none());
+
+        let reopened = shared_index(home.path(), repo.path())?;
+        assert_eq!(
+            files(&reopened),
+            vec![".gitignore", "src/lib.rs", "src/nested/mod.rs"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn watcher_applies_created_files() -> anyhow::Result<()> {
+        let repo = repo()?;
+        let home = tempfile::tempdir()?;
+        let index = Arc::new(FileSearchIndex::open(home.path(), repo.path())?);
+        index.watch()?;
+        assert!(index.status().watching);
+        let root = index.root().to_path_buf();
+
+        std::fs::write(root.join("src/watched.rs"), "")?;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while search(&index, "watched", &root).is_empty() {
+            assert!(
+                Instant::now() < deadline,
+                "watcher did not pick up the file"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert_eq!(search(&index, "watched", &root), vec!["src/watched.rs"]);
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::num::NonZero;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
@@ -19,8 +20,14 @@ use std::sync::atomic::Ordering;
 use tokio::process::Command;
 
 mod cli;
+mod index;
 
 pub use cli::Cli;
+pub use index::FileSearchIndex;
+pub use index::IndexState;
+pub use index::IndexStatus;
+pub use index::index_status;
+pub use index::shared_index;
 
 /// A single match result returned from the search.
 ///
@@ -50,6 +57,7 @@ pub trait Reporter {
     fn report_match(&self, file_match: &FileMatch);
     fn warn_matches_truncated(&self, total_match_count: usize, shown_match_count: usize);
     fn warn_no_search_pattern(&self, search_directory: &Path);
+    fn report_index_status(&self, status: &IndexStatus);
 }
 
 pub async fn run_main<T: Reporter>(
@@ -61,6 +69,9 @@ pub async fn run_main<T: Reporter>(
         json: _,
         exclude,
         threads,
+        index,
+        index_status: show_index_status,
+        codex_home,
     }: Cli,
     reporter: T,
 ) -> anyhow::Result<()> {
@@ -68,6 +79,14 @@ pub async fn run_main<T: Reporter>(
         Some(dir) => dir,
         None => std::env::current_dir()?,
     };
+    let codex_home = match codex_home {
+        Some(dir) => dir,
+        None => default_codex_home()?,
+    };
+    if show_index_status {
+        reporter.report_index_status(&index_status(&codex_home, &search_directory));
+        return Ok(());
+    }
     let pattern_text = match pattern {
         Some(pattern) => pattern,
         None => {
@@ -98,16 +117,27 @@ pub async fn run_main<T: Reporter>(
     let FileSearchResults {
         total_match_count,
         matches,
-    } = run(
-        &pattern_text,
-        limit,
-        &search_directory,
-        exclude,
-        threads,
-        cancel_flag,
-        compute_indices,
-        true,
-    )?;
+    } = if index {
+        FileSearchIndex::open(&codex_home, &search_directory)?.search(
+            &pattern_text,
+            limit,
+            &search_directory,
+            exclude,
+            cancel_flag,
+            compute_indices,
+        )?
+    } else {
+        run(
+            &pattern_text,
+            limit,
+            &search_directory,
+            exclude,
+            threads,
+            cancel_flag,
+            compute_indices,
+            true,
+        )?
+    };
     let match_count = matches.len();
     let matches_truncated = total_match_count > match_count;
 
@@ -121,6 +151,17 @@ pub async fn run_main<T: Reporter>(
     Ok(())
 }
 
+/// `$CODEX_HOME` when set, otherwise `~/.codex`.
+fn default_codex_home() -> anyhow::Result<PathBuf> {
+    if let Ok(val) = std::env::var("CODEX_HOME")
+        && !val.is_empty()
+    {
+        return Ok(PathBuf::from(val));
+    }
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("could not find home directory"))?;
+    Ok(home.join(".codex"))
+}
+
 /// The worker threads will periodically check `cancel_flag` to see if they
 /// should stop processing files.
 #[allow(clippy::too_many_arguments)]
@@ -257,17 +298,29 @@ pub fn run(
         }
     }
 
-    let mut raw_matches: Vec<(u32, String)> = global_heap.into_iter().map(|r| r.0).collect();
+    let raw_matches: Vec<(u32, String)> = global_heap.into_iter().map(|r| r.0).collect();
+    Ok(FileSearchResults {
+        matches: build_file_matches(raw_matches, &pattern, compute_indices),
+        total_match_count,
+    })
+}
+
+/// Sorts the best matches and turns them into `FileMatch`, optionally
+/// computing indices.
+fn build_file_matches(
+    mut raw_matches: Vec<(u32, String)>,
+    pattern: &Pattern,
+    compute_indices: bool,
+) -> Vec<FileMatch> {
     sort_matches(&mut raw_matches);
 
-    // Transform into `FileMatch`, optionally computing indices.
     let mut matcher = if compute_indices {
         Some(Matcher::new(nucleo_matcher::Config::DEFAULT))
     } else {
         None
     };
 
-    let matches: Vec<FileMatch> = raw_matches
+    raw_matches
         .into_iter()
         .map(|(score, path)| {
             let indices = if compute_indices {
@@ -291,12 +344,7 @@ pub fn run(
                 indices,
             }
         })
-        .collect();
-
-    Ok(FileSearchResults {
-        matches,
-        total_match_count,
-    })
+        .collect()
 }
 
 /// Sort matches in-place by descending score, then ascending path.
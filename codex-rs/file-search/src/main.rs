@@ -4,6 +4,8 @@ use std::path::Path;
 use clap::Parser;
 use codex_file_search::Cli;
 use codex_file_search::FileMatch;
+use codex_file_search::IndexState;
+use codex_file_search::IndexStatus;
 use codex_file_search::Reporter;
 use codex_file_search::run_main;
 use serde_json::json;
@@ -75,4 +77,30 @@ impl Reporter for StdioReporter {
             search_directory.to_string_lossy()
         );
     }
+
+    fn report_index_status(&self, status: &IndexStatus) {
+        if self.write_output_as_json {
+            println!("{}", serde_json::to_string(status).unwrap());
+            return;
+        }
+        let state = match status.state {
+            IndexState::Missing => "missing",
+            IndexState::Fresh => "fresh",
+            IndexState::Stale => "stale (changed directories are re-walked on the next search)",
+            IndexState::Invalid => "invalid (rebuilt on the next search)",
+        };
+        println!("Root: {}", status.root.display());
+        println!("Index: {}", status.index_path.display());
+        println!("State: {state}");
+        if status.state != IndexState::Missing {
+            println!("Files: {}", status.file_count);
+        }
+        if let Some(head) = &status.git_head {
+            println!("Git HEAD: {head}");
+        }
+        for dir in &status.stale_directories {
+            let dir = if dir.is_empty() { "." } else { dir.as_str() };
+            println!("Changed: {dir}");
+        }
+    }
 }
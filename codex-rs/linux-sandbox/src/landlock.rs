@@ -1,17 +1,24 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::read_deny::ReadDeniedPaths;
 use codex_utils_absolute_path::AbsolutePathBuf;
 
 use landlock::ABI;
 use landlock::Access;
 use landlock::AccessFs;
+use landlock::AccessNet;
+use landlock::BitFlags;
 use landlock::CompatLevel;
 use landlock::Compatible;
+use landlock::NetPort;
+use landlock::PathBeneath;
+use landlock::PathFd;
 use landlock::Ruleset;
 use landlock::RulesetAttr;
 use landlock::RulesetCreatedAttr;
@@ -24,24 +31,73 @@ use seccompiler::SeccompFilter;
 use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
+use tracing::warn;
+
+use crate::network_namespace::enter_proxy_only_network_namespace;
+use crate::read_deny_mounts::hide_read_denied_paths;
 
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// `network_proxy` is the egress proxy's port and Unix socket. When the
+/// policy has a `network_allowlist`, the command only reaches the network
+/// through it; if the namespace that enforces this cannot be set up, the
+/// network is disabled instead.
+///
+/// `read_denied_paths` are hidden from the command. When that is not
+/// possible, only their `carve_paths` are denied with Landlock instead.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_proxy: Option<(u16, &Path)>,
+    read_denied_paths: &ReadDeniedPaths,
 ) -> Result<()> {
+    // Hiding denied paths needs `mount`, which both the seccomp filter and
+    // Landlock forbid, so it has to come first.
+    let unhidden_paths: &[PathBuf] = if read_denied_paths.paths.is_empty() {
+        &[]
+    } else {
+        match hide_read_denied_paths(&read_denied_paths.paths) {
+            Ok(()) => &[],
+            Err(err) => {
+                warn!(
+                    "cannot hide read_deny paths, denying reads of the configured ones with Landlock instead: {err}"
+                );
+                &read_denied_paths.carve_paths
+            }
+        }
+    };
+
     if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+        let proxy_port = network_proxy
+            .filter(|_| !sandbox_policy.network_allowlist().is_empty())
+            .and_then(|(port, socket_path)| {
+                match enter_proxy_only_network_namespace(port, socket_path) {
+                    Ok(()) => Some(port),
+                    Err(err) => {
+                        eprintln!(
+                            "codex-linux-sandbox: cannot isolate the network for network_allowlist, disabling network access: {err}"
+                        );
+                        None
+                    }
+                }
+            });
+        match proxy_port {
+            Some(port) => {
+                install_proxy_only_network_seccomp_filter_on_current_thread()?;
+                install_proxy_only_network_landlock_rules_on_current_thread(port)?;
+            }
+            None => install_network_seccomp_filter_on_current_thread()?,
+        }
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
-        let writable_roots = sandbox_policy
+        let writable_roots: Vec<AbsolutePathBuf> = sandbox_policy
             .get_writable_roots_with_cwd(cwd)
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, unhidden_paths)?;
     }
 
     // TODO(ragona): Add appropriate restrictions if
@@ -54,10 +110,20 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
 /// access to the entire file-system while restricting write access to
 /// `/dev/null` and the provided list of `writable_roots`.
 ///
+/// Paths in `read_denied_paths` get no access at all. This is the fallback
+/// for when they cannot be hidden with a mount namespace: Landlock can only
+/// grant access, so each root that contains a denied path is split. The
+/// directories leading to the denied path keep every right except `ReadFile`,
+/// and each of their other children receives the full rights of the root.
+/// Files created by the command directly inside one of those directories are
+/// therefore not readable until the next command, which is why only paths
+/// from user-configured patterns get here.
+///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
 fn install_filesystem_landlock_rules_on_current_thread(
     writable_roots: Vec<AbsolutePathBuf>,
+    read_denied_paths: &[PathBuf],
 ) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
@@ -67,12 +133,20 @@ fn install_filesystem_landlock_rules_on_current_thread(
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .add_rules(carved_path_beneath_rules(
+            Path::new("/"),
+            access_ro,
+            read_denied_paths,
+        ))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
-    if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
+    for writable_root in &writable_roots {
+        ruleset = ruleset.add_rules(carved_path_beneath_rules(
+            writable_root.as_path(),
+            access_rw,
+            read_denied_paths,
+        ))?;
     }
 
     let status = ruleset.restrict_self()?;
@@ -84,9 +158,149 @@ fn install_filesystem_landlock_rules_on_current_thread(
     Ok(())
 }
 
+/// Builds the path-beneath rules that grant `access` to `root` minus the
+/// subtrees in `denied`. Like [`landlock::path_beneath_rules`], paths that
+/// cannot be opened are skipped.
+fn carved_path_beneath_rules(
+    root: &Path,
+    access: BitFlags<AccessFs>,
+    denied: &[PathBuf],
+) -> Vec<std::result::Result<PathBeneath<PathFd>, landlock::RulesetError>> {
+    // Denied paths are canonical, so compare against the canonical root.
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut rules = Vec::new();
+    let mut pending = vec![root];
+    while let Some(path) = pending.pop() {
+        if denied.iter().any(|denied| path.starts_with(denied)) {
+            continue;
+        }
+        let contains_denied = denied
+            .iter()
+            .any(|denied| denied != &path && denied.starts_with(&path));
+        let path_access = if contains_denied {
+            access & !AccessFs::ReadFile
+        } else {
+            access
+        };
+        rules.extend(landlock::path_beneath_rules([&path], path_access));
+        if contains_denied && let Ok(read_dir) = std::fs::read_dir(&path) {
+            pending.extend(read_dir.flatten().map(|entry| entry.path()));
+        }
+    }
+    rules
+}
+
+/// Installs Landlock network rules on the current thread that only allow TCP
+/// connections to `proxy_port` and forbid binding TCP ports.
+///
+/// The network namespace already confines the command to the proxy relay, so
+/// this is defense in depth and is skipped on kernels without Landlock
+/// network support (ABI < 4).
+fn install_proxy_only_network_landlock_rules_on_current_thread(proxy_port: u16) -> Result<()> {
+    Ruleset::default()
+        .set_compatibility(CompatLevel::BestEffort)
+        .handle_access(AccessNet::from_all(ABI::V4))?
+        .create()?
+        .add_rule(NetPort::new(proxy_port, AccessNet::ConnectTcp))?
+        .restrict_self()?;
+    Ok(())
+}
+
+/// Installs the seccomp filter used inside the proxy-only network namespace.
+///
+/// It starts from the network-off rules and only relaxes what a TCP client
+/// of the proxy relay needs: `connect` and the calls that operate on an
+/// already connected socket, plus IPv4/IPv6 TCP stream sockets in `socket`.
+/// `sendto` remains denied whenever it names a destination address, and
+/// datagram and raw sockets stay blocked so UDP (including DNS) cannot bypass
+/// the proxy. Serving (`bind`, `listen`, `accept`) and the batched
+/// `sendmmsg`/`recvmmsg` stay denied as well.
+fn install_proxy_only_network_seccomp_filter_on_current_thread()
+-> std::result::Result<(), SandboxErr> {
+    let mut rules = network_deny_rules()?;
+
+    for nr in [
+        libc::SYS_connect,
+        libc::SYS_getpeername,
+        libc::SYS_getsockname,
+        libc::SYS_getsockopt,
+        libc::SYS_setsockopt,
+        libc::SYS_shutdown,
+    ] {
+        rules.remove(&nr);
+    }
+
+    // `send()` is `sendto()` without a destination (argument 4).
+    rules.insert(
+        libc::SYS_sendto,
+        vec![SeccompRule::new(vec![SeccompCondition::new(
+            4,
+            SeccompCmpArgLen::Qword,
+            SeccompCmpOp::Ne,
+            0,
+        )?])?],
+    );
+
+    // Deny any domain other than AF_UNIX, AF_INET and AF_INET6.
+    let mut socket_rules = vec![SeccompRule::new(vec![
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_UNIX as u64,
+        )?,
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET as u64,
+        )?,
+        SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET6 as u64,
+        )?,
+    ])?];
+    for domain in [libc::AF_INET, libc::AF_INET6] {
+        // Every socket type other than SOCK_STREAM (1) sets one of these bits
+        // in the low nibble of `type`, which also carries SOCK_* flags.
+        for type_bit in [0x2u64, 0x4, 0x8] {
+            socket_rules.push(SeccompRule::new(vec![
+                SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Eq, domain as u64)?,
+                SeccompCondition::new(
+                    1,
+                    SeccompCmpArgLen::Dword,
+                    SeccompCmpOp::MaskedEq(type_bit),
+                    type_bit,
+                )?,
+            ])?);
+        }
+        // Stream sockets must be plain TCP (protocol 0 or IPPROTO_TCP).
+        socket_rules.push(SeccompRule::new(vec![
+            SeccompCondition::new(0, SeccompCmpArgLen::Dword, SeccompCmpOp::Eq, domain as u64)?,
+            SeccompCondition::new(2, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, 0)?,
+            SeccompCondition::new(
+                2,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::IPPROTO_TCP as u64,
+            )?,
+        ])?);
+    }
+    rules.insert(libc::SYS_socket, socket_rules);
+
+    apply_seccomp_rules(rules)
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    apply_seccomp_rules(network_deny_rules()?)
+}
+
+/// Rules of the network-off filter, which the proxy-only filter builds on.
+fn network_deny_rules() -> std::result::Result<BTreeMap<i64, Vec<SeccompRule>>, SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
@@ -124,6 +338,14 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    Ok(rules)
+}
+
+/// Compiles `rules` into a filter that returns EPERM for matching syscalls and
+/// applies it to the current thread.
+fn apply_seccomp_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -2,6 +2,10 @@
 mod landlock;
 #[cfg(target_os = "linux")]
 mod linux_run_main;
+#[cfg(target_os = "linux")]
+mod network_namespace;
+#[cfg(target_os = "linux")]
+mod read_deny_mounts;
 
 #[cfg(target_os = "linux")]
 pub fn run_main() -> ! {
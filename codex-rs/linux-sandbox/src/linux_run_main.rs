@@ -1,4 +1,5 @@
 use clap::Parser;
+use codex_core::read_deny::ReadDeniedPaths;
 use std::ffi::CString;
 use std::path::PathBuf;
 
@@ -14,6 +15,26 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Loopback port of the egress proxy that enforces the policy's
+    /// `network_allowlist`, as advertised to the command.
+    #[arg(long = "network-proxy-port", requires = "network_proxy_socket")]
+    pub network_proxy_port: Option<u16>,
+
+    /// Unix socket of the egress proxy. The command runs in its own network
+    /// namespace where `network_proxy_port` is relayed to this socket.
+    #[arg(long = "network-proxy-socket", requires = "network_proxy_port")]
+    pub network_proxy_socket: Option<PathBuf>,
+
+    /// Existing paths denied by the policy's `read_deny` list, as resolved by
+    /// the caller. They are hidden from the command.
+    #[arg(long = "read-deny-path")]
+    pub read_deny_paths: Vec<PathBuf>,
+
+    /// The `read_deny_paths` that Landlock should still deny when they cannot
+    /// be hidden.
+    #[arg(long = "read-deny-carve-path")]
+    pub read_deny_carve_paths: Vec<PathBuf>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +44,24 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        network_proxy_port,
+        network_proxy_socket,
+        read_deny_paths,
+        read_deny_carve_paths,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let read_denied_paths = ReadDeniedPaths {
+        paths: read_deny_paths,
+        carve_paths: read_deny_carve_paths,
+        complete: true,
+    };
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        network_proxy_port.zip(network_proxy_socket.as_deref()),
+        &read_denied_paths,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -0,0 +1,133 @@
+//! Network namespace for commands whose policy has a `network_allowlist`.
+//!
+//! The command runs in a fresh network namespace whose only interface is
+//! loopback, so no host can be reached directly, whatever the address or
+//! port. A relay process listening on `127.0.0.1:<proxy port>` inside the
+//! namespace forwards every connection to the egress proxy's Unix socket,
+//! which stays reachable through the file system.
+
+use std::io;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::UdpSocket;
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Moves the current process into a new network namespace with loopback up
+/// and forks a relay from `127.0.0.1:port` to `socket_path`.
+///
+/// Must run while the process is still single-threaded and before seccomp and
+/// Landlock are installed, since both would forbid the setup.
+pub(crate) fn enter_proxy_only_network_namespace(port: u16, socket_path: &Path) -> io::Result<()> {
+    unshare_namespace(libc::CLONE_NEWNET)?;
+    bring_up_loopback()?;
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    spawn_relay(listener, socket_path.to_path_buf())
+}
+
+/// Unshares the namespaces in `flags` directly when the process may
+/// administer namespaces, and otherwise inside a new user namespace that maps
+/// the caller's ids onto themselves so file ownership is unchanged.
+pub(crate) fn unshare_namespace(flags: libc::c_int) -> io::Result<()> {
+    if unsafe { libc::unshare(flags) } == 0 {
+        return Ok(());
+    }
+
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
+
+/// A new network namespace starts with `lo` down.
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        request.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS as _, &request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Forks the relay. It becomes a child of the sandboxed command (which keeps
+/// this process' pid across `execvp`) and is killed when the command exits.
+fn spawn_relay(listener: TcpListener, socket_path: PathBuf) -> io::Result<()> {
+    let parent_pid = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            unsafe {
+                libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+                // The command may already be gone if it exited before prctl.
+                if libc::getppid() != parent_pid {
+                    libc::_exit(0);
+                }
+            }
+            detach_stdio();
+            run_relay(&listener, &socket_path);
+            unsafe { libc::_exit(0) }
+        }
+        // The listener stays open in the relay only.
+        _ => Ok(()),
+    }
+}
+
+/// Points the relay's stdio at /dev/null so it does not keep the command's
+/// output pipes open.
+fn detach_stdio() {
+    if let Ok(dev_null) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    {
+        for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            unsafe {
+                libc::dup2(dev_null.as_raw_fd(), fd);
+            }
+        }
+    }
+}
+
+fn run_relay(listener: &TcpListener, socket_path: &Path) {
+    for client in listener.incoming().flatten() {
+        let socket_path = socket_path.to_path_buf();
+        std::thread::spawn(move || {
+            if let Ok(upstream) = UnixStream::connect(&socket_path) {
+                relay_connection(client, upstream);
+            }
+        });
+    }
+}
+
+fn relay_connection(client: TcpStream, upstream: UnixStream) {
+    let (Ok(mut client_read), Ok(mut upstream_write)) = (client.try_clone(), upstream.try_clone())
+    else {
+        return;
+    };
+    let forward = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+    let (mut upstream_read, mut client_write) = (upstream, client);
+    let _ = io::copy(&mut upstream_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = forward.join();
+}
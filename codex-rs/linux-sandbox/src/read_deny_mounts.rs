@@ -0,0 +1,79 @@
+//! Hides the paths denied by a policy's `read_deny` list.
+//!
+//! The command runs in a private mount namespace where every denied path is
+//! covered by a placeholder: an empty, mode `000` directory for directories
+//! and a Unix socket for everything else, which `open(2)` rejects even for
+//! root. The directories that contain a denied path are left untouched, so
+//! their other entries, including files the command creates later, stay
+//! readable.
+
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::network_namespace::unshare_namespace;
+
+/// Moves the current process into a new mount namespace and covers each of
+/// `denied_paths` with a placeholder.
+///
+/// Must run while the process is still single-threaded and before seccomp
+/// (which forbids `bind`) and Landlock (which forbids `mount`) are installed.
+pub(crate) fn hide_read_denied_paths(denied_paths: &[PathBuf]) -> io::Result<()> {
+    unshare_namespace(libc::CLONE_NEWNS)?;
+    // Keep the bind mounts below from propagating back to the host.
+    mount(None, Path::new("/"), libc::MS_REC | libc::MS_PRIVATE)?;
+
+    let placeholders = std::env::temp_dir().join(format!("codex-read-deny-{}", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&placeholders)?;
+    let empty_dir = placeholders.join("dir");
+    let socket = placeholders.join("file");
+    let result = cover_paths(denied_paths, &empty_dir, &socket);
+    // The mounts keep the placeholders alive once they are unlinked.
+    let _ = std::fs::remove_file(&socket);
+    let _ = std::fs::remove_dir(&empty_dir);
+    let _ = std::fs::remove_dir(&placeholders);
+    result
+}
+
+fn cover_paths(denied_paths: &[PathBuf], empty_dir: &Path, socket: &Path) -> io::Result<()> {
+    std::fs::DirBuilder::new().mode(0o000).create(empty_dir)?;
+    drop(UnixListener::bind(socket)?);
+
+    for path in denied_paths {
+        let metadata = path.symlink_metadata()?;
+        let placeholder = if metadata.is_dir() { empty_dir } else { socket };
+        mount(Some(placeholder), path, libc::MS_BIND)?;
+    }
+    Ok(())
+}
+
+fn mount(source: Option<&Path>, target: &Path, flags: libc::c_ulong) -> io::Result<()> {
+    let source = source.map(path_to_cstring).transpose()?;
+    let target = path_to_cstring(target)?;
+    let rc = unsafe {
+        libc::mount(
+            source
+                .as_ref()
+                .map_or(std::ptr::null(), |source| source.as_ptr()),
+            target.as_ptr(),
+            std::ptr::null(),
+            flags,
+            std::ptr::null(),
+        )
+    };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
@@ -5,8 +5,11 @@ use codex_core::error::SandboxErr;
 use codex_core::exec::ExecParams;
 use codex_core::exec::process_exec_tool_call;
 use codex_core::exec_env::create_env;
+use codex_core::landlock::spawn_command_under_linux_sandbox;
+use codex_core::network_proxy::NetworkProxy;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::sandboxing::SandboxPermissions;
+use codex_core::spawn::StdioPolicy;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -34,8 +37,17 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
+    run_cmd_with_read_deny(cmd, writable_roots, &[], timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
+async fn run_cmd_with_read_deny(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    read_deny: &[String],
+    timeout_ms: u64,
+) {
     let cwd = std::env::current_dir().expect("cwd should exist");
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
@@ -59,6 +71,8 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        network_allowlist: vec![],
+        read_deny: read_deny.to_vec(),
     };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
@@ -127,6 +141,71 @@ async fn test_writable_root() {
     .await;
 }
 
+#[expect(clippy::unwrap_used)]
+fn read_deny_fixture() -> (tempfile::TempDir, PathBuf, PathBuf) {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret.txt");
+    let visible = tmpdir.path().join("visible.txt");
+    std::fs::write(&secret, "token").unwrap();
+    std::fs::write(&visible, "hello").unwrap();
+    (tmpdir, secret, visible)
+}
+
+#[tokio::test]
+async fn test_read_deny_keeps_siblings_readable() {
+    let (tmpdir, secret, visible) = read_deny_fixture();
+    run_cmd_with_read_deny(
+        &["cat", &visible.to_string_lossy()],
+        &[tmpdir.path().to_path_buf()],
+        &[secret.to_string_lossy().to_string()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_read_deny_blocks_denied_file() {
+    let (tmpdir, secret, _visible) = read_deny_fixture();
+    run_cmd_with_read_deny(
+        &["cat", &secret.to_string_lossy()],
+        &[tmpdir.path().to_path_buf()],
+        &[secret.to_string_lossy().to_string()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_read_deny_keeps_new_files_in_parent_readable() {
+    if !mount_namespaces_available() {
+        return;
+    }
+    let (tmpdir, secret, _visible) = read_deny_fixture();
+    let created = tmpdir.path().join("created.txt");
+    let created = created.to_string_lossy();
+    run_cmd_with_read_deny(
+        &[
+            "bash",
+            "-c",
+            &format!("echo hi > {created} && cat {created}"),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[secret.to_string_lossy().to_string()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+/// Whether the helper can hide `read_deny` paths with a mount namespace
+/// instead of falling back to denying reads in their parent directories.
+fn mount_namespaces_available() -> bool {
+    std::process::Command::new("unshare")
+        .args(["--user", "--mount", "true"])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -238,3 +317,96 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+fn allowlist_policy(network_allowlist: Vec<String>) -> SandboxPolicy {
+    SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![],
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        network_allowlist,
+        read_deny: vec![],
+    }
+}
+
+/// Runs `script` with bash under the Linux sandbox, pointed at `proxy`.
+#[expect(clippy::expect_used)]
+async fn run_with_network_proxy(
+    script: &str,
+    policy: &SandboxPolicy,
+    proxy: &NetworkProxy,
+) -> std::process::Output {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let child = spawn_command_under_linux_sandbox(
+        sandbox_program,
+        vec!["bash".to_string(), "-c".to_string(), script.to_string()],
+        cwd.clone(),
+        policy,
+        cwd.as_path(),
+        StdioPolicy::RedirectForShellTool,
+        create_env_from_core_vars(),
+        Some(proxy.endpoint()),
+    )
+    .await
+    .expect("spawn sandboxed command");
+    tokio::time::timeout(
+        std::time::Duration::from_millis(NETWORK_TIMEOUT_MS),
+        child.wait_with_output(),
+    )
+    .await
+    .expect("sandboxed command timed out")
+    .expect("wait for sandboxed command")
+}
+
+/// Whether unprivileged user namespaces are available, which the helper
+/// needs to isolate the network for `network_allowlist`.
+fn user_namespaces_available() -> bool {
+    std::process::Command::new("unshare")
+        .args(["--user", "--net", "true"])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[tokio::test]
+#[expect(clippy::unwrap_used)]
+async fn network_allowlist_blocks_other_hosts_on_proxy_port() {
+    let allowed = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let allowed_port = allowed.local_addr().unwrap().port();
+    let policy = allowlist_policy(vec![format!("127.0.0.1:{allowed_port}")]);
+    let proxy = NetworkProxy::start(policy.network_allowlist().to_vec())
+        .await
+        .unwrap();
+    let proxy_port = proxy.endpoint().port;
+
+    // A host that is not allowlisted, listening on the proxy's port.
+    let other_host = std::net::TcpListener::bind(("127.0.0.2", proxy_port)).unwrap();
+    other_host.set_nonblocking(true).unwrap();
+
+    let output = run_with_network_proxy(
+        &format!("exec 3<>/dev/tcp/127.0.0.2/{proxy_port}"),
+        &policy,
+        &proxy,
+    )
+    .await;
+    assert!(
+        !output.status.success(),
+        "direct connection to 127.0.0.2:{proxy_port} succeeded"
+    );
+    assert!(other_host.accept().is_err());
+
+    if !user_namespaces_available() {
+        return;
+    }
+    let output = run_with_network_proxy(
+        &format!(
+            "exec 3<>/dev/tcp/127.0.0.1/{proxy_port} && \
+             printf 'CONNECT 127.0.0.1:{allowed_port} HTTP/1.1\\r\\n\\r\\n' >&3 && \
+             head -c 12 <&3"
+        ),
+        &policy,
+        &proxy,
+    )
+    .await;
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "HTTP/1.1 200");
+}
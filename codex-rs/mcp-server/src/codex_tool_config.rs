@@ -170,6 +170,7 @@ impl CodexToolCallParam {
             show_raw_agent_reasoning: None,
             tools_web_search_request: None,
             additional_writable_roots: Vec::new(),
+            model_cassette: None,
         };
 
         let cli_overrides = cli_overrides
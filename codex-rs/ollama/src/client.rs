@@ -188,7 +188,7 @@ impl OllamaClient {
     pub async fn pull_with_reporter(
         &self,
         model: &str,
-        reporter: &mut dyn PullProgressReporter,
+        reporter: &mut (dyn PullProgressReporter + Send),
     ) -> io::Result<()> {
         reporter.on_event(&PullEvent::Status(format!("Pulling model {model}...")))?;
         let mut stream = self.pull_model_stream(model).await?;
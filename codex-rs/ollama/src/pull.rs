@@ -135,13 +135,121 @@ impl PullProgressReporter for CliProgressReporter {
     }
 }
 
-/// For now the TUI reporter delegates to the CLI reporter. This keeps UI and
-/// CLI behavior aligned until a dedicated TUI integration is implemented.
-#[derive(Default)]
-pub struct TuiProgressReporter(CliProgressReporter);
+/// Reporter for the TUI: folds per-layer progress into a single status line
+/// and hands it to `on_progress`, which forwards it to the UI. Repeated lines
+/// are dropped so redraws follow visible changes only.
+pub struct TuiProgressReporter {
+    on_progress: Box<dyn FnMut(String) + Send>,
+    totals_by_digest: HashMap<String, (u64, u64)>,
+    last_line: Option<String>,
+}
+
+impl TuiProgressReporter {
+    pub fn new(on_progress: impl FnMut(String) + Send + 'static) -> Self {
+        Self {
+            on_progress: Box::new(on_progress),
+            totals_by_digest: HashMap::new(),
+            last_line: None,
+        }
+    }
+
+    fn line_for(&mut self, event: &PullEvent) -> Option<String> {
+        match event {
+            PullEvent::Status(status) => Some(status.clone()),
+            PullEvent::ChunkProgress {
+                digest,
+                total,
+                completed,
+            } => {
+                let entry = self.totals_by_digest.entry(digest.clone()).or_default();
+                if let Some(t) = *total {
+                    entry.0 = t;
+                }
+                if let Some(c) = *completed {
+                    entry.1 = c;
+                }
+                let (sum_total, sum_completed) = self
+                    .totals_by_digest
+                    .values()
+                    .fold((0u64, 0u64), |acc, (t, c)| (acc.0 + *t, acc.1 + *c));
+                if sum_total == 0 {
+                    return None;
+                }
+                let done_gb = (sum_completed as f64) / (1024.0 * 1024.0 * 1024.0);
+                let total_gb = (sum_total as f64) / (1024.0 * 1024.0 * 1024.0);
+                let pct = (sum_completed as f64) * 100.0 / (sum_total as f64);
+                Some(format!(
+                    "Downloading model: {done_gb:.2}/{total_gb:.2} GB ({pct:.0}%)"
+                ))
+            }
+            PullEvent::Success => Some("Model downloaded".to_string()),
+            // Surfaced by the caller through the returned error.
+            PullEvent::Error(_) => None,
+        }
+    }
+}
 
 impl PullProgressReporter for TuiProgressReporter {
     fn on_event(&mut self, event: &PullEvent) -> io::Result<()> {
-        self.0.on_event(event)
+        if let Some(line) = self.line_for(event)
+            && self.last_line.as_ref() != Some(&line)
+        {
+            (self.on_progress)(line.clone());
+            self.last_line = Some(line);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[test]
+    fn tui_reporter_sums_layers_and_skips_repeated_lines() {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&lines);
+        let mut reporter = TuiProgressReporter::new(move |line| {
+            if let Ok(mut lines) = sink.lock() {
+                lines.push(line);
+            }
+        });
+        const GB: u64 = 1024 * 1024 * 1024;
+        let events = [
+            PullEvent::Status("pulling manifest".to_string()),
+            PullEvent::ChunkProgress {
+                digest: "a".to_string(),
+                total: Some(GB),
+                completed: Some(0),
+            },
+            PullEvent::ChunkProgress {
+                digest: "b".to_string(),
+                total: Some(GB),
+                completed: Some(GB / 2),
+            },
+            PullEvent::ChunkProgress {
+                digest: "b".to_string(),
+                total: None,
+                completed: Some(GB / 2),
+            },
+            PullEvent::Error("ignored".to_string()),
+            PullEvent::Success,
+        ];
+        for event in &events {
+            reporter.on_event(event).expect("report");
+        }
+
+        let lines = lines.lock().expect("lines").clone();
+        assert_eq!(
+            lines,
+            vec![
+                "pulling manifest".to_string(),
+                "Downloading model: 0.00/1.00 GB (0%)".to_string(),
+                "Downloading model: 0.50/2.00 GB (25%)".to_string(),
+                "Model downloaded".to_string(),
+            ]
+        );
     }
 }
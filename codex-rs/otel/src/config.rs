@@ -11,6 +11,7 @@ pub struct OtelSettings {
     pub codex_home: PathBuf,
     pub exporter: OtelExporter,
     pub trace_exporter: OtelExporter,
+    pub metrics_exporter: OtelExporter,
 }
 
 #[derive(Clone, Debug)]
@@ -31,6 +32,8 @@ pub struct OtelTlsConfig {
 #[derive(Clone, Debug)]
 pub enum OtelExporter {
     None,
+    /// OTLP-JSON written to rotating files under `codex_home/otel/`.
+    File,
     OtlpGrpc {
         endpoint: String,
         headers: HashMap<String, String>,
@@ -0,0 +1,250 @@
+//! Span and log exporters that write OTLP-JSON to rotating files, for machines
+//! that cannot reach a collector.
+//!
+//! Every export batch becomes one line holding an `ExportTraceServiceRequest`
+//! or `ExportLogsServiceRequest`, the layout the OpenTelemetry Collector's file
+//! exporter uses, so the files can be replayed into a collector later.
+
+use chrono::Utc;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
+use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
+use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkError;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::LogBatch;
+use opentelemetry_sdk::logs::LogExporter;
+use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::trace::SpanExporter;
+use serde::Serialize;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+pub const TRACES_FILE_PREFIX: &str = "traces";
+pub const LOGS_FILE_PREFIX: &str = "logs";
+pub const FILE_EXTENSION: &str = "jsonl";
+
+/// Size after which a new file is started.
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+/// Files kept per signal; older ones are deleted on rotation.
+const MAX_FILES: usize = 20;
+
+/// Directory the file exporter writes to.
+pub fn otel_dir(codex_home: &Path) -> PathBuf {
+    codex_home.join("otel")
+}
+
+#[derive(Debug)]
+pub(crate) struct FileSpanExporter {
+    file: OtlpJsonFile,
+    resource: Resource,
+}
+
+impl FileSpanExporter {
+    pub(crate) fn new(dir: PathBuf, resource: &Resource) -> Self {
+        Self {
+            file: OtlpJsonFile::new(dir, TRACES_FILE_PREFIX),
+            resource: resource.clone(),
+        }
+    }
+}
+
+impl SpanExporter for FileSpanExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let resource = ResourceAttributesWithSchema::from(&self.resource);
+        let request = ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &resource),
+        };
+        self.file.write(&request)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.clone();
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct FileLogExporter {
+    file: OtlpJsonFile,
+    resource: Resource,
+}
+
+impl FileLogExporter {
+    pub(crate) fn new(dir: PathBuf, resource: &Resource) -> Self {
+        Self {
+            file: OtlpJsonFile::new(dir, LOGS_FILE_PREFIX),
+            resource: resource.clone(),
+        }
+    }
+}
+
+impl LogExporter for FileLogExporter {
+    async fn export(&self, batch: LogBatch<'_>) -> OTelSdkResult {
+        let resource = ResourceAttributesWithSchema::from(&self.resource);
+        let request = ExportLogsServiceRequest {
+            resource_logs: group_logs_by_resource_and_scope(batch, &resource),
+        };
+        self.file.write(&request)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.clone();
+    }
+}
+
+#[derive(Debug)]
+struct OtlpJsonFile {
+    file: Mutex<RotatingFile>,
+}
+
+impl OtlpJsonFile {
+    fn new(dir: PathBuf, prefix: &'static str) -> Self {
+        Self {
+            file: Mutex::new(RotatingFile::new(dir, prefix, MAX_FILE_BYTES, MAX_FILES)),
+        }
+    }
+
+    fn write<T: Serialize>(&self, request: &T) -> OTelSdkResult {
+        let line = serde_json::to_vec(request)
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))?;
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))?;
+        file.write_line(&line)
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))
+    }
+}
+
+/// Append-only JSON lines file that moves on to a new file once `max_bytes`
+/// is reached, keeping the newest `max_files` files with the same prefix.
+#[derive(Debug)]
+struct RotatingFile {
+    dir: PathBuf,
+    prefix: &'static str,
+    max_bytes: u64,
+    max_files: usize,
+    current: Option<(File, u64)>,
+    files_opened: u32,
+}
+
+impl RotatingFile {
+    fn new(dir: PathBuf, prefix: &'static str, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            dir,
+            prefix,
+            max_bytes,
+            max_files,
+            current: None,
+            files_opened: 0,
+        }
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|(_, size)| *size > 0 && size + len > self.max_bytes)
+        {
+            self.current = None;
+        }
+
+        let (mut file, size) = match self.current.take() {
+            Some(current) => current,
+            None => {
+                let file = self.open_next()?;
+                self.prune();
+                (file, 0)
+            }
+        };
+        file.write_all(line)?;
+        file.write_all(b"\n")?;
+        file.flush()?;
+        self.current = Some((file, size + len));
+        Ok(())
+    }
+
+    fn open_next(&mut self) -> io::Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        // The timestamp and counter keep names sorted by age; the pid keeps
+        // concurrent sessions from sharing a file.
+        let name = format!(
+            "{}-{}-{}-{:04}.{FILE_EXTENSION}",
+            self.prefix,
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+            std::process::id(),
+            self.files_opened,
+        );
+        self.files_opened += 1;
+        OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(self.dir.join(name))
+    }
+
+    /// Best-effort removal of the oldest files beyond `max_files`.
+    fn prune(&self) {
+        let mut files = signal_files(&self.dir, self.prefix);
+        if files.len() <= self.max_files {
+            return;
+        }
+        files.sort();
+        let excess = files.len() - self.max_files;
+        for path in files.into_iter().take(excess) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Files written for the signal with `prefix` (`traces` or `logs`) in `dir`.
+pub fn signal_files(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let start = format!("{prefix}-");
+    let end = format!(".{FILE_EXTENSION}");
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(&start) && name.ends_with(&end))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn rotates_when_full_and_keeps_newest_files() {
+        let dir = TempDir::new().expect("tempdir");
+        let mut file = RotatingFile::new(dir.path().to_path_buf(), LOGS_FILE_PREFIX, 10, 2);
+
+        for line in ["first", "second", "third"] {
+            file.write_line(line.as_bytes()).expect("write line");
+        }
+
+        let mut files = signal_files(dir.path(), LOGS_FILE_PREFIX);
+        files.sort();
+        let contents: Vec<String> = files
+            .iter()
+            .map(|path| fs::read_to_string(path).expect("read file"))
+            .collect();
+        assert_eq!(contents, vec!["second\n", "third\n"]);
+        assert!(signal_files(dir.path(), TRACES_FILE_PREFIX).is_empty());
+    }
+}
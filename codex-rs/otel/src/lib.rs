@@ -1,4 +1,7 @@
 pub mod config;
 
+pub mod file_exporter;
+pub mod metrics;
 pub mod otel_manager;
 pub mod otel_provider;
+pub mod summary;
@@ -0,0 +1,168 @@
+//! Metric instruments recorded by [`OtelManager`](crate::otel_manager::OtelManager).
+//!
+//! Instruments are created from the global meter provider, which
+//! [`OtelProvider`](crate::otel_provider::OtelProvider) installs when a metrics
+//! exporter is configured. Without one they are no-ops.
+
+use std::fmt;
+
+use codex_protocol::protocol::ReviewDecision;
+use opentelemetry::global;
+use opentelemetry::metrics::Counter;
+use opentelemetry::metrics::Gauge;
+use opentelemetry::metrics::Histogram;
+use opentelemetry::metrics::Meter;
+use strum_macros::Display;
+
+pub(crate) const METER_NAME: &str = "codex_otel";
+
+/// What started a compaction.
+#[derive(Debug, Clone, Copy, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CompactionTrigger {
+    /// The conversation reached the model's auto-compact token limit.
+    Auto,
+    /// The user ran `/compact`.
+    Manual,
+}
+
+/// Where the compaction summary was produced.
+#[derive(Debug, Clone, Copy, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum CompactionMode {
+    /// Summarized by a regular model turn.
+    Local,
+    /// Summarized by the provider's compaction endpoint.
+    Remote,
+}
+
+/// How a worktree drift warning was surfaced.
+#[derive(Debug, Clone, Copy, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum WorktreeDriftNotice {
+    /// Non-blocking warning emitted at the start of a turn.
+    Warning,
+    /// Submission paused until the user confirms.
+    SoftPause,
+}
+
+#[derive(Clone)]
+pub(crate) struct SessionMetrics {
+    pub(crate) turn_duration: Histogram<f64>,
+    pub(crate) tool_duration: Histogram<f64>,
+    pub(crate) time_to_first_token: Histogram<f64>,
+    pub(crate) compaction_duration: Histogram<f64>,
+    pub(crate) approval_requests: Counter<u64>,
+    pub(crate) approval_decisions: Counter<u64>,
+    pub(crate) sandbox_denials: Counter<u64>,
+    pub(crate) compactions: Counter<u64>,
+    pub(crate) worktree_drift_warnings: Counter<u64>,
+    pub(crate) context_fill: Gauge<f64>,
+}
+
+impl SessionMetrics {
+    pub(crate) fn from_global() -> Self {
+        Self::new(&global::meter(METER_NAME))
+    }
+
+    pub(crate) fn new(meter: &Meter) -> Self {
+        Self {
+            turn_duration: meter
+                .f64_histogram("codex.turn.duration")
+                .with_unit("ms")
+                .with_description("Wall time of a turn, from submission to completion.")
+                .build(),
+            tool_duration: meter
+                .f64_histogram("codex.tool.duration")
+                .with_unit("ms")
+                .with_description("Execution time of a tool call.")
+                .build(),
+            time_to_first_token: meter
+                .f64_histogram("codex.turn.time_to_first_token")
+                .with_unit("ms")
+                .with_description("Time from sending a model request to its first output.")
+                .build(),
+            compaction_duration: meter
+                .f64_histogram("codex.compaction.duration")
+                .with_unit("ms")
+                .with_description("Time spent compacting the conversation.")
+                .build(),
+            approval_requests: meter
+                .u64_counter("codex.approval.requests")
+                .with_description("Approvals requested from the user.")
+                .build(),
+            approval_decisions: meter
+                .u64_counter("codex.approval.decisions")
+                .with_description("Approval requests answered by the user.")
+                .build(),
+            sandbox_denials: meter
+                .u64_counter("codex.sandbox.denials")
+                .with_description("Tool calls blocked by the sandbox.")
+                .build(),
+            compactions: meter
+                .u64_counter("codex.compactions")
+                .with_description("Conversation compactions.")
+                .build(),
+            worktree_drift_warnings: meter
+                .u64_counter("codex.worktree.drift_warnings")
+                .with_description("Warnings about working tree changes made outside the session.")
+                .build(),
+            context_fill: meter
+                .f64_gauge("codex.context.fill")
+                .with_unit("%")
+                .with_description("Share of the model context window used by the conversation.")
+                .build(),
+        }
+    }
+}
+
+impl fmt::Debug for SessionMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionMetrics").finish_non_exhaustive()
+    }
+}
+
+/// Value of the `decision` attribute of `codex.approval.decisions`.
+pub(crate) fn approval_outcome(decision: &ReviewDecision) -> &'static str {
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForSession => "granted",
+        ReviewDecision::Denied | ReviewDecision::Abort => "denied",
+    }
+}
+
+/// Percentage of `context_window` taken by `tokens_in_context`, or `None`
+/// when the window is unknown.
+pub(crate) fn context_fill_percent(tokens_in_context: i64, context_window: i64) -> Option<f64> {
+    if context_window <= 0 {
+        return None;
+    }
+    let percent = tokens_in_context.max(0) as f64 / context_window as f64 * 100.0;
+    Some(percent.min(100.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn approval_outcome_groups_decisions() {
+        assert_eq!(approval_outcome(&ReviewDecision::Approved), "granted");
+        assert_eq!(
+            approval_outcome(&ReviewDecision::ApprovedForSession),
+            "granted"
+        );
+        assert_eq!(approval_outcome(&ReviewDecision::Denied), "denied");
+        assert_eq!(approval_outcome(&ReviewDecision::Abort), "denied");
+    }
+
+    #[test]
+    fn context_fill_is_clamped_and_requires_a_window() {
+        assert_eq!(context_fill_percent(50_000, 200_000), Some(25.0));
+        assert_eq!(context_fill_percent(300_000, 200_000), Some(100.0));
+        assert_eq!(context_fill_percent(-5, 200_000), Some(0.0));
+        assert_eq!(context_fill_percent(1_000, 0), None);
+    }
+}
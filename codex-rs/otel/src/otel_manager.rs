@@ -1,3 +1,9 @@
+use crate::metrics::CompactionMode;
+use crate::metrics::CompactionTrigger;
+use crate::metrics::SessionMetrics;
+use crate::metrics::WorktreeDriftNotice;
+use crate::metrics::approval_outcome;
+use crate::metrics::context_fill_percent;
 use crate::otel_provider::traceparent_context_from_env;
 use chrono::SecondsFormat;
 use chrono::Utc;
@@ -14,6 +20,7 @@ use codex_protocol::protocol::SessionSource;
 use codex_protocol::user_input::UserInput;
 use eventsource_stream::Event as StreamEvent;
 use eventsource_stream::EventStreamError as StreamError;
+use opentelemetry::KeyValue;
 use reqwest::Error;
 use reqwest::Response;
 use serde::Serialize;
@@ -52,6 +59,7 @@ pub struct OtelEventMetadata {
 pub struct OtelManager {
     metadata: OtelEventMetadata,
     session_span: Span,
+    metrics: SessionMetrics,
 }
 
 impl OtelManager {
@@ -86,6 +94,7 @@ impl OtelManager {
                 terminal_type,
             },
             session_span,
+            metrics: SessionMetrics::from_global(),
         }
     }
 
@@ -393,6 +402,7 @@ impl OtelManager {
         call_id: &str,
         decision: &ReviewDecision,
         source: ToolDecisionSource,
+        duration: Duration,
     ) {
         tracing::event!(
             tracing::Level::INFO,
@@ -410,7 +420,18 @@ impl OtelManager {
             call_id = %call_id,
             decision = %decision.clone().to_string().to_lowercase(),
             source = %source.to_string(),
+            duration_ms = %duration.as_millis(),
         );
+
+        if matches!(source, ToolDecisionSource::User) {
+            self.metrics.approval_decisions.add(
+                1,
+                &[
+                    KeyValue::new("tool_name", tool_name.to_owned()),
+                    KeyValue::new("decision", approval_outcome(decision)),
+                ],
+            );
+        }
     }
 
     pub async fn log_tool_result<F, Fut, E>(
@@ -496,6 +517,74 @@ impl OtelManager {
             success = %success_str,
             output = %output,
         );
+
+        self.metrics.tool_duration.record(
+            duration_millis(duration),
+            &[
+                KeyValue::new("tool_name", tool_name.to_owned()),
+                KeyValue::new("success", success),
+            ],
+        );
+    }
+
+    pub fn record_turn_duration(&self, duration: Duration) {
+        self.metrics
+            .turn_duration
+            .record(duration_millis(duration), &[self.model_attribute()]);
+    }
+
+    pub fn record_time_to_first_token(&self, duration: Duration) {
+        self.metrics
+            .time_to_first_token
+            .record(duration_millis(duration), &[self.model_attribute()]);
+    }
+
+    pub fn record_compaction(
+        &self,
+        trigger: CompactionTrigger,
+        mode: CompactionMode,
+        duration: Duration,
+    ) {
+        let attributes = [
+            KeyValue::new("trigger", trigger.to_string()),
+            KeyValue::new("mode", mode.to_string()),
+        ];
+        self.metrics.compactions.add(1, &attributes);
+        self.metrics
+            .compaction_duration
+            .record(duration_millis(duration), &attributes);
+    }
+
+    /// Counts an approval asked of the user. Recorded when the request goes
+    /// out, so approvals that are never answered are counted too.
+    pub fn approval_requested(&self, tool_name: &str) {
+        self.metrics
+            .approval_requests
+            .add(1, &[KeyValue::new("tool_name", tool_name.to_owned())]);
+    }
+
+    pub fn sandbox_denied(&self, tool_name: &str) {
+        self.metrics
+            .sandbox_denials
+            .add(1, &[KeyValue::new("tool_name", tool_name.to_owned())]);
+    }
+
+    pub fn worktree_drift_warning(&self, notice: WorktreeDriftNotice) {
+        self.metrics
+            .worktree_drift_warnings
+            .add(1, &[KeyValue::new("notice", notice.to_string())]);
+    }
+
+    pub fn record_context_fill(&self, tokens_in_context: i64, context_window: i64) {
+        if let Some(percent) = context_fill_percent(tokens_in_context, context_window) {
+            self.metrics
+                .context_fill
+                .record(percent, &[self.model_attribute()]);
+        }
+    }
+
+    fn model_attribute(&self) -> KeyValue {
+        KeyValue::new("model", self.metadata.model.clone())
     }
 
     fn responses_type(event: &ResponseEvent) -> String {
@@ -531,6 +620,10 @@ impl OtelManager {
     }
 }
 
+fn duration_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
 fn timestamp() -> String {
     Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
 }
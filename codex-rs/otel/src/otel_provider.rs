@@ -2,6 +2,9 @@ use crate::config::OtelExporter;
 use crate::config::OtelHttpProtocol;
 use crate::config::OtelSettings;
 use crate::config::OtelTlsConfig;
+use crate::file_exporter::FileLogExporter;
+use crate::file_exporter::FileSpanExporter;
+use crate::file_exporter::otel_dir;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use http::Uri;
 use opentelemetry::Context;
@@ -13,7 +16,9 @@ use opentelemetry::trace::TraceContextExt;
 use opentelemetry::trace::TracerProvider as _;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use opentelemetry_otlp::LogExporter;
+use opentelemetry_otlp::MetricExporter;
 use opentelemetry_otlp::OTEL_EXPORTER_OTLP_LOGS_TIMEOUT;
+use opentelemetry_otlp::OTEL_EXPORTER_OTLP_METRICS_TIMEOUT;
 use opentelemetry_otlp::OTEL_EXPORTER_OTLP_TIMEOUT;
 use opentelemetry_otlp::OTEL_EXPORTER_OTLP_TIMEOUT_DEFAULT;
 use opentelemetry_otlp::OTEL_EXPORTER_OTLP_TRACES_TIMEOUT;
@@ -24,6 +29,7 @@ use opentelemetry_otlp::WithHttpConfig;
 use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::logs::SdkLoggerProvider;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::trace::BatchSpanProcessor;
 use opentelemetry_sdk::trace::SdkTracerProvider;
@@ -41,6 +47,7 @@ use std::error::Error;
 use std::fs;
 use std::io::ErrorKind;
 use std::io::{self};
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 use std::time::Duration;
@@ -67,6 +74,7 @@ pub struct OtelProvider {
     pub logger: Option<SdkLoggerProvider>,
     pub tracer_provider: Option<SdkTracerProvider>,
     pub tracer: Option<Tracer>,
+    pub meter_provider: Option<SdkMeterProvider>,
 }
 
 impl OtelProvider {
@@ -77,30 +85,44 @@ impl OtelProvider {
         if let Some(tracer_provider) = &self.tracer_provider {
             let _ = tracer_provider.shutdown();
         }
+        if let Some(meter_provider) = &self.meter_provider {
+            let _ = meter_provider.shutdown();
+        }
     }
 
     pub fn from(settings: &OtelSettings) -> Result<Option<Self>, Box<dyn Error>> {
         let log_enabled = !matches!(settings.exporter, OtelExporter::None);
         let trace_enabled = !matches!(settings.trace_exporter, OtelExporter::None);
+        // The file exporter writes spans and logs only.
+        let metrics_enabled = !matches!(
+            settings.metrics_exporter,
+            OtelExporter::None | OtelExporter::File
+        );
 
-        if !log_enabled && !trace_enabled {
+        if !log_enabled && !trace_enabled && !metrics_enabled {
             debug!("No exporter enabled in OTLP settings.");
             return Ok(None);
         }
 
         let resource = make_resource(settings);
         let logger = log_enabled
-            .then(|| build_logger(&resource, &settings.exporter))
+            .then(|| build_logger(&resource, &settings.exporter, &settings.codex_home))
             .transpose()?;
 
         let tracer_provider = trace_enabled
-            .then(|| build_tracer_provider(&resource, &settings.trace_exporter))
+            .then(|| {
+                build_tracer_provider(&resource, &settings.trace_exporter, &settings.codex_home)
+            })
             .transpose()?;
 
         let tracer = tracer_provider
             .as_ref()
             .map(|provider| provider.tracer(settings.service_name.clone()));
 
+        let meter_provider = metrics_enabled
+            .then(|| build_meter_provider(&resource, &settings.metrics_exporter))
+            .transpose()?;
+
         if let Some(provider) = tracer_provider.clone() {
             let _ = global::set_tracer_provider(provider);
             global::set_text_map_propagator(TraceContextPropagator::new());
@@ -108,11 +130,15 @@ impl OtelProvider {
         if tracer.is_some() {
             attach_traceparent_context();
         }
+        if let Some(provider) = meter_provider.clone() {
+            global::set_meter_provider(provider);
+        }
 
         Ok(Some(Self {
             logger,
             tracer_provider,
             tracer,
+            meter_provider,
         }))
     }
 
@@ -151,6 +177,9 @@ impl Drop for OtelProvider {
         if let Some(tracer_provider) = &self.tracer_provider {
             let _ = tracer_provider.shutdown();
         }
+        if let Some(meter_provider) = &self.meter_provider {
+            let _ = meter_provider.shutdown();
+        }
     }
 }
 
@@ -220,11 +249,18 @@ fn make_resource(settings: &OtelSettings) -> Resource {
 fn build_logger(
     resource: &Resource,
     exporter: &OtelExporter,
+    codex_home: &Path,
 ) -> Result<SdkLoggerProvider, Box<dyn Error>> {
     let mut builder = SdkLoggerProvider::builder().with_resource(resource.clone());
 
     match exporter {
         OtelExporter::None => return Ok(builder.build()),
+        OtelExporter::File => {
+            let dir = otel_dir(codex_home);
+            debug!("Using OTLP file exporter: {}", dir.display());
+
+            builder = builder.with_batch_exporter(FileLogExporter::new(dir, resource));
+        }
         OtelExporter::OtlpGrpc {
             endpoint,
             headers,
@@ -288,9 +324,19 @@ fn build_logger(
 fn build_tracer_provider(
     resource: &Resource,
     exporter: &OtelExporter,
+    codex_home: &Path,
 ) -> Result<SdkTracerProvider, Box<dyn Error>> {
     let span_exporter = match exporter {
         OtelExporter::None => return Ok(SdkTracerProvider::builder().build()),
+        OtelExporter::File => {
+            let dir = otel_dir(codex_home);
+            debug!("Using OTLP file exporter for traces: {}", dir.display());
+
+            return Ok(SdkTracerProvider::builder()
+                .with_resource(resource.clone())
+                .with_batch_exporter(FileSpanExporter::new(dir, resource))
+                .build());
+        }
         OtelExporter::OtlpGrpc {
             endpoint,
             headers,
@@ -352,6 +398,71 @@ fn build_tracer_provider(
         .build())
 }
 
+fn build_meter_provider(
+    resource: &Resource,
+    exporter: &OtelExporter,
+) -> Result<SdkMeterProvider, Box<dyn Error>> {
+    let metric_exporter = match exporter {
+        OtelExporter::None | OtelExporter::File => return Ok(SdkMeterProvider::builder().build()),
+        OtelExporter::OtlpGrpc {
+            endpoint,
+            headers,
+            tls,
+        } => {
+            debug!("Using OTLP Grpc exporter for metrics: {endpoint}");
+
+            let header_map = build_header_map(headers);
+
+            let base_tls_config = ClientTlsConfig::new()
+                .with_enabled_roots()
+                .assume_http2(true);
+
+            let tls_config = match tls.as_ref() {
+                Some(tls) => build_grpc_tls_config(endpoint, base_tls_config, tls)?,
+                None => base_tls_config,
+            };
+
+            MetricExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .with_metadata(MetadataMap::from_headers(header_map))
+                .with_tls_config(tls_config)
+                .build()?
+        }
+        OtelExporter::OtlpHttp {
+            endpoint,
+            headers,
+            protocol,
+            tls,
+        } => {
+            debug!("Using OTLP Http exporter for metrics: {endpoint}");
+
+            let protocol = match protocol {
+                OtelHttpProtocol::Binary => Protocol::HttpBinary,
+                OtelHttpProtocol::Json => Protocol::HttpJson,
+            };
+
+            let mut exporter_builder = MetricExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .with_protocol(protocol)
+                .with_headers(headers.clone());
+
+            if let Some(tls) = tls.as_ref() {
+                let client = build_http_client(tls, OTEL_EXPORTER_OTLP_METRICS_TIMEOUT)?;
+                exporter_builder = exporter_builder.with_http_client(client);
+            }
+
+            exporter_builder.build()?
+        }
+    };
+
+    Ok(SdkMeterProvider::builder()
+        .with_resource(resource.clone())
+        .with_periodic_exporter(metric_exporter)
+        .build())
+}
+
 fn build_header_map(headers: &HashMap<String, String>) -> HeaderMap {
     let mut header_map = HeaderMap::new();
     for (key, value) in headers {
@@ -403,8 +514,8 @@ fn build_grpc_tls_config(
 /// Build a blocking HTTP client with TLS configuration for the OTLP HTTP exporter.
 ///
 /// We use `reqwest::blocking::Client` instead of the async client because the
-/// `opentelemetry_sdk` `BatchLogProcessor` spawns a dedicated OS thread that uses
-/// `futures_executor::block_on()` rather than tokio. When the async reqwest client's
+/// `opentelemetry_sdk` `BatchLogProcessor` and `PeriodicReader` spawn a dedicated OS
+/// thread that uses `futures_executor::block_on()` rather than tokio. When the async reqwest client's
 /// timeout calls `tokio::time::sleep()`, it panics with "no reactor running".
 fn build_http_client(
     tls: &OtelTlsConfig,
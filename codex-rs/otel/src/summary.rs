@@ -0,0 +1,328 @@
+//! Per-session timing breakdown of the log records written by the file
+//! exporter.
+//!
+//! Model time is the sum of `codex.api_request` and `codex.sse_event`
+//! durations, approval time the `duration_ms` of user `codex.tool_decision`
+//! events, and tool time the `codex.tool_result` durations minus the approval
+//! wait of the same call.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::file_exporter::LOGS_FILE_PREFIX;
+use crate::file_exporter::signal_files;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SessionSummary {
+    pub conversation_id: String,
+    /// `event.timestamp` of the first event of the session.
+    pub started_at: Option<String>,
+    pub model_ms: u64,
+    pub tool_ms: u64,
+    pub approval_ms: u64,
+    pub model_requests: u64,
+    pub tool_calls: u64,
+    pub approvals: u64,
+    /// Per-tool timings, slowest call first.
+    pub tools: Vec<ToolSummary>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ToolSummary {
+    pub tool_name: String,
+    pub calls: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Summarize every log file the file exporter wrote to `dir`, oldest session
+/// first.
+pub fn summarize_dir(dir: &Path) -> io::Result<Vec<SessionSummary>> {
+    let mut files = signal_files(dir, LOGS_FILE_PREFIX);
+    files.sort();
+    let mut summarizer = Summarizer::default();
+    for path in files {
+        let reader = BufReader::new(File::open(&path)?);
+        for line in reader.lines() {
+            summarizer.add_line(&line?);
+        }
+    }
+    Ok(summarizer.finish())
+}
+
+#[derive(Default)]
+struct Summarizer {
+    sessions: HashMap<String, SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    summary: SessionSummary,
+    /// `(tool_name, call_id, duration_ms)` of each tool result.
+    tool_results: Vec<(String, Option<String>, u64)>,
+    approval_ms_by_call: HashMap<String, u64>,
+}
+
+impl Summarizer {
+    /// Add one `ExportLogsServiceRequest` line. Lines that do not parse are
+    /// skipped so a truncated last line does not hide the rest of the file.
+    fn add_line(&mut self, line: &str) {
+        let Ok(request) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        let records = request["resourceLogs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|resource| resource["scopeLogs"].as_array().into_iter().flatten())
+            .flat_map(|scope| scope["logRecords"].as_array().into_iter().flatten());
+        for record in records {
+            self.add_record(record);
+        }
+    }
+
+    fn add_record(&mut self, record: &Value) {
+        let attributes = attributes(record);
+        let Some(conversation_id) = attributes.get("conversation.id") else {
+            return;
+        };
+        let event_name = attributes
+            .get("event.name")
+            .map(String::as_str)
+            .or_else(|| record["eventName"].as_str())
+            .unwrap_or_default();
+        let duration_ms = attributes
+            .get("duration_ms")
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let session = self
+            .sessions
+            .entry(conversation_id.clone())
+            .or_insert_with(|| SessionState {
+                summary: SessionSummary {
+                    conversation_id: conversation_id.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        if let Some(timestamp) = attributes.get("event.timestamp")
+            && session
+                .summary
+                .started_at
+                .as_ref()
+                .is_none_or(|started_at| timestamp < started_at)
+        {
+            session.summary.started_at = Some(timestamp.clone());
+        }
+
+        match event_name {
+            "codex.api_request" => {
+                session.summary.model_requests += 1;
+                session.summary.model_ms += duration_ms;
+            }
+            "codex.sse_event" => session.summary.model_ms += duration_ms,
+            "codex.tool_decision"
+                if attributes
+                    .get("source")
+                    .is_some_and(|source| source.eq_ignore_ascii_case("user")) =>
+            {
+                session.summary.approvals += 1;
+                session.summary.approval_ms += duration_ms;
+                if let Some(call_id) = attributes.get("call_id") {
+                    *session
+                        .approval_ms_by_call
+                        .entry(call_id.clone())
+                        .or_default() += duration_ms;
+                }
+            }
+            "codex.tool_result" => {
+                let tool_name = attributes.get("tool_name").cloned().unwrap_or_default();
+                let call_id = attributes.get("call_id").cloned();
+                session.tool_results.push((tool_name, call_id, duration_ms));
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Vec<SessionSummary> {
+        let mut summaries: Vec<SessionSummary> = self
+            .sessions
+            .into_values()
+            .map(|mut session| {
+                let mut tools: HashMap<String, ToolSummary> = HashMap::new();
+                for (tool_name, call_id, duration_ms) in session.tool_results {
+                    // Tool results include the time spent waiting on approval.
+                    let waited = call_id
+                        .and_then(|call_id| session.approval_ms_by_call.get(&call_id).copied())
+                        .unwrap_or(0);
+                    let duration_ms = duration_ms.saturating_sub(waited);
+                    session.summary.tool_calls += 1;
+                    session.summary.tool_ms += duration_ms;
+
+                    let tool = tools
+                        .entry(tool_name.clone())
+                        .or_insert_with(|| ToolSummary {
+                            tool_name,
+                            ..Default::default()
+                        });
+                    tool.calls += 1;
+                    tool.total_ms += duration_ms;
+                    tool.max_ms = tool.max_ms.max(duration_ms);
+                }
+                let mut tools: Vec<ToolSummary> = tools.into_values().collect();
+                tools.sort_by(|a, b| {
+                    b.max_ms
+                        .cmp(&a.max_ms)
+                        .then_with(|| a.tool_name.cmp(&b.tool_name))
+                });
+                session.summary.tools = tools;
+                session.summary
+            })
+            .collect();
+        summaries.sort_by(|a, b| {
+            a.started_at
+                .cmp(&b.started_at)
+                .then_with(|| a.conversation_id.cmp(&b.conversation_id))
+        });
+        summaries
+    }
+}
+
+/// Attributes of an OTLP-JSON log record, with values rendered as strings.
+fn attributes(record: &Value) -> HashMap<String, String> {
+    record["attributes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|attribute| {
+            let key = attribute["key"].as_str()?;
+            let value = &attribute["value"];
+            let value = match value
+                .get("stringValue")
+                .or_else(|| value.get("intValue"))
+                .or_else(|| value.get("doubleValue"))
+                .or_else(|| value.get("boolValue"))?
+            {
+                Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn record(attributes: &[(&str, &str)]) -> Value {
+        let attributes: Vec<Value> = attributes
+            .iter()
+            .map(|(key, value)| json!({"key": key, "value": {"stringValue": value}}))
+            .collect();
+        json!({ "attributes": attributes })
+    }
+
+    fn line(records: Vec<Value>) -> String {
+        json!({ "resourceLogs": [{ "scopeLogs": [{ "logRecords": records }] }] }).to_string()
+    }
+
+    #[test]
+    fn splits_session_time_between_model_tools_and_approvals() {
+        let session = |event: &str, extra: &[(&str, &str)]| {
+            let mut attributes = vec![
+                ("conversation.id", "conv-1"),
+                ("event.name", event),
+                ("event.timestamp", "2025-01-01T00:00:00.000Z"),
+            ];
+            attributes.extend_from_slice(extra);
+            record(&attributes)
+        };
+        let mut summarizer = Summarizer::default();
+        summarizer.add_line(&line(vec![
+            session("codex.api_request", &[("duration_ms", "300")]),
+            session("codex.sse_event", &[("duration_ms", "700")]),
+            session(
+                "codex.tool_decision",
+                &[
+                    ("call_id", "call-1"),
+                    ("source", "User"),
+                    ("duration_ms", "5000"),
+                ],
+            ),
+            session(
+                "codex.tool_decision",
+                &[
+                    ("call_id", "call-2"),
+                    ("source", "Config"),
+                    ("duration_ms", "0"),
+                ],
+            ),
+        ]));
+        summarizer.add_line("{not json");
+        summarizer.add_line(&line(vec![
+            session(
+                "codex.tool_result",
+                &[
+                    ("tool_name", "shell"),
+                    ("call_id", "call-1"),
+                    ("duration_ms", "5400"),
+                ],
+            ),
+            session(
+                "codex.tool_result",
+                &[
+                    ("tool_name", "shell"),
+                    ("call_id", "call-2"),
+                    ("duration_ms", "100"),
+                ],
+            ),
+            session(
+                "codex.tool_result",
+                &[
+                    ("tool_name", "apply_patch"),
+                    ("call_id", "call-3"),
+                    ("duration_ms", "50"),
+                ],
+            ),
+        ]));
+
+        assert_eq!(
+            summarizer.finish(),
+            vec![SessionSummary {
+                conversation_id: "conv-1".to_string(),
+                started_at: Some("2025-01-01T00:00:00.000Z".to_string()),
+                model_ms: 1000,
+                tool_ms: 550,
+                approval_ms: 5000,
+                model_requests: 1,
+                tool_calls: 3,
+                approvals: 1,
+                tools: vec![
+                    ToolSummary {
+                        tool_name: "shell".to_string(),
+                        calls: 2,
+                        total_ms: 500,
+                        max_ms: 400,
+                    },
+                    ToolSummary {
+                        tool_name: "apply_patch".to_string(),
+                        calls: 1,
+                        total_ms: 50,
+                        max_ms: 50,
+                    },
+                ],
+            }]
+        );
+    }
+}
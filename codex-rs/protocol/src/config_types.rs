@@ -46,6 +46,22 @@ pub enum Verbosity {
     High,
 }
 
+/// Class of model request failure that makes a session switch to the next
+/// entry of `fallback_providers`.
+#[derive(
+    Hash, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FallbackTrigger {
+    /// The provider answered with a 5xx status after retries were exhausted.
+    ServerError,
+    /// The provider could not be reached or the stream kept disconnecting.
+    Connection,
+    /// The account hit its usage limit or quota on this provider.
+    UsageLimit,
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
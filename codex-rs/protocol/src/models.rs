@@ -149,6 +149,11 @@ pub enum ResponseItem {
     // Generated by the harness but considered exactly as a model response.
     GhostSnapshot {
         ghost_commit: GhostCommit,
+        /// Turn the snapshot was taken for. Compaction moves snapshots away
+        /// from their turn, so it is recorded with them; older rollouts lack it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        turn: Option<GhostSnapshotTurn>,
     },
     #[serde(alias = "compaction_summary")]
     Compaction {
@@ -232,6 +237,17 @@ impl From<ResponseInputItem> for ResponseItem {
     }
 }
 
+/// The turn a ghost snapshot was taken for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct GhostSnapshotTurn {
+    /// 1-based number of the turn.
+    pub number: u32,
+    /// The user prompt that started the turn, if it had any text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub prompt: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum LocalShellStatus {
@@ -75,6 +75,15 @@ pub struct ModelPreset {
     pub upgrade: Option<ModelUpgrade>,
     /// Whether this preset should appear in the picker UI.
     pub show_in_picker: bool,
+    /// Context window in tokens, when the serving provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub context_window: Option<i64>,
+    /// Provider that serves the model (a key of `model_providers`), for models
+    /// discovered from a local server. `None` for the session's own provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model_provider: Option<String>,
 }
 
 /// Visibility of a model in the picker or APIs.
@@ -216,6 +225,8 @@ impl From<ModelInfo> for ModelPreset {
                 migration_config_key: info.slug.clone(),
             }),
             show_in_picker: info.visibility == ModelVisibility::List,
+            context_window: info.context_window,
+            model_provider: None,
         }
     }
 }
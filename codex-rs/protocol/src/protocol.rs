@@ -12,6 +12,7 @@ use std::time::Duration;
 
 use crate::ConversationId;
 use crate::approvals::ElicitationRequestEvent;
+use crate::config_types::FallbackTrigger;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use crate::custom_prompts::CustomPrompt;
 use crate::items::TurnItem;
@@ -23,6 +24,7 @@ use crate::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use crate::user_input::UserInput;
+use codex_git::GhostCommit;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
 use mcp_types::RequestId;
@@ -147,6 +149,12 @@ pub enum Op {
         /// Updated reasoning summary preference (honored only for reasoning-capable models).
         #[serde(skip_serializing_if = "Option::is_none")]
         summary: Option<ReasoningSummaryConfig>,
+
+        /// Updated model provider id, a key of `model_providers` (e.g.
+        /// `"ollama"`). Usually sent together with `model` when switching to a
+        /// model served by a different provider.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model_provider: Option<String>,
     },
 
     /// Approve a command execution
@@ -208,9 +216,36 @@ pub enum Op {
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Restore the workspace to the ghost snapshot `snapshot_id`, undoing
+    /// every turn since it was taken. Snapshot ids are listed by
+    /// `Op::ListUndoSnapshots`.
+    UndoTo { snapshot_id: String },
+
+    /// Undo the `turns` most recent turns that have a ghost snapshot,
+    /// removing them from the conversation history.
+    UndoTurns { turns: u32 },
+
+    /// Reapply the most recent undo, if nothing has happened since.
+    Redo,
+
+    /// Request the ghost snapshots that can be undone to.
+    /// Reply is delivered via `EventMsg::ListUndoSnapshotsResponse`.
+    ListUndoSnapshots,
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
+    /// Start a regular turn that fixes findings from a completed review.
+    /// Findings are numbered from 1 in the order the review reported them.
+    FixReviewFindings {
+        /// Id of the review turn. Defaults to the most recent review.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        review_id: Option<String>,
+        /// Numbers of the findings to fix. Empty means all of them.
+        #[serde(default)]
+        findings: Vec<usize>,
+    },
+
     /// Request to shut down codex instance.
     Shutdown,
 
@@ -226,6 +261,21 @@ pub enum Op {
 
     /// Request the list of available models.
     ListModels,
+
+    /// Request the unified exec sessions that are still running.
+    /// Reply is delivered via `EventMsg::ListExecSessionsResponse`.
+    ListExecSessions,
+
+    /// Request the output a unified exec session has printed so far.
+    /// Reply is delivered via `EventMsg::ExecSessionOutputResponse`.
+    GetExecSessionOutput { process_id: String },
+
+    /// Write `input` to the stdin of a running unified exec session.
+    WriteExecSessionStdin { process_id: String, input: String },
+
+    /// Terminate a unified exec session. Reply is an updated
+    /// `EventMsg::ListExecSessionsResponse`.
+    KillExecSession { process_id: String },
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -296,6 +346,19 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
 
+        /// Hosts, CIDR ranges and ports that sandboxed commands may still
+        /// reach when `network_access` is `false`. Traffic is routed through a
+        /// local egress proxy that enforces the list. Entries look like
+        /// `crates.io`, `*.example.com:443`, `10.0.0.0/8` or `localhost:8080`.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
+
+        /// Glob patterns for paths that sandboxed commands and the file tools
+        /// must not read, e.g. `~/.ssh` or `**/.env`. A path is denied when it
+        /// or any of its ancestors matches.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        read_deny: Vec<String>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -361,6 +424,8 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            network_allowlist: vec![],
+            read_deny: vec![],
         }
     }
 
@@ -385,6 +450,29 @@ impl SandboxPolicy {
         }
     }
 
+    /// Returns the egress allowlist that applies when full network access is
+    /// not granted. Empty when the policy has full network access or does not
+    /// allow any network at all.
+    pub fn network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
+    /// Glob patterns for paths that must not be read. Only `WorkspaceWrite`
+    /// carries a list; empty for the other policies.
+    pub fn read_deny(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite { read_deny, .. } => read_deny,
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ReadOnly => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
@@ -397,6 +485,8 @@ impl SandboxPolicy {
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                network_allowlist: _,
+                read_deny: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<AbsolutePathBuf> = writable_roots.clone();
@@ -517,6 +607,10 @@ pub enum EventMsg {
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
 
+    /// Token usage and cost of a finished turn. Persisted in the rollout so
+    /// the per-turn ledger of a session can be read back later.
+    TurnCost(TurnCostEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
@@ -591,6 +685,10 @@ pub enum EventMsg {
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
 
+    /// The session switched to a fallback provider after the current one
+    /// failed. Later turns keep using the new provider.
+    ProviderFallback(ProviderFallbackEvent),
+
     /// Notification that the agent is about to apply a code patch. Mirrors
     /// `ExecCommandBegin` so front‑ends can show progress indicators.
     PatchApplyBegin(PatchApplyBeginEvent),
@@ -609,6 +707,15 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Ghost snapshots available to undo to.
+    ListUndoSnapshotsResponse(ListUndoSnapshotsResponseEvent),
+
+    /// Unified exec sessions that are still running.
+    ListExecSessionsResponse(ListExecSessionsResponseEvent),
+
+    /// Output of a unified exec session, in response to `Op::GetExecSessionOutput`.
+    ExecSessionOutputResponse(ExecSessionOutputResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -622,6 +729,9 @@ pub enum EventMsg {
     /// Exited review mode with an optional final result to apply.
     ExitedReviewMode(ExitedReviewModeEvent),
 
+    /// A turn was started to fix findings from a review.
+    ReviewFixStarted(ReviewFixStartedEvent),
+
     RawResponseItem(RawResponseItemEvent),
 
     ItemStarted(ItemStartedEvent),
@@ -783,6 +893,24 @@ impl HasLegacyEvent for EventMsg {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExitedReviewModeEvent {
     pub review_output: Option<ReviewOutputEvent>,
+    /// Id of the review turn, which [`review_finding_id`]s are built from.
+    /// Recorded so findings can still be fixed after the session is resumed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub review_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ReviewFixStartedEvent {
+    /// Id of the review turn the findings came from.
+    pub review_id: String,
+    /// Ids of the findings being fixed, see [`review_finding_id`].
+    pub finding_ids: Vec<String>,
+}
+
+/// Stable id of the `number`th (1-based) finding of the review `review_id`.
+pub fn review_finding_id(review_id: &str, number: usize) -> String {
+    format!("{review_id}#{number}")
 }
 
 // Individual event payload types matching each `EventMsg` variant.
@@ -900,6 +1028,21 @@ pub struct TokenCountEvent {
     pub rate_limits: Option<RateLimitSnapshot>,
 }
 
+/// One entry of a session's cost ledger.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TurnCostEvent {
+    pub turn_id: String,
+    /// Model that served the turn's last request.
+    pub model: String,
+    /// Usage summed over every model request of the turn.
+    pub usage: TokenUsage,
+    /// Whether the conversation was compacted during the turn.
+    pub compacted: bool,
+    /// Cost in USD according to `model_prices`; `None` when no price is
+    /// configured for `model`.
+    pub cost_usd: Option<f64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct RateLimitSnapshot {
     pub primary: Option<RateLimitWindow>,
@@ -1267,6 +1410,8 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    TurnEndSnapshot(TurnEndSnapshotItem),
+    HistoryRewritten(HistoryRewrittenItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1299,6 +1444,22 @@ pub struct TurnContextItem {
     pub summary: ReasoningSummaryConfig,
 }
 
+/// Snapshot of the workspace as a turn left it, recorded so undo can still
+/// tell the agent's edits from later ones after the session is resumed.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+pub struct TurnEndSnapshotItem {
+    /// Id of the ghost snapshot taken when the turn started.
+    pub turn_start_id: String,
+    pub ghost_commit: GhostCommit,
+}
+
+/// History rewritten by an undo or redo. Unlike a compaction it carries no
+/// summary; a resumed session just replaces its history.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+pub struct HistoryRewrittenItem {
+    pub replacement_history: Vec<ResponseItem>,
+}
+
 #[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct RolloutLine {
     pub timestamp: String,
@@ -1347,6 +1508,11 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the changes between two revisions, as in `git diff base..head`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Range { base: String, head: String },
+
     /// Arbitrary instructions provided by the user.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1360,6 +1526,10 @@ pub struct ReviewRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub user_facing_hint: Option<String>,
+    /// Limit the review to these paths, relative to the working directory.
+    /// Empty reviews every changed path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
 }
 
 /// Structured review result produced by a child review session.
@@ -1549,6 +1719,10 @@ pub struct UndoCompletedEvent {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Files edited outside the session whose changes from the undone turns
+    /// could not be reversed without losing those edits. They are left as-is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1558,6 +1732,19 @@ pub struct StreamErrorEvent {
     pub codex_error_info: Option<CodexErrorInfo>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderFallbackEvent {
+    pub from_provider: String,
+    pub from_model: String,
+    pub to_provider: String,
+    pub to_model: String,
+    pub reason: FallbackTrigger,
+    /// The error that triggered the switch.
+    pub message: String,
+    /// Context window of the fallback model, when known.
+    pub model_context_window: Option<i64>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamInfoEvent {
     pub message: String,
@@ -1681,6 +1868,62 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListUndoSnapshots`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListUndoSnapshotsResponseEvent {
+    /// Oldest first.
+    pub snapshots: Vec<UndoSnapshot>,
+    /// Whether `Op::Redo` would currently reapply an undo.
+    pub redo_available: bool,
+}
+
+/// A ghost snapshot of the workspace taken at the start of a turn.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct UndoSnapshot {
+    /// Ghost commit id; pass it to `Op::UndoTo`.
+    pub id: String,
+    /// 1-based number of the turn the snapshot was taken for.
+    pub turn: u32,
+    /// The user prompt that started the turn, if it had any text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+}
+
+/// Response payload for `Op::ListExecSessions`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListExecSessionsResponseEvent {
+    /// Oldest first.
+    pub sessions: Vec<ExecSessionInfo>,
+}
+
+/// A unified exec session whose command is still running.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ExecSessionInfo {
+    /// Session id reported to the model; pass it to the `*ExecSession*` ops.
+    pub process_id: String,
+    /// Name the model gave the session, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    /// OS process id of the command, when the platform reports one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Unix timestamp (seconds since epoch) when the command started.
+    pub started_at: i64,
+    /// Last non-empty line the command printed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_output: Option<String>,
+}
+
+/// Response payload for `Op::GetExecSessionOutput`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ExecSessionOutputResponseEvent {
+    pub process_id: String,
+    /// Output printed so far; only the most recent 1 MiB is kept.
+    pub output: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SkillInfo {
     pub name: String,
@@ -1775,6 +2018,22 @@ pub enum ReviewDecision {
 pub enum FileChange {
     Add {
         content: String,
+        /// Unix permission bits (e.g. `0o755`) the patch gives the new file.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+    },
+    /// A binary file added from base64 data in the patch. Only its size is
+    /// carried here; the bytes are written when the patch is applied.
+    AddBinary {
+        size: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+    },
+    /// A symbolic link pointing at `target`, stored verbatim.
+    AddSymlink {
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -1782,6 +2041,17 @@ pub enum FileChange {
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        /// New Unix permission bits, when the patch changes the file's mode.
+        /// `unified_diff` is empty for a change that only sets the mode.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+        /// Set when part of the patch could only be located by fuzzy
+        /// matching: the lowest match score, in `0.0..=1.0`, among the
+        /// affected chunks. UIs show such changes as "applied with fuzz".
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        fuzz_score: Option<f32>,
     },
 }
 
@@ -1859,7 +2129,7 @@ mod tests {
                 model: "codex-mini-latest".to_string(),
                 model_provider_id: "openai".to_string(),
                 approval_policy: AskForApproval::Never,
-                sandbox_policy: SandboxPolicy::ReadOnly,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 cwd: PathBuf::from("/home/user/project"),
                 reasoning_effort: Some(ReasoningEffortConfig::default()),
                 history_log_id: 0,
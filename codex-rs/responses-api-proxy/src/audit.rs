@@ -0,0 +1,81 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::usage::TokenUsage;
+
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+pub(crate) struct AuditRecord {
+    /// RFC 3339 time at which the request arrived.
+    pub(crate) timestamp: String,
+    /// Sequence number assigned by the proxy.
+    pub(crate) request_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) upstream_request_id: Option<String>,
+    pub(crate) method: String,
+    pub(crate) path: String,
+    /// Name of the upstream the request was routed to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) upstream: Option<String>,
+    pub(crate) status: u16,
+    pub(crate) duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) usage: Option<TokenUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) request: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) response: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+/// Append-only JSONL audit log shared by all request threads.
+pub(crate) struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    pub(crate) fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening audit log {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    pub(crate) fn write(&self, record: &AuditRecord) {
+        let mut line = match serde_json::to_string(record) {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("failed to serialize audit record: {err}");
+                return;
+            }
+        };
+        line.push('\n');
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Err(err) = file.write_all(line.as_bytes()) {
+            eprintln!("failed to write audit record: {err}");
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::Mutex;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Per-UTC-day request and token budgets shared by every client of the proxy.
+///
+/// Requests are counted when they are forwarded; tokens are counted from the
+/// upstream `usage` once a response completes, so the request that crosses the
+/// token budget still finishes and the next one is rejected.
+pub(crate) struct Budget {
+    max_requests_per_day: Option<u64>,
+    max_tokens_per_day: Option<u64>,
+    usage: Mutex<DayUsage>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DayUsage {
+    /// Days since the Unix epoch, in UTC.
+    pub(crate) day: u64,
+    pub(crate) requests: u64,
+    pub(crate) tokens: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BudgetExceeded {
+    Requests { limit: u64, resets_at: u64 },
+    Tokens { limit: u64, resets_at: u64 },
+}
+
+impl BudgetExceeded {
+    pub(crate) fn message(&self) -> String {
+        match self {
+            BudgetExceeded::Requests { limit, .. } => {
+                format!("Daily request budget of {limit} requests for this proxy is exhausted.")
+            }
+            BudgetExceeded::Tokens { limit, .. } => {
+                format!("Daily token budget of {limit} tokens for this proxy is exhausted.")
+            }
+        }
+    }
+
+    /// Unix timestamp (seconds) at which the budget resets.
+    pub(crate) fn resets_at(&self) -> u64 {
+        match self {
+            BudgetExceeded::Requests { resets_at, .. }
+            | BudgetExceeded::Tokens { resets_at, .. } => *resets_at,
+        }
+    }
+}
+
+impl Budget {
+    pub(crate) fn new(max_requests_per_day: Option<u64>, max_tokens_per_day: Option<u64>) -> Self {
+        Self {
+            max_requests_per_day,
+            max_tokens_per_day,
+            usage: Mutex::new(DayUsage::default()),
+        }
+    }
+
+    pub(crate) fn max_requests_per_day(&self) -> Option<u64> {
+        self.max_requests_per_day
+    }
+
+    pub(crate) fn max_tokens_per_day(&self) -> Option<u64> {
+        self.max_tokens_per_day
+    }
+
+    /// Admits a request against today's budget, counting it when admitted.
+    pub(crate) fn try_start_request(&self) -> Result<(), BudgetExceeded> {
+        self.try_start_request_at(SystemTime::now())
+    }
+
+    /// Adds the tokens reported by a completed response to today's usage.
+    pub(crate) fn record_tokens(&self, tokens: u64) {
+        self.record_tokens_at(tokens, SystemTime::now());
+    }
+
+    /// Usage for the current UTC day.
+    pub(crate) fn today(&self) -> DayUsage {
+        let now = SystemTime::now();
+        let mut usage = self.lock();
+        roll_over(&mut usage, day_of(now));
+        *usage
+    }
+
+    fn try_start_request_at(&self, now: SystemTime) -> Result<(), BudgetExceeded> {
+        let day = day_of(now);
+        let resets_at = (day + 1) * SECONDS_PER_DAY;
+        let mut usage = self.lock();
+        roll_over(&mut usage, day);
+
+        if let Some(limit) = self.max_requests_per_day
+            && usage.requests >= limit
+        {
+            return Err(BudgetExceeded::Requests { limit, resets_at });
+        }
+        if let Some(limit) = self.max_tokens_per_day
+            && usage.tokens >= limit
+        {
+            return Err(BudgetExceeded::Tokens { limit, resets_at });
+        }
+        usage.requests += 1;
+        Ok(())
+    }
+
+    fn record_tokens_at(&self, tokens: u64, now: SystemTime) {
+        let mut usage = self.lock();
+        roll_over(&mut usage, day_of(now));
+        usage.tokens = usage.tokens.saturating_add(tokens);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DayUsage> {
+        self.usage
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn day_of(now: SystemTime) -> u64 {
+    now.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+        .unwrap_or_default()
+}
+
+fn roll_over(usage: &mut DayUsage, day: u64) {
+    if usage.day != day {
+        *usage = DayUsage {
+            day,
+            ..DayUsage::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at_day(day: u64, seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(day * SECONDS_PER_DAY + seconds)
+    }
+
+    #[test]
+    fn request_budget_rejects_once_exhausted_and_resets_next_day() {
+        let budget = Budget::new(Some(2), None);
+        let morning = at_day(100, 60);
+
+        assert_eq!(budget.try_start_request_at(morning), Ok(()));
+        assert_eq!(budget.try_start_request_at(morning), Ok(()));
+        assert_eq!(
+            budget.try_start_request_at(morning),
+            Err(BudgetExceeded::Requests {
+                limit: 2,
+                resets_at: 101 * SECONDS_PER_DAY,
+            })
+        );
+
+        assert_eq!(budget.try_start_request_at(at_day(101, 0)), Ok(()));
+    }
+
+    #[test]
+    fn token_budget_counts_completed_usage() {
+        let budget = Budget::new(None, Some(1_000));
+        let now = at_day(5, 10);
+
+        assert_eq!(budget.try_start_request_at(now), Ok(()));
+        budget.record_tokens_at(600, now);
+        assert_eq!(budget.try_start_request_at(now), Ok(()));
+        budget.record_tokens_at(600, now);
+
+        let err = budget.try_start_request_at(now).unwrap_err();
+        assert_eq!(
+            err,
+            BudgetExceeded::Tokens {
+                limit: 1_000,
+                resets_at: 6 * SECONDS_PER_DAY,
+            }
+        );
+        assert_eq!(
+            err.message(),
+            "Daily token budget of 1000 tokens for this proxy is exhausted."
+        );
+    }
+}
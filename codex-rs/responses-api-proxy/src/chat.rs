@@ -0,0 +1,489 @@
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use bytes::Bytes;
+use codex_api::ApiError;
+use codex_api::ChatRequestBuilder;
+use codex_api::Provider;
+use codex_api::ResponseEvent;
+use codex_api::TransportError;
+use codex_api::WireApi;
+use codex_api::provider::RetryConfig;
+use codex_api::sse::chat::process_chat_sse;
+use codex_protocol::models::ResponseItem;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+/// Matches the default stream idle timeout Codex uses for model providers.
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+const READ_CHUNK_BYTES: usize = 8 * 1024;
+
+/// The parts of a Responses API request that Chat Completions can express.
+#[derive(Deserialize)]
+struct ResponsesRequestBody {
+    model: String,
+    #[serde(default)]
+    instructions: String,
+    #[serde(default)]
+    input: Vec<ResponseItem>,
+    #[serde(default)]
+    tools: Vec<Value>,
+}
+
+/// Rewrites a Responses API request body as a streaming Chat Completions
+/// request, using the same mapping Codex applies for `wire_api = "chat"`.
+pub(crate) fn chat_request_body(request: &Value) -> Result<Value> {
+    let request = ResponsesRequestBody::deserialize(request)
+        .context("request body is not a Responses API request")?;
+    let tools: Vec<Value> = request.tools.into_iter().filter_map(chat_tool).collect();
+    let provider = Provider {
+        name: "responses-api-proxy".to_string(),
+        base_url: String::new(),
+        query_params: None,
+        wire: WireApi::Chat,
+        headers: Default::default(),
+        retry: RetryConfig {
+            max_attempts: 0,
+            base_delay: Duration::ZERO,
+            retry_429: false,
+            retry_5xx: false,
+            retry_transport: false,
+        },
+        stream_idle_timeout: STREAM_IDLE_TIMEOUT,
+    };
+    let mut body = ChatRequestBuilder::new(
+        &request.model,
+        &request.instructions,
+        &request.input,
+        &tools,
+    )
+    .build(&provider)?
+    .body;
+    // Chat Completions only reports usage when asked to; budgets and metrics
+    // rely on it.
+    body["stream_options"] = json!({ "include_usage": true });
+    Ok(body)
+}
+
+/// Chat Completions only understands function tools.
+fn chat_tool(mut tool: Value) -> Option<Value> {
+    if tool.get("type").and_then(Value::as_str) != Some("function") {
+        return None;
+    }
+    let map = tool.as_object_mut()?;
+    map.remove("type");
+    let name = map.get("name").cloned().unwrap_or_default();
+    Some(json!({
+        "type": "function",
+        "name": name,
+        "function": map,
+    }))
+}
+
+/// Identifier and usage seen on the raw Chat Completions stream, which the
+/// shared SSE mapping does not carry through.
+#[derive(Default)]
+struct ChatStreamInfo {
+    id: Option<String>,
+    usage: Option<Value>,
+}
+
+/// Serves a Chat Completions SSE body as a Responses API SSE body.
+///
+/// The upstream body is read on a dedicated thread and fed to
+/// `process_chat_sse`; its events are re-encoded as `response.*` events as the
+/// client reads.
+pub(crate) struct ChatToResponses {
+    runtime: tokio::runtime::Runtime,
+    events: mpsc::Receiver<Result<ResponseEvent, ApiError>>,
+    info: Arc<Mutex<ChatStreamInfo>>,
+    fallback_id: String,
+    pending: Vec<u8>,
+    position: usize,
+    started: bool,
+    done: bool,
+}
+
+impl ChatToResponses {
+    pub(crate) fn new(upstream: impl Read + Send + 'static, fallback_id: String) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .context("building translation runtime")?;
+        let info = Arc::new(Mutex::new(ChatStreamInfo::default()));
+        let (tx_bytes, rx_bytes) = mpsc::channel::<Result<Bytes, TransportError>>(16);
+        let (tx_event, events) = mpsc::channel(1600);
+
+        let reader_info = Arc::clone(&info);
+        std::thread::spawn(move || pump_upstream(upstream, tx_bytes, &reader_info));
+
+        let bytes = Box::pin(futures::stream::unfold(rx_bytes, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        }));
+        runtime.spawn(process_chat_sse(bytes, tx_event, STREAM_IDLE_TIMEOUT, None));
+
+        Ok(Self {
+            runtime,
+            events,
+            info,
+            fallback_id,
+            pending: Vec::new(),
+            position: 0,
+            started: false,
+            done: false,
+        })
+    }
+
+    fn next_events(&mut self) {
+        self.pending.clear();
+        self.position = 0;
+        if !self.started {
+            self.started = true;
+            self.push_event(
+                "response.created",
+                json!({ "response": { "id": self.response_id() } }),
+            );
+        }
+
+        match self.runtime.block_on(self.events.recv()) {
+            Some(Ok(event)) => self.push_response_event(event),
+            Some(Err(err)) => {
+                let code = match err {
+                    ApiError::ContextWindowExceeded => "context_length_exceeded",
+                    _ => "server_error",
+                };
+                self.push_event(
+                    "response.failed",
+                    json!({
+                        "response": {
+                            "id": self.response_id(),
+                            "status": "failed",
+                            "error": { "code": code, "message": err.to_string() },
+                        }
+                    }),
+                );
+                self.done = true;
+            }
+            None => {
+                // `process_chat_sse` reports completion as soon as it sees
+                // `finish_reason`, but usage arrives in a later chunk, so the
+                // completion event is only written once the stream has ended.
+                let usage = self.lock_info().usage.as_ref().map(responses_usage);
+                let mut response = json!({ "id": self.response_id(), "status": "completed" });
+                if let Some(usage) = usage {
+                    response["usage"] = usage;
+                }
+                self.push_event("response.completed", json!({ "response": response }));
+                self.done = true;
+            }
+        }
+    }
+
+    fn push_response_event(&mut self, event: ResponseEvent) {
+        match event {
+            ResponseEvent::OutputItemAdded(item) => {
+                self.push_event("response.output_item.added", json!({ "item": item }));
+            }
+            ResponseEvent::OutputItemDone(item) => {
+                self.push_event("response.output_item.done", json!({ "item": item }));
+            }
+            ResponseEvent::OutputTextDelta(delta) => {
+                self.push_event("response.output_text.delta", json!({ "delta": delta }));
+            }
+            ResponseEvent::ReasoningContentDelta {
+                delta,
+                content_index,
+            } => {
+                self.push_event(
+                    "response.reasoning_text.delta",
+                    json!({ "delta": delta, "content_index": content_index }),
+                );
+            }
+            ResponseEvent::ReasoningSummaryDelta {
+                delta,
+                summary_index,
+            } => {
+                self.push_event(
+                    "response.reasoning_summary_text.delta",
+                    json!({ "delta": delta, "summary_index": summary_index }),
+                );
+            }
+            ResponseEvent::ReasoningSummaryPartAdded { summary_index } => {
+                self.push_event(
+                    "response.reasoning_summary_part.added",
+                    json!({ "summary_index": summary_index }),
+                );
+            }
+            ResponseEvent::Created
+            | ResponseEvent::Completed { .. }
+            | ResponseEvent::RateLimits(_) => {}
+        }
+    }
+
+    fn push_event(&mut self, kind: &str, mut payload: Value) {
+        payload["type"] = Value::String(kind.to_string());
+        self.pending
+            .extend_from_slice(format!("event: {kind}\ndata: {payload}\n\n").as_bytes());
+    }
+
+    fn response_id(&self) -> String {
+        self.lock_info()
+            .id
+            .clone()
+            .unwrap_or_else(|| self.fallback_id.clone())
+    }
+
+    fn lock_info(&self) -> std::sync::MutexGuard<'_, ChatStreamInfo> {
+        self.info
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Read for ChatToResponses {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.position < self.pending.len() {
+                let available = &self.pending[self.position..];
+                let len = available.len().min(buf.len());
+                buf[..len].copy_from_slice(&available[..len]);
+                self.position += len;
+                return Ok(len);
+            }
+            if self.done {
+                return Ok(0);
+            }
+            self.next_events();
+        }
+    }
+}
+
+/// Copies the upstream body into the SSE parser's channel, noting the
+/// completion id and final usage chunk on the way.
+fn pump_upstream(
+    mut upstream: impl Read,
+    tx_bytes: mpsc::Sender<Result<Bytes, TransportError>>,
+    info: &Mutex<ChatStreamInfo>,
+) {
+    let mut line = Vec::new();
+    let mut chunk = vec![0u8; READ_CHUNK_BYTES];
+    loop {
+        let read = match upstream.read(&mut chunk) {
+            Ok(0) => return,
+            Ok(read) => read,
+            Err(err) => {
+                let _ = tx_bytes.blocking_send(Err(TransportError::Network(err.to_string())));
+                return;
+            }
+        };
+        for &byte in &chunk[..read] {
+            if byte == b'\n' {
+                observe_chat_line(&line, info);
+                line.clear();
+            } else {
+                line.push(byte);
+            }
+        }
+        let bytes = Bytes::copy_from_slice(&chunk[..read]);
+        if tx_bytes.blocking_send(Ok(bytes)).is_err() {
+            // The client went away.
+            return;
+        }
+    }
+}
+
+fn observe_chat_line(line: &[u8], info: &Mutex<ChatStreamInfo>) {
+    let Some(data) = line.strip_prefix(b"data:") else {
+        return;
+    };
+    let Ok(chunk) = serde_json::from_slice::<Value>(data.trim_ascii()) else {
+        return;
+    };
+    let mut info = info
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if info.id.is_none()
+        && let Some(id) = chunk.get("id").and_then(Value::as_str)
+    {
+        info.id = Some(id.to_string());
+    }
+    if let Some(usage) = chunk.get("usage").filter(|usage| !usage.is_null()) {
+        info.usage = Some(usage.clone());
+    }
+}
+
+/// Maps Chat Completions `usage` onto the Responses API shape.
+fn responses_usage(usage: &Value) -> Value {
+    let field = |value: Option<&Value>| value.and_then(Value::as_u64).unwrap_or_default();
+    let input_tokens = field(usage.get("prompt_tokens"));
+    let output_tokens = field(usage.get("completion_tokens"));
+    json!({
+        "input_tokens": input_tokens,
+        "input_tokens_details": {
+            "cached_tokens": field(usage.pointer("/prompt_tokens_details/cached_tokens")),
+        },
+        "output_tokens": output_tokens,
+        "output_tokens_details": {
+            "reasoning_tokens": field(usage.pointer("/completion_tokens_details/reasoning_tokens")),
+        },
+        "total_tokens": usage
+            .get("total_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(input_tokens + output_tokens),
+    })
+}
+
+/// Request headers that must not be forwarded once the body is rewritten.
+pub(crate) fn strip_body_headers(headers: &mut HeaderMap) {
+    headers.remove(reqwest::header::CONTENT_LENGTH);
+    // The translated stream has to be parsed, so it must arrive uncompressed.
+    headers.remove(reqwest::header::ACCEPT_ENCODING);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(body: &str) -> Vec<Value> {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn translates_request_body() {
+        let request = json!({
+            "model": "qwen3-coder",
+            "instructions": "You are a coding agent.",
+            "input": [
+                {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "hi"}]},
+            ],
+            "tools": [
+                {"type": "function", "name": "shell", "description": "Run a command", "strict": false,
+                 "parameters": {"type": "object", "properties": {}}},
+                {"type": "web_search"},
+            ],
+            "stream": true,
+        });
+
+        let body = chat_request_body(&request).unwrap();
+
+        assert_eq!(body["model"], "qwen3-coder");
+        assert_eq!(
+            body["messages"],
+            json!([
+                {"role": "system", "content": "You are a coding agent."},
+                {"role": "user", "content": "hi"},
+            ])
+        );
+        assert_eq!(
+            body["tools"],
+            json!([{
+                "type": "function",
+                "name": "shell",
+                "function": {
+                    "name": "shell",
+                    "description": "Run a command",
+                    "strict": false,
+                    "parameters": {"type": "object", "properties": {}},
+                },
+            }])
+        );
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["stream_options"], json!({"include_usage": true}));
+    }
+
+    #[test]
+    fn translates_chat_stream_to_responses_events() {
+        let chat = concat!(
+            "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"choices\":[],\"usage\":{\"prompt_tokens\":12,",
+            "\"completion_tokens\":3,\"total_tokens\":15}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let mut translated = String::new();
+        ChatToResponses::new(chat.as_bytes(), "resp_fallback".to_string())
+            .unwrap()
+            .read_to_string(&mut translated)
+            .unwrap();
+
+        let events = events(&translated);
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|event| event["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "response.created",
+                "response.output_item.added",
+                "response.output_text.delta",
+                "response.output_text.delta",
+                "response.output_item.done",
+                "response.completed",
+            ]
+        );
+        assert_eq!(
+            events[4]["item"],
+            json!({
+                "type": "message",
+                "role": "assistant",
+                "content": [
+                    {"type": "output_text", "text": "Hel"},
+                    {"type": "output_text", "text": "lo"},
+                ],
+            })
+        );
+        assert_eq!(events[5]["response"]["id"], "chatcmpl-1");
+        assert_eq!(events[5]["response"]["usage"]["input_tokens"], 12);
+        assert_eq!(events[5]["response"]["usage"]["total_tokens"], 15);
+    }
+
+    #[test]
+    fn reports_tool_calls_as_function_call_items() {
+        let chat = concat!(
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",",
+            "\"function\":{\"name\":\"shell\",\"arguments\":\"{\\\"cmd\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,",
+            "\"function\":{\"arguments\":\"\\\"ls\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+        );
+        let mut translated = String::new();
+        ChatToResponses::new(chat.as_bytes(), "resp_fallback".to_string())
+            .unwrap()
+            .read_to_string(&mut translated)
+            .unwrap();
+
+        let events = events(&translated);
+        assert_eq!(
+            events[1],
+            json!({
+                "type": "response.output_item.done",
+                "item": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": "{\"cmd\":\"ls\"}",
+                    "call_id": "call_1",
+                },
+            })
+        );
+        assert_eq!(
+            events[2],
+            json!({
+                "type": "response.completed",
+                "response": {"id": "resp_fallback", "status": "completed"},
+            })
+        );
+    }
+}
@@ -1,25 +1,32 @@
 use std::fs::File;
 use std::fs::{self};
+use std::io::Read;
 use std::io::Write;
 use std::net::SocketAddr;
 use std::net::TcpListener;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
+use chrono::SecondsFormat;
+use chrono::Utc;
 use clap::Parser;
-use reqwest::Url;
 use reqwest::blocking::Client;
-use reqwest::header::AUTHORIZATION;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::header::HOST;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderName;
 use reqwest::header::HeaderValue;
 use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
 use tiny_http::Header;
 use tiny_http::Method;
 use tiny_http::Request;
@@ -27,8 +34,28 @@ use tiny_http::Response;
 use tiny_http::Server;
 use tiny_http::StatusCode;
 
+mod audit;
+mod budget;
+mod chat;
+mod metrics;
 mod read_api_key;
+mod redact;
+mod upstream;
+mod usage;
+use audit::AuditLog;
+use audit::AuditRecord;
+use budget::Budget;
+use budget::BudgetExceeded;
+use chat::ChatToResponses;
+use chat::chat_request_body;
+use chat::strip_body_headers;
+use metrics::Metrics;
 use read_api_key::read_auth_header_from_stdin;
+use redact::Redactor;
+use upstream::Router;
+use upstream::WireApi;
+use usage::ResponseTap;
+use usage::TokenUsage;
 
 /// CLI arguments for the proxy.
 #[derive(Debug, Clone, Parser)]
@@ -49,6 +76,35 @@ pub struct Args {
     /// Absolute URL the proxy should forward requests to (defaults to OpenAI).
     #[arg(long, default_value = "https://api.openai.com/v1/responses")]
     pub upstream_url: String,
+
+    /// TOML file listing upstreams to route between by model name. Replaces
+    /// `--upstream-url`.
+    #[arg(long, value_name = "FILE")]
+    pub upstreams_config: Option<PathBuf>,
+
+    /// Append a JSONL audit record for every request to this file.
+    #[arg(long, value_name = "FILE")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Replace tool outputs in logged requests with a placeholder.
+    #[arg(long)]
+    pub redact_tool_outputs: bool,
+
+    /// Mask common credential formats (API keys, tokens, private keys) in logged payloads.
+    #[arg(long)]
+    pub redact_secrets: bool,
+
+    /// Regular expression whose matches are masked in logged payloads. May be repeated.
+    #[arg(long = "redact-pattern", value_name = "REGEX")]
+    pub redact_patterns: Vec<String>,
+
+    /// Maximum number of requests forwarded per UTC day.
+    #[arg(long, value_name = "REQUESTS")]
+    pub daily_request_budget: Option<u64>,
+
+    /// Maximum number of tokens (upstream `usage.total_tokens`) per UTC day.
+    #[arg(long, value_name = "TOKENS")]
+    pub daily_token_budget: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -57,27 +113,35 @@ struct ServerInfo {
     pid: u32,
 }
 
-struct ForwardConfig {
-    upstream_url: Url,
-    host_header: HeaderValue,
+struct ProxyState {
+    router: Router,
+    audit: Option<AuditLog>,
+    redactor: Redactor,
+    budget: Budget,
+    metrics: Metrics,
+    next_request_id: AtomicU64,
 }
 
 /// Entry point for the library main, for parity with other crates.
 pub fn run_main(args: Args) -> Result<()> {
-    let auth_header = read_auth_header_from_stdin()?;
-
-    let upstream_url = Url::parse(&args.upstream_url).context("parsing --upstream-url")?;
-    let host = match (upstream_url.host_str(), upstream_url.port()) {
-        (Some(host), Some(port)) => format!("{host}:{port}"),
-        (Some(host), None) => host.to_string(),
-        _ => return Err(anyhow!("upstream URL must include a host")),
+    let router = match args.upstreams_config.as_deref() {
+        Some(path) => Router::load(path, read_auth_header_from_stdin)?,
+        None => Router::single(&args.upstream_url, read_auth_header_from_stdin()?)?,
     };
-    let host_header =
-        HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
 
-    let forward_config = Arc::new(ForwardConfig {
-        upstream_url,
-        host_header,
+    let audit = args.audit_log.as_deref().map(AuditLog::open).transpose()?;
+    let redactor = Redactor::new(
+        args.redact_tool_outputs,
+        args.redact_secrets,
+        &args.redact_patterns,
+    )?;
+    let state = Arc::new(ProxyState {
+        router,
+        audit,
+        redactor,
+        budget: Budget::new(args.daily_request_budget, args.daily_token_budget),
+        metrics: Metrics::default(),
+        next_request_id: AtomicU64::new(0),
     });
 
     let (listener, bound_addr) = bind_listener(args.port)?;
@@ -99,14 +163,14 @@ pub fn run_main(args: Args) -> Result<()> {
     let http_shutdown = args.http_shutdown;
     for request in server.incoming_requests() {
         let client = client.clone();
-        let forward_config = forward_config.clone();
+        let state = state.clone();
         std::thread::spawn(move || {
             if http_shutdown && request.method() == &Method::Get && request.url() == "/shutdown" {
                 let _ = request.respond(Response::new_empty(StatusCode(200)));
                 std::process::exit(0);
             }
 
-            if let Err(e) = forward_request(&client, auth_header, &forward_config, request) {
+            if let Err(e) = forward_request(&client, &state, request) {
                 eprintln!("forwarding error: {e}");
             }
         });
@@ -140,28 +204,138 @@ fn write_server_info(path: &Path, port: u16) -> Result<()> {
     Ok(())
 }
 
-fn forward_request(
-    client: &Client,
-    auth_header: &'static str,
-    config: &ForwardConfig,
-    mut req: Request,
-) -> Result<()> {
-    // Only allow POST /v1/responses exactly, no query string.
+fn forward_request(client: &Client, state: &Arc<ProxyState>, mut req: Request) -> Result<()> {
     let method = req.method().clone();
     let url_path = req.url().to_string();
+
+    if method == Method::Get && url_path == "/metrics" {
+        let body = state.metrics.render(&state.budget);
+        let mut resp = Response::from_string(body);
+        if let Ok(header) = Header::from_bytes(
+            &b"Content-Type"[..],
+            &b"text/plain; version=0.0.4; charset=utf-8"[..],
+        ) {
+            resp = resp.with_header(header);
+        }
+        let _ = req.respond(resp);
+        return Ok(());
+    }
+
+    // Only allow POST /v1/responses exactly, no query string.
     let allow = method == Method::Post && url_path == "/v1/responses";
 
     if !allow {
+        state.metrics.record_forbidden();
         let resp = Response::new_empty(StatusCode(403));
         let _ = req.respond(resp);
         return Ok(());
     }
 
+    let started = Instant::now();
+    let audit_entry = AuditEntry {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        request_id: state.next_request_id.fetch_add(1, Ordering::Relaxed) + 1,
+        method: method.to_string(),
+        path: url_path,
+    };
+
     // Read request body
     let mut body = Vec::new();
     let mut reader = req.as_reader();
     std::io::Read::read_to_end(&mut reader, &mut body)?;
 
+    let request_json = serde_json::from_slice::<Value>(&body).ok();
+    let model = request_json
+        .as_ref()
+        .and_then(|request| request.get("model"))
+        .and_then(Value::as_str);
+    let logged_request =
+        state
+            .audit
+            .as_ref()
+            .and_then(|_| request_json.clone())
+            .map(|mut request| {
+                state.redactor.redact_request(&mut request);
+                request
+            });
+
+    let Some(upstream) = state.router.route(model) else {
+        let message = match model {
+            Some(model) => format!("No upstream is configured for model `{model}`."),
+            None => "No upstream is configured for requests without a model.".to_string(),
+        };
+        audit_entry.write(
+            state,
+            AuditOutcome {
+                status: 404,
+                error: Some(message.clone()),
+                ..AuditOutcome::new(started, logged_request)
+            },
+        );
+        let _ = req.respond(error_response(
+            404,
+            json!({"type": "invalid_request_error", "code": "model_not_found", "message": message}),
+        ));
+        return Ok(());
+    };
+    let upstream_name = Some(upstream.name.clone());
+
+    let body = match upstream.wire_api {
+        WireApi::Responses => body,
+        WireApi::Chat => match request_json.as_ref().map(chat_request_body) {
+            Some(Ok(chat_body)) => serde_json::to_vec(&chat_body)?,
+            Some(Err(err)) => {
+                let message = format!("{err:#}");
+                audit_entry.write(
+                    state,
+                    AuditOutcome {
+                        status: 400,
+                        upstream: upstream_name,
+                        error: Some(message.clone()),
+                        ..AuditOutcome::new(started, logged_request)
+                    },
+                );
+                let _ = req.respond(error_response(
+                    400,
+                    json!({"type": "invalid_request_error", "message": message}),
+                ));
+                return Ok(());
+            }
+            None => {
+                audit_entry.write(
+                    state,
+                    AuditOutcome {
+                        status: 400,
+                        upstream: upstream_name,
+                        error: Some("request body is not JSON".to_string()),
+                        ..AuditOutcome::new(started, logged_request)
+                    },
+                );
+                let _ = req.respond(error_response(
+                    400,
+                    json!({"type": "invalid_request_error", "message": "Request body is not JSON."}),
+                ));
+                return Ok(());
+            }
+        },
+    };
+
+    if let Err(exceeded) = state.budget.try_start_request() {
+        state.metrics.record_over_budget();
+        audit_entry.write(
+            state,
+            AuditOutcome {
+                status: 429,
+                upstream: upstream_name,
+                error: Some(exceeded.message()),
+                ..AuditOutcome::new(started, logged_request)
+            },
+        );
+        let _ = req.respond(budget_exceeded_response(&exceeded));
+        return Ok(());
+    }
+    state.metrics.record_forwarded();
+
     // Build headers for upstream, forwarding everything from the incoming
     // request except Authorization (we replace it below).
     let mut headers = HeaderMap::new();
@@ -183,24 +357,63 @@ fn forward_request(
 
     // As part of our effort to to keep `auth_header` secret, we use a
     // combination of `from_static()` and `set_sensitive(true)`.
-    let mut auth_header_value = HeaderValue::from_static(auth_header);
-    auth_header_value.set_sensitive(true);
-    headers.insert(AUTHORIZATION, auth_header_value);
+    if let Some((auth_header_name, auth_header)) = upstream.auth_header() {
+        let mut auth_header_value = HeaderValue::from_static(auth_header);
+        auth_header_value.set_sensitive(true);
+        headers.insert(auth_header_name, auth_header_value);
+    }
 
-    headers.insert(HOST, config.host_header.clone());
+    headers.insert(HOST, upstream.host_header.clone());
+    let translate = upstream.wire_api == WireApi::Chat;
+    if translate {
+        strip_body_headers(&mut headers);
+    }
 
-    let upstream_resp = client
-        .post(config.upstream_url.clone())
+    let upstream_resp = match client
+        .post(upstream.url.clone())
         .headers(headers)
         .body(body)
         .send()
-        .context("forwarding request to upstream")?;
+    {
+        Ok(resp) => resp,
+        Err(err) => {
+            state.metrics.record_upstream_error();
+            audit_entry.write(
+                state,
+                AuditOutcome {
+                    status: 502,
+                    upstream: upstream_name,
+                    error: Some(err.to_string()),
+                    ..AuditOutcome::new(started, logged_request)
+                },
+            );
+            let _ = req.respond(Response::new_empty(StatusCode(502)));
+            return Err(err).context("forwarding request to upstream");
+        }
+    };
 
     // We have to create an adapter between a `reqwest::blocking::Response`
     // and a `tiny_http::Response`. Fortunately, `reqwest::blocking::Response`
     // implements `Read`, so we can use it directly as the body of the
     // `tiny_http::Response`.
     let status = upstream_resp.status();
+    if !status.is_success() {
+        state.metrics.record_upstream_error();
+    }
+    let upstream_request_id = upstream_resp
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    // Error bodies from Chat Completions upstreams already use the
+    // `{"error": {...}}` shape, so only successful streams are translated.
+    let translate = translate && status.is_success();
+    let is_sse = translate
+        || upstream_resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
     let mut response_headers = Vec::new();
     for (name, value) in upstream_resp.headers().iter() {
         // Skip headers that tiny_http manages itself.
@@ -210,6 +423,9 @@ fn forward_request(
         ) {
             continue;
         }
+        if translate && matches!(name.as_str(), "content-type" | "content-encoding") {
+            continue;
+        }
 
         if let Ok(header) = Header::from_bytes(name.as_str().as_bytes(), value.as_bytes()) {
             response_headers.push(header);
@@ -224,10 +440,49 @@ fn forward_request(
         }
     });
 
+    let (upstream_body, content_length): (Box<dyn Read + Send>, _) = if translate {
+        if let Ok(header) = Header::from_bytes(&b"Content-Type"[..], &b"text/event-stream"[..]) {
+            response_headers.push(header);
+        }
+        let fallback_id = format!("resp_proxy_{}", audit_entry.request_id);
+        (
+            Box::new(ChatToResponses::new(upstream_resp, fallback_id)?),
+            None,
+        )
+    } else {
+        (Box::new(upstream_resp), content_length)
+    };
+
+    // Usage accounting and the audit record happen once the body has been
+    // relayed, since streamed responses only report usage at the end.
+    let tap_state = Arc::clone(state);
+    let body = ResponseTap::new(upstream_body, is_sse, move |response| {
+        let usage = response.as_ref().and_then(TokenUsage::from_response);
+        if let Some(usage) = &usage {
+            tap_state.budget.record_tokens(usage.total_tokens);
+            tap_state.metrics.record_usage(usage);
+        }
+        let response = tap_state.audit.as_ref().and(response).map(|mut response| {
+            tap_state.redactor.mask(&mut response);
+            response
+        });
+        audit_entry.write(
+            &tap_state,
+            AuditOutcome {
+                status: status.as_u16(),
+                upstream: upstream_name,
+                upstream_request_id,
+                usage,
+                response,
+                ..AuditOutcome::new(started, logged_request)
+            },
+        );
+    });
+
     let response = Response::new(
         StatusCode(status.as_u16()),
         response_headers,
-        upstream_resp,
+        body,
         content_length,
         None,
     );
@@ -235,3 +490,91 @@ fn forward_request(
     let _ = req.respond(response);
     Ok(())
 }
+
+/// Request metadata captured when a request arrives.
+struct AuditEntry {
+    timestamp: String,
+    request_id: u64,
+    method: String,
+    path: String,
+}
+
+/// What happened to a request, for its audit record.
+struct AuditOutcome {
+    started: Instant,
+    status: u16,
+    upstream: Option<String>,
+    upstream_request_id: Option<String>,
+    usage: Option<TokenUsage>,
+    request: Option<Value>,
+    response: Option<Value>,
+    error: Option<String>,
+}
+
+impl AuditOutcome {
+    fn new(started: Instant, request: Option<Value>) -> Self {
+        Self {
+            started,
+            status: 0,
+            upstream: None,
+            upstream_request_id: None,
+            usage: None,
+            request,
+            response: None,
+            error: None,
+        }
+    }
+}
+
+impl AuditEntry {
+    fn write(self, state: &ProxyState, outcome: AuditOutcome) {
+        let Some(audit) = state.audit.as_ref() else {
+            return;
+        };
+        let model = outcome
+            .request
+            .as_ref()
+            .and_then(|request| request.get("model"))
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        audit.write(&AuditRecord {
+            timestamp: self.timestamp,
+            request_id: self.request_id,
+            upstream_request_id: outcome.upstream_request_id,
+            method: self.method,
+            path: self.path,
+            upstream: outcome.upstream,
+            status: outcome.status,
+            duration_ms: u64::try_from(outcome.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            model,
+            usage: outcome.usage,
+            request: outcome.request,
+            response: outcome.response,
+            error: outcome.error,
+        });
+    }
+}
+
+/// 429 in the shape of an upstream `usage_limit_reached` error so clients
+/// surface it as a usage limit with a reset time instead of retrying.
+fn budget_exceeded_response(exceeded: &BudgetExceeded) -> Response<std::io::Cursor<Vec<u8>>> {
+    error_response(
+        429,
+        json!({
+            "type": "usage_limit_reached",
+            "code": "proxy_budget_exceeded",
+            "message": exceeded.message(),
+            "resets_at": exceeded.resets_at(),
+        }),
+    )
+}
+
+/// JSON error in the OpenAI `{"error": {...}}` shape.
+fn error_response(status: u16, error: Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    let body = json!({ "error": error });
+    let mut resp = Response::from_string(body.to_string()).with_status_code(StatusCode(status));
+    if let Ok(header) = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]) {
+        resp = resp.with_header(header);
+    }
+    resp
+}
@@ -0,0 +1,136 @@
+use std::fmt::Write;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use crate::budget::Budget;
+use crate::usage::TokenUsage;
+
+/// Counters served on `GET /metrics` in the Prometheus text format.
+#[derive(Default)]
+pub(crate) struct Metrics {
+    requests_total: AtomicU64,
+    requests_forbidden: AtomicU64,
+    requests_over_budget: AtomicU64,
+    upstream_errors: AtomicU64,
+    input_tokens: AtomicU64,
+    cached_input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+impl Metrics {
+    pub(crate) fn record_forwarded(&self) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_forbidden(&self) {
+        self.requests_forbidden.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_over_budget(&self) {
+        self.requests_over_budget.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_upstream_error(&self) {
+        self.upstream_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_usage(&self, usage: &TokenUsage) {
+        self.input_tokens
+            .fetch_add(usage.input_tokens, Ordering::Relaxed);
+        self.cached_input_tokens
+            .fetch_add(usage.cached_input_tokens, Ordering::Relaxed);
+        self.output_tokens
+            .fetch_add(usage.output_tokens, Ordering::Relaxed);
+    }
+
+    pub(crate) fn render(&self, budget: &Budget) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "codex_proxy_requests_total",
+                "Requests forwarded upstream.",
+                &self.requests_total,
+            ),
+            (
+                "codex_proxy_requests_forbidden_total",
+                "Requests rejected because they are not POST /v1/responses.",
+                &self.requests_forbidden,
+            ),
+            (
+                "codex_proxy_requests_over_budget_total",
+                "Requests rejected because a daily budget was exhausted.",
+                &self.requests_over_budget,
+            ),
+            (
+                "codex_proxy_upstream_errors_total",
+                "Upstream responses with an error status or failed connections.",
+                &self.upstream_errors,
+            ),
+            (
+                "codex_proxy_input_tokens_total",
+                "Input tokens reported by upstream usage.",
+                &self.input_tokens,
+            ),
+            (
+                "codex_proxy_cached_input_tokens_total",
+                "Cached input tokens reported by upstream usage.",
+                &self.cached_input_tokens,
+            ),
+            (
+                "codex_proxy_output_tokens_total",
+                "Output tokens reported by upstream usage.",
+                &self.output_tokens,
+            ),
+        ];
+        for (name, help, value) in counters {
+            write_metric(
+                &mut out,
+                name,
+                help,
+                "counter",
+                value.load(Ordering::Relaxed),
+            );
+        }
+
+        let today = budget.today();
+        write_metric(
+            &mut out,
+            "codex_proxy_budget_requests_used",
+            "Requests counted against today's (UTC) budget.",
+            "gauge",
+            today.requests,
+        );
+        write_metric(
+            &mut out,
+            "codex_proxy_budget_tokens_used",
+            "Tokens counted against today's (UTC) budget.",
+            "gauge",
+            today.tokens,
+        );
+        if let Some(limit) = budget.max_requests_per_day() {
+            write_metric(
+                &mut out,
+                "codex_proxy_budget_requests_limit",
+                "Daily request budget.",
+                "gauge",
+                limit,
+            );
+        }
+        if let Some(limit) = budget.max_tokens_per_day() {
+            write_metric(
+                &mut out,
+                "codex_proxy_budget_tokens_limit",
+                "Daily token budget.",
+                "gauge",
+                limit,
+            );
+        }
+        out
+    }
+}
+
+fn write_metric(out: &mut String, name: &str, help: &str, kind: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    let _ = writeln!(out, "{name} {value}");
+}
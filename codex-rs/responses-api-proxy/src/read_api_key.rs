@@ -1,3 +1,6 @@
+use std::fs::File;
+use std::path::Path;
+
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
@@ -29,6 +32,18 @@ pub(crate) fn read_auth_header_from_stdin() -> Result<&'static str> {
     read_auth_header_with(|buffer| std::io::stdin().read(buffer))
 }
 
+/// Reads the auth token from the first line of `path`, with the same handling
+/// as [`read_auth_header_from_stdin`]. `File` is unbuffered, so the only copy
+/// of the key is the locked header value.
+pub(crate) fn read_auth_header_from_file(path: &Path) -> Result<&'static str> {
+    use std::io::Read;
+
+    let mut file =
+        File::open(path).with_context(|| format!("opening API key file {}", path.display()))?;
+    read_auth_header_with(|buffer| file.read(buffer))
+        .with_context(|| format!("reading API key from {}", path.display()))
+}
+
 /// We perform a low-level read with `read(2)` because `stdio::io::stdin()` has
 /// an internal BufReader:
 ///
@@ -0,0 +1,151 @@
+use anyhow::Context;
+use anyhow::Result;
+use regex_lite::Regex;
+use serde_json::Value;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Formats of credentials that should never end up in an audit log.
+const SECRET_PATTERNS: &[&str] = &[
+    // OpenAI and Anthropic style API keys.
+    r"sk-[A-Za-z0-9_-]{16,}",
+    // GitHub tokens.
+    r"gh[pousr]_[A-Za-z0-9]{20,}",
+    r"github_pat_[A-Za-z0-9_]{20,}",
+    // AWS access key ids.
+    r"(?:AKIA|ASIA)[0-9A-Z]{16}",
+    // Slack tokens.
+    r"xox[abprs]-[A-Za-z0-9-]{10,}",
+    // Bearer tokens in captured headers or command output.
+    r"(?i)bearer\s+[A-Za-z0-9._~+/=-]{16,}",
+    // PEM private keys.
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+];
+
+/// Input item types whose `output` carries tool results.
+const TOOL_OUTPUT_ITEM_TYPES: &[&str] = &[
+    "function_call_output",
+    "custom_tool_call_output",
+    "local_shell_call_output",
+];
+
+/// Scrubs request and response payloads before they are written to the audit
+/// log. Forwarded traffic is never modified.
+pub(crate) struct Redactor {
+    strip_tool_outputs: bool,
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    pub(crate) fn new(
+        strip_tool_outputs: bool,
+        redact_secrets: bool,
+        extra_patterns: &[String],
+    ) -> Result<Self> {
+        let builtin = if redact_secrets { SECRET_PATTERNS } else { &[] };
+        let mut patterns = Vec::new();
+        for pattern in builtin.iter().copied() {
+            patterns.push(Regex::new(pattern).context("compiling built-in secret pattern")?);
+        }
+        for pattern in extra_patterns {
+            patterns.push(
+                Regex::new(pattern)
+                    .with_context(|| format!("invalid --redact-pattern `{pattern}`"))?,
+            );
+        }
+        Ok(Self {
+            strip_tool_outputs,
+            patterns,
+        })
+    }
+
+    /// Redacts a Responses API request body in place.
+    pub(crate) fn redact_request(&self, body: &mut Value) {
+        if self.strip_tool_outputs
+            && let Some(items) = body.get_mut("input").and_then(Value::as_array_mut)
+        {
+            for item in items {
+                let is_tool_output = item
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .is_some_and(|ty| TOOL_OUTPUT_ITEM_TYPES.contains(&ty));
+                if is_tool_output && let Some(output) = item.get_mut("output") {
+                    *output = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+        self.mask(body);
+    }
+
+    /// Masks every pattern match in the string values of `value`.
+    pub(crate) fn mask(&self, value: &mut Value) {
+        if self.patterns.is_empty() {
+            return;
+        }
+        match value {
+            Value::String(text) => {
+                for pattern in &self.patterns {
+                    if let std::borrow::Cow::Owned(replaced) = pattern.replace_all(text, REDACTED) {
+                        *text = replaced;
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.mask(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.mask(item)),
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_tool_outputs_and_masks_secrets() {
+        let redactor = Redactor::new(true, true, &["internal-[0-9]+".to_string()]).unwrap();
+        let mut body = json!({
+            "model": "gpt-5.1",
+            "input": [
+                {"type": "message", "role": "user", "content": [
+                    {"type": "input_text", "text": "use key sk-abcdefghijklmnopqrstuv on internal-42"}
+                ]},
+                {"type": "function_call_output", "call_id": "c1", "output": "cat ~/.env"},
+            ],
+        });
+
+        redactor.redact_request(&mut body);
+
+        assert_eq!(
+            body,
+            json!({
+                "model": "gpt-5.1",
+                "input": [
+                    {"type": "message", "role": "user", "content": [
+                        {"type": "input_text", "text": "use key [REDACTED] on [REDACTED]"}
+                    ]},
+                    {"type": "function_call_output", "call_id": "c1", "output": "[REDACTED]"},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn leaves_payload_untouched_without_rules() {
+        let redactor = Redactor::new(false, false, &[]).unwrap();
+        let original = json!({
+            "input": [{"type": "function_call_output", "call_id": "c1", "output": "sk-abcdefghijklmnopqrstuv"}],
+        });
+        let mut body = original.clone();
+
+        redactor.redact_request(&mut body);
+
+        assert_eq!(body, original);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(Redactor::new(false, false, &["(".to_string()]).is_err());
+    }
+}
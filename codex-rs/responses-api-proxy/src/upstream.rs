@@ -0,0 +1,346 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use reqwest::Url;
+use reqwest::header::AUTHORIZATION;
+use reqwest::header::HeaderName;
+use reqwest::header::HeaderValue;
+use serde::Deserialize;
+
+use crate::read_api_key::read_auth_header_from_file;
+
+/// Wire protocol spoken by an upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum WireApi {
+    /// Requests are forwarded unchanged.
+    #[default]
+    Responses,
+    /// Requests and streamed responses are translated to and from Chat
+    /// Completions.
+    Chat,
+}
+
+/// Where the API key for an upstream comes from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum KeySource {
+    /// The key piped to the proxy on stdin, shared by every upstream that uses it.
+    #[default]
+    Stdin,
+    /// The first line of a file.
+    File(PathBuf),
+    /// No `Authorization` header, e.g. for a local inference server.
+    None,
+}
+
+/// How the API key is presented to an upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuthStyle {
+    /// `Authorization: Bearer <key>`.
+    #[default]
+    Bearer,
+    /// `api-key: <key>`, as Azure OpenAI expects.
+    ApiKey,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpstreamsToml {
+    #[serde(rename = "upstream")]
+    upstreams: Vec<UpstreamToml>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpstreamToml {
+    name: String,
+    url: String,
+    #[serde(default)]
+    wire_api: WireApi,
+    /// Model names this upstream serves; `*` matches any run of characters.
+    /// An upstream without `models` serves every model.
+    #[serde(default)]
+    models: Vec<String>,
+    #[serde(default)]
+    api_key: KeySource,
+    #[serde(default)]
+    auth_style: AuthStyle,
+}
+
+pub(crate) struct Upstream {
+    pub(crate) name: String,
+    pub(crate) url: Url,
+    pub(crate) host_header: HeaderValue,
+    pub(crate) wire_api: WireApi,
+    /// `Bearer <key>`, locked in memory; see `read_api_key`.
+    bearer_auth: Option<&'static str>,
+    auth_style: AuthStyle,
+    models: Vec<String>,
+}
+
+impl Upstream {
+    fn new(
+        name: String,
+        url: &str,
+        wire_api: WireApi,
+        models: Vec<String>,
+        bearer_auth: Option<&'static str>,
+        auth_style: AuthStyle,
+    ) -> Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("parsing URL of upstream `{name}`"))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => return Err(anyhow!("URL of upstream `{name}` must include a host")),
+        };
+        let host_header =
+            HeaderValue::from_str(&host).context("constructing Host header from upstream URL")?;
+        Ok(Self {
+            name,
+            url,
+            host_header,
+            wire_api,
+            bearer_auth,
+            auth_style,
+            models,
+        })
+    }
+
+    /// The header carrying the API key, in the upstream's [`AuthStyle`]. The
+    /// value borrows the locked key rather than copying it.
+    pub(crate) fn auth_header(&self) -> Option<(HeaderName, &'static str)> {
+        let bearer_auth = self.bearer_auth?;
+        match self.auth_style {
+            AuthStyle::Bearer => Some((AUTHORIZATION, bearer_auth)),
+            AuthStyle::ApiKey => Some((
+                HeaderName::from_static("api-key"),
+                bearer_auth.strip_prefix("Bearer ").unwrap_or(bearer_auth),
+            )),
+        }
+    }
+
+    fn serves(&self, model: Option<&str>) -> bool {
+        if self.models.is_empty() {
+            return true;
+        }
+        model.is_some_and(|model| {
+            self.models
+                .iter()
+                .any(|pattern| glob_matches(pattern, model))
+        })
+    }
+}
+
+/// Picks the upstream for a request from the model it names.
+pub(crate) struct Router {
+    upstreams: Vec<Upstream>,
+}
+
+impl Router {
+    /// The historical single-upstream setup: every request goes to `url`
+    /// with the key read from stdin.
+    pub(crate) fn single(url: &str, auth_header: &'static str) -> Result<Self> {
+        let upstream = Upstream::new(
+            "default".to_string(),
+            url,
+            WireApi::Responses,
+            Vec::new(),
+            Some(auth_header),
+            AuthStyle::Bearer,
+        )
+        .context("parsing --upstream-url")?;
+        Ok(Self {
+            upstreams: vec![upstream],
+        })
+    }
+
+    /// Loads upstreams from a TOML file, reading API keys as it goes.
+    pub(crate) fn load(
+        path: &Path,
+        read_stdin_key: impl FnOnce() -> Result<&'static str>,
+    ) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("reading upstreams config {}", path.display()))?;
+        Self::from_toml(&contents, read_stdin_key, read_auth_header_from_file)
+            .with_context(|| format!("loading upstreams config {}", path.display()))
+    }
+
+    fn from_toml(
+        contents: &str,
+        read_stdin_key: impl FnOnce() -> Result<&'static str>,
+        mut read_file_key: impl FnMut(&Path) -> Result<&'static str>,
+    ) -> Result<Self> {
+        let config: UpstreamsToml = toml::from_str(contents)?;
+        if config.upstreams.is_empty() {
+            return Err(anyhow!("at least one [[upstream]] is required"));
+        }
+
+        let mut read_stdin_key = Some(read_stdin_key);
+        let mut stdin_key = None;
+        let mut upstreams = Vec::with_capacity(config.upstreams.len());
+        for upstream in config.upstreams {
+            if upstreams
+                .iter()
+                .any(|existing: &Upstream| existing.name == upstream.name)
+            {
+                return Err(anyhow!("duplicate upstream name `{}`", upstream.name));
+            }
+            let auth_header = match &upstream.api_key {
+                KeySource::Stdin => {
+                    if let Some(read) = read_stdin_key.take() {
+                        stdin_key = Some(read()?);
+                    }
+                    stdin_key
+                }
+                KeySource::File(path) => Some(read_file_key(path)?),
+                KeySource::None => None,
+            };
+            upstreams.push(Upstream::new(
+                upstream.name,
+                &upstream.url,
+                upstream.wire_api,
+                upstream.models,
+                auth_header,
+                upstream.auth_style,
+            )?);
+        }
+        Ok(Self { upstreams })
+    }
+
+    /// The first upstream, in file order, that serves `model`.
+    pub(crate) fn route(&self, model: Option<&str>) -> Option<&Upstream> {
+        self.upstreams
+            .iter()
+            .find(|upstream| upstream.serves(model))
+    }
+}
+
+/// Matches `value` against `pattern`, where `*` stands for any run of
+/// characters.
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return value.is_empty();
+    };
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_matches("gpt-5.1", "gpt-5.1"));
+        assert!(!glob_matches("gpt-5.1", "gpt-5.1-codex"));
+        assert!(glob_matches("gpt-*", "gpt-5.1-codex"));
+        assert!(glob_matches("*-instruct", "qwen-2.5-instruct"));
+        assert!(glob_matches("qwen*coder*", "qwen3-coder-30b"));
+        assert!(!glob_matches("qwen*coder*", "llama-3"));
+        assert!(glob_matches("*", "anything"));
+    }
+
+    #[test]
+    fn routes_by_model_and_resolves_keys() {
+        let config = r#"
+[[upstream]]
+name = "vllm"
+url = "http://gpu-box:8000/v1/chat/completions"
+wire_api = "chat"
+models = ["qwen*", "llama-*"]
+api_key = "none"
+
+[[upstream]]
+name = "azure"
+url = "https://example.openai.azure.com/openai/responses?api-version=2025-04-01-preview"
+models = ["gpt-4.1"]
+api_key = { file = "/etc/codex/azure-key" }
+auth_style = "api_key"
+
+[[upstream]]
+name = "openai"
+url = "https://api.openai.com/v1/responses"
+"#;
+        let mut stdin_reads = 0;
+        let router = Router::from_toml(
+            config,
+            || {
+                stdin_reads += 1;
+                Ok("Bearer stdin-key")
+            },
+            |path| {
+                assert_eq!(path, Path::new("/etc/codex/azure-key"));
+                Ok("Bearer file-key")
+            },
+        )
+        .unwrap();
+        assert_eq!(stdin_reads, 1);
+
+        let vllm = router.route(Some("qwen3-coder")).unwrap();
+        assert_eq!(vllm.name, "vllm");
+        assert_eq!(vllm.wire_api, WireApi::Chat);
+        assert_eq!(vllm.auth_header(), None);
+        assert_eq!(vllm.host_header, "gpu-box:8000");
+
+        let azure = router.route(Some("gpt-4.1")).unwrap();
+        assert_eq!(azure.name, "azure");
+        assert_eq!(
+            azure.auth_header(),
+            Some((HeaderName::from_static("api-key"), "file-key"))
+        );
+
+        let openai = router.route(Some("gpt-5.1")).unwrap();
+        assert_eq!(openai.name, "openai");
+        assert_eq!(
+            openai.auth_header(),
+            Some((AUTHORIZATION, "Bearer stdin-key"))
+        );
+        assert_eq!(router.route(None).unwrap().name, "openai");
+    }
+
+    #[test]
+    fn rejects_duplicate_names_and_unknown_fields() {
+        let duplicate = r#"
+[[upstream]]
+name = "a"
+url = "http://localhost:1/v1/responses"
+api_key = "none"
+
+[[upstream]]
+name = "a"
+url = "http://localhost:2/v1/responses"
+api_key = "none"
+"#;
+        let no_key = || -> Result<&'static str> { Err(anyhow!("unexpected stdin read")) };
+        let err = Router::from_toml(duplicate, no_key, |_| unreachable!())
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), "duplicate upstream name `a`");
+
+        let typo = r#"
+[[upstream]]
+name = "a"
+url = "http://localhost:1/v1/responses"
+wire = "chat"
+"#;
+        assert!(Router::from_toml(typo, no_key, |_| unreachable!()).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::io::Read;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Upper bound on how much of a non-streaming response body is kept for
+/// usage extraction and audit logging.
+const MAX_BUFFERED_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// SSE events whose `response` object carries the final output and usage.
+const TERMINAL_EVENT_TYPES: &[&str] = &[
+    "response.completed",
+    "response.incomplete",
+    "response.failed",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct TokenUsage {
+    pub(crate) input_tokens: u64,
+    pub(crate) cached_input_tokens: u64,
+    pub(crate) output_tokens: u64,
+    pub(crate) total_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Reads the `usage` object of a Responses API `response`.
+    pub(crate) fn from_response(response: &Value) -> Option<Self> {
+        let usage = response.get("usage")?;
+        let field = |value: Option<&Value>| value.and_then(Value::as_u64).unwrap_or_default();
+        let input_tokens = field(usage.get("input_tokens"));
+        let output_tokens = field(usage.get("output_tokens"));
+        let total_tokens = usage
+            .get("total_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(input_tokens + output_tokens);
+        Some(Self {
+            input_tokens,
+            cached_input_tokens: field(
+                usage
+                    .get("input_tokens_details")
+                    .and_then(|details| details.get("cached_tokens")),
+            ),
+            output_tokens,
+            total_tokens,
+        })
+    }
+}
+
+type FinishFn = Box<dyn FnOnce(Option<Value>) + Send>;
+
+/// Passes an upstream response body through unchanged while picking out the
+/// final `response` object, either from the terminal SSE event of a stream or
+/// from a plain JSON body. `on_finish` runs once, when the body is exhausted
+/// or the client goes away.
+pub(crate) struct ResponseTap<R> {
+    inner: R,
+    is_sse: bool,
+    buffer: Vec<u8>,
+    overflowed: bool,
+    response: Option<Value>,
+    on_finish: Option<FinishFn>,
+}
+
+impl<R: Read> ResponseTap<R> {
+    pub(crate) fn new(
+        inner: R,
+        is_sse: bool,
+        on_finish: impl FnOnce(Option<Value>) + Send + 'static,
+    ) -> Self {
+        Self {
+            inner,
+            is_sse,
+            buffer: Vec::new(),
+            overflowed: false,
+            response: None,
+            on_finish: Some(Box::new(on_finish)),
+        }
+    }
+
+    fn observe(&mut self, chunk: &[u8]) {
+        if !self.is_sse {
+            if self.buffer.len() + chunk.len() > MAX_BUFFERED_BODY_BYTES {
+                self.overflowed = true;
+                self.buffer = Vec::new();
+            }
+            if !self.overflowed {
+                self.buffer.extend_from_slice(chunk);
+            }
+            return;
+        }
+
+        for &byte in chunk {
+            if byte == b'\n' {
+                let line = std::mem::take(&mut self.buffer);
+                self.observe_sse_line(&line);
+            } else {
+                self.buffer.push(byte);
+            }
+        }
+    }
+
+    fn observe_sse_line(&mut self, line: &[u8]) {
+        let Some(data) = line.strip_prefix(b"data:") else {
+            return;
+        };
+        let Ok(event) = serde_json::from_slice::<Value>(data.trim_ascii()) else {
+            return;
+        };
+        let is_terminal = event
+            .get("type")
+            .and_then(Value::as_str)
+            .is_some_and(|ty| TERMINAL_EVENT_TYPES.contains(&ty));
+        if is_terminal && let Some(response) = event.get("response") {
+            self.response = Some(response.clone());
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(on_finish) = self.on_finish.take() else {
+            return;
+        };
+        if self.is_sse {
+            let line = std::mem::take(&mut self.buffer);
+            self.observe_sse_line(&line);
+        } else if !self.overflowed {
+            self.response = serde_json::from_slice(&self.buffer).ok();
+        }
+        on_finish(self.response.take());
+    }
+}
+
+impl<R: Read> Read for ResponseTap<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 {
+            self.finish();
+        } else {
+            self.observe(&buf[..read]);
+        }
+        Ok(read)
+    }
+}
+
+impl<R> Drop for ResponseTap<R> {
+    fn drop(&mut self) {
+        // Still report what was seen when the client disconnects mid-stream.
+        if let Some(on_finish) = self.on_finish.take() {
+            on_finish(self.response.take());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    fn drain(tap: ResponseTap<&[u8]>) -> Vec<u8> {
+        let mut tap = tap;
+        let mut out = Vec::new();
+        tap.read_to_end(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn extracts_usage_from_completed_sse_event() {
+        let body = concat!(
+            "event: response.output_text.delta\n",
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"hi\"}\n\n",
+            "event: response.completed\n",
+            "data: {\"type\":\"response.completed\",\"response\":{\"id\":\"r1\",\"usage\":",
+            "{\"input_tokens\":10,\"input_tokens_details\":{\"cached_tokens\":4},",
+            "\"output_tokens\":5,\"total_tokens\":15}}}\n\n",
+        );
+        let seen = Arc::new(Mutex::new(None));
+        let seen_in_tap = Arc::clone(&seen);
+        let tap = ResponseTap::new(body.as_bytes(), true, move |response| {
+            *seen_in_tap.lock().unwrap() = response;
+        });
+
+        assert_eq!(drain(tap), body.as_bytes());
+
+        let response = seen.lock().unwrap().clone().unwrap();
+        assert_eq!(response["id"], json!("r1"));
+        assert_eq!(
+            TokenUsage::from_response(&response),
+            Some(TokenUsage {
+                input_tokens: 10,
+                cached_input_tokens: 4,
+                output_tokens: 5,
+                total_tokens: 15,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_plain_json_bodies_and_reports_on_drop() {
+        let body = br#"{"id":"r2","usage":{"input_tokens":3,"output_tokens":2}}"#;
+        let seen = Arc::new(Mutex::new(None));
+        let seen_in_tap = Arc::clone(&seen);
+        let tap = ResponseTap::new(&body[..], false, move |response| {
+            *seen_in_tap.lock().unwrap() = response;
+        });
+        drain(tap);
+        let usage = TokenUsage::from_response(&seen.lock().unwrap().clone().unwrap());
+        assert_eq!(usage.map(|usage| usage.total_tokens), Some(5));
+
+        let called = Arc::new(Mutex::new(false));
+        let called_in_tap = Arc::clone(&called);
+        let tap = ResponseTap::new(&b"data: {}\n"[..], true, move |response| {
+            assert!(response.is_none());
+            *called_in_tap.lock().unwrap() = true;
+        });
+        drop(tap);
+        assert!(*called.lock().unwrap());
+    }
+}
@@ -20,6 +20,7 @@ use crate::tui;
 use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
 use codex_ansi_escape::ansi_escape_line;
+use codex_common::oss::ensure_oss_model_ready;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
@@ -398,7 +399,13 @@ impl App {
 
         chat_widget.maybe_prompt_windows_sandbox_enable();
 
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone())
+            .with_index_home(
+                config
+                    .features
+                    .enabled(Feature::FileSearchIndex)
+                    .then(|| config.codex_home.clone()),
+            );
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
 
@@ -752,6 +759,43 @@ impl App {
                 self.chat_widget.set_model(&model, model_family);
                 self.current_model = model;
             }
+            AppEvent::SwitchToLocalModel {
+                model_provider,
+                model,
+                effort,
+            } => {
+                self.chat_widget
+                    .on_local_model_progress(format!("Preparing {model}"));
+                let config = self.config.clone();
+                let app_event_tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    let progress_tx = app_event_tx.clone();
+                    let result =
+                        ensure_oss_model_ready(&model_provider, &model, &config, move |line| {
+                            progress_tx.send(AppEvent::LocalModelProgress(line));
+                        })
+                        .await
+                        .map_err(|err| err.to_string());
+                    app_event_tx.send(AppEvent::LocalModelReady {
+                        model_provider,
+                        model,
+                        effort,
+                        result,
+                    });
+                });
+            }
+            AppEvent::LocalModelProgress(line) => {
+                self.chat_widget.on_local_model_progress(line);
+            }
+            AppEvent::LocalModelReady {
+                model_provider,
+                model,
+                effort,
+                result,
+            } => {
+                self.chat_widget
+                    .on_local_model_ready(model_provider, model, effort, result);
+            }
             AppEvent::OpenReasoningPopup { model } => {
                 self.chat_widget.open_reasoning_popup(model);
             }
@@ -820,6 +864,7 @@ impl App {
                                         model: None,
                                         effort: None,
                                         summary: None,
+                                        model_provider: None,
                                     },
                                 ));
                                 self.app_event_tx
@@ -1022,6 +1067,26 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::OpenExecSessionActions(session) => {
+                self.chat_widget.open_exec_session_actions(session);
+            }
+            AppEvent::OpenExecSessionInput { process_id } => {
+                self.chat_widget.show_exec_session_input(process_id);
+            }
+            AppEvent::ShowExecSessionOutput(ev) => {
+                let _ = tui.enter_alt_screen();
+                let pager_lines: Vec<ratatui::text::Line<'static>> = if ev.output.trim().is_empty()
+                {
+                    vec!["No output yet.".italic().into()]
+                } else {
+                    ev.output.lines().map(ansi_escape_line).collect()
+                };
+                self.overlay = Some(Overlay::new_static_with_lines(
+                    pager_lines,
+                    "O U T P U T".to_string(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -1379,7 +1444,7 @@ mod tests {
                 model: "gpt-test".to_string(),
                 model_provider_id: "test-provider".to_string(),
                 approval_policy: AskForApproval::Never,
-                sandbox_policy: SandboxPolicy::ReadOnly,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 cwd: PathBuf::from("/home/user/project"),
                 reasoning_effort: None,
                 history_log_id: 0,
@@ -1435,7 +1500,7 @@ mod tests {
             model: "gpt-test".to_string(),
             model_provider_id: "test-provider".to_string(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             cwd: PathBuf::from("/home/user/project"),
             reasoning_effort: None,
             history_log_id: 0,
@@ -3,6 +3,8 @@ use std::path::PathBuf;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::ExecSessionInfo;
+use codex_core::protocol::ExecSessionOutputResponseEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 use codex_protocol::openai_models::ModelPreset;
@@ -71,6 +73,25 @@ pub(crate) enum AppEvent {
         effort: Option<ReasoningEffort>,
     },
 
+    /// Switch the session to a model served by a local OSS provider, pulling
+    /// or loading it on that server first.
+    SwitchToLocalModel {
+        model_provider: String,
+        model: String,
+        effort: Option<ReasoningEffort>,
+    },
+
+    /// Status line from preparing a local model (download progress).
+    LocalModelProgress(String),
+
+    /// Preparing a local model finished; on success the session switches to it.
+    LocalModelReady {
+        model_provider: String,
+        model: String,
+        effort: Option<ReasoningEffort>,
+        result: Result<(), String>,
+    },
+
     /// Open the reasoning selection popup after picking a model.
     OpenReasoningPopup {
         model: ModelPreset,
@@ -164,6 +185,17 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
+    /// Open the actions for a background terminal picked in `/ps`.
+    OpenExecSessionActions(ExecSessionInfo),
+
+    /// Open the prompt that sends a line of input to a background terminal.
+    OpenExecSessionInput {
+        process_id: String,
+    },
+
+    /// Show the output of a background terminal in the pager.
+    ShowExecSessionOutput(ExecSessionOutputResponseEvent),
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
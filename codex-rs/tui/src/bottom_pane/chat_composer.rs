@@ -43,6 +43,7 @@ use crate::render::RectExt;
 use crate::render::renderable::Renderable;
 use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
+use crate::slash_command::parse_finding_numbers;
 use crate::style::user_message_style;
 use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
@@ -1123,6 +1124,18 @@ impl ChatComposer {
                         }
                     }
 
+                    if cmd == SlashCommand::Fix {
+                        if parse_finding_numbers(&args).is_some() {
+                            return (InputResult::CommandWithArgs { cmd, args }, true);
+                        }
+                        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                            history_cell::new_error_event(
+                                "Usage: /fix [finding numbers, e.g. 1 3]".to_string(),
+                            ),
+                        )));
+                        return (InputResult::None, true);
+                    }
+
                     self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
                         history_cell::new_error_event(format!("'/{name}' does not support args.")),
                     )));
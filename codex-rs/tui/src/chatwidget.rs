@@ -33,8 +33,11 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
+use codex_core::protocol::ExecSessionInfo;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListExecSessionsResponseEvent;
+use codex_core::protocol::ListUndoSnapshotsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
@@ -43,7 +46,10 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ProviderFallbackEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewFixStartedEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SkillLoadOutcomeInfo;
@@ -53,6 +59,7 @@ use codex_core::protocol::TerminalInteractionEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnCostEvent;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
@@ -100,6 +107,7 @@ use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::strip_bash_lc_and_escape;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
@@ -114,7 +122,10 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
+use crate::slash_command::parse_finding_numbers;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::status::format_tokens_compact;
+use crate::status_indicator_widget::fmt_elapsed_compact;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 mod interrupts;
@@ -292,6 +303,8 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    /// Cost ledger entries of the session's finished turns, for `/status`.
+    turn_costs: Vec<TurnCostEvent>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     plan_type: Option<PlanType>,
     rate_limit_warnings: RateLimitWarningState,
@@ -331,6 +344,8 @@ pub(crate) struct ChatWidget {
     is_review_mode: bool,
     // Snapshot of token usage to restore after review mode exits.
     pre_review_token_info: Option<Option<TokenUsageInfo>>,
+    // Findings of the last completed review, keyed by its turn id, for /fix.
+    last_review_findings: Option<(String, Vec<ReviewFinding>)>,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
 
@@ -1034,7 +1049,9 @@ impl ChatWidget {
     }
 
     fn on_undo_completed(&mut self, event: UndoCompletedEvent) {
-        let UndoCompletedEvent { success, message } = event;
+        let UndoCompletedEvent {
+            success, message, ..
+        } = event;
         self.bottom_pane.hide_status_indicator();
         let message = message.unwrap_or_else(|| {
             if success {
@@ -1050,6 +1067,14 @@ impl ChatWidget {
         }
     }
 
+    fn on_provider_fallback(&mut self, ev: ProviderFallbackEvent) {
+        self.session_header.set_model(&ev.to_model);
+        self.on_warning(format!(
+            "Switched from {} ({}) to {} ({}) after a {} failure: {}",
+            ev.from_provider, ev.from_model, ev.to_provider, ev.to_model, ev.reason, ev.message
+        ));
+    }
+
     fn on_stream_error(&mut self, message: String) {
         if self.retry_status_header.is_none() {
             self.retry_status_header = Some(self.current_status_header.clone());
@@ -1405,6 +1430,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            turn_costs: Vec::new(),
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -1430,6 +1456,7 @@ impl ChatWidget {
             pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
+            last_review_findings: None,
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -1493,6 +1520,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            turn_costs: Vec::new(),
             rate_limit_snapshot: None,
             plan_type: None,
             rate_limit_warnings: RateLimitWarningState::default(),
@@ -1518,6 +1546,7 @@ impl ChatWidget {
             pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
+            last_review_findings: None,
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -1709,7 +1738,18 @@ impl ChatWidget {
                 self.request_exit();
             }
             SlashCommand::Undo => {
-                self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::ListUndoSnapshots));
+            }
+            SlashCommand::Redo => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Redo));
+            }
+            SlashCommand::Ps => {
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::ListExecSessions));
+            }
+            SlashCommand::Fix => {
+                self.open_fix_findings_popup();
             }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
@@ -1773,6 +1813,7 @@ impl ChatWidget {
                                 PathBuf::from("/tmp/test.txt"),
                                 FileChange::Add {
                                     content: "test".to_string(),
+                                    mode: None,
                                 },
                             ),
                             (
@@ -1780,6 +1821,8 @@ impl ChatWidget {
                                 FileChange::Update {
                                     unified_diff: "+test\n-test2".to_string(),
                                     move_path: None,
+                                    fuzz_score: None,
+                                    mode: None,
                                 },
                             ),
                         ]),
@@ -1821,6 +1864,12 @@ impl ChatWidget {
                     }
                 }
             }
+            SlashCommand::Fix => match parse_finding_numbers(&args) {
+                Some(findings) => self.fix_review_findings(findings),
+                None => {
+                    self.add_error_message("Usage: /fix [finding numbers, e.g. 1 3]".to_string());
+                }
+            },
             _ => {
                 self.add_to_history(history_cell::new_error_event(format!(
                     "'/{cmd}' does not support args.",
@@ -2020,6 +2069,7 @@ impl ChatWidget {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
+            EventMsg::TurnCost(ev) => self.turn_costs.push(ev),
             EventMsg::Warning(WarningEvent { message }) => {
                 // This can occur as the response to a worktree-change preflight.
                 // In that case, we want to show the warning and then proceed with
@@ -2076,6 +2126,11 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListUndoSnapshotsResponse(ev) => self.on_list_undo_snapshots(ev),
+            EventMsg::ListExecSessionsResponse(ev) => self.on_list_exec_sessions(ev),
+            EventMsg::ExecSessionOutputResponse(ev) => {
+                self.app_event_tx.send(AppEvent::ShowExecSessionOutput(ev))
+            }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
@@ -2087,6 +2142,7 @@ impl ChatWidget {
             EventMsg::StreamError(StreamErrorEvent { message, .. }) => {
                 self.on_stream_error(message)
             }
+            EventMsg::ProviderFallback(ev) => self.on_provider_fallback(ev),
             EventMsg::UserMessage(ev) => {
                 if from_replay {
                     self.on_user_message_event(ev);
@@ -2095,7 +2151,8 @@ impl ChatWidget {
             EventMsg::EnteredReviewMode(review_request) => {
                 self.on_entered_review_mode(review_request)
             }
-            EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
+            EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(id, review),
+            EventMsg::ReviewFixStarted(ev) => self.on_review_fix_started(ev),
             EventMsg::ContextCompacted(_) => self.on_context_compacted(),
             EventMsg::ContextCompactionPreview(ev) => self.on_context_compaction_preview(ev),
             EventMsg::RawResponseItem(_)
@@ -2107,6 +2164,19 @@ impl ChatWidget {
         }
     }
 
+    fn on_review_fix_started(&mut self, ev: ReviewFixStartedEvent) {
+        let numbers: Vec<&str> = ev
+            .finding_ids
+            .iter()
+            .filter_map(|id| id.rsplit_once('#').map(|(_, number)| number))
+            .collect();
+        let message = match numbers.as_slice() {
+            [number] => format!("Fixing review finding {number}"),
+            _ => format!("Fixing review findings {}", numbers.join(", ")),
+        };
+        self.add_info_message(message, None);
+    }
+
     fn on_entered_review_mode(&mut self, review: ReviewRequest) {
         // Enter review mode and emit a concise banner
         if self.pre_review_token_info.is_none() {
@@ -2121,9 +2191,13 @@ impl ChatWidget {
         self.request_redraw();
     }
 
-    fn on_exited_review_mode(&mut self, review: ExitedReviewModeEvent) {
+    fn on_exited_review_mode(&mut self, review_id: Option<String>, review: ExitedReviewModeEvent) {
         // Leave review mode; if output is present, flush pending stream + show results.
         if let Some(output) = review.review_output {
+            // Replayed reviews are not known to the session, so only live ones can be fixed.
+            self.last_review_findings = review_id
+                .filter(|_| !output.findings.is_empty())
+                .map(|review_id| (review_id, output.findings.clone()));
             self.flush_answer_stream_with_separator();
             self.flush_interrupt_queue();
             self.flush_active_cell();
@@ -2151,6 +2225,12 @@ impl ChatWidget {
                 let body_cell = AgentMessageCell::new(message_lines, true);
                 self.app_event_tx
                     .send(AppEvent::InsertHistoryCell(Box::new(body_cell)));
+                if self.last_review_findings.is_some() {
+                    self.add_to_history(history_cell::new_info_event(
+                        "Run /fix to fix these findings".to_string(),
+                        Some("or /fix 1 3 to pick them by number".to_string()),
+                    ));
+                }
             }
         }
 
@@ -2253,6 +2333,7 @@ impl ChatWidget {
             self.plan_type,
             Local::now(),
             self.model_family.get_model_slug(),
+            &self.turn_costs,
         ));
     }
     fn stop_rate_limit_poller(&mut self) {
@@ -2335,6 +2416,7 @@ impl ChatWidget {
                 model: Some(switch_model.clone()),
                 effort: Some(Some(default_effort)),
                 summary: None,
+                model_provider: None,
             }));
             tx.send(AppEvent::UpdateModel(switch_model.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(Some(default_effort)));
@@ -2436,6 +2518,7 @@ impl ChatWidget {
                 let actions = Self::model_selection_actions(
                     model.clone(),
                     Some(preset.default_reasoning_effort),
+                    self.config.model_provider_id.clone(),
                 );
                 SelectionItem {
                     name: preset.display_name.clone(),
@@ -2506,8 +2589,7 @@ impl ChatWidget {
         let current_model = self.model_family.get_model_slug().to_string();
         let mut items: Vec<SelectionItem> = Vec::new();
         for preset in presets.into_iter() {
-            let description =
-                (!preset.description.is_empty()).then_some(preset.description.to_string());
+            let description = Self::model_description(&preset);
             let is_current = preset.model == current_model;
             let single_supported_effort = preset.supported_reasoning_efforts.len() == 1;
             let preset_for_action = preset.clone();
@@ -2540,9 +2622,28 @@ impl ChatWidget {
         });
     }
 
+    /// Picker description of a model, followed by its context window when
+    /// the provider reports one.
+    fn model_description(preset: &ModelPreset) -> Option<String> {
+        let context_window = preset
+            .context_window
+            .map(|tokens| format!("{} context window", format_tokens_compact(tokens)));
+        let parts: Vec<String> = [
+            (!preset.description.is_empty()).then(|| preset.description.clone()),
+            context_window,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+
+    /// Actions that switch to a built-in model. They also select the
+    /// configured provider, so picking one after a local model switches back.
     fn model_selection_actions(
         model_for_action: String,
         effort_for_action: Option<ReasoningEffortConfig>,
+        model_provider_for_action: String,
     ) -> Vec<SelectionAction> {
         vec![Box::new(move |tx| {
             let effort_label = effort_for_action
@@ -2555,6 +2656,7 @@ impl ChatWidget {
                 model: Some(model_for_action.clone()),
                 effort: Some(effort_for_action),
                 summary: None,
+                model_provider: Some(model_provider_for_action.clone()),
             }));
             tx.send(AppEvent::UpdateModel(model_for_action.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(effort_for_action));
@@ -2573,6 +2675,16 @@ impl ChatWidget {
     /// Open a popup to choose the reasoning effort (stage 2) for the given model.
     pub(crate) fn open_reasoning_popup(&mut self, preset: ModelPreset) {
         let default_effort: ReasoningEffortConfig = preset.default_reasoning_effort;
+        if let Some(model_provider) = preset.model_provider {
+            // Local servers do not advertise reasoning levels; make sure the
+            // model is available there, then switch to it.
+            self.app_event_tx.send(AppEvent::SwitchToLocalModel {
+                model_provider,
+                model: preset.model,
+                effort: Some(default_effort),
+            });
+            return;
+        }
         let supported = preset.supported_reasoning_efforts;
 
         let warn_effort = if supported
@@ -2679,7 +2791,11 @@ impl ChatWidget {
             };
 
             let model_for_action = model_slug.clone();
-            let actions = Self::model_selection_actions(model_for_action, choice.stored);
+            let actions = Self::model_selection_actions(
+                model_for_action,
+                choice.stored,
+                self.config.model_provider_id.clone(),
+            );
 
             items.push(SelectionItem {
                 name: effort_label,
@@ -2726,6 +2842,7 @@ impl ChatWidget {
                 model: Some(model.clone()),
                 effort: Some(effort),
                 summary: None,
+                model_provider: Some(self.config.model_provider_id.clone()),
             }));
         self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
         self.app_event_tx
@@ -2743,12 +2860,57 @@ impl ChatWidget {
         );
     }
 
+    pub(crate) fn on_local_model_progress(&mut self, line: String) {
+        self.bottom_pane.ensure_status_indicator();
+        self.bottom_pane.set_interrupt_hint_visible(false);
+        self.set_status_header(line);
+    }
+
+    /// Switch the session to a local model once its server has it ready. The
+    /// choice is not written to config.toml, since it also changes the
+    /// provider.
+    pub(crate) fn on_local_model_ready(
+        &mut self,
+        model_provider: String,
+        model: String,
+        effort: Option<ReasoningEffortConfig>,
+        result: Result<(), String>,
+    ) {
+        if !self.bottom_pane.is_task_running() {
+            self.bottom_pane.hide_status_indicator();
+        }
+        if let Err(err) = result {
+            self.add_error_message(format!("Could not switch to {model}: {err}"));
+            return;
+        }
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model: Some(model.clone()),
+                effort: Some(effort),
+                summary: None,
+                model_provider: Some(model_provider.clone()),
+            }));
+        self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
+        self.app_event_tx
+            .send(AppEvent::UpdateReasoningEffort(effort));
+        self.add_info_message(
+            format!("Model changed to {model} ({model_provider}) for this session"),
+            None,
+        );
+    }
+
     /// Open a popup to choose the approvals mode (ask for approval policy + sandbox policy).
     pub(crate) fn open_approvals_popup(&mut self) {
         let current_approval = self.config.approval_policy;
         let current_sandbox = self.config.sandbox_policy.clone();
         let mut items: Vec<SelectionItem> = Vec::new();
-        let presets: Vec<ApprovalPreset> = builtin_approval_presets();
+        let presets: Vec<ApprovalPreset> = builtin_approval_presets(
+            &self.config.sandbox_read_deny,
+            &self.config.sandbox_network_allowlist,
+        );
         for preset in presets.into_iter() {
             let is_current =
                 Self::preset_matches_current(current_approval, &current_sandbox, &preset);
@@ -2833,6 +2995,7 @@ impl ChatWidget {
                 model: None,
                 effort: None,
                 summary: None,
+                model_provider: None,
             }));
             tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
             tx.send(AppEvent::UpdateSandboxPolicy(sandbox_clone));
@@ -3121,9 +3284,12 @@ impl ChatWidget {
     pub(crate) fn maybe_prompt_windows_sandbox_enable(&mut self) {
         if self.config.forced_auto_mode_downgraded_on_windows
             && codex_core::get_platform_sandbox().is_none()
-            && let Some(preset) = builtin_approval_presets()
-                .into_iter()
-                .find(|preset| preset.id == "auto")
+            && let Some(preset) = builtin_approval_presets(
+                &self.config.sandbox_read_deny,
+                &self.config.sandbox_network_allowlist,
+            )
+            .into_iter()
+            .find(|preset| preset.id == "auto")
         {
             self.open_windows_sandbox_enable_prompt(preset);
         }
@@ -3290,6 +3456,268 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    /// Shows the `/undo` picker: one entry per snapshot, newest first, with
+    /// a redo entry on top when the last undo can still be reapplied.
+    fn on_list_undo_snapshots(&mut self, ev: ListUndoSnapshotsResponseEvent) {
+        let ListUndoSnapshotsResponseEvent {
+            snapshots,
+            redo_available,
+        } = ev;
+        if snapshots.is_empty() && !redo_available {
+            self.add_error_message("No ghost snapshot available to undo.".to_string());
+            return;
+        }
+
+        let mut items: Vec<SelectionItem> = Vec::with_capacity(snapshots.len() + 1);
+        if redo_available {
+            items.push(SelectionItem {
+                name: "Redo last undo".to_string(),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::Redo));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        let total = snapshots.len();
+        for (idx, snapshot) in snapshots.into_iter().enumerate().rev() {
+            let undone = total - idx;
+            let name = match &snapshot.prompt {
+                Some(prompt) => {
+                    let first_line = prompt.lines().next().unwrap_or_default();
+                    format!("Turn {} · {}", snapshot.turn, truncate_text(first_line, 60))
+                }
+                None => format!("Turn {}", snapshot.turn),
+            };
+            let description = if undone == 1 {
+                "undo the last turn".to_string()
+            } else {
+                format!("undo the last {undone} turns")
+            };
+            let search_value = format!("{name} {}", snapshot.id);
+            let snapshot_id = snapshot.id;
+            items.push(SelectionItem {
+                name,
+                description: Some(description),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::UndoTo {
+                        snapshot_id: snapshot_id.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                search_value: Some(search_value),
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Undo to the start of a turn".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search turns".to_string()),
+            ..Default::default()
+        });
+    }
+
+    /// Shows the `/ps` picker: one entry per running background terminal,
+    /// oldest first.
+    fn on_list_exec_sessions(&mut self, ev: ListExecSessionsResponseEvent) {
+        if ev.sessions.is_empty() {
+            self.add_info_message("No background terminals running.".to_string(), None);
+            return;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let items: Vec<SelectionItem> = ev
+            .sessions
+            .into_iter()
+            .map(|session| {
+                let command = strip_bash_lc_and_escape(&session.command);
+                let name = format!(
+                    "{} · {}",
+                    exec_session_label(&session),
+                    truncate_text(&command, 60)
+                );
+                let mut details = Vec::new();
+                if let Some(pid) = session.pid {
+                    details.push(format!("pid {pid}"));
+                }
+                let elapsed = u64::try_from(now - session.started_at).unwrap_or_default();
+                details.push(format!("running {}", fmt_elapsed_compact(elapsed)));
+                if let Some(last_output) = &session.last_output {
+                    details.push(truncate_text(last_output, 60));
+                }
+                let search_value = format!("{name} {}", session.process_id);
+                SelectionItem {
+                    name,
+                    description: Some(details.join(" · ")),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenExecSessionActions(session.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    search_value: Some(search_value),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Background terminals".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search terminals".to_string()),
+            ..Default::default()
+        });
+    }
+
+    /// Actions for one background terminal picked in `/ps`.
+    pub(crate) fn open_exec_session_actions(&mut self, session: ExecSessionInfo) {
+        let process_id = session.process_id.clone();
+        let view_id = process_id.clone();
+        let input_id = process_id.clone();
+        let items = vec![
+            SelectionItem {
+                name: "View output".to_string(),
+                description: Some("open everything it printed in the pager".to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::GetExecSessionOutput {
+                        process_id: view_id.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Send input".to_string(),
+                description: Some("type a line for its stdin".to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::OpenExecSessionInput {
+                        process_id: input_id.clone(),
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Kill".to_string(),
+                description: Some("terminate the command".to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::KillExecSession {
+                        process_id: process_id.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!(
+                "{} · {}",
+                exec_session_label(&session),
+                strip_bash_lc_and_escape(&session.command)
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_exec_session_input(&mut self, process_id: String) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            format!("Send input to terminal {process_id}"),
+            "Type a line and press Enter".to_string(),
+            None,
+            Box::new(move |input: String| {
+                tx.send(AppEvent::CodexOp(Op::WriteExecSessionStdin {
+                    process_id: process_id.clone(),
+                    input: format!("{input}\n"),
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    /// Shows the `/fix` picker: all findings of the last review, plus an
+    /// entry that fixes every one of them.
+    fn open_fix_findings_popup(&mut self) {
+        let Some((review_id, findings)) = self.last_review_findings.clone() else {
+            self.add_error_message("No review findings to fix. Run /review first.".to_string());
+            return;
+        };
+
+        let mut items: Vec<SelectionItem> = Vec::with_capacity(findings.len() + 1);
+        if findings.len() > 1 {
+            let review_id = review_id.clone();
+            items.push(SelectionItem {
+                name: "Fix all findings".to_string(),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::FixReviewFindings {
+                        review_id: Some(review_id.clone()),
+                        findings: Vec::new(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+        for (idx, finding) in findings.iter().enumerate() {
+            let number = idx + 1;
+            let location = &finding.code_location;
+            let description = format!(
+                "{}:{}",
+                display_path_for(&location.absolute_file_path, &self.config.cwd),
+                location.line_range.start
+            );
+            let review_id = review_id.clone();
+            items.push(SelectionItem {
+                name: format!("{number}. {}", finding.title),
+                description: Some(description),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::FixReviewFindings {
+                        review_id: Some(review_id.clone()),
+                        findings: vec![number],
+                    }));
+                })],
+                dismiss_on_select: true,
+                search_value: Some(format!("{number} {}", finding.title)),
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Fix review findings".to_string()),
+            subtitle: Some("Starts a turn that fixes the selected findings.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search findings".to_string()),
+            ..Default::default()
+        });
+    }
+
+    fn fix_review_findings(&mut self, findings: Vec<usize>) {
+        let Some((review_id, all)) = &self.last_review_findings else {
+            self.add_error_message("No review findings to fix. Run /review first.".to_string());
+            return;
+        };
+        if let Some(missing) = findings.iter().find(|number| **number > all.len()) {
+            self.add_error_message(format!(
+                "The last review has {} finding(s); there is no finding {missing}.",
+                all.len()
+            ));
+            return;
+        }
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::FixReviewFindings {
+                review_id: Some(review_id.clone()),
+                findings,
+            }));
+    }
+
     pub(crate) fn open_review_popup(&mut self) {
         let mut items: Vec<SelectionItem> = Vec::new();
 
@@ -3313,6 +3741,7 @@ impl ChatWidget {
                     review_request: ReviewRequest {
                         target: ReviewTarget::UncommittedChanges,
                         user_facing_hint: None,
+                        paths: Vec::new(),
                     },
                 }));
             })],
@@ -3368,6 +3797,7 @@ impl ChatWidget {
                                 branch: branch.clone(),
                             },
                             user_facing_hint: None,
+                            paths: Vec::new(),
                         },
                     }));
                 })],
@@ -3406,6 +3836,7 @@ impl ChatWidget {
                                 title: Some(subject.clone()),
                             },
                             user_facing_hint: None,
+                            paths: Vec::new(),
                         },
                     }));
                 })],
@@ -3442,6 +3873,7 @@ impl ChatWidget {
                             instructions: trimmed,
                         },
                         user_facing_hint: None,
+                        paths: Vec::new(),
                     },
                 }));
             }),
@@ -3655,6 +4087,7 @@ pub(crate) fn show_review_commit_picker_with_entries(
                             title: Some(subject.clone()),
                         },
                         user_facing_hint: None,
+                        paths: Vec::new(),
                     },
                 }));
             })],
@@ -3702,5 +4135,13 @@ fn find_skill_mentions(text: &str, skills: &[SkillMetadata]) -> Vec<SkillMetadat
     matches
 }
 
+/// The name the model gave a background terminal, or its session id.
+fn exec_session_label(session: &ExecSessionInfo) -> String {
+    session
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("#{}", session.process_id))
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
@@ -23,6 +23,7 @@ use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
@@ -46,6 +47,11 @@ pub(crate) struct FileSearchManager {
     state: Arc<Mutex<SearchState>>,
 
     search_dir: PathBuf,
+    /// `CODEX_HOME` when searches should use the persistent file index.
+    index_home: Option<PathBuf>,
+    /// The shared index, opened by the first search. Holding it keeps the
+    /// index loaded for as long as this session is open.
+    index: Arc<OnceLock<Option<Arc<file_search::FileSearchIndex>>>>,
     app_tx: AppEventSender,
 }
 
@@ -74,10 +80,18 @@ impl FileSearchManager {
                 active_search: None,
             })),
             search_dir,
+            index_home: None,
+            index: Arc::new(OnceLock::new()),
             app_tx: tx,
         }
     }
 
+    /// Serves searches from the persistent index stored under `index_home`.
+    pub fn with_index_home(mut self, index_home: Option<PathBuf>) -> Self {
+        self.index_home = index_home;
+        self
+    }
+
     /// Call whenever the user edits the `@` token.
     pub fn on_user_query(&self, query: String) {
         {
@@ -116,6 +130,8 @@ impl FileSearchManager {
         // debounce timer.
         let state = self.state.clone();
         let search_dir = self.search_dir.clone();
+        let index_home = self.index_home.clone();
+        let index = self.index.clone();
         let tx_clone = self.app_tx.clone();
         thread::spawn(move || {
             // Always do a minimum debounce, but then poll until the
@@ -148,6 +164,8 @@ impl FileSearchManager {
             FileSearchManager::spawn_file_search(
                 query,
                 search_dir,
+                index_home,
+                index,
                 tx_clone,
                 cancellation_token,
                 state,
@@ -158,24 +176,46 @@ impl FileSearchManager {
     fn spawn_file_search(
         query: String,
         search_dir: PathBuf,
+        index_home: Option<PathBuf>,
+        index: Arc<OnceLock<Option<Arc<file_search::FileSearchIndex>>>>,
         tx: AppEventSender,
         cancellation_token: Arc<AtomicBool>,
         search_state: Arc<Mutex<SearchState>>,
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
-            let matches = file_search::run(
-                &query,
-                MAX_FILE_SEARCH_RESULTS,
-                &search_dir,
-                Vec::new(),
-                NUM_FILE_SEARCH_THREADS,
-                cancellation_token.clone(),
-                compute_indices,
-                true,
-            )
-            .map(|res| res.matches)
-            .unwrap_or_default();
+            // Fall back to walking the tree when the index cannot be opened.
+            let index = index
+                .get_or_init(|| {
+                    let home = index_home?;
+                    file_search::shared_index(&home, &search_dir)
+                        .inspect_err(|err| {
+                            tracing::warn!("file search index unavailable: {err:#}");
+                        })
+                        .ok()
+                })
+                .clone();
+            let results = match index {
+                Some(index) => index.search(
+                    &query,
+                    MAX_FILE_SEARCH_RESULTS,
+                    &search_dir,
+                    Vec::new(),
+                    cancellation_token.clone(),
+                    compute_indices,
+                ),
+                None => file_search::run(
+                    &query,
+                    MAX_FILE_SEARCH_RESULTS,
+                    &search_dir,
+                    Vec::new(),
+                    NUM_FILE_SEARCH_THREADS,
+                    cancellation_token.clone(),
+                    compute_indices,
+                    true,
+                ),
+            };
+            let matches = results.map(|res| res.matches).unwrap_or_default();
 
             let is_cancelled = cancellation_token.load(Ordering::Relaxed);
             if !is_cancelled {
@@ -19,6 +19,7 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::config::types::ModelCassette;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::protocol::AskForApproval;
@@ -219,6 +220,11 @@ pub async fn run_main(
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: None,
         additional_writable_roots: additional_dirs,
+        model_cassette: cli
+            .record
+            .clone()
+            .map(ModelCassette::Record)
+            .or(cli.replay.clone().map(ModelCassette::Replay)),
     };
 
     let config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
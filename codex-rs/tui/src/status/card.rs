@@ -10,6 +10,7 @@ use codex_core::config::Config;
 use codex_core::openai_models::model_family::ModelFamily;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TurnCostEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::account::PlanType;
 use ratatui::prelude::*;
@@ -53,6 +54,14 @@ pub(crate) struct StatusTokenUsageData {
     context_window: Option<StatusContextWindowData>,
 }
 
+#[derive(Debug, Clone)]
+struct StatusCostData {
+    total_usd: f64,
+    turns: usize,
+    last_turn_usd: Option<f64>,
+    compacted_turns: usize,
+}
+
 #[derive(Debug)]
 struct StatusHistoryCell {
     model_name: String,
@@ -64,6 +73,7 @@ struct StatusHistoryCell {
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
     token_usage: StatusTokenUsageData,
+    cost: Option<StatusCostData>,
     rate_limits: StatusRateLimitData,
 }
 
@@ -79,6 +89,7 @@ pub(crate) fn new_status_output(
     plan_type: Option<PlanType>,
     now: DateTime<Local>,
     model_name: &str,
+    turn_costs: &[TurnCostEvent],
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
     let card = StatusHistoryCell::new(
@@ -92,6 +103,7 @@ pub(crate) fn new_status_output(
         plan_type,
         now,
         model_name,
+        turn_costs,
     );
 
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(card)])
@@ -110,6 +122,7 @@ impl StatusHistoryCell {
         plan_type: Option<PlanType>,
         now: DateTime<Local>,
         model_name: &str,
+        turn_costs: &[TurnCostEvent],
     ) -> Self {
         let config_entries = create_config_summary_entries(config, model_name);
         let (model_name, model_details) = compose_model_display(model_name, &config_entries);
@@ -140,6 +153,7 @@ impl StatusHistoryCell {
             output: total_usage.output_tokens,
             context_window,
         };
+        let cost = compose_cost_data(turn_costs);
         let rate_limits = compose_rate_limit_data(rate_limits, now);
 
         Self {
@@ -152,10 +166,29 @@ impl StatusHistoryCell {
             account,
             session_id,
             token_usage,
+            cost,
             rate_limits,
         }
     }
 
+    fn cost_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cost = self.cost.as_ref()?;
+        let mut details = vec![format!("{} turns", cost.turns)];
+        if let Some(last) = cost.last_turn_usd {
+            details.push(format!("last ${last:.2}"));
+        }
+        if cost.compacted_turns > 0 {
+            details.push(format!("{} compacted", cost.compacted_turns));
+        }
+
+        Some(vec![
+            Span::from(format!("${:.2}", cost.total_usd)),
+            Span::from(" (").dim(),
+            Span::from(details.join(", ")).dim(),
+            Span::from(")").dim(),
+        ])
+    }
+
     fn token_usage_spans(&self) -> Vec<Span<'static>> {
         let total_fmt = format_tokens_compact(self.token_usage.total);
         let input_fmt = format_tokens_compact(self.token_usage.input);
@@ -337,6 +370,9 @@ impl HistoryCell for StatusHistoryCell {
             push_label(&mut labels, &mut seen, "Session");
         }
         push_label(&mut labels, &mut seen, "Token usage");
+        if self.cost.is_some() {
+            push_label(&mut labels, &mut seen, "Cost");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -391,6 +427,10 @@ impl HistoryCell for StatusHistoryCell {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
         }
 
+        if let Some(spans) = self.cost_spans() {
+            lines.push(formatter.line("Cost", spans));
+        }
+
         if let Some(spans) = self.context_window_spans() {
             lines.push(formatter.line("Context window", spans));
         }
@@ -407,3 +447,18 @@ impl HistoryCell for StatusHistoryCell {
         with_border_with_inner_width(truncated_lines, inner_width)
     }
 }
+
+/// Session cost from the turns priced by `model_prices`; `None` when no turn
+/// has a price.
+fn compose_cost_data(turn_costs: &[TurnCostEvent]) -> Option<StatusCostData> {
+    let priced: Vec<f64> = turn_costs.iter().filter_map(|turn| turn.cost_usd).collect();
+    if priced.is_empty() {
+        return None;
+    }
+    Some(StatusCostData {
+        total_usd: priced.iter().sum(),
+        turns: turn_costs.len(),
+        last_turn_usd: turn_costs.last().and_then(|turn| turn.cost_usd),
+        compacted_turns: turn_costs.iter().filter(|turn| turn.compacted).count(),
+    })
+}
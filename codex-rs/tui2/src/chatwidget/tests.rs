@@ -25,8 +25,11 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExecPolicyAmendment;
+use codex_core::protocol::ExecSessionInfo;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::ListExecSessionsResponseEvent;
+use codex_core::protocol::ListUndoSnapshotsResponseEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::Op;
@@ -46,6 +49,7 @@ use codex_core::protocol::TokenCountEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::UndoCompletedEvent;
+use codex_core::protocol::UndoSnapshot;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
@@ -111,7 +115,7 @@ fn resumed_initial_messages_render_history() {
         model: "test-model".to_string(),
         model_provider_id: "test-provider".to_string(),
         approval_policy: AskForApproval::Never,
-        sandbox_policy: SandboxPolicy::ReadOnly,
+        sandbox_policy: SandboxPolicy::new_read_only_policy(),
         cwd: PathBuf::from("/home/user/project"),
         reasoning_effort: Some(ReasoningEffortConfig::default()),
         history_log_id: 0,
@@ -168,6 +172,7 @@ fn entered_review_mode_uses_request_hint() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            paths: Vec::new(),
         }),
     });
 
@@ -187,6 +192,7 @@ fn entered_review_mode_defaults_to_current_changes_banner() {
         msg: EventMsg::EnteredReviewMode(ReviewRequest {
             target: ReviewTarget::UncommittedChanges,
             user_facing_hint: None,
+            paths: Vec::new(),
         }),
     });
 
@@ -222,6 +228,7 @@ fn exited_review_mode_emits_results_and_finishes() {
         id: "review-end".into(),
         msg: EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
             review_output: Some(review),
+            review_id: None,
         }),
     });
 
@@ -256,6 +263,7 @@ fn review_restores_context_window_indicator() {
                 branch: "feature".to_string(),
             },
             user_facing_hint: Some("feature branch".to_string()),
+            paths: Vec::new(),
         }),
     });
 
@@ -272,6 +280,7 @@ fn review_restores_context_window_indicator() {
         id: "review-end".into(),
         msg: EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
             review_output: None,
+            review_id: None,
         }),
     });
     let _ = drain_insert_history(&mut rx);
@@ -417,6 +426,7 @@ fn make_chatwidget_manual(
         session_header: SessionHeader::new(resolved_model.clone()),
         initial_user_message: None,
         token_info: None,
+        turn_costs: Vec::new(),
         rate_limit_snapshot: None,
         plan_type: None,
         rate_limit_warnings: RateLimitWarningState::default(),
@@ -441,6 +451,7 @@ fn make_chatwidget_manual(
         pending_notification: None,
         is_review_mode: false,
         pre_review_token_info: None,
+        last_review_findings: None,
         needs_final_message_separator: false,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
@@ -1338,11 +1349,158 @@ fn slash_undo_sends_op() {
     chat.dispatch_command(SlashCommand::Undo);
 
     match rx.try_recv() {
-        Ok(AppEvent::CodexOp(Op::Undo)) => {}
-        other => panic!("expected AppEvent::CodexOp(Op::Undo), got {other:?}"),
+        Ok(AppEvent::CodexOp(Op::ListUndoSnapshots)) => {}
+        other => panic!("expected AppEvent::CodexOp(Op::ListUndoSnapshots), got {other:?}"),
     }
 }
 
+#[test]
+fn undo_picker_undoes_to_selected_turn() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ListUndoSnapshotsResponse(ListUndoSnapshotsResponseEvent {
+            snapshots: vec![
+                UndoSnapshot {
+                    id: "first".to_string(),
+                    turn: 1,
+                    prompt: Some("add a readme".to_string()),
+                },
+                UndoSnapshot {
+                    id: "second".to_string(),
+                    turn: 2,
+                    prompt: Some("fix the build".to_string()),
+                },
+            ],
+            redo_available: true,
+        }),
+    });
+
+    // Entries are "Redo", then the newest turn first; pick turn 1.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut snapshot_id = None;
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::UndoTo { snapshot_id: id }) = ev {
+            snapshot_id = Some(id);
+            break;
+        }
+    }
+    assert_eq!(snapshot_id.as_deref(), Some("first"));
+}
+
+#[test]
+fn slash_ps_sends_op() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+
+    chat.dispatch_command(SlashCommand::Ps);
+
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ListExecSessions)) => {}
+        other => panic!("expected AppEvent::CodexOp(Op::ListExecSessions), got {other:?}"),
+    }
+}
+
+#[test]
+fn exec_sessions_picker_opens_actions_for_selected_session() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+
+    let session = |process_id: &str, name: Option<&str>| ExecSessionInfo {
+        process_id: process_id.to_string(),
+        name: name.map(str::to_string),
+        command: vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "npm run dev".to_string(),
+        ],
+        cwd: PathBuf::from("/repo"),
+        pid: Some(4242),
+        started_at: 0,
+        last_output: Some("ready on :3000".to_string()),
+    };
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::ListExecSessionsResponse(ListExecSessionsResponseEvent {
+            sessions: vec![session("1000", Some("dev-server")), session("1001", None)],
+        }),
+    });
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut selected = None;
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::OpenExecSessionActions(session) = ev {
+            selected = Some(session.process_id);
+            break;
+        }
+    }
+    assert_eq!(selected.as_deref(), Some("1001"));
+}
+
+fn review_finding(title: &str, path: &str) -> ReviewFinding {
+    ReviewFinding {
+        title: title.to_string(),
+        body: format!("details for {title}"),
+        confidence_score: 0.8,
+        priority: 1,
+        code_location: ReviewCodeLocation {
+            absolute_file_path: PathBuf::from(path),
+            line_range: ReviewLineRange { start: 4, end: 6 },
+        },
+    }
+}
+
+fn finish_review_with_findings(chat: &mut ChatWidget, findings: Vec<ReviewFinding>) {
+    chat.handle_codex_event(Event {
+        id: "review-1".into(),
+        msg: EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(ReviewOutputEvent {
+                findings,
+                ..Default::default()
+            }),
+        }),
+    });
+}
+
+#[test]
+fn fix_picker_fixes_selected_finding() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+    finish_review_with_findings(
+        &mut chat,
+        vec![
+            review_finding("Off by one", "src/a.rs"),
+            review_finding("Missing check", "src/b.rs"),
+        ],
+    );
+    let _ = drain_insert_history(&mut rx);
+
+    chat.dispatch_command(SlashCommand::Fix);
+
+    // Entries are "Fix all findings", then the findings in order; pick #2.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut op = None;
+    while let Ok(ev) = rx.try_recv() {
+        if let AppEvent::CodexOp(fix @ Op::FixReviewFindings { .. }) = ev {
+            op = Some(fix);
+            break;
+        }
+    }
+    assert_eq!(
+        op,
+        Some(Op::FixReviewFindings {
+            review_id: Some("review-1".to_string()),
+            findings: vec![2],
+        })
+    );
+}
+
 #[test]
 fn slash_rollout_displays_current_path() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
@@ -1399,6 +1557,7 @@ fn undo_success_events_render_info_messages() {
         msg: EventMsg::UndoCompleted(UndoCompletedEvent {
             success: true,
             message: None,
+            conflicts: Vec::new(),
         }),
     });
 
@@ -1434,6 +1593,7 @@ fn undo_failure_events_render_error_message() {
         msg: EventMsg::UndoCompleted(UndoCompletedEvent {
             success: false,
             message: Some("Failed to restore workspace state.".to_string()),
+            conflicts: Vec::new(),
         }),
     });
 
@@ -1553,6 +1713,7 @@ fn custom_prompt_submit_sends_review_op() {
                         instructions: "please audit dependencies".to_string(),
                     },
                     user_facing_hint: None,
+                    paths: Vec::new(),
                 }
             );
         }
@@ -1803,7 +1964,7 @@ fn approvals_selection_popup_snapshot() {
 
 #[test]
 fn preset_matching_ignores_extra_writable_roots() {
-    let preset = builtin_approval_presets()
+    let preset = builtin_approval_presets(&[], &[])
         .into_iter()
         .find(|p| p.id == "auto")
         .expect("auto preset exists");
@@ -1812,6 +1973,8 @@ fn preset_matching_ignores_extra_writable_roots() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     assert!(
@@ -1828,7 +1991,7 @@ fn preset_matching_ignores_extra_writable_roots() {
 fn full_access_confirmation_popup_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None);
 
-    let preset = builtin_approval_presets()
+    let preset = builtin_approval_presets(&[], &[])
         .into_iter()
         .find(|preset| preset.id == "full-access")
         .expect("full access preset");
@@ -1843,7 +2006,7 @@ fn full_access_confirmation_popup_snapshot() {
 fn windows_auto_mode_prompt_requests_enabling_sandbox_feature() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None);
 
-    let preset = builtin_approval_presets()
+    let preset = builtin_approval_presets(&[], &[])
         .into_iter()
         .find(|preset| preset.id == "auto")
         .expect("auto preset");
@@ -1945,6 +2108,8 @@ fn single_reasoning_option_skips_selection() {
         is_default: false,
         upgrade: None,
         show_in_picker: true,
+        context_window: None,
+        model_provider: None,
     };
     chat.open_reasoning_popup(preset);
 
@@ -1967,6 +2132,104 @@ fn single_reasoning_option_skips_selection() {
     );
 }
 
+#[test]
+fn local_model_is_prepared_before_switching() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+
+    let preset = ModelPreset {
+        id: "ollama/qwen3-coder:30b".to_string(),
+        model: "qwen3-coder:30b".to_string(),
+        display_name: "qwen3-coder:30b".to_string(),
+        description: "Local model served by Ollama".to_string(),
+        default_reasoning_effort: ReasoningEffortConfig::Medium,
+        supported_reasoning_efforts: vec![ReasoningEffortPreset {
+            effort: ReasoningEffortConfig::Medium,
+            description: "Default reasoning for this model".to_string(),
+        }],
+        is_default: false,
+        upgrade: None,
+        show_in_picker: true,
+        context_window: Some(262_144),
+        model_provider: Some("ollama".to_string()),
+    };
+    chat.open_reasoning_popup(preset);
+
+    let events: Vec<AppEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [AppEvent::SwitchToLocalModel { model_provider, model, effort: Some(ReasoningEffortConfig::Medium) }]
+                if model_provider == "ollama" && model == "qwen3-coder:30b"
+        ),
+        "expected only a request to prepare the local model; events: {events:?}"
+    );
+
+    chat.on_local_model_ready(
+        "ollama".to_string(),
+        "qwen3-coder:30b".to_string(),
+        Some(ReasoningEffortConfig::Medium),
+        Ok(()),
+    );
+
+    let events: Vec<AppEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+    assert!(
+        events.iter().any(|ev| matches!(
+            ev,
+            AppEvent::CodexOp(Op::OverrideTurnContext { model: Some(model), model_provider: Some(provider), .. })
+                if model == "qwen3-coder:30b" && provider == "ollama"
+        )),
+        "expected the session to switch model and provider; events: {events:?}"
+    );
+    assert!(
+        !events
+            .iter()
+            .any(|ev| matches!(ev, AppEvent::PersistModelSelection { .. })),
+        "local model selection should not be persisted; events: {events:?}"
+    );
+}
+
+#[test]
+fn built_in_model_switches_back_from_local_provider() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None);
+    let configured_provider = chat.config.model_provider_id.clone();
+
+    chat.on_local_model_ready(
+        "ollama".to_string(),
+        "qwen3-coder:30b".to_string(),
+        Some(ReasoningEffortConfig::Medium),
+        Ok(()),
+    );
+    while rx.try_recv().is_ok() {}
+
+    let preset = ModelPreset {
+        id: "built-in-model".to_string(),
+        model: "built-in-model".to_string(),
+        display_name: "built-in-model".to_string(),
+        description: "".to_string(),
+        default_reasoning_effort: ReasoningEffortConfig::High,
+        supported_reasoning_efforts: vec![ReasoningEffortPreset {
+            effort: ReasoningEffortConfig::High,
+            description: "Greater reasoning depth for complex or ambiguous problems".to_string(),
+        }],
+        is_default: false,
+        upgrade: None,
+        show_in_picker: true,
+        context_window: None,
+        model_provider: None,
+    };
+    chat.open_reasoning_popup(preset);
+
+    let events: Vec<AppEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+    assert!(
+        events.iter().any(|ev| matches!(
+            ev,
+            AppEvent::CodexOp(Op::OverrideTurnContext { model: Some(model), model_provider: Some(provider), .. })
+                if model == "built-in-model" && *provider == configured_provider
+        )),
+        "expected the session to switch back to the configured provider; events: {events:?}"
+    );
+}
+
 #[test]
 fn feedback_selection_popup_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None);
@@ -2188,6 +2451,7 @@ fn approval_modal_patch_snapshot() {
         PathBuf::from("README.md"),
         FileChange::Add {
             content: "hello\nworld\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2468,6 +2732,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2509,6 +2774,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let begin = PatchApplyBeginEvent {
@@ -2535,6 +2801,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let end = PatchApplyEndEvent {
@@ -2565,6 +2832,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2584,6 +2852,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2614,6 +2883,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2637,6 +2907,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2667,6 +2938,7 @@ fn apply_patch_approval_sends_op_with_submission_id() {
         PathBuf::from("file.rs"),
         FileChange::Add {
             content: "fn main(){}\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2705,7 +2977,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -2746,7 +3021,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes2 = HashMap::new();
     changes2.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -2760,7 +3038,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut end_changes = HashMap::new();
     end_changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -2785,7 +3066,10 @@ fn apply_patch_untrusted_shows_approval_modal() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("a.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-1".into(),
@@ -2834,6 +3118,7 @@ fn apply_patch_request_shows_diff_summary() {
         FileChange::Add {
             // Two lines (no trailing empty line counted)
             content: "line one\nline two\n".into(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
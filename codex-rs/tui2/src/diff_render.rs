@@ -93,6 +93,8 @@ struct Row {
     move_path: Option<PathBuf>,
     added: usize,
     removed: usize,
+    fuzz_score: Option<f32>,
+    mode: Option<u32>,
     change: FileChange,
 }
 
@@ -100,7 +102,8 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
     let mut rows: Vec<Row> = Vec::new();
     for (path, change) in changes.iter() {
         let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
+            FileChange::Add { content, .. } => (content.lines().count(), 0),
+            FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => (0, 0),
             FileChange::Delete { content } => (0, content.lines().count()),
             FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
         };
@@ -111,11 +114,23 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
             } => Some(new.clone()),
             _ => None,
         };
+        let fuzz_score = match change {
+            FileChange::Update { fuzz_score, .. } => *fuzz_score,
+            _ => None,
+        };
+        let mode = match change {
+            FileChange::Add { mode, .. }
+            | FileChange::AddBinary { mode, .. }
+            | FileChange::Update { mode, .. } => *mode,
+            _ => None,
+        };
         rows.push(Row {
             path: path.clone(),
             move_path,
             added,
             removed,
+            fuzz_score,
+            mode,
             change: change.clone(),
         });
     }
@@ -133,6 +148,36 @@ fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static
     spans
 }
 
+/// Flags changes whose hunks were located by fuzzy matching so the reviewer
+/// knows to check where they landed.
+fn render_fuzz_note(fuzz_score: Option<f32>) -> Vec<RtSpan<'static>> {
+    match fuzz_score {
+        Some(score) => vec![
+            " · ".dim(),
+            "applied with fuzz".magenta(),
+            format!(" ({:.0}% match)", score * 100.0).dim(),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Binary files and symlinks have no lines to count, so describe them instead.
+fn render_row_summary(row: &Row) -> Vec<RtSpan<'static>> {
+    let mut spans = match &row.change {
+        FileChange::AddBinary { size, .. } => vec![format!("(binary, {size} bytes)").dim()],
+        FileChange::AddSymlink { target } => {
+            vec![format!("(symlink → {})", target.display()).dim()]
+        }
+        _ => render_line_count_summary(row.added, row.removed),
+    };
+    spans.extend(render_fuzz_note(row.fuzz_score));
+    if let Some(mode) = row.mode {
+        spans.push(" · ".dim());
+        spans.push(format!("mode {mode:o}").magenta());
+    }
+    spans
+}
+
 fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtLine<'static>> {
     let mut out: Vec<RtLine<'static>> = Vec::new();
 
@@ -153,7 +198,9 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
     let mut header_spans: Vec<RtSpan<'static>> = vec!["• ".dim()];
     if let [row] = &rows[..] {
         let verb = match &row.change {
-            FileChange::Add { .. } => "Added",
+            FileChange::Add { .. }
+            | FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. } => "Added",
             FileChange::Delete { .. } => "Deleted",
             _ => "Edited",
         };
@@ -161,7 +208,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
         header_spans.push(" ".into());
         header_spans.extend(render_path(row));
         header_spans.push(" ".into());
-        header_spans.extend(render_line_count_summary(row.added, row.removed));
+        header_spans.extend(render_row_summary(row));
     } else {
         header_spans.push("Edited".bold());
         header_spans.push(format!(" {file_count} {noun} ").into());
@@ -181,7 +228,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
             header.push("  └ ".dim());
             header.extend(render_path(&r));
             header.push(" ".into());
-            header.extend(render_line_count_summary(r.added, r.removed));
+            header.extend(render_row_summary(&r));
             out.push(RtLine::from(header));
         }
 
@@ -195,7 +242,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
 
 fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
     match change {
-        FileChange::Add { content } => {
+        FileChange::Add { content, .. } => {
             let line_number_width = line_number_width(content.lines().count());
             for (i, raw) in content.lines().enumerate() {
                 out.extend(push_wrapped_diff_line(
@@ -207,6 +254,8 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                 ));
             }
         }
+        // Nothing to show beyond the summary in the file header.
+        FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {}
         FileChange::Delete { content } => {
             let line_number_width = line_number_width(content.lines().count());
             for (i, raw) in content.lines().enumerate() {
@@ -484,6 +533,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -492,6 +543,83 @@ mod tests {
         snapshot_lines("apply_update_block", lines, 80, 12);
     }
 
+    #[test]
+    fn fuzzy_update_is_flagged_in_header() {
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        let patch = diffy::create_patch("a\nb\n", "a\nB\n").to_string();
+        changes.insert(
+            PathBuf::from("fuzzy.txt"),
+            FileChange::Update {
+                unified_diff: patch,
+                move_path: None,
+                fuzz_score: Some(0.87),
+                mode: None,
+            },
+        );
+
+        let lines = diff_summary_for_tests(&changes);
+        let header: String = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+
+        assert_eq!(
+            header,
+            "• Edited fuzzy.txt (+1 -1) · applied with fuzz (87% match)"
+        );
+    }
+
+    #[test]
+    fn mode_symlink_and_binary_changes_are_summarized() {
+        let changes: HashMap<PathBuf, FileChange> = HashMap::from([
+            (
+                PathBuf::from("latest"),
+                FileChange::AddSymlink {
+                    target: PathBuf::from("releases/v2"),
+                },
+            ),
+            (
+                PathBuf::from("logo.bin"),
+                FileChange::AddBinary {
+                    size: 4,
+                    mode: None,
+                },
+            ),
+            (
+                PathBuf::from("run.sh"),
+                FileChange::Update {
+                    unified_diff: String::new(),
+                    move_path: None,
+                    fuzz_score: None,
+                    mode: Some(0o755),
+                },
+            ),
+        ]);
+
+        let lines: Vec<String> = diff_summary_for_tests(&changes)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "• Edited 3 files (+0 -0)",
+                "  └ latest (symlink → releases/v2)",
+                "",
+                "  └ logo.bin (binary, 4 bytes)",
+                "",
+                "  └ run.sh (+0 -0) · mode 755",
+            ]
+        );
+    }
+
     #[test]
     fn ui_snapshot_apply_update_with_rename_block() {
         let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
@@ -504,6 +632,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("new_name.rs")),
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -524,6 +654,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch_a,
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -532,6 +664,7 @@ mod tests {
             PathBuf::from("b.txt"),
             FileChange::Add {
                 content: "new\n".to_string(),
+                mode: None,
             },
         );
 
@@ -547,6 +680,7 @@ mod tests {
             PathBuf::from("new_file.txt"),
             FileChange::Add {
                 content: "alpha\nbeta\n".to_string(),
+                mode: None,
             },
         );
 
@@ -590,6 +724,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -613,6 +749,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -640,6 +778,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -663,6 +803,8 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(abs_new),
+                fuzz_score: None,
+                mode: None,
             },
         );
 
@@ -16,12 +16,15 @@ pub enum SlashCommand {
     Approvals,
     Skills,
     Review,
+    Fix,
     New,
     Resume,
     Init,
     Compact,
     Undo,
+    Redo,
     Diff,
+    Ps,
     Mention,
     Status,
     Mcp,
@@ -42,10 +45,13 @@ impl SlashCommand {
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
+            SlashCommand::Fix => "fix findings from the last review",
             SlashCommand::Resume => "resume a saved chat",
-            SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::Undo => "undo one or more turns",
+            SlashCommand::Redo => "redo the last undo",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Ps => "list, inspect and stop background terminals",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
@@ -72,11 +78,14 @@ impl SlashCommand {
             | SlashCommand::Init
             | SlashCommand::Compact
             | SlashCommand::Undo
+            | SlashCommand::Redo
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Review
+            | SlashCommand::Fix
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Ps
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
@@ -15,6 +15,7 @@ use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TurnCostEvent;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ReasoningEffort;
 use insta::assert_snapshot;
@@ -97,6 +98,8 @@ fn status_snapshot_includes_reasoning_details() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        network_allowlist: vec![],
+        read_deny: vec![],
     };
 
     config.cwd = PathBuf::from("/workspace/tests");
@@ -144,6 +147,7 @@ fn status_snapshot_includes_reasoning_details() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -201,6 +205,7 @@ fn status_snapshot_includes_monthly_limit() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -246,6 +251,7 @@ fn status_snapshot_shows_unlimited_credits() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -290,6 +296,7 @@ fn status_snapshot_shows_positive_credits() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -334,6 +341,7 @@ fn status_snapshot_hides_zero_credits() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -376,6 +384,7 @@ fn status_snapshot_hides_when_has_no_credits_flag() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let rendered = render_lines(&composite.display_lines(120));
     assert!(
@@ -418,6 +427,7 @@ fn status_card_token_usage_excludes_cached_tokens() {
         None,
         now,
         &model_slug,
+        &[],
     );
     let rendered = render_lines(&composite.display_lines(120));
 
@@ -475,6 +485,7 @@ fn status_snapshot_truncates_in_narrow_terminal() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(70));
     if cfg!(windows) {
@@ -521,6 +532,7 @@ fn status_snapshot_shows_missing_limits_message() {
         None,
         now,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -585,6 +597,7 @@ fn status_snapshot_includes_credits_and_limits() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -637,6 +650,7 @@ fn status_snapshot_shows_empty_limits_message() {
         None,
         captured_at,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -698,6 +712,7 @@ fn status_snapshot_shows_stale_limits_message() {
         None,
         now,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -763,6 +778,7 @@ fn status_snapshot_cached_limits_hide_credits_without_flag() {
         None,
         now,
         &model_slug,
+        &[],
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
@@ -814,6 +830,7 @@ fn status_context_window_uses_last_usage() {
         None,
         now,
         &model_slug,
+        &[],
     );
     let rendered_lines = render_lines(&composite.display_lines(80));
     let context_line = rendered_lines
@@ -830,3 +847,62 @@ fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[test]
+fn status_card_shows_session_cost_of_priced_turns() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home);
+    let auth_manager = test_auth_manager(&config);
+    let usage = TokenUsage::default();
+    let turn = |turn_id: &str, compacted: bool, cost_usd: Option<f64>| TurnCostEvent {
+        turn_id: turn_id.to_string(),
+        model: "gpt-5.1-codex-max".to_string(),
+        usage: TokenUsage::default(),
+        compacted,
+        cost_usd,
+    };
+
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 6, 1, 12, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let model_family = test_model_family(&model_slug, &config);
+    let render = |turn_costs: &[TurnCostEvent]| {
+        let composite = new_status_output(
+            &config,
+            &auth_manager,
+            &model_family,
+            &usage,
+            Some(&usage),
+            &None,
+            None,
+            None,
+            now,
+            &model_slug,
+            turn_costs,
+        );
+        render_lines(&composite.display_lines(80))
+    };
+
+    let unpriced = render(&[turn("1", false, None)]);
+    assert!(
+        unpriced.iter().all(|line| !line.contains("Cost")),
+        "cost should be hidden without prices, got: {unpriced:?}"
+    );
+
+    let priced = render(&[
+        turn("1", false, Some(0.5)),
+        turn("2", true, Some(0.25)),
+        turn("3", false, Some(0.2)),
+    ]);
+    let cost_line = priced
+        .into_iter()
+        .find(|line| line.contains("Cost"))
+        .expect("cost line");
+    assert!(
+        cost_line.contains("$0.95 (3 turns, last $0.20, 1 compacted)"),
+        "expected session cost summary, got: {cost_line}"
+    );
+}
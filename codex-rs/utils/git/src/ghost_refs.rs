@@ -0,0 +1,467 @@
+//! Keeps ghost commits reachable through refs under [`GHOST_REF_PREFIX`].
+//!
+//! Ghost commits are not referenced by any branch, so `git gc` eventually
+//! deletes them and undo stops working for older sessions. Recording each one
+//! as `refs/codex/ghost/<conversation>/<n>` keeps it alive until
+//! [`prune_ghost_refs`] removes it.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::GhostCommit;
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+
+/// Ref namespace holding one ref per recorded ghost commit.
+pub const GHOST_REF_PREFIX: &str = "refs/codex/ghost";
+
+/// A ghost commit recorded under [`GHOST_REF_PREFIX`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostRef {
+    /// Full ref name, e.g. `refs/codex/ghost/<conversation>/3`.
+    pub name: String,
+    /// Conversation that captured the snapshot.
+    pub conversation_id: String,
+    /// Position of the snapshot within its conversation, starting at 1.
+    pub index: u64,
+    /// Ghost commit the ref points at.
+    pub commit_id: String,
+    /// Committer time of the ghost commit, in seconds since the Unix epoch.
+    pub created_at: i64,
+}
+
+/// Limits applied by [`prune_ghost_refs`]. Unset limits keep everything.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GhostRetention {
+    /// Prune refs whose ghost commit is older than this.
+    pub max_age: Option<Duration>,
+    /// Keep the newest refs until their combined size on disk exceeds this
+    /// many bytes and prune the rest.
+    pub max_total_size: Option<u64>,
+}
+
+/// Records `ghost_commit` as the next ref of `conversation_id` and returns the
+/// new ref name.
+pub fn record_ghost_ref(
+    repo_path: &Path,
+    conversation_id: &str,
+    ghost_commit: &GhostCommit,
+) -> Result<String, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let next_index = list_ghost_refs(repo_path)?
+        .iter()
+        .filter(|ghost_ref| ghost_ref.conversation_id == conversation_id)
+        .map(|ghost_ref| ghost_ref.index)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let name = format!("{GHOST_REF_PREFIX}/{conversation_id}/{next_index}");
+    // The empty old value makes `update-ref` refuse to overwrite a ref that
+    // another process created in the meantime.
+    run_git_for_status(
+        repo_path,
+        [
+            OsString::from("update-ref"),
+            OsString::from(&name),
+            OsString::from(ghost_commit.id()),
+            OsString::new(),
+        ],
+        None,
+    )?;
+    Ok(name)
+}
+
+/// Lists every recorded ghost ref, oldest first.
+pub fn list_ghost_refs(repo_path: &Path) -> Result<Vec<GhostRef>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let output = run_git_for_stdout(
+        repo_path,
+        [
+            OsString::from("for-each-ref"),
+            OsString::from("--format=%(refname)%09%(objectname)%09%(committerdate:unix)"),
+            OsString::from(GHOST_REF_PREFIX),
+        ],
+        None,
+    )?;
+    let mut refs: Vec<GhostRef> = output.lines().filter_map(parse_ghost_ref_line).collect();
+    refs.sort_by(|a, b| {
+        (a.created_at, &a.conversation_id, a.index).cmp(&(
+            b.created_at,
+            &b.conversation_id,
+            b.index,
+        ))
+    });
+    Ok(refs)
+}
+
+/// Returns the on-disk size in bytes of the objects only reachable from
+/// `ghost_ref`, i.e. not from any branch, tag or remote-tracking ref nor from
+/// the refs in `exclude`, or `None` when git is too old to measure it
+/// (`--disk-usage` needs git 2.38).
+pub fn ghost_ref_disk_usage(
+    repo_path: &Path,
+    ghost_ref: &GhostRef,
+    exclude: &[GhostRef],
+) -> Result<Option<u64>, GitToolingError> {
+    disk_usage(repo_path, std::slice::from_ref(ghost_ref), exclude)
+}
+
+/// Like [`ghost_ref_disk_usage`] for all of `refs` together, counting the
+/// objects they share once.
+pub fn ghost_refs_disk_usage(
+    repo_path: &Path,
+    refs: &[GhostRef],
+) -> Result<Option<u64>, GitToolingError> {
+    disk_usage(repo_path, refs, &[])
+}
+
+fn disk_usage(
+    repo_path: &Path,
+    include: &[GhostRef],
+    exclude: &[GhostRef],
+) -> Result<Option<u64>, GitToolingError> {
+    if include.is_empty() {
+        return Ok(Some(0));
+    }
+    let mut args: Vec<OsString> = ["rev-list", "--objects", "--disk-usage"]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    args.extend(
+        include
+            .iter()
+            .map(|ghost_ref| OsString::from(&ghost_ref.commit_id)),
+    );
+    args.extend(
+        ["--not", "--branches", "--tags", "--remotes"]
+            .into_iter()
+            .map(OsString::from),
+    );
+    args.extend(
+        exclude
+            .iter()
+            .map(|ghost_ref| OsString::from(&ghost_ref.commit_id)),
+    );
+    match run_git_for_stdout(repo_path, args, None) {
+        Ok(output) => Ok(Some(output.parse().unwrap_or(0))),
+        Err(GitToolingError::GitCommand { stderr, .. }) if stderr.contains("--disk-usage") => {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes the ghost ref called `name`.
+pub fn delete_ghost_ref(repo_path: &Path, name: &str) -> Result<(), GitToolingError> {
+    run_git_for_status(repo_path, ["update-ref", "-d", name], None)
+}
+
+/// Returns whether the commit `commit_id` is still present in the object
+/// database.
+pub fn ghost_commit_exists(repo_path: &Path, commit_id: &str) -> Result<bool, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let object = format!("{commit_id}^{{commit}}");
+    match run_git_for_status(repo_path, ["cat-file", "-e", object.as_str()], None) {
+        Ok(()) => Ok(true),
+        Err(GitToolingError::GitCommand { .. }) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes the ghost refs that fall outside `retention` and returns them,
+/// oldest first. With `dry_run` nothing is deleted.
+///
+/// The size limit is skipped when git cannot measure snapshot sizes; see
+/// [`ghost_ref_disk_usage`].
+pub fn prune_ghost_refs(
+    repo_path: &Path,
+    retention: &GhostRetention,
+    dry_run: bool,
+) -> Result<Vec<GhostRef>, GitToolingError> {
+    let refs = list_ghost_refs(repo_path)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0);
+    let expired = select_expired(&refs, retention, now, |ghost_ref, kept| {
+        ghost_ref_disk_usage(repo_path, ghost_ref, kept)
+    })?;
+    if !dry_run {
+        for ghost_ref in &expired {
+            delete_ghost_ref(repo_path, &ghost_ref.name)?;
+        }
+    }
+    Ok(expired)
+}
+
+/// Picks the refs outside `retention`. `measure` returns the size a ref adds
+/// on top of the newer refs already kept, so objects shared between snapshots
+/// count once against the size budget.
+fn select_expired<F>(
+    refs: &[GhostRef],
+    retention: &GhostRetention,
+    now: i64,
+    mut measure: F,
+) -> Result<Vec<GhostRef>, GitToolingError>
+where
+    F: FnMut(&GhostRef, &[GhostRef]) -> Result<Option<u64>, GitToolingError>,
+{
+    let cutoff = retention
+        .max_age
+        .map(|max_age| now.saturating_sub(max_age.as_secs() as i64));
+    let mut max_total_size = retention.max_total_size;
+    let mut expired = vec![false; refs.len()];
+    let mut kept = Vec::new();
+    let mut total_size = 0u64;
+    // Walk newest first so the size budget is spent on the latest snapshots.
+    for (position, ghost_ref) in refs.iter().enumerate().rev() {
+        if cutoff.is_some_and(|cutoff| ghost_ref.created_at < cutoff) {
+            expired[position] = true;
+            continue;
+        }
+        if let Some(limit) = max_total_size {
+            // Once the budget is spent every older ref goes, so there is no
+            // need to measure it.
+            if total_size <= limit {
+                match measure(ghost_ref, &kept)? {
+                    Some(size) => total_size = total_size.saturating_add(size),
+                    None => max_total_size = None,
+                }
+            }
+            if max_total_size.is_some() && total_size > limit {
+                expired[position] = true;
+                continue;
+            }
+        }
+        kept.push(ghost_ref.clone());
+    }
+    Ok(refs
+        .iter()
+        .zip(expired)
+        .filter(|(_, expired)| *expired)
+        .map(|(ghost_ref, _)| ghost_ref.clone())
+        .collect())
+}
+
+fn parse_ghost_ref_line(line: &str) -> Option<GhostRef> {
+    let mut fields = line.split('\t');
+    let name = fields.next()?;
+    let commit_id = fields.next()?;
+    let created_at = fields.next()?.parse().ok()?;
+    let rest = name.strip_prefix(GHOST_REF_PREFIX)?.strip_prefix('/')?;
+    let (conversation_id, index) = rest.rsplit_once('/')?;
+    Some(GhostRef {
+        name: name.to_string(),
+        conversation_id: conversation_id.to_string(),
+        index: index.parse().ok()?,
+        commit_id: commit_id.to_string(),
+        created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CreateGhostCommitOptions;
+    use crate::create_ghost_commit;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::process::Command;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn init_repo(repo: &Path) {
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        fs::write(repo.join("tracked.txt"), "tracked\n").expect("write file");
+        run_git_in(repo, &["add", "tracked.txt"]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+    }
+
+    fn ghost_ref(conversation_id: &str, index: u64, created_at: i64) -> GhostRef {
+        GhostRef {
+            name: format!("{GHOST_REF_PREFIX}/{conversation_id}/{index}"),
+            conversation_id: conversation_id.to_string(),
+            index,
+            commit_id: format!("{index:040}"),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn recorded_refs_keep_ghost_commits_through_gc() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_repo(repo);
+
+        fs::write(repo.join("tracked.txt"), "first\n")?;
+        let first = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        fs::write(repo.join("tracked.txt"), "second\n")?;
+        let second = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        assert_eq!(
+            record_ghost_ref(repo, "conv", &first)?,
+            format!("{GHOST_REF_PREFIX}/conv/1")
+        );
+        assert_eq!(
+            record_ghost_ref(repo, "conv", &second)?,
+            format!("{GHOST_REF_PREFIX}/conv/2")
+        );
+        assert_eq!(
+            record_ghost_ref(repo, "other", &second)?,
+            format!("{GHOST_REF_PREFIX}/other/1")
+        );
+
+        run_git_in(repo, &["reflog", "expire", "--expire=now", "--all"]);
+        run_git_in(repo, &["gc", "--prune=now", "--quiet"]);
+        assert!(ghost_commit_exists(repo, first.id())?);
+        assert!(ghost_commit_exists(repo, second.id())?);
+
+        let refs = list_ghost_refs(repo)?;
+        let names: Vec<(&str, u64, &str)> = refs
+            .iter()
+            .map(|r| (r.conversation_id.as_str(), r.index, r.commit_id.as_str()))
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&("conv", 1, first.id())));
+        assert!(names.contains(&("conv", 2, second.id())));
+        assert!(names.contains(&("other", 1, second.id())));
+        assert_ne!(ghost_ref_disk_usage(repo, &refs[0], &[])?, Some(0));
+
+        delete_ghost_ref(repo, &format!("{GHOST_REF_PREFIX}/conv/1"))?;
+        run_git_in(repo, &["gc", "--prune=now", "--quiet"]);
+        assert!(!ghost_commit_exists(repo, first.id())?);
+        assert!(ghost_commit_exists(repo, second.id())?);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_commit_is_reported_as_absent() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        init_repo(temp.path());
+        assert!(!ghost_commit_exists(
+            temp.path(),
+            "0123456789abcdef0123456789abcdef01234567"
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn disk_usage_counts_shared_objects_once() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_repo(repo);
+
+        // Both snapshots capture the same large untracked file.
+        let big: String = (0..200_000).map(|i| format!("{i}\n")).collect();
+        fs::write(repo.join("big.txt"), big)?;
+        fs::write(repo.join("tracked.txt"), "first\n")?;
+        let first = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        fs::write(repo.join("tracked.txt"), "second\n")?;
+        let second = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+        record_ghost_ref(repo, "conv", &first)?;
+        record_ghost_ref(repo, "conv", &second)?;
+        let refs = list_ghost_refs(repo)?;
+        let [older, newer] = refs.as_slice() else {
+            panic!("expected two ghost refs, got {refs:?}");
+        };
+
+        let Some(alone) = ghost_ref_disk_usage(repo, older, &[])? else {
+            // git is too old to measure sizes.
+            return Ok(());
+        };
+        let on_top = ghost_ref_disk_usage(repo, older, std::slice::from_ref(newer))?;
+        let together = ghost_refs_disk_usage(repo, &refs)?;
+        let newer_alone = ghost_ref_disk_usage(repo, newer, &[])?;
+
+        assert!(on_top.is_some_and(|on_top| on_top < alone / 2));
+        assert_eq!(
+            together,
+            newer_alone.zip(on_top).map(|(newer, older)| newer + older)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retention_prunes_old_refs_and_refs_beyond_size_budget() -> Result<(), GitToolingError> {
+        let refs = vec![
+            ghost_ref("conv", 1, 100),
+            ghost_ref("conv", 2, 1_000),
+            ghost_ref("conv", 3, 2_000),
+            ghost_ref("conv", 4, 3_000),
+        ];
+        let size = |_: &GhostRef, _: &[GhostRef]| Ok(Some(10));
+
+        let by_age = GhostRetention {
+            max_age: Some(Duration::from_secs(1_500)),
+            max_total_size: None,
+        };
+        assert_eq!(
+            select_expired(&refs, &by_age, 3_000, size)?,
+            vec![refs[0].clone(), refs[1].clone()]
+        );
+
+        let by_size = GhostRetention {
+            max_age: None,
+            max_total_size: Some(25),
+        };
+        assert_eq!(
+            select_expired(&refs, &by_size, 3_000, size)?,
+            vec![refs[0].clone(), refs[1].clone()]
+        );
+
+        assert_eq!(
+            select_expired(&refs, &GhostRetention::default(), 3_000, size)?,
+            Vec::<GhostRef>::new()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn retention_measures_each_ref_against_the_refs_kept() -> Result<(), GitToolingError> {
+        let refs = vec![
+            ghost_ref("conv", 1, 1_000),
+            ghost_ref("conv", 2, 2_000),
+            ghost_ref("conv", 3, 3_000),
+        ];
+        // Every snapshot holds the same 20 bytes plus 5 of its own.
+        let size = |_: &GhostRef, kept: &[GhostRef]| Ok(Some(if kept.is_empty() { 25 } else { 5 }));
+        let by_size = GhostRetention {
+            max_age: None,
+            max_total_size: Some(32),
+        };
+        assert_eq!(
+            select_expired(&refs, &by_size, 3_000, size)?,
+            vec![refs[0].clone()]
+        );
+
+        let unmeasured = |_: &GhostRef, _: &[GhostRef]| Ok(None);
+        assert_eq!(
+            select_expired(&refs, &by_size, 3_000, unmeasured)?,
+            Vec::<GhostRef>::new()
+        );
+        Ok(())
+    }
+}
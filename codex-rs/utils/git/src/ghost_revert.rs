@@ -0,0 +1,531 @@
+//! Reverses the changes between two ghost commits in the working tree while
+//! keeping edits made after the later commit was captured.
+//!
+//! [`restore_ghost_commit`](crate::restore_ghost_commit) resets every path to
+//! the snapshot, so anything edited outside the session since then is lost.
+//! [`revert_ghost_changes`] instead works path by path on the difference
+//! between the two commits, which is exactly what happened in between.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use tempfile::Builder;
+
+use crate::GhostCommit;
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_stdout;
+use crate::operations::run_git_for_stdout_all;
+use crate::operations::run_git_for_stdout_bytes;
+
+const REGULAR_MODE: &str = "100644";
+const EXECUTABLE_MODE: &str = "100755";
+const SYMLINK_MODE: &str = "120000";
+const MISSING_MODE: &str = "000000";
+
+/// Outcome of [`revert_ghost_changes`]. Paths are relative to the repository
+/// root.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GhostRevertReport {
+    /// Paths put back to their state in `base`.
+    pub reverted: Vec<PathBuf>,
+    /// Paths edited after `changed` was captured whose change could still be
+    /// reversed with a clean three-way merge.
+    pub merged: Vec<PathBuf>,
+    /// Paths edited after `changed` was captured whose change could not be
+    /// reversed without discarding those edits. They are left untouched.
+    pub conflicted: Vec<PathBuf>,
+}
+
+/// Reverses, in the working tree, the changes that lead from the ghost commit
+/// `base` to the ghost commit `changed`.
+///
+/// Each path that differs between the two commits is handled on its own. A
+/// path that still matches `changed` is restored to `base`; a path edited
+/// since is reversed with a three-way merge when that applies cleanly and is
+/// reported as conflicted otherwise. Paths the two commits agree on are never
+/// touched, and neither is the index.
+pub fn revert_ghost_changes(
+    repo_path: &Path,
+    base: &GhostCommit,
+    changed: &GhostCommit,
+) -> Result<GhostRevertReport, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let repo_prefix = repo_subdir(repo_root.as_path(), repo_path);
+
+    let mut args = vec![
+        OsString::from("diff-tree"),
+        OsString::from("-r"),
+        OsString::from("-z"),
+        OsString::from("--no-renames"),
+        OsString::from(base.id()),
+        OsString::from(changed.id()),
+    ];
+    if let Some(prefix) = repo_prefix.as_deref() {
+        args.push(OsString::from("--"));
+        args.push(prefix.as_os_str().to_os_string());
+    }
+    let output = run_git_for_stdout_all(repo_root.as_path(), args, None)?;
+
+    let mut report = GhostRevertReport::default();
+    for change in parse_diff_tree(&output) {
+        revert_path(repo_root.as_path(), &change, &mut report)?;
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TreeEntry {
+    mode: String,
+    oid: String,
+}
+
+impl TreeEntry {
+    fn is_regular(&self) -> bool {
+        self.mode == REGULAR_MODE || self.mode == EXECUTABLE_MODE
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.mode == SYMLINK_MODE
+    }
+}
+
+/// One path that differs between the two commits; `None` means absent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TreeChange {
+    path: PathBuf,
+    base: Option<TreeEntry>,
+    changed: Option<TreeEntry>,
+}
+
+/// What a path currently holds in the working tree.
+enum WorktreeEntry {
+    Missing,
+    File { executable: bool, oid: String },
+    Symlink { target: Vec<u8> },
+    Other,
+}
+
+/// Parses `git diff-tree -r -z` output: `:<mode> <mode> <oid> <oid> <status>`
+/// followed by the path, each NUL-terminated.
+fn parse_diff_tree(output: &str) -> Vec<TreeChange> {
+    let mut fields = output.split('\0');
+    let mut changes = Vec::new();
+    while let Some(meta) = fields.next() {
+        let Some(meta) = meta.strip_prefix(':') else {
+            continue;
+        };
+        let Some(path) = fields.next() else {
+            break;
+        };
+        let parts: Vec<&str> = meta.split(' ').collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        changes.push(TreeChange {
+            path: PathBuf::from(path),
+            base: tree_entry(parts[0], parts[2]),
+            changed: tree_entry(parts[1], parts[3]),
+        });
+    }
+    changes
+}
+
+fn tree_entry(mode: &str, oid: &str) -> Option<TreeEntry> {
+    (mode != MISSING_MODE).then(|| TreeEntry {
+        mode: mode.to_string(),
+        oid: oid.to_string(),
+    })
+}
+
+fn revert_path(
+    repo_root: &Path,
+    change: &TreeChange,
+    report: &mut GhostRevertReport,
+) -> Result<(), GitToolingError> {
+    // Submodules and other special entries are left alone.
+    if [&change.base, &change.changed]
+        .into_iter()
+        .flatten()
+        .any(|entry| !entry.is_regular() && !entry.is_symlink())
+    {
+        return Ok(());
+    }
+
+    let current = read_worktree_entry(repo_root, &change.path)?;
+    if matches_entry(repo_root, &change.path, change.base.as_ref(), &current)? {
+        return Ok(());
+    }
+    let full_path = repo_root.join(&change.path);
+    if matches_entry(repo_root, &change.path, change.changed.as_ref(), &current)? {
+        write_entry(repo_root, &change.path, &full_path, change.base.as_ref())?;
+        report.reverted.push(change.path.clone());
+        return Ok(());
+    }
+
+    if let (Some(base), Some(changed), WorktreeEntry::File { executable, .. }) =
+        (&change.base, &change.changed, &current)
+        && base.is_regular()
+        && changed.is_regular()
+        && let Some(merged) = merge_reversal(repo_root, &change.path, &full_path, base, changed)?
+    {
+        fs::write(&full_path, merged)?;
+        // Only reverse a mode change if the file still has the changed mode.
+        if *executable == (changed.mode == EXECUTABLE_MODE) {
+            set_executable(&full_path, base.mode == EXECUTABLE_MODE)?;
+        }
+        report.merged.push(change.path.clone());
+        return Ok(());
+    }
+
+    report.conflicted.push(change.path.clone());
+    Ok(())
+}
+
+fn read_worktree_entry(repo_root: &Path, path: &Path) -> Result<WorktreeEntry, GitToolingError> {
+    let full_path = repo_root.join(path);
+    let metadata = match fs::symlink_metadata(&full_path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(WorktreeEntry::Missing),
+        Err(err) => return Err(err.into()),
+    };
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        let target = fs::read_link(&full_path)?;
+        return Ok(WorktreeEntry::Symlink {
+            target: path_to_bytes(&target),
+        });
+    }
+    if !file_type.is_file() {
+        return Ok(WorktreeEntry::Other);
+    }
+    // `hash-object` applies the same clean filters `git add` used when the
+    // ghost commit was captured.
+    let oid = run_git_for_stdout(
+        repo_root,
+        vec![
+            OsString::from("hash-object"),
+            OsString::from("--"),
+            path.as_os_str().to_os_string(),
+        ],
+        None,
+    )?;
+    Ok(WorktreeEntry::File {
+        executable: is_executable(&metadata),
+        oid,
+    })
+}
+
+fn matches_entry(
+    repo_root: &Path,
+    path: &Path,
+    entry: Option<&TreeEntry>,
+    current: &WorktreeEntry,
+) -> Result<bool, GitToolingError> {
+    Ok(match (entry, current) {
+        (None, WorktreeEntry::Missing) => true,
+        (Some(entry), WorktreeEntry::File { executable, oid }) => {
+            entry.is_regular()
+                && entry.oid == *oid
+                && (!cfg!(unix) || *executable == (entry.mode == EXECUTABLE_MODE))
+        }
+        (Some(entry), WorktreeEntry::Symlink { target }) => {
+            entry.is_symlink() && read_blob(repo_root, path, entry)? == *target
+        }
+        _ => false,
+    })
+}
+
+fn write_entry(
+    repo_root: &Path,
+    path: &Path,
+    full_path: &Path,
+    entry: Option<&TreeEntry>,
+) -> Result<(), GitToolingError> {
+    let replace = match fs::symlink_metadata(full_path) {
+        Ok(metadata) => {
+            metadata.file_type().is_symlink() || entry.is_none_or(TreeEntry::is_symlink)
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+        Err(err) => return Err(err.into()),
+    };
+    if replace {
+        fs::remove_file(full_path)?;
+    }
+    let Some(entry) = entry else {
+        return Ok(());
+    };
+    if let Some(parent) = full_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = read_blob(repo_root, path, entry)?;
+    if entry.is_symlink() {
+        create_symlink(&contents, full_path)?;
+    } else {
+        fs::write(full_path, contents)?;
+        set_executable(full_path, entry.mode == EXECUTABLE_MODE)?;
+    }
+    Ok(())
+}
+
+/// Reads a blob as it would be checked out at `path`, i.e. with smudge
+/// filters applied. Symlink targets are read raw.
+fn read_blob(repo_root: &Path, path: &Path, entry: &TreeEntry) -> Result<Vec<u8>, GitToolingError> {
+    let args = if entry.is_symlink() {
+        vec![
+            OsString::from("cat-file"),
+            OsString::from("blob"),
+            OsString::from(&entry.oid),
+        ]
+    } else {
+        let mut path_arg = OsString::from("--path=");
+        path_arg.push(path.as_os_str());
+        vec![
+            OsString::from("cat-file"),
+            OsString::from("--filters"),
+            path_arg,
+            OsString::from(&entry.oid),
+        ]
+    };
+    run_git_for_stdout_bytes(repo_root, args, None)
+}
+
+/// Merges the reversal of `base -> changed` into the edited file at
+/// `full_path`. Returns `None` when the merge has conflicts.
+fn merge_reversal(
+    repo_root: &Path,
+    path: &Path,
+    full_path: &Path,
+    base: &TreeEntry,
+    changed: &TreeEntry,
+) -> Result<Option<Vec<u8>>, GitToolingError> {
+    let tempdir = Builder::new().prefix("codex-git-revert-").tempdir()?;
+    let base_path = tempdir.path().join("base");
+    let changed_path = tempdir.path().join("changed");
+    fs::write(&base_path, read_blob(repo_root, path, base)?)?;
+    fs::write(&changed_path, read_blob(repo_root, path, changed)?)?;
+
+    // `git merge-file <current> <base> <other>` applies the changes from
+    // <base> to <other> onto <current>, so this replays `changed -> base` on
+    // top of the working tree copy. It exits non-zero on conflicts (or on
+    // binary input, which is treated the same way).
+    let output = Command::new("git")
+        .current_dir(repo_root)
+        .args(["merge-file", "-p", "--quiet"])
+        .arg(full_path)
+        .arg(&changed_path)
+        .arg(&base_path)
+        .output()?;
+    Ok(output.status.success().then_some(output.stdout))
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let updated = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    if updated != mode {
+        permissions.set_mode(updated);
+        fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().replace('\\', "/").into_bytes()
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &[u8], destination: &Path) -> io::Result<()> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    std::os::unix::fs::symlink(OsStr::from_bytes(target), destination)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &[u8], destination: &Path) -> io::Result<()> {
+    let target = String::from_utf8_lossy(target).replace('/', "\\");
+    std::os::windows::fs::symlink_file(target, destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CreateGhostCommitOptions;
+    use crate::create_ghost_commit;
+    use pretty_assertions::assert_eq;
+
+    /// Runs a git command in the test repository and asserts success.
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    /// Creates a repository with one commit holding `files`.
+    fn init_repo_with(repo: &Path, files: &[(&str, &str)]) {
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        run_git_in(repo, &["config", "core.autocrlf", "false"]);
+        for (name, contents) in files {
+            fs::write(repo.join(name), contents).expect("write file");
+            run_git_in(repo, &["add", name]);
+        }
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+    }
+
+    fn snapshot(repo: &Path) -> GhostCommit {
+        create_ghost_commit(&CreateGhostCommitOptions::new(repo)).expect("ghost commit")
+    }
+
+    #[test]
+    fn reverts_changes_and_keeps_unrelated_edits() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_repo_with(repo, &[("agent.txt", "before\n"), ("other.txt", "other\n")]);
+
+        let base = snapshot(repo);
+        fs::write(repo.join("agent.txt"), "after\n")?;
+        fs::write(repo.join("created.txt"), "new\n")?;
+        let changed = snapshot(repo);
+
+        fs::write(repo.join("other.txt"), "edited outside\n")?;
+
+        let report = revert_ghost_changes(repo, &base, &changed)?;
+        assert_eq!(
+            report,
+            GhostRevertReport {
+                reverted: vec![PathBuf::from("agent.txt"), PathBuf::from("created.txt")],
+                merged: Vec::new(),
+                conflicted: Vec::new(),
+            }
+        );
+        assert_eq!(fs::read_to_string(repo.join("agent.txt"))?, "before\n");
+        assert!(!repo.join("created.txt").exists());
+        assert_eq!(
+            fs::read_to_string(repo.join("other.txt"))?,
+            "edited outside\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merges_or_reports_edits_to_changed_files() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_repo_with(
+            repo,
+            &[
+                ("merge.txt", "one\ntwo\nthree\nfour\nfive\n"),
+                ("conflict.txt", "original\n"),
+            ],
+        );
+
+        let base = snapshot(repo);
+        fs::write(repo.join("merge.txt"), "one\nTWO\nthree\nfour\nfive\n")?;
+        fs::write(repo.join("conflict.txt"), "agent\n")?;
+        let changed = snapshot(repo);
+
+        fs::write(repo.join("merge.txt"), "one\nTWO\nthree\nfour\nFIVE\n")?;
+        fs::write(repo.join("conflict.txt"), "teammate\n")?;
+
+        let report = revert_ghost_changes(repo, &base, &changed)?;
+        assert_eq!(
+            report,
+            GhostRevertReport {
+                reverted: Vec::new(),
+                merged: vec![PathBuf::from("merge.txt")],
+                conflicted: vec![PathBuf::from("conflict.txt")],
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(repo.join("merge.txt"))?,
+            "one\ntwo\nthree\nfour\nFIVE\n"
+        );
+        assert_eq!(fs::read_to_string(repo.join("conflict.txt"))?, "teammate\n");
+        Ok(())
+    }
+
+    #[test]
+    fn restores_deleted_files() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_repo_with(repo, &[("keep.txt", "keep\n")]);
+        fs::create_dir_all(repo.join("nested"))?;
+        fs::write(repo.join("nested/untracked.txt"), "untracked\n")?;
+
+        let base = snapshot(repo);
+        fs::remove_file(repo.join("keep.txt"))?;
+        fs::remove_dir_all(repo.join("nested"))?;
+        let changed = snapshot(repo);
+
+        let report = revert_ghost_changes(repo, &base, &changed)?;
+        assert_eq!(
+            report.reverted,
+            vec![
+                PathBuf::from("keep.txt"),
+                PathBuf::from("nested/untracked.txt")
+            ]
+        );
+        assert_eq!(fs::read_to_string(repo.join("keep.txt"))?, "keep\n");
+        assert_eq!(
+            fs::read_to_string(repo.join("nested/untracked.txt"))?,
+            "untracked\n"
+        );
+        Ok(())
+    }
+}
@@ -5,6 +5,8 @@ mod apply;
 mod branch;
 mod errors;
 mod ghost_commits;
+mod ghost_refs;
+mod ghost_revert;
 mod operations;
 mod platform;
 
@@ -24,6 +26,18 @@ pub use ghost_commits::create_ghost_commit;
 pub use ghost_commits::create_ghost_commit_with_report;
 pub use ghost_commits::restore_ghost_commit;
 pub use ghost_commits::restore_to_commit;
+pub use ghost_refs::GHOST_REF_PREFIX;
+pub use ghost_refs::GhostRef;
+pub use ghost_refs::GhostRetention;
+pub use ghost_refs::delete_ghost_ref;
+pub use ghost_refs::ghost_commit_exists;
+pub use ghost_refs::ghost_ref_disk_usage;
+pub use ghost_refs::ghost_refs_disk_usage;
+pub use ghost_refs::list_ghost_refs;
+pub use ghost_refs::prune_ghost_refs;
+pub use ghost_refs::record_ghost_ref;
+pub use ghost_revert::GhostRevertReport;
+pub use ghost_revert::revert_ghost_changes;
 pub use platform::create_symlink;
 use schemars::JsonSchema;
 use serde::Deserialize;
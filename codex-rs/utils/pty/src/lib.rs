@@ -38,6 +38,7 @@ pub struct ExecCommandSession {
     wait_handle: StdMutex<Option<JoinHandle<()>>>,
     exit_status: Arc<AtomicBool>,
     exit_code: Arc<StdMutex<Option<i32>>>,
+    pid: Option<u32>,
     // PtyPair must be preserved because the process will receive Control+C if the
     // slave is closed
     _pair: StdMutex<PtyPairWrapper>,
@@ -61,6 +62,7 @@ impl ExecCommandSession {
         wait_handle: JoinHandle<()>,
         exit_status: Arc<AtomicBool>,
         exit_code: Arc<StdMutex<Option<i32>>>,
+        pid: Option<u32>,
         pair: PtyPairWrapper,
     ) -> (Self, broadcast::Receiver<Vec<u8>>) {
         (
@@ -73,6 +75,7 @@ impl ExecCommandSession {
                 wait_handle: StdMutex::new(Some(wait_handle)),
                 exit_status,
                 exit_code,
+                pid,
                 _pair: StdMutex::new(pair),
             },
             initial_output_rx,
@@ -95,6 +98,11 @@ impl ExecCommandSession {
         self.exit_code.lock().ok().and_then(|guard| *guard)
     }
 
+    /// OS process id of the spawned command, when the platform reports one.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
     pub fn terminate(&self) {
         if let Ok(mut killer_opt) = self.killer.lock() {
             if let Some(mut killer) = killer_opt.take() {
@@ -174,6 +182,7 @@ pub async fn spawn_pty_process(
 
     let mut child = pair.slave.spawn_command(command_builder)?;
     let killer = child.clone_killer();
+    let pid = child.process_id();
 
     let (writer_tx, mut writer_rx) = mpsc::channel::<Vec<u8>>(128);
     let (output_tx, _) = broadcast::channel::<Vec<u8>>(256);
@@ -251,6 +260,7 @@ pub async fn spawn_pty_process(
         wait_handle,
         exit_status,
         exit_code,
+        pid,
         pair,
     );
 
@@ -6,8 +6,8 @@ use crate::logging::{debug_log, log_note};
 use crate::policy::SandboxPolicy;
 use crate::token::convert_string_sid_to_sid;
 use crate::token::world_sid;
-use anyhow::anyhow;
 use anyhow::Result;
+use anyhow::anyhow;
 use std::collections::HashSet;
 use std::ffi::c_void;
 use std::path::Path;
@@ -266,9 +266,8 @@ pub fn apply_capability_denies_for_world_writable(
             (sid, roots)
         }
         SandboxPolicy::ReadOnly => (
-            unsafe { convert_string_sid_to_sid(&caps.readonly) }.ok_or_else(|| {
-                anyhow!("ConvertStringSidToSidW failed for readonly capability")
-            })?,
+            unsafe { convert_string_sid_to_sid(&caps.readonly) }
+                .ok_or_else(|| anyhow!("ConvertStringSidToSidW failed for readonly capability"))?,
             Vec::new(),
         ),
         SandboxPolicy::DangerFullAccess => {